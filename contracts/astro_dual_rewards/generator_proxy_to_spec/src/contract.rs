@@ -1,17 +1,25 @@
 use cosmwasm_std::{
     entry_point, from_binary, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
-    Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
+    Decimal, Order, Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
 use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg};
 
 use crate::error::ContractError;
-use crate::state::{Config, CONFIG};
+use crate::state::{
+    Config, PoolConfig, PoolState, UserInfo, CONFIG, LEGACY_CONFIG, POOLS, POOL_REWARDS,
+    POOL_STATES, USERS,
+};
 use astroport::asset::addr_validate_to_lower;
 use astroport::generator_proxy::{
-    CallbackMsg, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    CallbackMsg, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PoolResponse,
+    QueryMsg, UserInfoResponse,
+};
+use spectrum_protocol::gov::{
+    BalanceResponse as GovBalanceResponse, QueryMsg as GovQueryMsg, StateInfo as GovStateInfo,
 };
 use spectrum_protocol::spec_farm::{
-    Cw20HookMsg as SpecCw20HookMsg, ExecuteMsg as SpecExecuteMsg, RewardInfoResponse as SpecRewardInfoResponse, QueryMsg as SpecQueryMsg
+    Cw20HookMsg as SpecCw20HookMsg, ExecuteMsg as SpecExecuteMsg, RewardInfoResponse as SpecRewardInfoResponse,
+    RewardInfoResponseItem as SpecRewardInfoResponseItem, QueryMsg as SpecQueryMsg
 };
 use cw2::set_contract_version;
 
@@ -29,29 +37,96 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 ///
 /// * **_env** is the object of type [`Env`].
 ///
-/// * **_info** is the object of type [`MessageInfo`].
+/// * **info** is the object of type [`MessageInfo`].
 /// * **msg** is a message of type [`InstantiateMsg`] which contains the basic settings for creating a contract
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     let config = Config {
         generator_contract_addr: addr_validate_to_lower(deps.api, &msg.generator_contract_addr)?,
-        pair_addr: addr_validate_to_lower(deps.api, &msg.pair_addr)?,
-        lp_token_addr: addr_validate_to_lower(deps.api, &msg.lp_token_addr)?,
-        reward_contract_addr: addr_validate_to_lower(deps.api, &msg.reward_contract_addr)?,
-        reward_token_addr: addr_validate_to_lower(deps.api, &msg.reward_token_addr)?,
+        owner: match msg.owner {
+            Some(owner) => addr_validate_to_lower(deps.api, &owner)?,
+            None => info.sender,
+        },
+        spectrum_gov: match msg.spectrum_gov {
+            Some(spectrum_gov) => Some(addr_validate_to_lower(deps.api, &spectrum_gov)?),
+            None => None,
+        },
     };
     CONFIG.save(deps.storage, &config)?;
 
+    save_pool(
+        deps.storage,
+        PoolConfig {
+            pair_addr: addr_validate_to_lower(deps.api, &msg.pair_addr)?,
+            lp_token_addr: addr_validate_to_lower(deps.api, &msg.lp_token_addr)?,
+            reward_contract_addr: addr_validate_to_lower(deps.api, &msg.reward_contract_addr)?,
+            reward_token_addr: addr_validate_to_lower(deps.api, &msg.reward_token_addr)?,
+        },
+    )?;
+
     Ok(Response::default())
 }
 
+/// ## Description
+/// Saves a new pool. Returns an [`ContractError`] if the liquidity pool token is already registered.
+fn save_pool(storage: &mut dyn Storage, pool: PoolConfig) -> Result<(), ContractError> {
+    if POOLS.has(storage, &pool.lp_token_addr) {
+        return Err(ContractError::PoolAlreadyRegistered {});
+    }
+    POOL_REWARDS.save(storage, &pool.lp_token_addr, &Uint128::zero())?;
+    POOL_STATES.save(storage, &pool.lp_token_addr, &PoolState::default())?;
+    POOLS.save(storage, &pool.lp_token_addr, &pool)?;
+    Ok(())
+}
+
+/// ## Description
+/// Loads the pool of the liquidity pool token. When `lp_token` is not specified,
+/// the only registered pool is returned, so single pool callers keep working.
+fn load_pool(storage: &dyn Storage, lp_token: Option<Addr>) -> Result<PoolConfig, ContractError> {
+    if let Some(lp_token) = lp_token {
+        return POOLS
+            .may_load(storage, &lp_token)?
+            .ok_or(ContractError::PoolNotFound {});
+    }
+
+    let mut pools = POOLS
+        .range(storage, None, None, Order::Ascending)
+        .take(2)
+        .collect::<StdResult<Vec<_>>>()?;
+    match pools.len() {
+        0 => Err(ContractError::PoolNotFound {}),
+        1 => Ok(pools.remove(0).1),
+        _ => Err(ContractError::LpTokenRequired {}),
+    }
+}
+
+/// ## Description
+/// Saves the depositor info, or removes it when nothing is left.
+fn save_user(storage: &mut dyn Storage, key: (&Addr, &Addr), user: &UserInfo) -> StdResult<()> {
+    if user.amount.is_zero() && user.pending_reward.is_zero() {
+        USERS.remove(storage, key);
+        Ok(())
+    } else {
+        USERS.save(storage, key, user)
+    }
+}
+
+/// ## Description
+/// Loads all registered pools.
+fn load_pools(storage: &dyn Storage) -> StdResult<Vec<PoolConfig>> {
+    POOLS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| Ok(item?.1))
+        .collect()
+}
+
 /// ## Description
 /// Available the execute messages of the contract.
 /// ## Params
@@ -65,15 +140,19 @@ pub fn instantiate(
 ///
 /// ## Queries
 /// * **ExecuteMsg::Receive(msg)** Receives a message of type [`Cw20ReceiveMsg`] and processes
-/// it depending on the received template.
+///   it depending on the received template.
 ///
 /// * **ExecuteMsg::UpdateRewards {}** Withdrawal pending rewards.
 ///
-/// * **ExecuteMsg::SendRewards { account, amount }** Sends rewards to the recipient.
+/// * **ExecuteMsg::SendRewards { account, amount, lp_token }** Sends rewards to the recipient.
 ///
-/// * **ExecuteMsg::Withdraw { account, amount }** Withdrawal the rewards.
+/// * **ExecuteMsg::Withdraw { account, amount, lp_token }** Withdrawal the rewards.
 ///
-/// * **ExecuteMsg::EmergencyWithdraw { account, amount }** Withdrawal the rewards.
+/// * **ExecuteMsg::EmergencyWithdraw { account, amount, lp_token }** Withdrawal the rewards.
+///
+/// * **ExecuteMsg::RegisterPool { .. }** Registers another liquidity pool.
+///
+/// * **ExecuteMsg::Kick { account, lp_token }** Recalculates the boost of the depositor.
 ///
 /// * **ExecuteMsg::Callback(msg)** Handles the callbacks describes in the [`CallbackMsg`].
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -85,12 +164,39 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
-        ExecuteMsg::UpdateRewards {} => update_rewards(deps),
-        ExecuteMsg::SendRewards { account, amount } => send_rewards(deps, info, account, amount),
-        ExecuteMsg::Withdraw { account, amount } => withdraw(deps, env, info, account, amount),
-        ExecuteMsg::EmergencyWithdraw { account, amount } => {
-            withdraw(deps, env, info, account, amount)
-        }
+        ExecuteMsg::UpdateRewards {} => update_rewards(deps, env),
+        ExecuteMsg::SendRewards {
+            account,
+            amount,
+            lp_token,
+        } => send_rewards(deps, info, account, amount, lp_token),
+        ExecuteMsg::Withdraw {
+            account,
+            amount,
+            lp_token,
+        } => withdraw(deps, env, info, account, amount, lp_token, false),
+        ExecuteMsg::EmergencyWithdraw {
+            account,
+            amount,
+            lp_token,
+        } => withdraw(deps, env, info, account, amount, lp_token, true),
+        ExecuteMsg::RegisterPool {
+            pair_addr,
+            lp_token_addr,
+            reward_contract_addr,
+            reward_token_addr,
+        } => register_pool(
+            deps,
+            info,
+            pair_addr,
+            lp_token_addr,
+            reward_contract_addr,
+            reward_token_addr,
+        ),
+        ExecuteMsg::Kick {
+            account,
+            lp_token,
+        } => kick(deps, env, account, lp_token),
         ExecuteMsg::Callback(msg) => handle_callback(deps, env, info, msg),
     }
 }
@@ -102,34 +208,53 @@ pub fn execute(
 /// ## Params
 /// * **deps** is the object of type [`DepsMut`].
 ///
-/// * **_env** is the object of type [`Env`].
+/// * **env** is the object of type [`Env`].
 ///
 /// * **info** is the object of type [`MessageInfo`].
 ///
 /// * **cw20_msg** is the object of type [`Cw20ReceiveMsg`].
 fn receive_cw20(
-    deps: DepsMut,
-    _env: Env,
+    mut deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
     let mut response = Response::new();
     let cfg = CONFIG.load(deps.storage)?;
 
-    if let Ok(Cw20HookMsg::Deposit {}) = from_binary(&cw20_msg.msg) {
-        if cw20_msg.sender != cfg.generator_contract_addr || info.sender != cfg.lp_token_addr {
-            return Err(ContractError::Unauthorized {});
-        }
+    if let Ok(Cw20HookMsg::Deposit { account }) = from_binary(&cw20_msg.msg) {
+        // the pool is picked by the liquidity pool token sent
+        let pool = POOLS.may_load(deps.storage, &info.sender)?;
+        let pool = match pool {
+            Some(pool) if cw20_msg.sender == cfg.generator_contract_addr => pool,
+            _ => return Err(ContractError::Unauthorized {}),
+        };
+
+        // rewards so far belong to the deposits before this one
+        response.messages.extend(update_pool(deps.branch(), &env, &pool)?);
+        let mut pool_state = POOL_STATES
+            .may_load(deps.storage, &pool.lp_token_addr)?
+            .unwrap_or_default();
+        let account = account.unwrap_or(cfg.generator_contract_addr);
+        let key = (&pool.lp_token_addr, &account);
+        let mut user = USERS.may_load(deps.storage, key)?.unwrap_or_default();
+        user.settle(pool_state.reward_index);
+        user.amount += cw20_msg.amount;
+        pool_state.total_deposit += cw20_msg.amount;
+        user.update_boost(&mut pool_state)?;
+        USERS.save(deps.storage, key, &user)?;
+        POOL_STATES.save(deps.storage, &pool.lp_token_addr, &pool_state)?;
+
         response
             .messages
             .push(SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: cfg.lp_token_addr.to_string(),
+                contract_addr: pool.lp_token_addr.to_string(),
                 funds: vec![],
                 msg: to_binary(&Cw20ExecuteMsg::Send {
-                    contract: cfg.reward_contract_addr.to_string(),
+                    contract: pool.reward_contract_addr.to_string(),
                     amount: cw20_msg.amount,
                     msg: to_binary(&SpecCw20HookMsg::bond {
-                        asset_token: cfg.pair_addr.into_string(),
+                        asset_token: pool.pair_addr.into_string(),
                         staker_addr: None
                     })?,
                 })?,
@@ -141,29 +266,57 @@ fn receive_cw20(
 }
 
 /// ## Description
-/// Withdraw pending rewards. Returns an [`ContractError`] on failure,
+/// Withdraw pending rewards of all pools. Returns an [`ContractError`] on failure,
 /// otherwise returns the [`Response`] object with the specified submessages.
 ///
 /// ## Params
 /// * **deps** is the object of type [`DepsMut`].
-fn update_rewards(deps: DepsMut) -> Result<Response, ContractError> {
+///
+/// * **env** is the object of type [`Env`].
+fn update_rewards(mut deps: DepsMut, env: Env) -> Result<Response, ContractError> {
     let mut response = Response::new();
-    let cfg = CONFIG.load(deps.storage)?;
 
-    response
-        .messages
-        .push(SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: cfg.reward_contract_addr.to_string(),
-            funds: vec![],
-            msg: to_binary(&SpecExecuteMsg::withdraw {
-                asset_token: None,
-                spec_amount: None,
-            })?,
-        })));
+    for pool in load_pools(deps.storage)? {
+        response.messages.extend(update_pool(deps.branch(), &env, &pool)?);
+    }
 
     Ok(response)
 }
 
+/// ## Description
+/// Withdraws pending rewards of the pool and distributes them over the current deposits.
+/// Returns the withdraw submessage if there is any pending reward.
+/// Must be called before the total deposit of the pool changes.
+fn update_pool(deps: DepsMut, env: &Env, pool: &PoolConfig) -> StdResult<Option<SubMsg>> {
+    // reward contract pays the pending amount of the pool, so it is credited to the pool
+    let pending_reward = query_reward_info(deps.as_ref(), env, pool)?.pending_spec_reward;
+    if pending_reward.is_zero() {
+        return Ok(None);
+    }
+    POOL_REWARDS.update(deps.storage, &pool.lp_token_addr, |rewards| -> StdResult<_> {
+        Ok(rewards.unwrap_or_default() + pending_reward)
+    })?;
+
+    // without deposits, rewards stay with the pool as orphan rewards
+    let mut pool_state = POOL_STATES
+        .may_load(deps.storage, &pool.lp_token_addr)?
+        .unwrap_or_default();
+    if !pool_state.total_deposit.is_zero() {
+        pool_state.reward_index = pool_state.reward_index
+            + Decimal::from_ratio(pending_reward, pool_state.total_deposit + pool_state.total_boost);
+        POOL_STATES.save(deps.storage, &pool.lp_token_addr, &pool_state)?;
+    }
+
+    Ok(Some(SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: pool.reward_contract_addr.to_string(),
+        funds: vec![],
+        msg: to_binary(&SpecExecuteMsg::withdraw {
+            asset_token: Some(pool.pair_addr.to_string()),
+            spec_amount: Some(pending_reward),
+        })?,
+    }))))
+}
+
 /// ## Description
 /// Sends rewards to the recipient. Returns an [`ContractError`] on failure,
 /// otherwise returns the [`Response`] object with the specified submessages.
@@ -177,6 +330,8 @@ fn update_rewards(deps: DepsMut) -> Result<Response, ContractError> {
 ///
 /// * **amount** is the object of type [`Uint128`].
 ///
+/// * **lp_token** is an [`Option`] of type [`Addr`]. Sets the pool to send rewards from.
+///
 /// ## Executor
 /// Only the contract of generator can execute it
 fn send_rewards(
@@ -184,6 +339,7 @@ fn send_rewards(
     info: MessageInfo,
     account: Addr,
     amount: Uint128,
+    lp_token: Option<Addr>,
 ) -> Result<Response, ContractError> {
     let mut response = Response::new();
     let cfg = CONFIG.load(deps.storage)?;
@@ -191,10 +347,30 @@ fn send_rewards(
         return Err(ContractError::Unauthorized {});
     };
 
+    let pool = load_pool(deps.storage, lp_token)?;
+    let rewards = POOL_REWARDS
+        .may_load(deps.storage, &pool.lp_token_addr)?
+        .unwrap_or_default();
+    let rewards = rewards
+        .checked_sub(amount)
+        .map_err(|_| ContractError::InsufficientPoolRewards {})?;
+    POOL_REWARDS.save(deps.storage, &pool.lp_token_addr, &rewards)?;
+
+    // orphan rewards may be sent to an account without deposit
+    let key = (&pool.lp_token_addr, &account);
+    if let Some(mut user) = USERS.may_load(deps.storage, key)? {
+        let pool_state = POOL_STATES
+            .may_load(deps.storage, &pool.lp_token_addr)?
+            .unwrap_or_default();
+        user.settle(pool_state.reward_index);
+        user.pending_reward = user.pending_reward.saturating_sub(amount);
+        save_user(deps.storage, key, &user)?;
+    }
+
     response
         .messages
         .push(SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: cfg.reward_token_addr.to_string(),
+            contract_addr: pool.reward_token_addr.to_string(),
             msg: to_binary(&Cw20ExecuteMsg::Transfer {
                 recipient: account.to_string(),
                 amount,
@@ -218,14 +394,20 @@ fn send_rewards(
 ///
 /// * **amount** is the object of type [`Uint128`].
 ///
+/// * **lp_token** is an [`Option`] of type [`Addr`]. Sets the pool to withdraw from.
+///
+/// * **forfeit_rewards** is the object of type [`bool`]. Drops the rewards of the account on emergency withdrawal.
+///
 /// ## Executor
 /// Only the contract of generator can execute it
 fn withdraw(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     account: Addr,
     amount: Uint128,
+    lp_token: Option<Addr>,
+    forfeit_rewards: bool,
 ) -> Result<Response, ContractError> {
     let mut response = Response::new();
     let cfg = CONFIG.load(deps.storage)?;
@@ -233,9 +415,39 @@ fn withdraw(
         return Err(ContractError::Unauthorized {});
     };
 
+    let pool = load_pool(deps.storage, lp_token)?;
+
+    // rewards so far belong to the deposits before this withdrawal
+    response.messages.extend(update_pool(deps.branch(), &env, &pool)?);
+    let mut pool_state = POOL_STATES
+        .may_load(deps.storage, &pool.lp_token_addr)?
+        .unwrap_or_default();
+
+    // deposits made without account are held by the generator
+    let owner = if USERS.has(deps.storage, (&pool.lp_token_addr, &account)) {
+        account.clone()
+    } else {
+        cfg.generator_contract_addr
+    };
+    let key = (&pool.lp_token_addr, &owner);
+    let mut user = USERS.may_load(deps.storage, key)?.unwrap_or_default();
+    user.settle(pool_state.reward_index);
+    user.amount = user
+        .amount
+        .checked_sub(amount)
+        .map_err(|_| ContractError::InsufficientDeposit {})?;
+    pool_state.total_deposit = pool_state.total_deposit.checked_sub(amount).map_err(StdError::from)?;
+    user.update_boost(&mut pool_state)?;
+    // forfeited rewards stay with the pool as orphan rewards
+    if forfeit_rewards {
+        user.pending_reward = Uint128::zero();
+    }
+    save_user(deps.storage, key, &user)?;
+    POOL_STATES.save(deps.storage, &pool.lp_token_addr, &pool_state)?;
+
     let prev_lp_balance = {
         let res: BalanceResponse = deps.querier.query_wasm_smart(
-            &cfg.lp_token_addr,
+            &pool.lp_token_addr,
             &Cw20QueryMsg::Balance {
                 address: env.contract.address.to_string(),
             },
@@ -245,10 +457,10 @@ fn withdraw(
 
     // withdraw from the end reward contract
     response.messages.push(SubMsg::new(WasmMsg::Execute {
-        contract_addr: cfg.reward_contract_addr.to_string(),
+        contract_addr: pool.reward_contract_addr.to_string(),
         funds: vec![],
         msg: to_binary(&SpecExecuteMsg::unbond {
-            asset_token: cfg.pair_addr.to_string(),
+            asset_token: pool.pair_addr.to_string(),
             amount,
         })?,
     }));
@@ -259,6 +471,7 @@ fn withdraw(
         msg: to_binary(&ExecuteMsg::Callback(
             CallbackMsg::TransferLpTokensAfterWithdraw {
                 account,
+                lp_token: pool.lp_token_addr,
                 prev_lp_balance,
             },
         ))?,
@@ -267,6 +480,127 @@ fn withdraw(
     Ok(response)
 }
 
+/// # Description
+/// Registers another liquidity pool. Returns an [`ContractError`] on failure,
+/// otherwise returns the [`Response`] with the specified attributes if the operation was successful.
+/// # Params
+/// * **deps** is the object of type [`DepsMut`].
+///
+/// * **info** is the object of type [`MessageInfo`].
+///
+/// * **pair_addr** is the pair contract address, used as asset token in the reward contract.
+///
+/// * **lp_token_addr** is the liquidity pool token contract address.
+///
+/// * **reward_contract_addr** is the reward contract address.
+///
+/// * **reward_token_addr** is the reward token contract address.
+///
+/// ## Executor
+/// Only the owner can execute it
+fn register_pool(
+    deps: DepsMut,
+    info: MessageInfo,
+    pair_addr: String,
+    lp_token_addr: String,
+    reward_contract_addr: String,
+    reward_token_addr: String,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    if info.sender != cfg.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    save_pool(
+        deps.storage,
+        PoolConfig {
+            pair_addr: addr_validate_to_lower(deps.api, &pair_addr)?,
+            lp_token_addr: addr_validate_to_lower(deps.api, &lp_token_addr)?,
+            reward_contract_addr: addr_validate_to_lower(deps.api, &reward_contract_addr)?,
+            reward_token_addr: addr_validate_to_lower(deps.api, &reward_token_addr)?,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "register_pool"),
+        ("lp_token", lp_token_addr.as_str()),
+    ]))
+}
+
+/// # Description
+/// Refreshes the SPEC lock ratio of the depositor from spectrum gov and recalculates its boost.
+/// Deposits and withdrawals recalculate the boost with the lock ratio of the last kick.
+/// Returns an [`ContractError`] on failure, otherwise returns the [`Response`] object
+/// with the specified submessages if the operation was successful.
+/// # Params
+/// * **deps** is the object of type [`DepsMut`].
+///
+/// * **env** is the object of type [`Env`].
+///
+/// * **account** is the object of type [`Addr`]. Sets the depositor to recalculate.
+///
+/// * **lp_token** is an [`Option`] of type [`Addr`]. Sets the pool of the deposit.
+///
+/// ## Executor
+/// Anyone can execute it
+fn kick(
+    mut deps: DepsMut,
+    env: Env,
+    account: Addr,
+    lp_token: Option<Addr>,
+) -> Result<Response, ContractError> {
+    let mut response = Response::new();
+    let cfg = CONFIG.load(deps.storage)?;
+    let spectrum_gov = cfg.spectrum_gov.ok_or(ContractError::BoostNotEnabled {})?;
+
+    let pool = load_pool(deps.storage, lp_token)?;
+
+    // rewards so far belong to the boosts before this kick
+    response.messages.extend(update_pool(deps.branch(), &env, &pool)?);
+    let mut pool_state = POOL_STATES
+        .may_load(deps.storage, &pool.lp_token_addr)?
+        .unwrap_or_default();
+
+    let key = (&pool.lp_token_addr, &account);
+    let mut user = USERS.may_load(deps.storage, key)?.unwrap_or_default();
+    user.settle(pool_state.reward_index);
+    user.lock_ratio = query_lock_ratio(deps.as_ref(), &spectrum_gov, &account)?;
+    user.update_boost(&mut pool_state)?;
+    save_user(deps.storage, key, &user)?;
+    POOL_STATES.save(deps.storage, &pool.lp_token_addr, &pool_state)?;
+
+    Ok(response.add_attributes(vec![
+        ("action", "kick"),
+        ("account", account.as_str()),
+        ("boost_amount", user.boost_amount.to_string().as_str()),
+    ]))
+}
+
+/// # Description
+/// Queries the share of the account in all SPEC locked in spectrum gov,
+/// SPEC in lock pools is weighted by lock days.
+fn query_lock_ratio(deps: Deps, spectrum_gov: &Addr, account: &Addr) -> StdResult<Decimal> {
+    let gov_state: GovStateInfo =
+        deps.querier.query_wasm_smart(spectrum_gov, &GovQueryMsg::state {})?;
+    let total_lock = gov_state.pools.iter().fold(Uint128::zero(), |sum, pool| {
+        sum + pool.total_balance.multiply_ratio(pool.days, 1u64)
+    });
+    if total_lock.is_zero() {
+        return Ok(Decimal::zero());
+    }
+
+    let staked: GovBalanceResponse = deps.querier.query_wasm_smart(
+        spectrum_gov,
+        &GovQueryMsg::balance {
+            address: account.to_string(),
+        },
+    )?;
+    let lock = staked.pools.iter().fold(Uint128::zero(), |sum, pool| {
+        sum + pool.balance.multiply_ratio(pool.days, 1u64)
+    });
+    Ok(Decimal::from_ratio(lock, total_lock))
+}
+
 /// # Description
 /// Handle the callbacks describes in the [`CallbackMsg`]. Returns an [`ContractError`] on failure, otherwise returns the [`Response`]
 /// object with the specified submessages if the operation was successful.
@@ -294,8 +628,9 @@ pub fn handle_callback(
     match msg {
         CallbackMsg::TransferLpTokensAfterWithdraw {
             account,
+            lp_token,
             prev_lp_balance,
-        } => transfer_lp_tokens_after_withdraw(deps, env, account, prev_lp_balance),
+        } => transfer_lp_tokens_after_withdraw(deps, env, account, lp_token, prev_lp_balance),
     }
 }
 
@@ -309,19 +644,20 @@ pub fn handle_callback(
 ///
 /// * **account** is the object of type [`MessageInfo`]. Sets the recipient of withdrawal.
 ///
+/// * **lp_token** is the object of type [`Addr`]. Sets the liquidity pool token to transfer.
+///
 /// * **prev_lp_balance** is the object of type [`CallbackMsg`]. Sets the previous balance for
-/// calculating the withdrawal amount.
+///   calculating the withdrawal amount.
 pub fn transfer_lp_tokens_after_withdraw(
     deps: DepsMut,
     env: Env,
     account: Addr,
+    lp_token: Addr,
     prev_lp_balance: Uint128,
 ) -> Result<Response, ContractError> {
-    let cfg = CONFIG.load(deps.storage)?;
-
     let amount = {
         let res: BalanceResponse = deps.querier.query_wasm_smart(
-            &cfg.lp_token_addr,
+            &lp_token,
             &Cw20QueryMsg::Balance {
                 address: env.contract.address.to_string(),
             },
@@ -330,7 +666,7 @@ pub fn transfer_lp_tokens_after_withdraw(
     };
 
     Ok(Response::new().add_message(WasmMsg::Execute {
-        contract_addr: cfg.lp_token_addr.to_string(),
+        contract_addr: lp_token.to_string(),
         funds: vec![],
         msg: to_binary(&Cw20ExecuteMsg::Transfer {
             recipient: account.to_string(),
@@ -339,6 +675,44 @@ pub fn transfer_lp_tokens_after_withdraw(
     }))
 }
 
+/// # Description
+/// Queries the reward info of the pool in the reward contract.
+fn query_reward_info(
+    deps: Deps,
+    env: &Env,
+    pool: &PoolConfig,
+) -> StdResult<SpecRewardInfoResponseItem> {
+    let res: SpecRewardInfoResponse = deps.querier.query_wasm_smart(
+        &pool.reward_contract_addr,
+        &SpecQueryMsg::reward_info {
+            staker_addr: env.contract.address.to_string(),
+            asset_token: Some(pool.pair_addr.to_string()),
+        },
+    )?;
+    Ok(res
+        .reward_infos
+        .into_iter()
+        .next()
+        .unwrap_or(SpecRewardInfoResponseItem {
+            asset_token: pool.pair_addr.to_string(),
+            bond_amount: Uint128::zero(),
+            pending_spec_reward: Uint128::zero(),
+            spec_share: Uint128::zero(),
+            spec_share_index: Decimal::zero(),
+            emission_rewards: vec![],
+        }))
+}
+
+/// # Description
+/// Loads the pool for queries, see [`load_pool`].
+fn query_pool(deps: Deps, lp_token: Option<String>) -> StdResult<PoolConfig> {
+    let lp_token = match lp_token {
+        Some(lp_token) => Some(addr_validate_to_lower(deps.api, &lp_token)?),
+        None => None,
+    };
+    load_pool(deps.storage, lp_token).map_err(|err| StdError::generic_err(err.to_string()))
+}
+
 /// # Description
 /// Describes all query messages.
 /// # Params
@@ -349,85 +723,154 @@ pub fn transfer_lp_tokens_after_withdraw(
 /// * **msg** is the object of type [`QueryMsg`].
 ///
 /// ## Queries
-/// * **QueryMsg::Deposit {}** returns the deposit amount.
+/// * **QueryMsg::Config {}** returns the config with all registered pools.
+///
+/// * **QueryMsg::Deposit { lp_token }** returns the deposit amount of the pool.
 ///
-/// * **QueryMsg::Reward {}** returns the balance of reward token.
+/// * **QueryMsg::Reward { lp_token }** returns the reward amount held for the pool.
 ///
-/// * **QueryMsg::PendingToken {}** returns the pending rewards.
+/// * **QueryMsg::PendingToken { lp_token }** returns the pending rewards of the pool.
 ///
-/// * **QueryMsg::RewardInfo {}** returns the reward token contract address.
+/// * **QueryMsg::RewardInfo { lp_token }** returns the reward token contract address of the pool.
+///
+/// * **QueryMsg::UserInfo { lp_token, account }** returns the deposit and pending rewards of the depositor.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    let cfg = CONFIG.load(deps.storage)?;
     match msg {
-        QueryMsg::Config {} => to_binary(&ConfigResponse {
-            generator_contract_addr: cfg.generator_contract_addr.to_string(),
-            pair_addr: cfg.pair_addr.to_string(),
-            lp_token_addr: cfg.lp_token_addr.to_string(),
-            reward_contract_addr: cfg.reward_contract_addr.to_string(),
-            reward_token_addr: cfg.reward_token_addr.to_string(),
-        }),
-        QueryMsg::Deposit {} => {
-            let res: StdResult<SpecRewardInfoResponse> = deps.querier.query_wasm_smart(
-                cfg.reward_contract_addr,
-                &SpecQueryMsg::reward_info {
-                    staker_addr: env.contract.address.to_string(),
-                    asset_token: Some(cfg.pair_addr.to_string()),
-                },
-            );
-            let reward_infos = res?.reward_infos;
-            let deposit_amount = if !reward_infos.is_empty() {
-                reward_infos[0].bond_amount
-            } else {
-                Uint128::zero()
-            };
-
-            to_binary(&deposit_amount)
+        QueryMsg::Config {} => {
+            let cfg = CONFIG.load(deps.storage)?;
+            to_binary(&ConfigResponse {
+                generator_contract_addr: cfg.generator_contract_addr.to_string(),
+                owner: cfg.owner.to_string(),
+                spectrum_gov: cfg.spectrum_gov.map(|it| it.to_string()),
+                pools: load_pools(deps.storage)?
+                    .into_iter()
+                    .map(|pool| PoolResponse {
+                        pair_addr: pool.pair_addr.to_string(),
+                        lp_token_addr: pool.lp_token_addr.to_string(),
+                        reward_contract_addr: pool.reward_contract_addr.to_string(),
+                        reward_token_addr: pool.reward_token_addr.to_string(),
+                    })
+                    .collect(),
+            })
         }
-        QueryMsg::Reward {} => {
-            let res: Result<BalanceResponse, StdError> = deps.querier.query_wasm_smart(
-                cfg.reward_token_addr,
-                &Cw20QueryMsg::Balance {
-                    address: env.contract.address.into_string(),
-                },
-            );
-            let reward_amount = res?.balance;
+        QueryMsg::Deposit { lp_token } => {
+            let pool = query_pool(deps, lp_token)?;
+            to_binary(&query_reward_info(deps, &env, &pool)?.bond_amount)
+        }
+        QueryMsg::Reward { lp_token } => {
+            let pool = query_pool(deps, lp_token)?;
+            let reward_amount = POOL_REWARDS
+                .may_load(deps.storage, &pool.lp_token_addr)?
+                .unwrap_or_default();
 
             to_binary(&reward_amount)
         }
-        QueryMsg::PendingToken {} => {
-            let res: StdResult<SpecRewardInfoResponse> = deps.querier.query_wasm_smart(
-                cfg.reward_contract_addr,
-                &SpecQueryMsg::reward_info {
-                    staker_addr: env.contract.address.to_string(),
-                    asset_token: Some(cfg.pair_addr.to_string()),
-                },
-            );
-            let reward_infos = res?.reward_infos;
-            let pending_reward = if !reward_infos.is_empty() {
-                reward_infos[0].pending_spec_reward
-            } else {
-                Uint128::zero()
-            };
-
-            to_binary(&pending_reward)
+        QueryMsg::PendingToken { lp_token } => {
+            let pool = query_pool(deps, lp_token)?;
+            to_binary(&query_reward_info(deps, &env, &pool)?.pending_spec_reward)
+        }
+        QueryMsg::RewardInfo { lp_token } => {
+            let pool = query_pool(deps, lp_token)?;
+            to_binary(&pool.reward_token_addr)
         }
-        QueryMsg::RewardInfo {} => {
-            let config = CONFIG.load(deps.storage)?;
-            to_binary(&config.reward_token_addr)
+        QueryMsg::UserInfo { lp_token, account } => {
+            let pool = query_pool(deps, lp_token)?;
+            to_binary(&query_user_info(deps, &env, &pool, account)?)
         }
     }
 }
 
+/// # Description
+/// Returns the deposit and rewards of the depositor, including its share of
+/// rewards still pending in the reward contract.
+fn query_user_info(
+    deps: Deps,
+    env: &Env,
+    pool: &PoolConfig,
+    account: String,
+) -> StdResult<UserInfoResponse> {
+    let account = addr_validate_to_lower(deps.api, &account)?;
+    let mut user = USERS
+        .may_load(deps.storage, (&pool.lp_token_addr, &account))?
+        .unwrap_or_default();
+    let mut pool_state = POOL_STATES
+        .may_load(deps.storage, &pool.lp_token_addr)?
+        .unwrap_or_default();
+
+    if !pool_state.total_deposit.is_zero() {
+        let pending_reward = query_reward_info(deps, env, pool)?.pending_spec_reward;
+        pool_state.reward_index = pool_state.reward_index
+            + Decimal::from_ratio(pending_reward, pool_state.total_deposit + pool_state.total_boost);
+    }
+    user.settle(pool_state.reward_index);
+
+    Ok(UserInfoResponse {
+        amount: user.amount,
+        pending_reward: user.pending_reward,
+        boost_amount: user.boost_amount,
+    })
+}
+
 /// ## Description
-/// Used for migration of contract. Returns the default object of type [`Response`].
+/// Used for migration of contract. Moves the single pool config to the pool list,
+/// and credits reward tokens held by the proxy to that pool. The bonded amount is held by the generator.
 /// ## Params
-/// * **_deps** is the object of type [`DepsMut`].
+/// * **deps** is the object of type [`DepsMut`].
 ///
-/// * **_env** is the object of type [`Env`].
+/// * **env** is the object of type [`Env`].
 ///
-/// * **_msg** is the object of type [`MigrateMsg`].
+/// * **msg** is the object of type [`MigrateMsg`].
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let legacy = LEGACY_CONFIG.load(deps.storage)?;
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            generator_contract_addr: legacy.generator_contract_addr.clone(),
+            owner: addr_validate_to_lower(deps.api, &msg.owner)?,
+            spectrum_gov: match msg.spectrum_gov {
+                Some(spectrum_gov) => Some(addr_validate_to_lower(deps.api, &spectrum_gov)?),
+                None => None,
+            },
+        },
+    )?;
+
+    let rewards: BalanceResponse = deps.querier.query_wasm_smart(
+        &legacy.reward_token_addr,
+        &Cw20QueryMsg::Balance {
+            address: env.contract.address.to_string(),
+        },
+    )?;
+    let pool = PoolConfig {
+        pair_addr: legacy.pair_addr,
+        lp_token_addr: legacy.lp_token_addr,
+        reward_contract_addr: legacy.reward_contract_addr,
+        reward_token_addr: legacy.reward_token_addr,
+    };
+    let bond_amount = query_reward_info(deps.as_ref(), &env, &pool)?.bond_amount;
+    let lp_token = pool.lp_token_addr.clone();
+    save_pool(deps.storage, pool)?;
+    POOL_REWARDS.save(deps.storage, &lp_token, &rewards.balance)?;
+    POOL_STATES.save(
+        deps.storage,
+        &lp_token,
+        &PoolState {
+            total_deposit: bond_amount,
+            reward_index: Decimal::zero(),
+            total_boost: Uint128::zero(),
+        },
+    )?;
+    if !bond_amount.is_zero() {
+        USERS.save(
+            deps.storage,
+            (&lp_token, &legacy.generator_contract_addr),
+            &UserInfo {
+                amount: bond_amount,
+                ..UserInfo::default()
+            },
+        )?;
+    }
+
     Ok(Response::default())
 }
@@ -13,4 +13,22 @@ pub enum ContractError {
 
     #[error("Incorrect CW20 hook message variant!")]
     IncorrectCw20HookMessageVariant {},
+
+    #[error("Pool not found")]
+    PoolNotFound {},
+
+    #[error("Pool already registered")]
+    PoolAlreadyRegistered {},
+
+    #[error("lp_token must be specified when several pools are registered")]
+    LpTokenRequired {},
+
+    #[error("Insufficient pool rewards")]
+    InsufficientPoolRewards {},
+
+    #[error("Cannot withdraw more than deposit amount")]
+    InsufficientDeposit {},
+
+    #[error("spectrum_gov must be set to boost rewards")]
+    BoostNotEnabled {},
 }
@@ -1,8 +1,8 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Addr;
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Decimal, StdResult, Uint128};
+use cw_storage_plus::{Item, Map};
 
 /// ## Description
 /// This structure describes the main controls configs of generator_proxy_to_mirror contract.
@@ -10,7 +10,38 @@ use cw_storage_plus::Item;
 pub struct Config {
     /// the generator contract address
     pub generator_contract_addr: Addr,
-    /// the pair contract address
+    /// the owner who can register pools
+    pub owner: Addr,
+    /// the spectrum gov contract, SPEC locked there boosts rewards of depositors
+    #[serde(default)]
+    pub spectrum_gov: Option<Addr>,
+}
+
+/// ## Description
+/// Stores config at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// ## Description
+/// This structure describes the single pool config before pools could be registered.
+/// It is only read during migration.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
+    pub generator_contract_addr: Addr,
+    pub pair_addr: Addr,
+    pub lp_token_addr: Addr,
+    pub reward_contract_addr: Addr,
+    pub reward_token_addr: Addr,
+}
+
+/// ## Description
+/// Stores legacy config at the same key as [`CONFIG`]
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
+
+/// ## Description
+/// This structure describes a liquidity pool served by the proxy.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolConfig {
+    /// the pair contract address, used as asset token in the reward contract
     pub pair_addr: Addr,
     /// the contract address for liquidity pool token
     pub lp_token_addr: Addr,
@@ -21,5 +52,78 @@ pub struct Config {
 }
 
 /// ## Description
-/// Stores config at the given key
-pub const CONFIG: Item<Config> = Item::new("config");
+/// Stores pool configs by liquidity pool token address
+pub const POOLS: Map<&Addr, PoolConfig> = Map::new("pools");
+
+/// ## Description
+/// Stores reward amount claimed for a pool and not yet sent, by liquidity pool token address
+pub const POOL_REWARDS: Map<&Addr, Uint128> = Map::new("pool_rewards");
+
+/// ## Description
+/// This structure describes the reward accounting of a pool.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PoolState {
+    /// the total deposit amount of all depositors
+    pub total_deposit: Uint128,
+    /// the accumulated rewards per boosted deposit
+    pub reward_index: Decimal,
+    /// the total boost amount of all depositors
+    #[serde(default)]
+    pub total_boost: Uint128,
+}
+
+/// ## Description
+/// Stores pool reward accounting by liquidity pool token address
+pub const POOL_STATES: Map<&Addr, PoolState> = Map::new("pool_states");
+
+/// ## Description
+/// This structure describes the deposit of a depositor in a pool.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct UserInfo {
+    /// the deposit amount
+    pub amount: Uint128,
+    /// the rewards already accounted for the deposit amount
+    pub reward_debt: Uint128,
+    /// the rewards accrued and not yet sent
+    pub pending_reward: Uint128,
+    /// the extra deposit weight from SPEC locked in spectrum gov
+    #[serde(default)]
+    pub boost_amount: Uint128,
+    /// the share of all SPEC lock weight in spectrum gov, as of the last kick
+    #[serde(default)]
+    pub lock_ratio: Decimal,
+}
+
+impl UserInfo {
+    /// ## Description
+    /// Returns the deposit weight for rewards, the deposit amount plus the boost amount.
+    pub fn weight(&self) -> Uint128 {
+        self.amount + self.boost_amount
+    }
+
+    /// ## Description
+    /// Accrues rewards since the last settlement, must be called before the deposit weight changes.
+    pub fn settle(&mut self, reward_index: Decimal) {
+        let accumulated = self.weight() * reward_index;
+        self.pending_reward += accumulated.checked_sub(self.reward_debt).unwrap_or_default();
+        self.reward_debt = accumulated;
+    }
+
+    /// ## Description
+    /// Recalculates the boost in veCRV style, boost = min(1.5 * total deposit * lock ratio, 1.5 * deposit),
+    /// so the deposit weight is capped at 2.5x. Must be called after [`UserInfo::settle`] and
+    /// after the deposit amounts change.
+    pub fn update_boost(&mut self, pool_state: &mut PoolState) -> StdResult<()> {
+        let max_boost = Decimal::percent(150);
+        let boost_amount = (pool_state.total_deposit * self.lock_ratio * max_boost)
+            .min(self.amount * max_boost);
+        pool_state.total_boost = pool_state.total_boost.checked_sub(self.boost_amount)? + boost_amount;
+        self.boost_amount = boost_amount;
+        self.reward_debt = self.weight() * pool_state.reward_index;
+        Ok(())
+    }
+}
+
+/// ## Description
+/// Stores depositor info by liquidity pool token address and depositor address
+pub const USERS: Map<(&Addr, &Addr), UserInfo> = Map::new("users");
@@ -1,10 +1,13 @@
-use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use spectrum_protocol::gov::{
+    BalancePoolInfo, BalanceResponse as GovBalanceResponse, QueryMsg as GovQueryMsg, StateInfo,
+    StatePoolInfo,
+};
 use spectrum_protocol::spec_farm::{
-    RewardInfoResponse
-};use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+    QueryMsg as SpecQueryMsg, RewardInfoResponse, RewardInfoResponseItem,
+};
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
     from_binary, from_slice, to_binary, Coin, ContractResult, Decimal, Empty, OwnedDeps, Querier,
     QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
@@ -30,6 +33,8 @@ pub struct WasmMockQuerier {
     base: MockQuerier<Empty>,
     token_querier: TokenQuerier,
     reward_querier: RewardQuerier,
+    // spectrum gov lock pools by address: (days, balance)
+    gov_locks: HashMap<String, Vec<(u64, Uint128)>>,
 }
 
 #[derive(Clone, Default)]
@@ -63,26 +68,8 @@ pub(crate) fn balances_to_map(
 
 #[derive(Clone, Default)]
 pub struct RewardQuerier {
-    pending_reward: Uint128,
-    deposit_amount: Uint128,
-}
-
-impl RewardQuerier {
-    pub fn new(pending_reward: Uint128, deposit_amount: Uint128) -> Self {
-        RewardQuerier {
-            pending_reward,
-            deposit_amount,
-        }
-    }
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-#[serde(rename_all = "snake_case")]
-pub enum QueryMsg {
-    StakerInfo {
-        staker: String,
-        block_height: Option<u64>,
-    },
+    // asset token -> (pending reward, deposit amount)
+    reward_infos: HashMap<String, (Uint128, Uint128)>,
 }
 
 impl Querier for WasmMockQuerier {
@@ -104,18 +91,33 @@ impl Querier for WasmMockQuerier {
 impl WasmMockQuerier {
     pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
         match &request {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) if contract_addr == "gov0000" => {
+                self.handle_gov_query(from_binary(msg).unwrap())
+            }
             QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => match from_binary(msg) {
-                Ok(QueryMsg::StakerInfo {
-                    staker: _,
-                    block_height: _,
-                }) => SystemResult::Ok(ContractResult::from(to_binary(&RewardInfoResponse {
-                    staker: "generator0000".to_string(),
-                    reward_index: Decimal::zero(),
-                    bond_amount: self.reward_querier.deposit_amount,
-                    pending_reward: self.reward_querier.pending_reward,
-                    staker_addr: todo!(),
-                    reward_infos: todo!(),
-                }))),
+                Ok(SpecQueryMsg::reward_info {
+                    staker_addr,
+                    asset_token,
+                }) => {
+                    let reward_infos = self
+                        .reward_querier
+                        .reward_infos
+                        .iter()
+                        .filter(|(token, _)| asset_token.is_none() || asset_token.as_ref() == Some(*token))
+                        .map(|(token, (pending_reward, deposit_amount))| RewardInfoResponseItem {
+                            asset_token: token.clone(),
+                            bond_amount: *deposit_amount,
+                            pending_spec_reward: *pending_reward,
+                            spec_share: Uint128::zero(),
+                            spec_share_index: Decimal::zero(),
+                            emission_rewards: vec![],
+                        })
+                        .collect();
+                    SystemResult::Ok(ContractResult::from(to_binary(&RewardInfoResponse {
+                        staker_addr,
+                        reward_infos,
+                    })))
+                }
                 _ => match from_binary(msg).unwrap() {
                     Cw20QueryMsg::Balance { address } => {
                         let balances: &HashMap<String, Uint128> =
@@ -156,12 +158,74 @@ impl WasmMockQuerier {
     }
 }
 
+impl WasmMockQuerier {
+    fn handle_gov_query(&self, msg: GovQueryMsg) -> QuerierResult {
+        match msg {
+            GovQueryMsg::balance { address } => {
+                let pools = self
+                    .gov_locks
+                    .get(&address)
+                    .map(|locks| {
+                        locks
+                            .iter()
+                            .map(|(days, balance)| BalancePoolInfo {
+                                days: *days,
+                                share: *balance,
+                                balance: *balance,
+                                unlock: 0u64,
+                                aust_index: Decimal::zero(),
+                                pending_aust: Uint128::zero(),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                SystemResult::Ok(ContractResult::from(to_binary(&GovBalanceResponse {
+                    balance: Uint128::zero(),
+                    share: Uint128::zero(),
+                    locked_balance: vec![],
+                    pools,
+                })))
+            }
+            GovQueryMsg::state {} => {
+                let mut pools: Vec<StatePoolInfo> = vec![];
+                for (days, balance) in self.gov_locks.values().flatten() {
+                    match pools.iter_mut().find(|it| it.days == *days) {
+                        Some(pool) => pool.total_balance += *balance,
+                        None => pools.push(StatePoolInfo {
+                            days: *days,
+                            total_share: *balance,
+                            total_balance: *balance,
+                            weight: 0u32,
+                            aust_index: Decimal::zero(),
+                        }),
+                    }
+                }
+                SystemResult::Ok(ContractResult::from(to_binary(&StateInfo {
+                    poll_count: 0u64,
+                    poll_deposit: Uint128::zero(),
+                    last_mint: 0u64,
+                    total_weight: 0u32,
+                    total_staked: Uint128::zero(),
+                    prev_balance: Uint128::zero(),
+                    pools,
+                    prev_aust_balance: Uint128::zero(),
+                    vault_balances: Uint128::zero(),
+                    vault_share_multiplier: Decimal::zero(),
+                    pool_weight: 0u32,
+                })))
+            }
+            _ => panic!("Query Not Mocked"),
+        }
+    }
+}
+
 impl WasmMockQuerier {
     pub fn new(base: MockQuerier<Empty>) -> Self {
         WasmMockQuerier {
             base,
             token_querier: TokenQuerier::default(),
             reward_querier: RewardQuerier::default(),
+            gov_locks: HashMap::new(),
         }
     }
 
@@ -169,7 +233,21 @@ impl WasmMockQuerier {
         self.token_querier = TokenQuerier::new(balances);
     }
 
-    pub fn with_reward_info(&mut self, pending_reward: Uint128, deposit_amount: Uint128) {
-        self.reward_querier = RewardQuerier::new(pending_reward, deposit_amount);
+    pub fn with_reward_info(
+        &mut self,
+        asset_token: &str,
+        pending_reward: Uint128,
+        deposit_amount: Uint128,
+    ) {
+        self.reward_querier
+            .reward_infos
+            .insert(asset_token.to_string(), (pending_reward, deposit_amount));
+    }
+
+    pub fn with_gov_locks(&mut self, locks: &[(&str, &[(u64, Uint128)])]) {
+        self.gov_locks = locks
+            .iter()
+            .map(|(addr, pools)| (addr.to_string(), pools.to_vec()))
+            .collect();
     }
 }
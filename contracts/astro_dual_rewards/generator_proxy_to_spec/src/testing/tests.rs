@@ -1,60 +1,74 @@
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{execute, instantiate, migrate, query};
 use crate::error::ContractError;
-use crate::state::{Config, CONFIG};
-use crate::testing::mock_querier::mock_dependencies;
+use crate::state::{LegacyConfig, CONFIG, LEGACY_CONFIG, POOLS};
+use crate::testing::mock_querier::{mock_dependencies, WasmMockQuerier};
 use astroport::generator_proxy::{
-    CallbackMsg, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    CallbackMsg, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg,
+    PoolResponse, QueryMsg, UserInfoResponse,
 };
-use spectrum_protocol::spec_farm::{
-    Cw20HookMsg as SpecCw20HookMsg, ExecuteMsg as SpecExecuteMsg, RewardInfoResponse as SpecRewardInfoResponse, QueryMsg as SpecQueryMsg
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, CosmosMsg, OwnedDeps, Response, SubMsg, Uint128, WasmMsg,
 };
-use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{from_binary, to_binary, Addr, CosmosMsg, SubMsg, Uint128, WasmMsg};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use spectrum_protocol::spec_farm::{
+    Cw20HookMsg as SpecCw20HookMsg, ExecuteMsg as SpecExecuteMsg,
+};
 
-#[test]
-fn test_proper_initialization() {
-    let mut deps = mock_dependencies(&[]);
-
-    let msg = InstantiateMsg {
+fn default_instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
         generator_contract_addr: "generator0000".to_string(),
         pair_addr: "pair0000".to_string(),
-        lp_token_addr: "ancust0000".to_string(),
+        lp_token_addr: "lptoken0000".to_string(),
         reward_contract_addr: "reward0000".to_string(),
-        reward_token_addr: "anc0000".to_string(),
-    };
+        reward_token_addr: "spec0000".to_string(),
+        owner: None,
+        spectrum_gov: Some("gov0000".to_string()),
+    }
+}
+
+#[test]
+fn test_proper_initialization() {
+    let mut deps = mock_dependencies(&[]);
 
     let info = mock_info("addr0000", &[]);
-    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let _res = instantiate(deps.as_mut(), mock_env(), info, default_instantiate_msg()).unwrap();
 
-    let config: Config = CONFIG.load(deps.as_ref().storage).unwrap();
+    let config = CONFIG.load(deps.as_ref().storage).unwrap();
     assert_eq!("generator0000", config.generator_contract_addr.as_str());
-    assert_eq!("pair0000", config.pair_addr.as_str());
-    assert_eq!("ancust0000", config.lp_token_addr.as_str());
-    assert_eq!("reward0000", config.reward_contract_addr.as_str());
-    assert_eq!("anc0000", config.reward_token_addr.as_str());
+    assert_eq!("addr0000", config.owner.as_str());
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        config,
+        ConfigResponse {
+            generator_contract_addr: "generator0000".to_string(),
+            owner: "addr0000".to_string(),
+            spectrum_gov: Some("gov0000".to_string()),
+            pools: vec![PoolResponse {
+                pair_addr: "pair0000".to_string(),
+                lp_token_addr: "lptoken0000".to_string(),
+                reward_contract_addr: "reward0000".to_string(),
+                reward_token_addr: "spec0000".to_string(),
+            }],
+        }
+    );
 }
 
 #[test]
 fn test_deposit() {
     let mut deps = mock_dependencies(&[]);
 
-    let msg = InstantiateMsg {
-        generator_contract_addr: "generator0000".to_string(),
-        pair_addr: "pair0000".to_string(),
-        lp_token_addr: "ancust0000".to_string(),
-        reward_contract_addr: "reward0000".to_string(),
-        reward_token_addr: "anc0000".to_string(),
-    };
-
     let info = mock_info("addr0000", &[]);
-    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    let _res =
+        instantiate(deps.as_mut(), mock_env(), info.clone(), default_instantiate_msg()).unwrap();
 
     // deposit fails when not sent by LP token
     let deposit_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "generator0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Deposit {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Deposit { account: None }).unwrap(),
     });
 
     let res = execute(deps.as_mut(), mock_env(), info, deposit_msg).unwrap_err();
@@ -64,11 +78,11 @@ fn test_deposit() {
     };
 
     // deposit fails when cw20 sender is not generator
-    let info = mock_info("ancust0000", &[]);
+    let info = mock_info("lptoken0000", &[]);
     let deposit_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Deposit {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Deposit { account: None }).unwrap(),
     });
 
     let res = execute(deps.as_mut(), mock_env(), info, deposit_msg).unwrap_err();
@@ -78,35 +92,49 @@ fn test_deposit() {
     };
 
     // successfull deposit
-    let info = mock_info("ancust0000", &[]);
+    let info = mock_info("lptoken0000", &[]);
     let deposit_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "generator0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Deposit {}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Deposit { account: None }).unwrap(),
     });
     let res = execute(deps.as_mut(), mock_env(), info, deposit_msg).unwrap();
 
     assert_eq!(
         res.messages,
         vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: "ancust0000".to_string(),
+            contract_addr: "lptoken0000".to_string(),
             funds: vec![],
             msg: to_binary(&Cw20ExecuteMsg::Send {
                 contract: "reward0000".to_string(),
                 amount: Uint128::from(100u128),
-                msg: to_binary(&AncCw20HookMsg::Bond {}).unwrap(),
+                msg: to_binary(&SpecCw20HookMsg::bond {
+                    asset_token: "pair0000".to_string(),
+                    staker_addr: None,
+                })
+                .unwrap(),
             })
             .unwrap(),
         }))]
     );
 
     deps.querier
-        .with_reward_info(Uint128::from(5u128), Uint128::from(100u128));
-    let res = query(deps.as_ref(), mock_env(), QueryMsg::Deposit {}).unwrap();
+        .with_reward_info("pair0000", Uint128::from(5u128), Uint128::from(100u128));
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Deposit { lp_token: None },
+    )
+    .unwrap();
     let query_res: Uint128 = from_binary(&res).unwrap();
     assert_eq!(query_res, Uint128::from(100u128));
 
-    let res = query(deps.as_ref(), mock_env(), QueryMsg::PendingToken {}).unwrap();
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PendingToken { lp_token: None },
+    )
+    .unwrap();
     let query_res: Uint128 = from_binary(&res).unwrap();
     assert_eq!(query_res, Uint128::from(5u128));
 }
@@ -115,18 +143,14 @@ fn test_deposit() {
 fn test_update_rewards() {
     let mut deps = mock_dependencies(&[]);
 
-    let msg = InstantiateMsg {
-        generator_contract_addr: "generator0000".to_string(),
-        pair_addr: "pair0000".to_string(),
-        lp_token_addr: "ancust0000".to_string(),
-        reward_contract_addr: "reward0000".to_string(),
-        reward_token_addr: "anc0000".to_string(),
-    };
-
     let info = mock_info("addr0000", &[]);
-    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    let _res =
+        instantiate(deps.as_mut(), mock_env(), info.clone(), default_instantiate_msg()).unwrap();
+
+    deps.querier
+        .with_reward_info("pair0000", Uint128::from(5u128), Uint128::from(100u128));
 
-    // claim rewards from ANC staking contract
+    // claim rewards from SPEC farm
     let claim_rewards_msg = ExecuteMsg::UpdateRewards {};
     let res = execute(deps.as_mut(), mock_env(), info, claim_rewards_msg).unwrap();
 
@@ -135,24 +159,29 @@ fn test_update_rewards() {
         vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: "reward0000".to_string(),
             funds: vec![],
-            msg: to_binary(&AncExecuteMsg::Withdraw {}).unwrap(),
+            msg: to_binary(&SpecExecuteMsg::withdraw {
+                asset_token: Some("pair0000".to_string()),
+                spec_amount: Some(Uint128::from(5u128)),
+            })
+            .unwrap(),
         }))]
     );
 
-    deps.querier.with_token_balances(&[(
-        &"anc0000".to_string(),
-        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(5u128))],
-    )]);
     deps.querier
-        .with_reward_info(Uint128::from(0u128), Uint128::from(100u128));
+        .with_reward_info("pair0000", Uint128::from(0u128), Uint128::from(100u128));
 
-    // token balance on contract increases from claim
-    let res = query(deps.as_ref(), mock_env(), QueryMsg::Reward {}).unwrap();
+    // rewards claimed are credited to the pool
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Reward { lp_token: None }).unwrap();
     let query_res: Uint128 = from_binary(&res).unwrap();
     assert_eq!(query_res, Uint128::from(5u128));
 
     // no pending tokens
-    let res = query(deps.as_ref(), mock_env(), QueryMsg::PendingToken {}).unwrap();
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PendingToken { lp_token: None },
+    )
+    .unwrap();
     let query_res: Uint128 = from_binary(&res).unwrap();
     assert_eq!(query_res, Uint128::from(0u128));
 }
@@ -161,22 +190,20 @@ fn test_update_rewards() {
 fn test_send_rewards() {
     let mut deps = mock_dependencies(&[]);
 
-    let msg = InstantiateMsg {
-        generator_contract_addr: "generator0000".to_string(),
-        pair_addr: "pair0000".to_string(),
-        lp_token_addr: "ancust0000".to_string(),
-        reward_contract_addr: "reward0000".to_string(),
-        reward_token_addr: "anc0000".to_string(),
-    };
-
     let info = mock_info("addr0000", &[]);
-    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    let _res =
+        instantiate(deps.as_mut(), mock_env(), info.clone(), default_instantiate_msg()).unwrap();
+
+    deps.querier
+        .with_reward_info("pair0000", Uint128::from(100u128), Uint128::from(100u128));
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::UpdateRewards {}).unwrap();
 
     // transfer reward token to user
     // fails when called from unauthorized
     let send_rewards_msg = ExecuteMsg::SendRewards {
         account: Addr::unchecked("addr0000"),
         amount: Uint128::new(100),
+        lp_token: None,
     };
     let res = execute(deps.as_mut(), mock_env(), info, send_rewards_msg.clone()).unwrap_err();
     match res {
@@ -186,12 +213,18 @@ fn test_send_rewards() {
 
     // succeeds when coming from generator
     let generator_info = mock_info("generator0000", &[]);
-    let res = execute(deps.as_mut(), mock_env(), generator_info, send_rewards_msg).unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        generator_info.clone(),
+        send_rewards_msg.clone(),
+    )
+    .unwrap();
 
     assert_eq!(
         res.messages,
         vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: "anc0000".to_string(),
+            contract_addr: "spec0000".to_string(),
             msg: to_binary(&Cw20ExecuteMsg::Transfer {
                 recipient: "addr0000".to_string(),
                 amount: Uint128::new(100),
@@ -200,30 +233,45 @@ fn test_send_rewards() {
             funds: vec![],
         }))]
     );
+
+    // pool rewards are spent
+    let res = execute(deps.as_mut(), mock_env(), generator_info, send_rewards_msg).unwrap_err();
+    assert!(matches!(res, ContractError::InsufficientPoolRewards {}));
 }
 
 #[test]
 fn test_withdraw() {
     let mut deps = mock_dependencies(&[]);
 
-    let msg = InstantiateMsg {
-        generator_contract_addr: "generator0000".to_string(),
-        pair_addr: "pair0000".to_string(),
-        lp_token_addr: "ancust0000".to_string(),
-        reward_contract_addr: "reward0000".to_string(),
-        reward_token_addr: "anc0000".to_string(),
-    };
-
     let info = mock_info("addr0000", &[]);
-    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    let _res =
+        instantiate(deps.as_mut(), mock_env(), info.clone(), default_instantiate_msg()).unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &"lptoken0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(10u128))],
+    )]);
+    let deposit_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "generator0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Deposit { account: None }).unwrap(),
+    });
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("lptoken0000", &[]),
+        deposit_msg,
+    )
+    .unwrap();
 
     // unbond and send lp tokens to user
     // fails when called from unauthorized
-    let withrdaw_msg = ExecuteMsg::Withdraw {
+    let withdraw_msg = ExecuteMsg::Withdraw {
         account: Addr::unchecked("addr0000"),
         amount: Uint128::new(100),
+        lp_token: None,
     };
-    let res = execute(deps.as_mut(), mock_env(), info, withrdaw_msg.clone()).unwrap_err();
+    let res = execute(deps.as_mut(), mock_env(), info, withdraw_msg.clone()).unwrap_err();
     match res {
         ContractError::Unauthorized {} => {}
         _ => panic!("Must return unauthorized error"),
@@ -231,7 +279,7 @@ fn test_withdraw() {
 
     // succeeds when coming from generator
     let generator_info = mock_info("generator0000", &[]);
-    let res = execute(deps.as_mut(), mock_env(), generator_info, withrdaw_msg).unwrap();
+    let res = execute(deps.as_mut(), mock_env(), generator_info, withdraw_msg).unwrap();
 
     assert_eq!(
         res.messages,
@@ -239,95 +287,599 @@ fn test_withdraw() {
             SubMsg::new(WasmMsg::Execute {
                 contract_addr: "reward0000".to_string(),
                 funds: vec![],
-                msg: to_binary(&AncExecuteMsg::Unbond {
+                msg: to_binary(&SpecExecuteMsg::unbond {
+                    asset_token: "pair0000".to_string(),
                     amount: Uint128::new(100),
                 })
                 .unwrap(),
             }),
             SubMsg::new(WasmMsg::Execute {
-                contract_addr: "ancust0000".to_string(),
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
                 funds: vec![],
-                msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                    recipient: "addr0000".to_string(),
-                    amount: Uint128::new(100),
-                })
+                msg: to_binary(&ExecuteMsg::Callback(
+                    CallbackMsg::TransferLpTokensAfterWithdraw {
+                        account: Addr::unchecked("addr0000"),
+                        lp_token: Addr::unchecked("lptoken0000"),
+                        prev_lp_balance: Uint128::new(10),
+                    }
+                ))
                 .unwrap(),
             })
         ]
     );
+
+    // lp tokens unbonded are sent to user
+    deps.querier.with_token_balances(&[(
+        &"lptoken0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(110u128))],
+    )]);
+    let callback_msg = ExecuteMsg::Callback(CallbackMsg::TransferLpTokensAfterWithdraw {
+        account: Addr::unchecked("addr0000"),
+        lp_token: Addr::unchecked("lptoken0000"),
+        prev_lp_balance: Uint128::new(10),
+    });
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        callback_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "lptoken0000".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::new(100),
+            })
+            .unwrap(),
+        })]
+    );
 }
 
 #[test]
-fn test_emergency_withdraw() {
+fn test_multiple_pools() {
     let mut deps = mock_dependencies(&[]);
 
-    let msg = InstantiateMsg {
-        generator_contract_addr: "generator0000".to_string(),
-        pair_addr: "pair0000".to_string(),
-        lp_token_addr: "ancust0000".to_string(),
+    let info = mock_info("addr0000", &[]);
+    let _res =
+        instantiate(deps.as_mut(), mock_env(), info.clone(), default_instantiate_msg()).unwrap();
+
+    let register_msg = ExecuteMsg::RegisterPool {
+        pair_addr: "pair0001".to_string(),
+        lp_token_addr: "lptoken0001".to_string(),
         reward_contract_addr: "reward0000".to_string(),
-        reward_token_addr: "anc0000".to_string(),
+        reward_token_addr: "spec0000".to_string(),
     };
 
-    let info = mock_info("addr0000", &[]);
-    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    // only owner can register
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        register_msg.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(res, ContractError::Unauthorized {}));
 
-    // unbond and send lp tokens to user
-    // fails when called from unauthorized
-    let withrdaw_msg = ExecuteMsg::EmergencyWithdraw {
-        account: Addr::unchecked("addr0000"),
-        amount: Uint128::new(100),
-    };
-    let res = execute(deps.as_mut(), mock_env(), info, withrdaw_msg.clone()).unwrap_err();
-    match res {
-        ContractError::Unauthorized {} => {}
-        _ => panic!("Must return unauthorized error"),
-    };
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), register_msg.clone()).unwrap();
 
-    // succeeds when coming from generator
-    let generator_info = mock_info("generator0000", &[]);
-    let res = execute(deps.as_mut(), mock_env(), generator_info, withrdaw_msg).unwrap();
+    // cannot register twice
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), register_msg).unwrap_err();
+    assert!(matches!(res, ContractError::PoolAlreadyRegistered {}));
 
+    // deposit is routed by lp token
+    let deposit_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "generator0000".to_string(),
+        amount: Uint128::from(50u128),
+        msg: to_binary(&Cw20HookMsg::Deposit { account: None }).unwrap(),
+    });
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("lptoken0001", &[]),
+        deposit_msg,
+    )
+    .unwrap();
     assert_eq!(
         res.messages,
-        vec![
-            SubMsg::new(WasmMsg::Execute {
-                contract_addr: "reward0000".to_string(),
-                funds: vec![],
-                msg: to_binary(&AncExecuteMsg::Unbond {
-                    amount: Uint128::new(100),
-                })
-                .unwrap(),
-            }),
-            SubMsg::new(WasmMsg::Execute {
-                contract_addr: "ancust0000".to_string(),
-                funds: vec![],
-                msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                    recipient: "addr0000".to_string(),
-                    amount: Uint128::new(100),
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "lptoken0001".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "reward0000".to_string(),
+                amount: Uint128::from(50u128),
+                msg: to_binary(&SpecCw20HookMsg::bond {
+                    asset_token: "pair0001".to_string(),
+                    staker_addr: None,
                 })
                 .unwrap(),
             })
-        ]
+            .unwrap(),
+        }))]
     );
+
+    // lp token must be specified once several pools are registered
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Deposit { lp_token: None },
+    )
+    .unwrap_err();
+    assert_eq!(
+        res.to_string(),
+        "Generic error: lp_token must be specified when several pools are registered"
+    );
+
+    deps.querier
+        .with_reward_info("pair0000", Uint128::from(10u128), Uint128::from(100u128));
+    deps.querier
+        .with_reward_info("pair0001", Uint128::from(3u128), Uint128::from(50u128));
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Deposit {
+            lp_token: Some("lptoken0001".to_string()),
+        },
+    )
+    .unwrap();
+    let query_res: Uint128 = from_binary(&res).unwrap();
+    assert_eq!(query_res, Uint128::from(50u128));
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PendingToken {
+            lp_token: Some("lptoken0000".to_string()),
+        },
+    )
+    .unwrap();
+    let query_res: Uint128 = from_binary(&res).unwrap();
+    assert_eq!(query_res, Uint128::from(10u128));
+
+    // rewards are withdrawn and credited per pool
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::UpdateRewards {}).unwrap();
+    assert_eq!(res.messages.len(), 2);
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Reward {
+            lp_token: Some("lptoken0001".to_string()),
+        },
+    )
+    .unwrap();
+    let query_res: Uint128 = from_binary(&res).unwrap();
+    assert_eq!(query_res, Uint128::from(3u128));
+
+    // pool rewards cannot pay another pool
+    let generator_info = mock_info("generator0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        generator_info.clone(),
+        ExecuteMsg::SendRewards {
+            account: Addr::unchecked("addr0000"),
+            amount: Uint128::new(5),
+            lp_token: Some(Addr::unchecked("lptoken0001")),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(res, ContractError::InsufficientPoolRewards {}));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        generator_info.clone(),
+        ExecuteMsg::SendRewards {
+            account: Addr::unchecked("addr0000"),
+            amount: Uint128::new(5),
+            lp_token: Some(Addr::unchecked("lptoken0000")),
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+
+    // unknown pool
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        generator_info,
+        ExecuteMsg::EmergencyWithdraw {
+            account: Addr::unchecked("addr0000"),
+            amount: Uint128::new(5),
+            lp_token: Some(Addr::unchecked("lptoken0002")),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(res, ContractError::PoolNotFound {}));
 }
 
 #[test]
-fn test_query_reward_info() {
+fn test_migrate() {
     let mut deps = mock_dependencies(&[]);
 
-    let msg = InstantiateMsg {
-        generator_contract_addr: "generator0000".to_string(),
-        pair_addr: "pair0000".to_string(),
-        lp_token_addr: "ancust0000".to_string(),
-        reward_contract_addr: "reward0000".to_string(),
-        reward_token_addr: "anc0000".to_string(),
-    };
+    LEGACY_CONFIG
+        .save(
+            deps.as_mut().storage,
+            &LegacyConfig {
+                generator_contract_addr: Addr::unchecked("generator0000"),
+                pair_addr: Addr::unchecked("pair0000"),
+                lp_token_addr: Addr::unchecked("lptoken0000"),
+                reward_contract_addr: Addr::unchecked("reward0000"),
+                reward_token_addr: Addr::unchecked("spec0000"),
+            },
+        )
+        .unwrap();
+    deps.querier.with_token_balances(&[(
+        &"spec0000".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(7u128))],
+    )]);
 
-    let info = mock_info("addr0000", &[]);
-    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            owner: "owner0000".to_string(),
+            spectrum_gov: None,
+        },
+    )
+    .unwrap();
+
+    let config = CONFIG.load(deps.as_ref().storage).unwrap();
+    assert_eq!("owner0000", config.owner.as_str());
+    assert!(POOLS.has(deps.as_ref().storage, &Addr::unchecked("lptoken0000")));
+
+    // reward tokens held are credited to the migrated pool
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Reward { lp_token: None }).unwrap();
+    let query_res: Uint128 = from_binary(&res).unwrap();
+    assert_eq!(query_res, Uint128::from(7u128));
+
+    // boost needs spectrum gov
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::Kick {
+            account: Addr::unchecked("addr0001"),
+            lp_token: None,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(res, ContractError::BoostNotEnabled {}));
+}
 
-    let res = query(deps.as_ref(), mock_env(), QueryMsg::RewardInfo {}).unwrap();
+#[test]
+fn test_query_reward_info() {
+    let mut deps = mock_dependencies(&[]);
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, default_instantiate_msg()).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RewardInfo { lp_token: None },
+    )
+    .unwrap();
     let query_res: Addr = from_binary(&res).unwrap();
-    assert_eq!(query_res, Addr::unchecked("anc0000"));
+    assert_eq!(query_res, Addr::unchecked("spec0000"));
+}
+
+/// Simulates the generator driving the proxy for several depositors,
+/// while the mock querier plays the reward contract.
+struct MockGenerator {
+    deps: OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    bond_amount: Uint128,
+    pending_reward: Uint128,
+}
+
+impl MockGenerator {
+    fn new() -> Self {
+        let mut deps = mock_dependencies(&[]);
+        let info = mock_info("addr0000", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, default_instantiate_msg()).unwrap();
+        deps.querier.with_token_balances(&[(
+            &"lptoken0000".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::zero())],
+        )]);
+        MockGenerator {
+            deps,
+            bond_amount: Uint128::zero(),
+            pending_reward: Uint128::zero(),
+        }
+    }
+
+    fn sync_reward_contract(&mut self) {
+        self.deps
+            .querier
+            .with_reward_info("pair0000", self.pending_reward, self.bond_amount);
+    }
+
+    /// reward contract pays pending rewards when the proxy withdraws them
+    fn handle_response(&mut self, res: &Response) {
+        let withdraw_msg = to_binary(&SpecExecuteMsg::withdraw {
+            asset_token: Some("pair0000".to_string()),
+            spec_amount: Some(self.pending_reward),
+        })
+        .unwrap();
+        for sub_msg in res.messages.iter() {
+            if let CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) = &sub_msg.msg {
+                if *msg == withdraw_msg {
+                    self.pending_reward = Uint128::zero();
+                }
+            }
+        }
+        self.sync_reward_contract();
+    }
+
+    fn accrue(&mut self, amount: u128) {
+        self.pending_reward += Uint128::from(amount);
+        self.sync_reward_contract();
+    }
+
+    fn deposit(&mut self, account: &str, amount: u128) {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "generator0000".to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::Deposit {
+                account: Some(Addr::unchecked(account)),
+            })
+            .unwrap(),
+        });
+        let res = execute(
+            self.deps.as_mut(),
+            mock_env(),
+            mock_info("lptoken0000", &[]),
+            msg,
+        )
+        .unwrap();
+        self.bond_amount += Uint128::from(amount);
+        self.handle_response(&res);
+    }
+
+    fn withdraw(&mut self, account: &str, amount: u128, emergency: bool) {
+        let msg = if emergency {
+            ExecuteMsg::EmergencyWithdraw {
+                account: Addr::unchecked(account),
+                amount: Uint128::from(amount),
+                lp_token: None,
+            }
+        } else {
+            ExecuteMsg::Withdraw {
+                account: Addr::unchecked(account),
+                amount: Uint128::from(amount),
+                lp_token: None,
+            }
+        };
+        let res = execute(
+            self.deps.as_mut(),
+            mock_env(),
+            mock_info("generator0000", &[]),
+            msg,
+        )
+        .unwrap();
+        self.bond_amount -= Uint128::from(amount);
+        self.handle_response(&res);
+    }
+
+    /// claims rewards to the account the way the generator does
+    fn send_rewards(&mut self, account: &str, amount: Uint128) {
+        let generator_info = mock_info("generator0000", &[]);
+        let res = execute(
+            self.deps.as_mut(),
+            mock_env(),
+            generator_info.clone(),
+            ExecuteMsg::UpdateRewards {},
+        )
+        .unwrap();
+        self.handle_response(&res);
+        let msg = ExecuteMsg::SendRewards {
+            account: Addr::unchecked(account),
+            amount,
+            lp_token: None,
+        };
+        let res = execute(self.deps.as_mut(), mock_env(), generator_info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "spec0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: account.to_string(),
+                    amount,
+                })
+                .unwrap(),
+                funds: vec![],
+            }))]
+        );
+    }
+
+    fn kick(&mut self, account: &str) {
+        let msg = ExecuteMsg::Kick {
+            account: Addr::unchecked(account),
+            lp_token: None,
+        };
+        let res = execute(self.deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+        self.handle_response(&res);
+    }
+
+    fn user_info(&self, account: &str) -> UserInfoResponse {
+        let msg = QueryMsg::UserInfo {
+            lp_token: None,
+            account: account.to_string(),
+        };
+        from_binary(&query(self.deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+    }
+
+    fn pending_reward(&self, account: &str) -> Uint128 {
+        self.user_info(account).pending_reward
+    }
+
+    fn query_uint(&self, msg: QueryMsg) -> Uint128 {
+        from_binary(&query(self.deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+    }
+}
+
+#[test]
+fn test_reward_debt_with_several_depositors() {
+    let mut generator = MockGenerator::new();
+
+    generator.deposit("addr0001", 100);
+    generator.deposit("addr0002", 300);
+
+    // rewards are split by deposit, pending rewards of the reward contract included
+    generator.accrue(40);
+    assert_eq!(generator.pending_reward("addr0001"), Uint128::new(10));
+    assert_eq!(generator.pending_reward("addr0002"), Uint128::new(30));
+    assert_eq!(
+        generator.query_uint(QueryMsg::PendingToken { lp_token: None }),
+        Uint128::new(40)
+    );
+
+    // partial withdrawal keeps rewards accrued before it
+    generator.withdraw("addr0001", 50, false);
+    assert_eq!(
+        generator.user_info("addr0001"),
+        UserInfoResponse {
+            amount: Uint128::new(50),
+            pending_reward: Uint128::new(10),
+            boost_amount: Uint128::zero(),
+        }
+    );
+    assert_eq!(
+        generator.query_uint(QueryMsg::Reward { lp_token: None }),
+        Uint128::new(40)
+    );
+    assert_eq!(
+        generator.query_uint(QueryMsg::PendingToken { lp_token: None }),
+        Uint128::zero()
+    );
+
+    generator.accrue(35);
+    assert_eq!(generator.pending_reward("addr0001"), Uint128::new(15));
+    assert_eq!(generator.pending_reward("addr0002"), Uint128::new(60));
+
+    // new depositor does not take rewards accrued before its deposit
+    generator.deposit("addr0003", 100);
+    assert_eq!(generator.pending_reward("addr0003"), Uint128::zero());
+
+    generator.accrue(45);
+    assert_eq!(generator.pending_reward("addr0001"), Uint128::new(20));
+    assert_eq!(generator.pending_reward("addr0002"), Uint128::new(90));
+    assert_eq!(generator.pending_reward("addr0003"), Uint128::new(10));
+
+    // emergency withdrawal forfeits rewards, they stay with the pool
+    generator.withdraw("addr0002", 300, true);
+    assert_eq!(
+        generator.user_info("addr0002"),
+        UserInfoResponse {
+            amount: Uint128::zero(),
+            pending_reward: Uint128::zero(),
+            boost_amount: Uint128::zero(),
+        }
+    );
+    assert_eq!(
+        generator.query_uint(QueryMsg::Reward { lp_token: None }),
+        Uint128::new(120)
+    );
+
+    // cannot withdraw more than deposit
+    let res = execute(
+        generator.deps.as_mut(),
+        mock_env(),
+        mock_info("generator0000", &[]),
+        ExecuteMsg::Withdraw {
+            account: Addr::unchecked("addr0003"),
+            amount: Uint128::new(101),
+            lp_token: None,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(res, ContractError::InsufficientDeposit {}));
+
+    // claims
+    let pending_reward = generator.pending_reward("addr0001");
+    generator.send_rewards("addr0001", pending_reward);
+    assert_eq!(generator.pending_reward("addr0001"), Uint128::zero());
+    let pending_reward = generator.pending_reward("addr0003");
+    generator.send_rewards("addr0003", pending_reward);
+    assert_eq!(generator.pending_reward("addr0003"), Uint128::zero());
+
+    // only orphan rewards are left
+    assert_eq!(
+        generator.query_uint(QueryMsg::Reward { lp_token: None }),
+        Uint128::new(90)
+    );
+    generator.send_rewards("addr0004", Uint128::new(90));
+    assert_eq!(
+        generator.query_uint(QueryMsg::Reward { lp_token: None }),
+        Uint128::zero()
+    );
+    assert_eq!(
+        generator.query_uint(QueryMsg::Deposit { lp_token: None }),
+        Uint128::new(150)
+    );
+}
+
+#[test]
+fn test_boost() {
+    let mut generator = MockGenerator::new();
+
+    generator.deposit("addr0001", 100);
+    generator.deposit("addr0002", 100);
+
+    // addr0001 holds 1/4 of SPEC lock weight, boost is applied only after kick
+    generator.deps.querier.with_gov_locks(&[
+        ("addr0001", &[(30u64, Uint128::new(100))]),
+        ("addr0009", &[(30u64, Uint128::new(300))]),
+    ]);
+    assert_eq!(generator.user_info("addr0001").boost_amount, Uint128::zero());
+
+    // boost = 1.5 * 200 * 1/4
+    generator.kick("addr0001");
+    assert_eq!(generator.user_info("addr0001").boost_amount, Uint128::new(75));
+    assert_eq!(generator.user_info("addr0002").boost_amount, Uint128::zero());
+
+    // rewards are split by boosted deposit, 175:100
+    generator.accrue(275);
+    assert_eq!(generator.pending_reward("addr0001"), Uint128::new(175));
+    assert_eq!(generator.pending_reward("addr0002"), Uint128::new(100));
+
+    // boost is capped at 1.5 * deposit, rewards before the kick are kept
+    generator.deps.querier.with_gov_locks(&[
+        ("addr0001", &[(365u64, Uint128::new(10000))]),
+        ("addr0009", &[(30u64, Uint128::new(300))]),
+    ]);
+    generator.kick("addr0001");
+    assert_eq!(generator.user_info("addr0001").boost_amount, Uint128::new(150));
+    assert_eq!(generator.pending_reward("addr0001"), Uint128::new(175));
+
+    generator.accrue(350);
+    assert_eq!(generator.pending_reward("addr0001"), Uint128::new(425));
+    assert_eq!(generator.pending_reward("addr0002"), Uint128::new(200));
+
+    // withdrawal recalculates boost with the remaining deposit
+    generator.withdraw("addr0001", 50, false);
+    assert_eq!(
+        generator.user_info("addr0001"),
+        UserInfoResponse {
+            amount: Uint128::new(50),
+            pending_reward: Uint128::new(425),
+            boost_amount: Uint128::new(75),
+        }
+    );
+
+    // lock is withdrawn from gov, kick removes boost
+    generator.deps.querier.with_gov_locks(&[
+        ("addr0009", &[(30u64, Uint128::new(300))]),
+    ]);
+    generator.kick("addr0001");
+    assert_eq!(generator.user_info("addr0001").boost_amount, Uint128::zero());
+
+    generator.accrue(150);
+    assert_eq!(generator.pending_reward("addr0001"), Uint128::new(475));
+    assert_eq!(generator.pending_reward("addr0002"), Uint128::new(300));
 }
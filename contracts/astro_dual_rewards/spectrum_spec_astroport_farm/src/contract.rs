@@ -28,7 +28,7 @@ pub fn instantiate(
         spectrum_gov: deps.api.addr_canonicalize(&msg.spectrum_gov)?,
         spectrum_token: deps.api.addr_canonicalize(&msg.spectrum_token)?,
         generator_proxy: if let Some(generator_proxy) = &msg.generator_proxy {
-            Some(deps.api.addr_canonicalize(&generator_proxy)?)
+            Some(deps.api.addr_canonicalize(generator_proxy)?)
         } else {
             None
         },
@@ -171,7 +171,7 @@ fn query_config(deps: Deps) -> StdResult<ConfigInfo> {
         spectrum_token: deps.api.addr_humanize(&config.spectrum_token)?.to_string(),
         spectrum_gov: deps.api.addr_humanize(&config.spectrum_gov)?.to_string(),
         generator_proxy: if let Some(generator_proxy) = &config.generator_proxy {
-            Some(deps.api.addr_humanize(&generator_proxy)?.to_string())
+            Some(deps.api.addr_humanize(generator_proxy)?.to_string())
         } else {
             None
         },
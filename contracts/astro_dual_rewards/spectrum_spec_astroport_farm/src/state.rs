@@ -16,7 +16,7 @@ pub struct Config {
     pub generator_proxy: Option<CanonicalAddr>
 }
 
-pub fn config_store(storage: &mut dyn Storage) -> Singleton<Config> {
+pub fn config_store(storage: &mut dyn Storage) -> Singleton<'_, Config> {
     singleton(storage, KEY_CONFIG)
 }
 
@@ -34,7 +34,7 @@ pub struct State {
     pub total_weight: u32,
 }
 
-pub fn state_store(storage: &mut dyn Storage) -> Singleton<State> {
+pub fn state_store(storage: &mut dyn Storage) -> Singleton<'_, State> {
     singleton(storage, KEY_STATE)
 }
 
@@ -53,11 +53,11 @@ pub struct PoolInfo {
     pub spec_share_index: Decimal, // per bond amount
 }
 
-pub fn pool_info_store(storage: &mut dyn Storage) -> Bucket<PoolInfo> {
+pub fn pool_info_store(storage: &mut dyn Storage) -> Bucket<'_, PoolInfo> {
     bucket(storage, PREFIX_POOL_INFO)
 }
 
-pub fn pool_info_read(storage: &dyn Storage) -> ReadonlyBucket<PoolInfo> {
+pub fn pool_info_read(storage: &dyn Storage) -> ReadonlyBucket<'_, PoolInfo> {
     bucket_read(storage, PREFIX_POOL_INFO)
 }
 
@@ -13,7 +13,6 @@ use std::str::FromStr;
 const GOV: &str = "gov";
 const TOKEN: &str = "token";
 const TEST_CREATOR: &str = "creator";
-const USER1: &str = "user1";
 const USER2: &str = "user2";
 const LP: &str = "lp_token";
 const GENERATOR_PROXY: &str = "generator";
@@ -133,15 +132,15 @@ fn clone_storage(storage: &MockStorage) -> MockStorage {
     cloned
 }
 
-fn test_bond(mut deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+fn test_bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     // bond err from non-generator_proxy
     let mut env = mock_env();
     let info = mock_info(TEST_CREATOR, &[]);
     let msg = ExecuteMsg::receive(Cw20ReceiveMsg {
-        sender: USER1.to_string(),
+        sender: GENERATOR_PROXY.to_string(),
         amount: Uint128::from(100u128),
         msg: to_binary(&Cw20HookMsg::bond {
-            staker_addr: LP.to_string(),
+            staker_addr: GENERATOR_PROXY.to_string(),
             asset_token: TOKEN.to_string(),
         })
         .unwrap(),
@@ -155,7 +154,7 @@ fn test_bond(mut deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     )]);
 
     // bond success
-    let info = mock_info(GENERATOR_PROXY, &[]);
+    let info = mock_info(LP, &[]);
     let res = execute(deps.as_mut(), env.clone(), info, msg);
     assert!(res.is_ok());
 
@@ -167,7 +166,7 @@ fn test_bond(mut deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     // query balance
     env.block.height = 100u64;
     let msg = QueryMsg::reward_info {
-        staker_addr: USER1.to_string(),
+        staker_addr: GENERATOR_PROXY.to_string(),
         asset_token: None,
     };
     let res: RewardInfoResponse =
@@ -184,7 +183,7 @@ fn test_bond(mut deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     );
 
     // unbond
-    let info = mock_info(USER1, &[]);
+    let info = mock_info(GENERATOR_PROXY, &[]);
     let msg = ExecuteMsg::unbond {
         asset_token: TOKEN.to_string(),
         amount: Uint128::from(20u128),
@@ -197,7 +196,7 @@ fn test_bond(mut deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
             contract_addr: LP.to_string(),
             funds: vec![],
             msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: USER1.to_string(),
+                recipient: GENERATOR_PROXY.to_string(),
                 amount: Uint128::from(20u128),
             })
             .unwrap(),
@@ -235,7 +234,7 @@ fn test_bond(mut deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
                 contract_addr: TOKEN.to_string(),
                 funds: vec![],
                 msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                    recipient: USER1.to_string(),
+                    recipient: GENERATOR_PROXY.to_string(),
                     amount: Uint128::from(200u128),
                 })
                 .unwrap(),
@@ -272,7 +271,7 @@ fn test_bond(mut deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
                 contract_addr: TOKEN.to_string(),
                 funds: vec![],
                 msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                    recipient: USER1.to_string(),
+                    recipient: GENERATOR_PROXY.to_string(),
                     amount: Uint128::from(300u128),
                 })
                     .unwrap(),
@@ -288,10 +287,10 @@ fn test_bond(mut deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     // bond user2
     let info = mock_info(LP, &[]);
     let msg = ExecuteMsg::receive(Cw20ReceiveMsg {
-        sender: USER2.to_string(),
+        sender: GENERATOR_PROXY.to_string(),
         amount: Uint128::from(70u128),
         msg: to_binary(&Cw20HookMsg::bond {
-            staker_addr: LP.to_string(),
+            staker_addr: USER2.to_string(),
             asset_token: TOKEN.to_string(),
         })
         .unwrap(),
@@ -307,7 +306,7 @@ fn test_bond(mut deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     // query balance1
     env.block.height = 40u64;
     let msg = QueryMsg::reward_info {
-        staker_addr: USER1.to_string(),
+        staker_addr: GENERATOR_PROXY.to_string(),
         asset_token: None,
     };
     let res: RewardInfoResponse =
@@ -1,14 +1,18 @@
-use cosmwasm_std::{attr, to_binary, Attribute, CanonicalAddr, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128, WasmMsg, QueryRequest, WasmQuery};
+use cosmwasm_std::{attr, to_binary, Addr, Attribute, CanonicalAddr, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128, WasmMsg, QueryRequest, WasmQuery};
 
 use crate::{
     bond::deposit_farm_share,
     querier::{query_astroport_pending_token, query_astroport_pool_balance},
     state::{read_config, state_store},
 };
+use spectrum_protocol::incentive::query_free_token_balance;
 
 use cw20::Cw20ExecuteMsg;
 
-use crate::state::{pool_info_read, pool_info_store, read_state, Config, PoolInfo};
+use crate::state::{
+    pool_info_read, pool_info_store, pool_pending_astro_read, pool_pending_astro_store, read_state,
+    Config, PoolInfo,
+};
 use astroport::asset::{Asset, AssetInfo};
 use astroport::generator::{
     Cw20HookMsg as AstroportCw20HookMsg, ExecuteMsg as AstroportExecuteMsg
@@ -17,27 +21,43 @@ use astroport::pair::{
     Cw20HookMsg as AstroportPairCw20HookMsg, ExecuteMsg as AstroportPairExecuteMsg, PoolResponse,
     QueryMsg as AstroportPairQueryMsg,
 };
-use astroport::querier::{query_token_balance, simulate};
+use astroport::querier::{simulate};
 
 use spectrum_protocol::astroport_farm::ExecuteMsg;
 
 use spectrum_protocol::gov_proxy::Cw20HookMsg as GovProxyCw20HookMsg;
 use spectrum_protocol::gov::{ExecuteMsg as GovExecuteMsg};
 use spectrum_protocol::farm_helper::{deduct_tax};
+use spectrum_protocol::fee::{fee_recipient_weights, split_fee, transfer_fee_msgs};
 use moneymarket::market::{ExecuteMsg as MoneyMarketExecuteMsg};
 
-pub fn compound(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
+pub fn compound(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_token: Option<String>,
+) -> StdResult<Response> {
     let config = read_config(deps.storage)?;
 
     if config.controller != deps.api.addr_canonicalize(info.sender.as_str())? {
         return Err(StdError::generic_err("unauthorized"));
     }
 
-    let pair_contract = deps.api.addr_humanize(&config.pair_contract)?;
+    let asset_token_raw = if let Some(asset_token) = asset_token {
+        deps.api.addr_canonicalize(&asset_token)?
+    } else {
+        config.astro_token.clone()
+    };
+    let reward_pair_contract = deps.api.addr_humanize(&config.pair_contract)?;
     let astro_token = deps.api.addr_humanize(&config.astro_token)?;
     let astro_gov_proxy = deps.api.addr_humanize(&config.astro_gov_proxy)?;
 
-    let mut pool_info = pool_info_read(deps.storage).load(config.astro_token.as_slice())?;
+    let mut pool_info = pool_info_read(deps.storage).load(asset_token_raw.as_slice())?;
+    let pair_contract = if let Some(pair_contract) = &pool_info.pair_contract {
+        deps.api.addr_humanize(pair_contract)?
+    } else {
+        reward_pair_contract.clone()
+    };
 
     // This get pending (ASTRO) reward
     let pending_token_response = query_astroport_pending_token(
@@ -54,7 +74,6 @@ pub fn compound(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Respons
         &config.astroport_generator,
     )?;
 
-    let mut total_astro_swap_amount = Uint128::zero();
     let mut total_astro_stake_amount = Uint128::zero();
     let mut total_astro_commission = Uint128::zero();
     let mut compound_amount = Uint128::zero();
@@ -65,14 +84,17 @@ pub fn compound(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Respons
     let controller_fee = config.controller_fee;
     let total_fee = community_fee + platform_fee + controller_fee;
 
-    // calculate auto-compound, auto-Stake, and commission in ANC
-    let reward = query_token_balance(&deps.querier, astro_token.clone(), env.contract.address.clone())? + pending_token_response.pending;
+    // calculate auto-compound, auto-Stake, and commission in ASTRO
+    // include ASTRO claimed by generator on bond/unbond of this pool
+    let pending_astro = pool_pending_astro_read(deps.storage)
+        .may_load(asset_token_raw.as_slice())?
+        .unwrap_or_default();
+    pool_pending_astro_store(deps.storage).remove(asset_token_raw.as_slice());
+    let reward = pending_astro + pending_token_response.pending;
     if !reward.is_zero() && !lp_balance.is_zero() {
         let commission = reward * total_fee;
         let astro_amount = reward.checked_sub(commission)?;
-        // add commission to total swap amount
         total_astro_commission += commission;
-        total_astro_swap_amount += commission;
 
         let auto_bond_amount = lp_balance
             .checked_sub(pool_info.total_stake_bond_amount)?;
@@ -96,55 +118,29 @@ pub fn compound(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Respons
         total_astro_stake_amount,
     )?;
     state_store(deps.storage).save(&state)?;
-    pool_info_store(deps.storage).save(config.astro_token.as_slice(), &pool_info)?;
+    pool_info_store(deps.storage).save(asset_token_raw.as_slice(), &pool_info)?;
 
     // get reinvest amount
     let reinvest_amount =  compound_amount;
     // split reinvest amount
     let swap_amount = reinvest_amount.multiply_ratio(1u128, 2u128);
-    // add commission to reinvest ANC to total swap amount
-    total_astro_swap_amount += swap_amount;
-
-    // find ASTRO swap rate
-    let astro = Asset {
-        info: AssetInfo::Token {
-            contract_addr: astro_token.clone(),
-        },
-        amount: total_astro_swap_amount,
-    };
-    let astro_swap_rate = simulate(
-        &deps.querier,
-        pair_contract.clone(),
-        &astro,
-    )?;
-    let total_ust_return_amount = deduct_tax(&deps.querier, astro_swap_rate.return_amount, config.base_denom.clone())?;
-    attributes.push(attr("total_ust_return_amount", total_ust_return_amount));
 
-    let total_ust_commission_amount = if total_astro_swap_amount != Uint128::zero() {
-        total_ust_return_amount.multiply_ratio(total_astro_commission, total_astro_swap_amount)
-    } else {
-        Uint128::zero()
-    };
-    let total_ust_reinvest_amount =
-        total_ust_return_amount.checked_sub(total_ust_commission_amount)?;
-
-    // deduct tax for provided UST
-    let net_reinvest_ust = deduct_tax(
-        &deps.querier,
-        total_ust_reinvest_amount,
-        config.base_denom.clone(),
-    )?;
     let pool: PoolResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: pair_contract.to_string(),
         msg: to_binary(&AstroportPairQueryMsg::Pool {})?,
     }))?;
-
-    let provide_astro = compute_provide_after_swap(
-        &pool,
-        &astro,
-        astro_swap_rate.return_amount,
-        net_reinvest_ust
-    )?;
+    let astro_info = AssetInfo::Token {
+        contract_addr: astro_token.clone(),
+    };
+    if !pool.assets.iter().any(|it| it.info == astro_info) {
+        return Err(StdError::generic_err("pair_contract must contain ASTRO"));
+    }
+    let paired_info = pool
+        .assets
+        .iter()
+        .find(|it| it.info != astro_info)
+        .map(|it| it.info.clone())
+        .unwrap_or_else(|| astro_info.clone());
 
     let mut messages: Vec<CosmosMsg> = vec![];
     let manual_claim_pending_token = CosmosMsg::Wasm(WasmMsg::Execute {
@@ -157,22 +153,91 @@ pub fn compound(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Respons
     });
     messages.push(manual_claim_pending_token);
 
-    if !total_astro_swap_amount.is_zero() {
-        let swap_astro: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: astro_token.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Send {
-                contract: pair_contract.to_string(),
+    let (astro, reinvest_return_amount, total_ust_commission_amount, net_reinvest_amount) =
+        if pair_contract == reward_pair_contract {
+            // swap commission and reinvest ASTRO to UST together
+            let total_astro_swap_amount = total_astro_commission + swap_amount;
+            let astro = Asset {
+                info: astro_info.clone(),
                 amount: total_astro_swap_amount,
-                msg: to_binary(&AstroportPairCw20HookMsg::Swap {
-                    max_spread: None,
-                    belief_price: None,
-                    to: None,
-                })?,
-            })?,
-            funds: vec![],
-        });
-        messages.push(swap_astro);
-    }
+            };
+            let astro_swap_rate = simulate(
+                &deps.querier,
+                pair_contract.clone(),
+                &astro,
+            )?;
+            let total_ust_return_amount = deduct_tax(&deps.querier, astro_swap_rate.return_amount, config.base_denom.clone())?;
+            attributes.push(attr("total_ust_return_amount", total_ust_return_amount));
+
+            let total_ust_commission_amount = if total_astro_swap_amount != Uint128::zero() {
+                total_ust_return_amount.multiply_ratio(total_astro_commission, total_astro_swap_amount)
+            } else {
+                Uint128::zero()
+            };
+            let total_ust_reinvest_amount =
+                total_ust_return_amount.checked_sub(total_ust_commission_amount)?;
+
+            // deduct tax for provided UST
+            let net_reinvest_ust = deduct_tax(
+                &deps.querier,
+                total_ust_reinvest_amount,
+                config.base_denom.clone(),
+            )?;
+
+            if !total_astro_swap_amount.is_zero() {
+                messages.push(swap_astro_msg(&astro_token, &pair_contract, total_astro_swap_amount)?);
+            }
+
+            (astro, astro_swap_rate.return_amount, total_ust_commission_amount, net_reinvest_ust)
+        } else {
+            // swap commission to UST on config pair, reinvest ASTRO on pool pair
+            let total_ust_commission_amount = if !total_astro_commission.is_zero() {
+                let commission_swap_rate = simulate(
+                    &deps.querier,
+                    reward_pair_contract.clone(),
+                    &Asset {
+                        info: astro_info.clone(),
+                        amount: total_astro_commission,
+                    },
+                )?;
+                messages.push(swap_astro_msg(&astro_token, &reward_pair_contract, total_astro_commission)?);
+                deduct_tax(&deps.querier, commission_swap_rate.return_amount, config.base_denom.clone())?
+            } else {
+                Uint128::zero()
+            };
+
+            let astro = Asset {
+                info: astro_info.clone(),
+                amount: swap_amount,
+            };
+            let reinvest_swap_rate = simulate(
+                &deps.querier,
+                pair_contract.clone(),
+                &astro,
+            )?;
+
+            // deduct tax for received and provided native asset
+            let net_reinvest_amount = match &paired_info {
+                AssetInfo::NativeToken { denom } => {
+                    let received_amount = deduct_tax(&deps.querier, reinvest_swap_rate.return_amount, denom.clone())?;
+                    deduct_tax(&deps.querier, received_amount, denom.clone())?
+                }
+                AssetInfo::Token { .. } => reinvest_swap_rate.return_amount,
+            };
+
+            if !swap_amount.is_zero() {
+                messages.push(swap_astro_msg(&astro_token, &pair_contract, swap_amount)?);
+            }
+
+            (astro, reinvest_swap_rate.return_amount, total_ust_commission_amount, net_reinvest_amount)
+        };
+
+    let provide_astro = compute_provide_after_swap(
+        &pool,
+        &astro,
+        reinvest_return_amount,
+        net_reinvest_amount
+    )?;
 
     if !total_ust_commission_amount.is_zero() {
 
@@ -189,6 +254,9 @@ pub fn compound(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Respons
 
         attributes.push(attr("net_commission", net_commission_amount));
 
+        let aust_token = deps.api.addr_humanize(&config.aust_token)?;
+        let previous_aust_balance = query_free_token_balance(deps.storage, &deps.querier, aust_token, env.contract.address.clone())?;
+
         messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: deps.api.addr_humanize(&config.anchor_market)?.to_string(),
             msg: to_binary(&MoneyMarketExecuteMsg::DepositStable {})?,
@@ -204,7 +272,10 @@ pub fn compound(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Respons
         }));
         messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: env.contract.address.to_string(),
-            msg: to_binary(&ExecuteMsg::send_fee {})?,
+            msg: to_binary(&ExecuteMsg::send_fee {
+                asset_token: Some(deps.api.addr_humanize(&asset_token_raw)?.to_string()),
+                previous_aust_balance: Some(previous_aust_balance),
+            })?,
             funds: vec![],
         }));
     }
@@ -234,55 +305,74 @@ pub fn compound(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Respons
         });
         messages.push(increase_allowance);
 
+        let funds = match &paired_info {
+            AssetInfo::NativeToken { denom } => vec![Coin {
+                denom: denom.clone(),
+                amount: net_reinvest_amount,
+            }],
+            AssetInfo::Token { contract_addr } => {
+                messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: contract_addr.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                        spender: pair_contract.to_string(),
+                        amount: net_reinvest_amount,
+                        expires: None,
+                    })?,
+                    funds: vec![],
+                }));
+                vec![]
+            }
+        };
+
         let provide_liquidity = CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: pair_contract.to_string(),
             msg: to_binary(&AstroportPairExecuteMsg::ProvideLiquidity {
                 assets: [
                     Asset {
-                        info: AssetInfo::Token {
-                            contract_addr: astro_token.clone(),
-                        },
+                        info: astro_info,
                         amount: provide_astro,
                     },
                     Asset {
-                        info: AssetInfo::NativeToken {
-                            denom: config.base_denom.clone(),
-                        },
-                        amount: net_reinvest_ust,
+                        info: paired_info,
+                        amount: net_reinvest_amount,
                     },
                 ],
                 slippage_tolerance: None,
                 receiver: None,
                 auto_stake: Some(true),
             })?,
-            funds: vec![Coin {
-                denom: config.base_denom,
-                amount: net_reinvest_ust,
-            }],
+            funds,
         });
         messages.push(provide_liquidity);
-
-        // let stake = CosmosMsg::Wasm(WasmMsg::Execute {
-        //     contract_addr: env.contract.address.to_string(),
-        //     msg: to_binary(&ExecuteMsg::stake {
-        //         asset_token: astro_token.to_string(),
-        //     })?,
-        //     funds: vec![],
-        // });
-        // messages.push(stake);
     }
 
     attributes.push(attr("action", "compound"));
-    attributes.push(attr("asset_token", astro_token));
+    attributes.push(attr("asset_token", deps.api.addr_humanize(&asset_token_raw)?));
     attributes.push(attr("reinvest_amount", reinvest_amount));
     attributes.push(attr("provide_token_amount", provide_astro));
-    attributes.push(attr("provide_ust_amount", net_reinvest_ust));
+    attributes.push(attr("provide_ust_amount", net_reinvest_amount));
 
     Ok(Response::new()
         .add_messages(messages)
         .add_attributes(attributes))
 }
 
+fn swap_astro_msg(astro_token: &Addr, pair_contract: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: astro_token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Send {
+            contract: pair_contract.to_string(),
+            amount,
+            msg: to_binary(&AstroportPairCw20HookMsg::Swap {
+                max_spread: None,
+                belief_price: None,
+                to: None,
+            })?,
+        })?,
+        funds: vec![],
+    }))
+}
+
 pub fn compute_provide_after_swap(
     pool: &PoolResponse,
     offer: &Asset,
@@ -318,7 +408,7 @@ pub fn stake(
     let pool_info: PoolInfo = pool_info_read(deps.storage).load(asset_token_raw.as_slice())?;
     let staking_token = deps.api.addr_humanize(&pool_info.staking_token)?;
 
-    let amount = query_token_balance(&deps.querier, staking_token.clone(), env.contract.address)?;
+    let amount = query_free_token_balance(deps.storage, &deps.querier, staking_token.clone(), env.contract.address)?;
 
     Ok(Response::new()
         .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
@@ -338,10 +428,27 @@ pub fn stake(
         ]))
 }
 
+// pool override, then config recipients, then community/platform/controller fee split
+fn pool_fee_recipient_weights(config: &Config, pool_info: Option<PoolInfo>) -> Vec<(CanonicalAddr, Uint128)> {
+    let fee_recipients = pool_info
+        .and_then(|pool_info| pool_info.fee_recipients)
+        .unwrap_or_else(|| config.fee_recipients.clone());
+    fee_recipient_weights(
+        &fee_recipients,
+        vec![
+            (config.spectrum_gov.clone(), config.community_fee),
+            (config.platform.clone(), config.platform_fee),
+            (config.controller.clone(), config.controller_fee),
+        ],
+    )
+}
+
 pub fn send_fee(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    asset_token: Option<String>,
+    previous_aust_balance: Option<Uint128>,
 ) -> StdResult<Response> {
 
     // only farm contract can execute this message
@@ -350,51 +457,29 @@ pub fn send_fee(
     }
     let config = read_config(deps.storage)?;
     let aust_token = deps.api.addr_humanize(&config.aust_token)?;
-    let spectrum_gov = deps.api.addr_humanize(&config.spectrum_gov)?;
-
-    let aust_balance = query_token_balance(&deps.querier, aust_token.clone(), env.contract.address)?;
+    let pool_info = if let Some(asset_token) = asset_token {
+        let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+        pool_info_read(deps.storage).may_load(asset_token_raw.as_slice())?
+    } else {
+        None
+    };
 
-    let mut messages: Vec<CosmosMsg> = vec![];
-    let thousand = Uint128::from(1000u64);
-    let total_fee = config.community_fee + config.controller_fee + config.platform_fee;
-    let community_amount = aust_balance.multiply_ratio(thousand * config.community_fee, thousand * total_fee);
-    if !community_amount.is_zero() {
-        let transfer_community_fee = CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: aust_token.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: spectrum_gov.to_string(),
-                amount: community_amount,
-            })?,
-            funds: vec![],
-        });
-        messages.push(transfer_community_fee);
-    }
+    let aust_balance = query_free_token_balance(deps.storage, &deps.querier, aust_token.clone(), env.contract.address)?;
 
-    let platform_amount = aust_balance.multiply_ratio(thousand * config.platform_fee, thousand * total_fee);
-    if !platform_amount.is_zero() {
-        let stake_platform_fee = CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: aust_token.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: deps.api.addr_humanize(&config.platform)?.to_string(),
-                amount: platform_amount,
-            })?,
-            funds: vec![],
-        });
-        messages.push(stake_platform_fee);
-    }
+    // pool override only applies to fee of this compound, the rest goes to default recipients
+    let previous_aust_balance = previous_aust_balance
+        .unwrap_or(aust_balance)
+        .min(aust_balance);
+    let pool_fee_amount = if pool_info.is_some() {
+        aust_balance.checked_sub(previous_aust_balance)?
+    } else {
+        Uint128::zero()
+    };
+    let mut fee_amounts: Vec<(CanonicalAddr, Uint128)> = vec![];
+    split_fee(&mut fee_amounts, pool_fee_recipient_weights(&config, pool_info), pool_fee_amount)?;
+    split_fee(&mut fee_amounts, pool_fee_recipient_weights(&config, None), aust_balance.checked_sub(pool_fee_amount)?)?;
 
-    let controller_amount = aust_balance.checked_sub(community_amount + platform_amount)?;
-    if !controller_amount.is_zero() {
-        let stake_controller_fee = CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: aust_token.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: deps.api.addr_humanize(&config.controller)?.to_string(),
-                amount: controller_amount,
-            })?,
-            funds: vec![],
-        });
-        messages.push(stake_controller_fee);
-    }
+    let messages = transfer_fee_msgs(deps.api, &aust_token, fee_amounts)?;
     Ok(Response::new()
         .add_messages(messages))
 }
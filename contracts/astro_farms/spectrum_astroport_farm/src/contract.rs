@@ -1,8 +1,8 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, from_binary, to_binary, Binary, CanonicalAddr, Decimal, Deps, DepsMut, Env, MessageInfo,
-    Order, Response, StdError, StdResult, Uint128,
+    attr, from_binary, to_binary, Binary, CanonicalAddr, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Order, Response, StdError, StdResult, Uint128,
 };
 
 use crate::{
@@ -13,11 +13,18 @@ use crate::{
 
 use cw20::Cw20ReceiveMsg;
 
-use crate::bond::{deposit_spec_reward, query_reward_info, unbond, withdraw, update_bond};
+use crate::bond::{add_incentive, deposit_spec_reward, query_reward_info, query_user_stats, unbond, unbond_for, withdraw, update_bond, refund_incentive, withdraw_incentives};
+use spectrum_protocol::incentive::{
+    self, query_incentive_tokens, to_incentive_items, IncentiveExecuteMsg,
+};
 use crate::state::{pool_info_read, pool_info_store, read_state};
 use spectrum_protocol::astroport_farm::{
     ConfigInfo, Cw20HookMsg, ExecuteMsg, MigrateMsg, PoolItem, PoolsResponse, QueryMsg, StateInfo,
 };
+use spectrum_protocol::fee::{
+    canonicalize_fee_exempt, humanize_fee_exempt, humanize_fee_recipients, update_fee_exempt,
+    validate_deposit_fee_tiers, validate_fee_recipients, DepositFeeTier, FeeRecipient,
+};
 use crate::compound::send_fee;
 
 /// (we require 0-1)
@@ -40,6 +47,10 @@ pub fn instantiate(
     validate_percentage(msg.platform_fee, "platform_fee")?;
     validate_percentage(msg.controller_fee, "controller_fee")?;
     validate_percentage(msg.deposit_fee, "deposit_fee")?;
+    validate_deposit_fee_tiers(&msg.deposit_fee_tiers)?;
+    validate_percentage(msg.withdraw_fee, "withdraw_fee")?;
+    let fee_recipients = validate_fee_recipients(deps.api, msg.fee_recipients)?;
+    let deposit_fee_exempt = canonicalize_fee_exempt(deps.api, &msg.deposit_fee_exempt)?;
 
     store_config(
         deps.storage,
@@ -60,6 +71,16 @@ pub fn instantiate(
             anchor_market: deps.api.addr_canonicalize(&msg.anchor_market)?,
             aust_token: deps.api.addr_canonicalize(&msg.aust_token)?,
             pair_contract: deps.api.addr_canonicalize(&msg.pair_contract)?,
+            spectrum_staker: if let Some(spectrum_staker) = msg.spectrum_staker {
+                Some(deps.api.addr_canonicalize(&spectrum_staker)?)
+            } else {
+                None
+            },
+            fee_recipients,
+            deposit_fee_tiers: msg.deposit_fee_tiers,
+            deposit_fee_exempt,
+            withdraw_fee: msg.withdraw_fee,
+            withdraw_fee_period: msg.withdraw_fee_period,
         },
     )?;
 
@@ -85,6 +106,7 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             platform_fee,
             controller_fee,
             deposit_fee,
+            spectrum_staker,
         } => update_config(
             deps,
             info,
@@ -94,11 +116,13 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             platform_fee,
             controller_fee,
             deposit_fee,
+            spectrum_staker,
         ),
         ExecuteMsg::register_asset {
             asset_token,
             staking_token,
             weight,
+            pair_contract,
         } => register_asset(
             deps,
             env,
@@ -106,16 +130,58 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             asset_token,
             staking_token,
             weight,
+            pair_contract,
         ),
         ExecuteMsg::unbond {
             asset_token,
             amount,
-        } => unbond(deps, env, info, asset_token, amount),
+            zap_out,
+        } => unbond(deps, env, info, asset_token, amount, zap_out),
+        ExecuteMsg::unbond_for {
+            staker_addr,
+            asset_token,
+            amount,
+        } => unbond_for(deps, env, info, staker_addr, asset_token, amount),
         ExecuteMsg::withdraw { asset_token, spec_amount, farm_amount } => withdraw(deps, env, info, asset_token, spec_amount, farm_amount),
         ExecuteMsg::stake { asset_token } => stake(deps, env, info, asset_token),
-        ExecuteMsg::compound {} => compound(deps, env, info),
+        ExecuteMsg::compound { asset_token } => compound(deps, env, info, asset_token),
         ExecuteMsg::update_bond { asset_token, amount_to_auto, amount_to_stake } => update_bond(deps, env, info, asset_token, amount_to_auto, amount_to_stake),
-        ExecuteMsg::send_fee {} => send_fee(deps, env, info),
+        ExecuteMsg::send_fee {
+            asset_token,
+            previous_aust_balance,
+        } => send_fee(deps, env, info, asset_token, previous_aust_balance),
+        ExecuteMsg::update_fee_config {
+            fee_recipients,
+            deposit_fee_tiers,
+            insert_fee_exempt,
+            remove_fee_exempt,
+            withdraw_fee,
+            withdraw_fee_period,
+        } => update_fee_config(
+            deps,
+            info,
+            fee_recipients,
+            deposit_fee_tiers,
+            insert_fee_exempt,
+            remove_fee_exempt,
+            withdraw_fee,
+            withdraw_fee_period,
+        ),
+        ExecuteMsg::update_pool_fee_recipients {
+            asset_token,
+            fee_recipients,
+        } => update_pool_fee_recipients(deps, info, asset_token, fee_recipients),
+        ExecuteMsg::incentive(msg) => match msg {
+            IncentiveExecuteMsg::update_incentive_tokens { add, remove } => {
+                update_incentive_tokens(deps, info, add, remove)
+            }
+            IncentiveExecuteMsg::withdraw_incentives { asset_token, tokens } => {
+                withdraw_incentives(deps, env, info, asset_token, tokens)
+            }
+            IncentiveExecuteMsg::refund_incentive { asset_token, token } => {
+                refund_incentive(deps, env, info, asset_token, token)
+            }
+        },
     }
 }
 
@@ -139,6 +205,18 @@ fn receive_cw20(
             cw20_msg.amount,
             compound_rate,
         ),
+        Ok(Cw20HookMsg::add_incentive {
+            asset_token,
+            duration,
+        }) => add_incentive(
+            deps,
+            env,
+            info,
+            cw20_msg.sender,
+            asset_token,
+            cw20_msg.amount,
+            duration,
+        ),
         Err(_) => Err(StdError::generic_err("data should be given")),
     }
 }
@@ -153,6 +231,7 @@ pub fn update_config(
     platform_fee: Option<Decimal>,
     controller_fee: Option<Decimal>,
     deposit_fee: Option<Decimal>,
+    spectrum_staker: Option<String>,
 ) -> StdResult<Response> {
     let mut config: Config = read_config(deps.storage)?;
 
@@ -191,11 +270,105 @@ pub fn update_config(
         config.deposit_fee = deposit_fee;
     }
 
+    if let Some(spectrum_staker) = spectrum_staker {
+        config.spectrum_staker = Some(deps.api.addr_canonicalize(&spectrum_staker)?);
+    }
+
     store_config(deps.storage, &config)?;
 
     Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
 }
 
+fn update_incentive_tokens(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Option<Vec<String>>,
+    remove: Option<Vec<String>>,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    incentive::update_incentive_tokens(deps.api, deps.storage, add, remove)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "update_incentive_tokens")]))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_fee_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    fee_recipients: Option<Vec<FeeRecipient>>,
+    deposit_fee_tiers: Option<Vec<DepositFeeTier>>,
+    insert_fee_exempt: Option<Vec<String>>,
+    remove_fee_exempt: Option<Vec<String>>,
+    withdraw_fee: Option<Decimal>,
+    withdraw_fee_period: Option<u64>,
+) -> StdResult<Response> {
+    let mut config: Config = read_config(deps.storage)?;
+
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    if let Some(fee_recipients) = fee_recipients {
+        config.fee_recipients = validate_fee_recipients(deps.api, fee_recipients)?;
+    }
+
+    if let Some(deposit_fee_tiers) = deposit_fee_tiers {
+        validate_deposit_fee_tiers(&deposit_fee_tiers)?;
+        config.deposit_fee_tiers = deposit_fee_tiers;
+    }
+
+    update_fee_exempt(
+        deps.api,
+        &mut config.deposit_fee_exempt,
+        insert_fee_exempt,
+        remove_fee_exempt,
+    )?;
+
+    if let Some(withdraw_fee) = withdraw_fee {
+        validate_percentage(withdraw_fee, "withdraw_fee")?;
+        config.withdraw_fee = withdraw_fee;
+    }
+
+    if let Some(withdraw_fee_period) = withdraw_fee_period {
+        config.withdraw_fee_period = withdraw_fee_period;
+    }
+
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "update_fee_config")]))
+}
+
+fn update_pool_fee_recipients(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_token: String,
+    fee_recipients: Option<Vec<FeeRecipient>>,
+) -> StdResult<Response> {
+    let config: Config = read_config(deps.storage)?;
+
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    let mut pool_info = pool_info_read(deps.storage).load(asset_token_raw.as_slice())?;
+    pool_info.fee_recipients = if let Some(fee_recipients) = fee_recipients {
+        Some(validate_fee_recipients(deps.api, fee_recipients)?)
+    } else {
+        None
+    };
+    pool_info_store(deps.storage).save(asset_token_raw.as_slice(), &pool_info)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_pool_fee_recipients"),
+        attr("asset_token", asset_token),
+    ]))
+}
+
 fn register_asset(
     deps: DepsMut,
     env: Env,
@@ -203,6 +376,7 @@ fn register_asset(
     asset_token: String,
     staking_token: String,
     weight: u32,
+    pair_contract: Option<String>,
 ) -> StdResult<Response> {
     let config: Config = read_config(deps.storage)?;
     let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
@@ -211,13 +385,11 @@ fn register_asset(
         return Err(StdError::generic_err("unauthorized"));
     }
 
-    let pool_count = pool_info_read(deps.storage)
-        .range(None, None, Order::Descending)
-        .count();
-
-    if pool_count >= 1 {
-        return Err(StdError::generic_err("Already registered one asset"));
-    }
+    let pair_contract_raw = if let Some(pair_contract) = pair_contract {
+        Some(deps.api.addr_canonicalize(&pair_contract)?)
+    } else {
+        None
+    };
 
     let mut state = read_state(deps.storage)?;
     deposit_spec_reward(deps.as_ref(), &env, &mut state, &config, false)?;
@@ -235,7 +407,19 @@ fn register_asset(
             state_spec_share_index: state.spec_share_index,
             auto_spec_share_index: Decimal::zero(),
             stake_spec_share_index: Decimal::zero(),
+            fee_recipients: None,
+            pair_contract: None,
+            incentives: vec![],
         });
+    if pair_contract_raw.is_some() {
+        pool_info.pair_contract = pair_contract_raw;
+    }
+
+    // only ASTRO pool can use config pair_contract
+    if pool_info.pair_contract.is_none() && asset_token_raw != config.astro_token {
+        return Err(StdError::generic_err("pair_contract is required"));
+    }
+
     state.total_weight = state.total_weight + weight - pool_info.weight;
     pool_info.weight = weight;
 
@@ -256,6 +440,8 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             staker_addr,
         } => to_binary(&query_reward_info(deps, env, staker_addr)?),
         QueryMsg::state {} => to_binary(&query_state(deps)?),
+        QueryMsg::user_stats { staker_addr } => to_binary(&query_user_stats(deps, env, staker_addr)?),
+        QueryMsg::incentive_tokens {} => to_binary(&query_incentive_tokens(deps.api, deps.storage)?),
     }
 }
 
@@ -278,6 +464,16 @@ fn query_config(deps: Deps) -> StdResult<ConfigInfo> {
         anchor_market: deps.api.addr_humanize(&config.anchor_market)?.to_string(),
         aust_token: deps.api.addr_humanize(&config.aust_token)?.to_string(),
         pair_contract: deps.api.addr_humanize(&config.pair_contract)?.to_string(),
+        spectrum_staker: if let Some(spectrum_staker) = config.spectrum_staker {
+            Some(deps.api.addr_humanize(&spectrum_staker)?.to_string())
+        } else {
+            None
+        },
+        fee_recipients: humanize_fee_recipients(deps.api, &config.fee_recipients)?,
+        deposit_fee_tiers: config.deposit_fee_tiers,
+        deposit_fee_exempt: humanize_fee_exempt(deps.api, &config.deposit_fee_exempt)?,
+        withdraw_fee: config.withdraw_fee,
+        withdraw_fee_period: config.withdraw_fee_period,
     };
 
     Ok(resp)
@@ -306,6 +502,17 @@ fn query_pools(deps: Deps) -> StdResult<PoolsResponse> {
                 farm_share_index: pool_info.farm_share_index,
                 stake_spec_share_index: pool_info.stake_spec_share_index,
                 auto_spec_share_index: pool_info.auto_spec_share_index,
+                fee_recipients: if let Some(fee_recipients) = pool_info.fee_recipients {
+                    Some(humanize_fee_recipients(deps.api, &fee_recipients)?)
+                } else {
+                    None
+                },
+                pair_contract: if let Some(pair_contract) = pool_info.pair_contract {
+                    Some(deps.api.addr_humanize(&pair_contract)?.to_string())
+                } else {
+                    None
+                },
+                incentives: to_incentive_items(deps.api, &pool_info.incentives)?,
             })
         })
         .collect::<StdResult<Vec<PoolItem>>>()?;
@@ -3,18 +3,23 @@ use crate::contract::{execute, instantiate, query};
 use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
 use crate::state::{pool_info_read, pool_info_store, read_config, read_state, state_store};
 use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{from_binary, to_binary, CosmosMsg, OwnedDeps, Uint128, WasmMsg, Decimal, Api};
+use cosmwasm_std::{from_binary, to_binary, CosmosMsg, OwnedDeps, Uint128, WasmMsg, Decimal, Api, StdError};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use spectrum_protocol::astroport_farm::{
-    ConfigInfo, Cw20HookMsg, ExecuteMsg, PoolItem, PoolsResponse, QueryMsg, StateInfo,
+    ConfigInfo, Cw20HookMsg, ExecuteMsg, PoolItem, PoolsResponse, QueryMsg,
+    RewardInfoResponse as FarmRewardInfoResponse, StateInfo,
 };
 use spectrum_protocol::gov::ExecuteMsg as GovExecuteMsg;
+use spectrum_protocol::incentive::IncentiveExecuteMsg;
+use spectrum_protocol::user_stat::{UserStatsResponse, UserStatsResponseItem};
 use astroport::generator::{
     ExecuteMsg as AstroportExecuteMsg,
 };
 use spectrum_protocol::gov_proxy::{ExecuteMsg as GovProxyExecuteMsg};
+use spectrum_protocol::staker::{Cw20HookMsg as StakerCw20HookMsg, ZapOutParams};
+use terraswap::asset::AssetInfo;
 
 use std::fmt::Debug;
 
@@ -32,6 +37,7 @@ const ANC_MARKET: &str = "anc_market";
 const AUST_TOKEN: &str = "aust_token";
 const PAIR_CONTRACT: &str = "pair_contract";
 const ASTRO_GOV_PROXY: &str = "astro_gov_proxy";
+const SPEC_STAKER: &str = "spec_staker";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct RewardInfoResponse {
@@ -66,10 +72,101 @@ fn test() {
     let _ = test_config(&mut deps);
     test_register_asset(&mut deps);
     test_bond(&mut deps);
+    test_user_stats(&mut deps);
+    test_unbond_zap_out(&mut deps);
+    test_unbond_for(&mut deps);
+    test_unbond_withdraw_fee(&mut deps);
     // test_deposit_fee(&mut deps);
     // test_staked_reward(&mut deps);
 }
 
+#[test]
+fn test_incentive_refund() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_balance_percent(100);
+
+    let _ = test_config(&mut deps);
+    test_register_asset(&mut deps);
+
+    let tokens: Vec<String> = (0..6).map(|i| format!("bribe_token{}", i)).collect();
+    let msg = ExecuteMsg::incentive(IncentiveExecuteMsg::update_incentive_tokens {
+        add: Some(tokens.clone()),
+        remove: None,
+    });
+    let res = execute(deps.as_mut(), mock_env(), mock_info(SPEC_GOV, &[]), msg);
+    assert!(res.is_ok());
+
+    let add_incentive = |token: &str, sender: &str, amount: u128| {
+        (
+            mock_info(token, &[]),
+            ExecuteMsg::receive(Cw20ReceiveMsg {
+                sender: sender.to_string(),
+                amount: Uint128::from(amount),
+                msg: to_binary(&Cw20HookMsg::add_incentive {
+                    asset_token: ASTRO_TOKEN.to_string(),
+                    duration: 100u64,
+                })
+                .unwrap(),
+            }),
+        )
+    };
+
+    // nobody bonded, so the whole stream stays in the pool
+    let (info, msg) = add_incentive(&tokens[0], USER1, 1000u128);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert!(res.is_ok());
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(200);
+    let msg = ExecuteMsg::incentive(IncentiveExecuteMsg::refund_incentive {
+        asset_token: ASTRO_TOKEN.to_string(),
+        token: tokens[0].clone(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER2, &[]), msg.clone());
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER1, &[]), msg.clone()).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: tokens[0].clone(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: USER1.to_string(),
+                amount: Uint128::from(1000u128),
+            })
+            .unwrap(),
+        })]
+    );
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER1, &[]), msg);
+    assert_eq!(res, Err(StdError::generic_err("nothing to refund")));
+
+    // stream is over, another depositor can take the slot
+    let (info, msg) = add_incentive(&tokens[0], USER2, 500u128);
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    // pool incentives are capped
+    for token in tokens[1..5].iter() {
+        let (info, msg) = add_incentive(token, USER1, 100u128);
+        let res = execute(deps.as_mut(), env.clone(), info, msg);
+        assert!(res.is_ok());
+    }
+    let (info, msg) = add_incentive(&tokens[5], USER1, 100u128);
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("too many incentives in pool")));
+
+    let msg = QueryMsg::pools {};
+    let res: PoolsResponse = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+    assert_eq!(res.pools[0].incentives.len(), 5);
+    assert_eq!(res.pools[0].incentives[0].depositor, USER2.to_string());
+    assert_eq!(res.pools[0].incentives[0].remaining_amount, Uint128::from(500u128));
+}
+
 fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> ConfigInfo {
     // test init & read config & read state
     let env = mock_env();
@@ -91,6 +188,12 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         anchor_market: ANC_MARKET.to_string(),
         aust_token: AUST_TOKEN.to_string(),
         pair_contract: PAIR_CONTRACT.to_string(),
+        spectrum_staker: None,
+        fee_recipients: vec![],
+        deposit_fee_tiers: vec![],
+        deposit_fee_exempt: vec![],
+        withdraw_fee: Decimal::zero(),
+        withdraw_fee_period: 0u64,
     };
 
     // success init
@@ -125,6 +228,7 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         platform_fee: None,
         controller_fee: None,
         deposit_fee: None,
+        spectrum_staker: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
     assert!(res.is_err());
@@ -150,6 +254,7 @@ fn test_register_asset(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
         asset_token: ASTRO_TOKEN.to_string(),
         staking_token: ASTRO_LP.to_string(),
         weight: 1u32,
+        pair_contract: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
     assert!(res.is_err());
@@ -177,6 +282,9 @@ fn test_register_asset(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
                 total_stake_bond_amount: Uint128::zero(),
                 total_stake_bond_share: Uint128::zero(),
                 total_auto_bond_share: Uint128::zero(),
+                fee_recipients: None,
+                pair_contract: None,
+                incentives: vec![],
             }]
         }
     );
@@ -186,6 +294,7 @@ fn test_register_asset(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
         asset_token: SPY_TOKEN.to_string(),
         staking_token: SPY_LP.to_string(),
         weight: 1u32,
+        pair_contract: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg);
     assert!(res.is_err());
@@ -319,6 +428,7 @@ fn test_bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     let msg = ExecuteMsg::unbond {
         asset_token: ASTRO_TOKEN.to_string(),
         amount: Uint128::from(3000u128),
+        zap_out: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
     assert!(res.is_ok());
@@ -565,12 +675,350 @@ fn test_bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     );
 }
 
+fn test_user_stats(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+
+    // user1 bonded 10000, unbonded 3000, no gain yet
+    let msg = QueryMsg::user_stats {
+        staker_addr: USER1.to_string(),
+    };
+    let res: UserStatsResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg.clone()).unwrap()).unwrap();
+    assert_eq!(
+        res.user_stats,
+        vec![UserStatsResponseItem {
+            asset_token: ASTRO_TOKEN.to_string(),
+            bond_amount: Uint128::from(7000u128),
+            lp_bonded: Uint128::from(10000u128),
+            lp_unbonded: Uint128::from(3000u128),
+            farm_withdrawn: Uint128::from(1000u128),
+            spec_withdrawn: Uint128::from(2700u128),
+            deposit_fee_paid: Uint128::zero(),
+            cost_basis: Uint128::from(7000u128),
+            realized_gain: Uint128::zero(),
+            realized_loss: Uint128::zero(),
+            unrealized_gain: Uint128::zero(),
+            unrealized_loss: Uint128::zero(),
+            withdraw_fee_paid: Uint128::zero(),
+            farm2_withdrawn: Uint128::zero(),
+        }]
+    );
+
+    // auto-compound +1200 LP, all to user1 auto bond
+    deps.querier.with_token_balances(&[
+        (
+            &ASTROPORT_GENERATOR.to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(13200u128))],
+        ),
+    ]);
+    let res: UserStatsResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg.clone()).unwrap()).unwrap();
+    assert_eq!(res.user_stats[0].bond_amount, Uint128::from(8200u128));
+    assert_eq!(res.user_stats[0].unrealized_gain, Uint128::from(1200u128));
+
+    // unbond half, realize half of the gain
+    let info = mock_info(USER1, &[]);
+    let unbond_msg = ExecuteMsg::unbond {
+        asset_token: ASTRO_TOKEN.to_string(),
+        amount: Uint128::from(4100u128),
+        zap_out: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, unbond_msg);
+    assert!(res.is_ok());
+    deps.querier.with_token_balances(&[
+        (
+            &ASTROPORT_GENERATOR.to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(9100u128))],
+        ),
+    ]);
+
+    let res: UserStatsResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res.user_stats,
+        vec![UserStatsResponseItem {
+            asset_token: ASTRO_TOKEN.to_string(),
+            bond_amount: Uint128::from(4100u128),
+            lp_bonded: Uint128::from(10000u128),
+            lp_unbonded: Uint128::from(7100u128),
+            farm_withdrawn: Uint128::from(1000u128),
+            spec_withdrawn: Uint128::from(2700u128),
+            deposit_fee_paid: Uint128::zero(),
+            cost_basis: Uint128::from(3500u128),
+            realized_gain: Uint128::from(600u128),
+            realized_loss: Uint128::zero(),
+            unrealized_gain: Uint128::from(600u128),
+            unrealized_loss: Uint128::zero(),
+            withdraw_fee_paid: Uint128::zero(),
+            farm2_withdrawn: Uint128::zero(),
+        }]
+    );
+
+    // LP value drops below cost, loss is not hidden by gain
+    deps.querier.with_token_balances(&[
+        (
+            &ASTROPORT_GENERATOR.to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(8100u128))],
+        ),
+    ]);
+    let msg = QueryMsg::user_stats {
+        staker_addr: USER1.to_string(),
+    };
+    let res: UserStatsResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg.clone()).unwrap()).unwrap();
+    assert_eq!(res.user_stats[0].bond_amount, Uint128::from(3100u128));
+    assert_eq!(res.user_stats[0].unrealized_gain, Uint128::zero());
+    assert_eq!(res.user_stats[0].unrealized_loss, Uint128::from(400u128));
+
+    // cost of 500 LP = 3500 * 500 / 3100 = 564
+    let info = mock_info(USER1, &[]);
+    let unbond_msg = ExecuteMsg::unbond {
+        asset_token: ASTRO_TOKEN.to_string(),
+        amount: Uint128::from(500u128),
+        zap_out: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, unbond_msg);
+    assert!(res.is_ok());
+    deps.querier.with_token_balances(&[
+        (
+            &ASTROPORT_GENERATOR.to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(7600u128))],
+        ),
+    ]);
+    let res: UserStatsResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.user_stats[0].cost_basis, Uint128::from(2936u128));
+    assert_eq!(res.user_stats[0].realized_gain, Uint128::from(600u128));
+    assert_eq!(res.user_stats[0].realized_loss, Uint128::from(64u128));
+    assert_eq!(res.user_stats[0].unrealized_loss, Uint128::from(336u128));
+
+    // user2 stats are initialized on bond
+    let msg = QueryMsg::user_stats {
+        staker_addr: USER2.to_string(),
+    };
+    let res: UserStatsResponse = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+    assert_eq!(res.user_stats[0].lp_bonded, Uint128::from(5000u128));
+    assert_eq!(res.user_stats[0].cost_basis, Uint128::from(5000u128));
+}
+
+fn test_unbond_zap_out(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+    let zap_out = ZapOutParams {
+        sell_asset: AssetInfo::Token {
+            contract_addr: ASTRO_TOKEN.to_string(),
+        },
+        sell_asset_b: None,
+        target_asset: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        belief_price: Some(Decimal::percent(150)),
+        belief_price_b: None,
+        max_spread: Decimal::percent(1),
+        swap_hints: None,
+        min_receive: Some(Uint128::from(290u128)),
+    };
+
+    // staker is not set
+    let info = mock_info(USER1, &[]);
+    let msg = ExecuteMsg::unbond {
+        asset_token: ASTRO_TOKEN.to_string(),
+        amount: Uint128::from(100u128),
+        zap_out: Some(zap_out.clone()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert!(res.is_err());
+
+    let update_msg = ExecuteMsg::update_config {
+        owner: None,
+        controller: None,
+        community_fee: None,
+        platform_fee: None,
+        controller_fee: None,
+        deposit_fee: None,
+        spectrum_staker: Some(SPEC_STAKER.to_string()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(SPEC_GOV, &[]), update_msg);
+    assert!(res.is_ok());
+
+    // LP is sent to staker with user as receiver
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTROPORT_GENERATOR.to_string(),
+                funds: vec![],
+                msg: to_binary(&AstroportExecuteMsg::Withdraw {
+                    amount: Uint128::from(100u128),
+                    lp_token: deps.api.addr_validate(ASTRO_LP).unwrap(),
+                }).unwrap(),
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_LP.to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: SPEC_STAKER.to_string(),
+                    amount: Uint128::from(100u128),
+                    msg: to_binary(&StakerCw20HookMsg::zap_to_unbond {
+                        sell_asset: zap_out.sell_asset,
+                        sell_asset_b: None,
+                        target_asset: zap_out.target_asset,
+                        belief_price: zap_out.belief_price,
+                        belief_price_b: None,
+                        max_spread: zap_out.max_spread,
+                        swap_hints: None,
+                        staker_addr: Some(USER1.to_string()),
+                        min_receive: zap_out.min_receive,
+                    }).unwrap(),
+                })
+                    .unwrap(),
+            }),
+        ]
+    );
+}
+
+fn test_unbond_for(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+    let msg = ExecuteMsg::unbond_for {
+        staker_addr: USER1.to_string(),
+        asset_token: ASTRO_TOKEN.to_string(),
+        amount: Uint128::from(100u128),
+    };
+
+    // only spectrum_staker
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER1, &[]), msg.clone());
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    // LP is sent to staker
+    let res = execute(deps.as_mut(), env, mock_info(SPEC_STAKER, &[]), msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTROPORT_GENERATOR.to_string(),
+                funds: vec![],
+                msg: to_binary(&AstroportExecuteMsg::Withdraw {
+                    amount: Uint128::from(100u128),
+                    lp_token: deps.api.addr_validate(ASTRO_LP).unwrap(),
+                }).unwrap(),
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_LP.to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: SPEC_STAKER.to_string(),
+                    amount: Uint128::from(100u128),
+                }).unwrap(),
+            }),
+        ]
+    );
+}
+
+fn test_unbond_withdraw_fee(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let mut env = mock_env();
+    let msg = ExecuteMsg::update_fee_config {
+        fee_recipients: None,
+        deposit_fee_tiers: None,
+        insert_fee_exempt: None,
+        remove_fee_exempt: None,
+        withdraw_fee: Some(Decimal::percent(10)),
+        withdraw_fee_period: Some(1000u64),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(SPEC_GOV, &[]), msg);
+    assert!(res.is_ok());
+
+    // half of the period has passed
+    env.block.time = env.block.time.plus_seconds(500);
+    let res: FarmRewardInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::reward_info {
+                staker_addr: USER1.to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.reward_infos[0].withdraw_fee, Decimal::percent(5));
+
+    let stats_msg = QueryMsg::user_stats {
+        staker_addr: USER1.to_string(),
+    };
+    let before: UserStatsResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), stats_msg.clone()).unwrap()).unwrap();
+
+    // fee stays staked and is credited to auto-compound bonders
+    let info = mock_info(USER1, &[]);
+    let msg = ExecuteMsg::unbond {
+        asset_token: ASTRO_TOKEN.to_string(),
+        amount: Uint128::from(100u128),
+        zap_out: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTROPORT_GENERATOR.to_string(),
+                funds: vec![],
+                msg: to_binary(&AstroportExecuteMsg::Withdraw {
+                    amount: Uint128::from(95u128),
+                    lp_token: deps.api.addr_validate(ASTRO_LP).unwrap(),
+                }).unwrap(),
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_LP.to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: USER1.to_string(),
+                    amount: Uint128::from(95u128),
+                }).unwrap(),
+            }),
+        ]
+    );
+
+    // stats record net amount, fee is realized as loss
+    let after: UserStatsResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), stats_msg).unwrap()).unwrap();
+    let (before, after) = (&before.user_stats[0], &after.user_stats[0]);
+    assert_eq!(after.lp_unbonded, before.lp_unbonded + Uint128::from(95u128));
+    assert_eq!(after.withdraw_fee_paid, Uint128::from(5u128));
+    assert_eq!(after.cost_basis, before.cost_basis - Uint128::from(100u128));
+    assert_eq!(after.realized_loss, before.realized_loss + Uint128::from(5u128));
+
+    // no fee after the period
+    env.block.time = env.block.time.plus_seconds(500);
+    let res: FarmRewardInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env,
+            QueryMsg::reward_info {
+                staker_addr: USER1.to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.reward_infos[0].withdraw_fee, Decimal::zero());
+}
+
 // fn test_staked_reward(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
 //     // unbond user1
 //     let info = mock_info(USER1, &[]);
 //     let msg = ExecuteMsg::unbond {
 //         asset_token: MIR_TOKEN.to_string(),
 //         amount: Uint128::from(13200u128),
+//         zap_out: None,
 //     };
 //     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
 //     assert!(res.is_ok());
@@ -583,6 +1031,7 @@ fn test_bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
 //                 msg: to_binary(&MirrorStakingExecuteMsg::unbond {
 //                     amount: Uint128::from(13200u128),
 //                     asset_token: MIR_TOKEN.to_string(),
+//                     zap_out: None,
 //                 })
 //                 .unwrap(),
 //             }),
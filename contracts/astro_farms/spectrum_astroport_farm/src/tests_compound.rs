@@ -1,7 +1,9 @@
 use crate::bond::{deposit_farm_share};
 use crate::contract::{execute, instantiate, query};
 use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
-use crate::state::{pool_info_read, pool_info_store, read_config, read_state, state_store};
+use crate::state::{
+    pool_info_read, pool_info_store, pool_pending_astro_read, read_config, read_state, state_store,
+};
 use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{from_binary, to_binary, Coin, CosmosMsg, Decimal, OwnedDeps, Uint128, WasmMsg, Api};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
@@ -15,6 +17,7 @@ use astroport::pair::{Cw20HookMsg as AstroportCw20HookMsg, ExecuteMsg as Astropo
 use spectrum_protocol::astroport_farm::{
     ConfigInfo, Cw20HookMsg, ExecuteMsg, PoolItem, PoolsResponse, QueryMsg, StateInfo,
 };
+use spectrum_protocol::fee::FeeRecipient;
 use astroport::generator::{
     ExecuteMsg as AstroportExecuteMsg,
 };
@@ -37,6 +40,9 @@ const USER2: &str = "user2";
 const ANC_MARKET: &str = "anc_market";
 const AUST_TOKEN: &str = "aust_token";
 const PAIR_CONTRACT: &str = "pair_contract";
+const SPY_TOKEN: &str = "spy_token";
+const SPY_LP: &str = "spy_lp";
+const SPY_PAIR: &str = "spy_pair";
 
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -84,7 +90,23 @@ fn test() {
                 liquidity_token: deps.api.addr_validate(ASTRO_LP).unwrap(),
                 pair_type: PairType::Xyk {}
             },
-        )
+        ),
+        (
+            &"astro_tokenspy_token".to_string(),
+            &PairInfo {
+                asset_infos: [
+                    AssetInfo::Token {
+                        contract_addr: deps.api.addr_validate(ASTRO_TOKEN).unwrap(),
+                    },
+                    AssetInfo::Token {
+                        contract_addr: deps.api.addr_validate(SPY_TOKEN).unwrap(),
+                    },
+                ],
+                contract_addr: deps.api.addr_validate(SPY_PAIR).unwrap(),
+                liquidity_token: deps.api.addr_validate(SPY_LP).unwrap(),
+                pair_type: PairType::Xyk {}
+            },
+        ),
     ]);
     deps.querier.with_tax(
         Decimal::percent(1),
@@ -98,6 +120,8 @@ fn test() {
     test_bond(&mut deps);
     test_compound_astro(&mut deps);
     test_compound_astro_with_fees(&mut deps);
+    test_send_fee_recipients(&mut deps);
+    test_compound_multi_pool(&mut deps);
 }
 
 fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> ConfigInfo {
@@ -121,6 +145,12 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         anchor_market: ANC_MARKET.to_string(),
         aust_token: AUST_TOKEN.to_string(),
         pair_contract: PAIR_CONTRACT.to_string(),
+        spectrum_staker: None,
+        fee_recipients: vec![],
+        deposit_fee_tiers: vec![],
+        deposit_fee_exempt: vec![],
+        withdraw_fee: Decimal::zero(),
+        withdraw_fee_period: 0u64,
     };
 
     // success init
@@ -155,6 +185,7 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         platform_fee: None,
         controller_fee: None,
         deposit_fee: None,
+        spectrum_staker: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
     assert!(res.is_err());
@@ -180,6 +211,7 @@ fn test_register_asset(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
         asset_token: ASTRO_TOKEN.to_string(),
         staking_token: ASTRO_LP.to_string(),
         weight: 1u32,
+        pair_contract: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
     assert!(res.is_err());
@@ -207,6 +239,9 @@ fn test_register_asset(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
                 total_stake_bond_amount: Uint128::zero(),
                 total_stake_bond_share: Uint128::zero(),
                 total_auto_bond_share: Uint128::zero(),
+                fee_recipients: None,
+                pair_contract: None,
+                incentives: vec![],
             }]
         }
     );
@@ -216,6 +251,7 @@ fn test_register_asset(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
         asset_token: FAIL_TOKEN.to_string(),
         staking_token: FAIL_LP.to_string(),
         weight: 2u32,
+        pair_contract: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg);
     assert!(res.is_err());
@@ -230,7 +266,7 @@ fn test_compound_unauthorized(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMoc
     // reinvest err
     let env = mock_env();
     let info = mock_info(TEST_CREATOR, &[]);
-    let msg = ExecuteMsg::compound {};
+    let msg = ExecuteMsg::compound { asset_token: None };
     let res = execute(deps.as_mut(), env, info, msg);
     assert!(res.is_err());
 }
@@ -239,7 +275,7 @@ fn test_compound_zero(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier
     // reinvest zero
     let env = mock_env();
     let info = mock_info(TEST_CONTROLLER, &[]);
-    let msg = ExecuteMsg::compound {};
+    let msg = ExecuteMsg::compound { asset_token: None };
     let res = execute(deps.as_mut(), env, info, msg).unwrap();
 
     assert_eq!(
@@ -384,6 +420,7 @@ fn test_bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     let msg = ExecuteMsg::unbond {
         asset_token: ASTRO_TOKEN.to_string(),
         amount: Uint128::from(3000u128),
+        zap_out: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
     assert!(res.is_ok());
@@ -637,11 +674,14 @@ fn test_compound_astro(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
     deps.querier.with_token_balances(&[
         (
             &ASTRO_TOKEN.to_string(),
-            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(12000u128))],
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(3000u128))],
         ),
         (
             &ASTROPORT_GENERATOR.to_string(),
-            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(12000u128))],
+            &[
+                (&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(12000u128)),
+                (&ASTRO_TOKEN.to_string(), &Uint128::from(12000u128)),
+            ],
         ),
         (
             &ASTRO_GOV_PROXY.to_string(),
@@ -665,7 +705,7 @@ fn test_compound_astro(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
     provide ASTRO = 2052
     remaining = 48
     */
-    let msg = ExecuteMsg::compound {};
+    let msg = ExecuteMsg::compound { asset_token: None };
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
     assert_eq!(
@@ -826,6 +866,7 @@ fn test_compound_astro_with_fees(deps: &mut OwnedDeps<MockStorage, MockApi, Wasm
         platform_fee: Some(Decimal::percent(1u64)),
         controller_fee: Some(Decimal::percent(1u64)),
         deposit_fee: None,
+        spectrum_staker: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg);
     assert!(res.is_ok());
@@ -835,11 +876,14 @@ fn test_compound_astro_with_fees(deps: &mut OwnedDeps<MockStorage, MockApi, Wasm
     deps.querier.with_token_balances(&[
         (
             &ASTRO_TOKEN.to_string(),
-            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(12100u128))],
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(3000u128))],
         ),
         (
             &ASTROPORT_GENERATOR.to_string(),
-            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(12100u128))],
+            &[
+                (&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(12100u128)),
+                (&ASTRO_TOKEN.to_string(), &Uint128::from(12100u128)),
+            ],
         ),
         (
             &ASTRO_GOV_PROXY.to_string(),
@@ -870,7 +914,7 @@ fn test_compound_astro_with_fees(deps: &mut OwnedDeps<MockStorage, MockApi, Wasm
     controller fee = 121 / 605 * 590 = 118
     total swap amount 2647 ASTRO
     */
-    let msg = ExecuteMsg::compound {};
+    let msg = ExecuteMsg::compound { asset_token: None };
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
     assert_eq!(
@@ -915,7 +959,10 @@ fn test_compound_astro_with_fees(deps: &mut OwnedDeps<MockStorage, MockApi, Wasm
             }),
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: MOCK_CONTRACT_ADDR.to_string(),
-                msg: to_binary(&ExecuteMsg::send_fee {}).unwrap(),
+                msg: to_binary(&ExecuteMsg::send_fee {
+                    asset_token: Some(ASTRO_TOKEN.to_string()),
+                    previous_aust_balance: Some(Uint128::zero()),
+                }).unwrap(),
                 funds: vec![],
             }),
             CosmosMsg::Wasm(WasmMsg::Execute {
@@ -975,7 +1022,10 @@ fn test_compound_astro_with_fees(deps: &mut OwnedDeps<MockStorage, MockApi, Wasm
 
     // cannot call send fee from others
     let info = mock_info(SPEC_GOV, &[]);
-    let msg = ExecuteMsg::send_fee {};
+    let msg = ExecuteMsg::send_fee {
+        asset_token: None,
+        previous_aust_balance: None,
+    };
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
     assert!(res.is_err());
 
@@ -1014,3 +1064,299 @@ fn test_compound_astro_with_fees(deps: &mut OwnedDeps<MockStorage, MockApi, Wasm
             }),
         ]);
 }
+
+fn test_send_fee_recipients(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+    let msg = ExecuteMsg::update_fee_config {
+        fee_recipients: Some(vec![
+            FeeRecipient {
+                address: SPEC_GOV.to_string(),
+                weight: 1u32,
+            },
+            FeeRecipient {
+                address: TEST_CONTROLLER.to_string(),
+                weight: 3u32,
+            },
+        ]),
+        deposit_fee_tiers: None,
+        insert_fee_exempt: None,
+        remove_fee_exempt: None,
+        withdraw_fee: None,
+        withdraw_fee_period: None,
+    };
+
+    // only owner
+    let res = execute(deps.as_mut(), env.clone(), mock_info(TEST_CONTROLLER, &[]), msg.clone());
+    assert!(res.is_err());
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info(SPEC_GOV, &[]), msg);
+    assert!(res.is_ok());
+
+    // fee recipients replace community/platform/controller split
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let msg = ExecuteMsg::send_fee {
+        asset_token: Some(ASTRO_TOKEN.to_string()),
+        previous_aust_balance: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: AUST_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: SPEC_GOV.to_string(),
+                    amount: Uint128::from(147u128),
+                }).unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: AUST_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: TEST_CONTROLLER.to_string(),
+                    amount: Uint128::from(443u128),
+                }).unwrap(),
+                funds: vec![],
+            }),
+        ]);
+
+    // pool override
+    let pool_msg = ExecuteMsg::update_pool_fee_recipients {
+        asset_token: ASTRO_TOKEN.to_string(),
+        fee_recipients: Some(vec![FeeRecipient {
+            address: SPEC_PLATFORM.to_string(),
+            weight: 1u32,
+        }]),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(SPEC_GOV, &[]), pool_msg);
+    assert!(res.is_ok());
+
+    // pool override only splits aUST from this compound, 90 aUST left before goes to fee recipients
+    let msg = ExecuteMsg::send_fee {
+        asset_token: Some(ASTRO_TOKEN.to_string()),
+        previous_aust_balance: Some(Uint128::from(90u128)),
+    };
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: AUST_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: SPEC_PLATFORM.to_string(),
+                    amount: Uint128::from(500u128),
+                }).unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: AUST_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: SPEC_GOV.to_string(),
+                    amount: Uint128::from(22u128),
+                }).unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: AUST_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: TEST_CONTROLLER.to_string(),
+                    amount: Uint128::from(68u128),
+                }).unwrap(),
+                funds: vec![],
+            }),
+        ]);
+}
+
+fn test_compound_multi_pool(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+
+    // register another ASTRO pair
+    let info = mock_info(SPEC_GOV, &[]);
+    let msg = ExecuteMsg::register_asset {
+        asset_token: SPY_TOKEN.to_string(),
+        staking_token: SPY_LP.to_string(),
+        weight: 1u32,
+        pair_contract: Some(SPY_PAIR.to_string()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    let msg = QueryMsg::state {};
+    let res: StateInfo = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.total_weight, 2u32);
+
+    deps.querier.with_token_balances(&[
+        (
+            &ASTROPORT_GENERATOR.to_string(),
+            &[
+                (&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(14000u128)),
+                (&ASTRO_TOKEN.to_string(), &Uint128::from(1000u128)),
+            ],
+        ),
+    ]);
+
+    // bond to ASTRO pool, ASTRO claimed by generator is kept for ASTRO pool
+    let info = mock_info(ASTRO_LP, &[]);
+    let msg = ExecuteMsg::receive(Cw20ReceiveMsg {
+        sender: USER2.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::bond {
+            staker_addr: None,
+            asset_token: ASTRO_TOKEN.to_string(),
+            compound_rate: None,
+        }).unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    let astro_token_raw = deps.api.addr_canonicalize(ASTRO_TOKEN).unwrap();
+    let pending_astro = pool_pending_astro_read(&deps.storage)
+        .load(astro_token_raw.as_slice())
+        .unwrap();
+    assert_eq!(pending_astro, Uint128::from(1000u128));
+
+    deps.querier.with_token_balances(&[
+        (
+            &ASTROPORT_GENERATOR.to_string(),
+            &[
+                (&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000u128)),
+                (&ASTRO_TOKEN.to_string(), &Uint128::from(1000u128)),
+            ],
+        ),
+    ]);
+
+    /*
+    pending rewards 1000 ASTRO (ASTRO claimed for ASTRO pool is not included)
+    total fee = 50
+    compound amount = 950
+    swap amount 475 ASTRO -> 474 SPY
+    provide ASTRO = 474
+    fee swap amount 50 ASTRO -> 50 UST -> 49 UST -> 48 UST deposited
+    */
+    let info = mock_info(TEST_CONTROLLER, &[]);
+    let msg = ExecuteMsg::compound {
+        asset_token: Some(SPY_TOKEN.to_string()),
+    };
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTROPORT_GENERATOR.to_string(),
+                funds: vec![],
+                msg: to_binary(&AstroportExecuteMsg::Withdraw {
+                    lp_token: deps.api.addr_validate(SPY_LP).unwrap(),
+                    amount: Uint128::zero()
+                }).unwrap(),
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: PAIR_CONTRACT.to_string(),
+                    amount: Uint128::from(50u128),
+                    msg: to_binary(&AstroportCw20HookMsg::Swap {
+                        max_spread: None,
+                        belief_price: None,
+                        to: None,
+                    }).unwrap()
+                }).unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: SPY_PAIR.to_string(),
+                    amount: Uint128::from(475u128),
+                    msg: to_binary(&AstroportCw20HookMsg::Swap {
+                        max_spread: None,
+                        belief_price: None,
+                        to: None,
+                    }).unwrap()
+                }).unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ANC_MARKET.to_string(),
+                msg: to_binary(&moneymarket::market::ExecuteMsg::DepositStable {}).unwrap(),
+                funds: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(48u128),
+                }],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: SPEC_GOV.to_string(),
+                msg: to_binary(&spectrum_protocol::gov::ExecuteMsg::mint {}).unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::send_fee {
+                    asset_token: Some(SPY_TOKEN.to_string()),
+                    previous_aust_balance: Some(Uint128::zero()),
+                }).unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: SPY_PAIR.to_string(),
+                    amount: Uint128::from(474u128),
+                    expires: None
+                }).unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: SPY_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: SPY_PAIR.to_string(),
+                    amount: Uint128::from(474u128),
+                    expires: None
+                }).unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: SPY_PAIR.to_string(),
+                msg: to_binary(&AstroportPairExecuteMsg::ProvideLiquidity {
+                    assets: [
+                        Asset {
+                            info: AssetInfo::Token {
+                                contract_addr: deps.api.addr_validate(ASTRO_TOKEN).unwrap(),
+                            },
+                            amount: Uint128::from(474u128),
+                        },
+                        Asset {
+                            info: AssetInfo::Token {
+                                contract_addr: deps.api.addr_validate(SPY_TOKEN).unwrap(),
+                            },
+                            amount: Uint128::from(474u128),
+                        },
+                    ],
+                    slippage_tolerance: None,
+                    auto_stake: Some(true),
+                    receiver: None
+                }).unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+
+    let spy_token_raw = deps.api.addr_canonicalize(SPY_TOKEN).unwrap();
+    let pending_astro = pool_pending_astro_read(&deps.storage)
+        .may_load(spy_token_raw.as_slice())
+        .unwrap();
+    assert_eq!(pending_astro, None);
+    let pending_astro = pool_pending_astro_read(&deps.storage)
+        .load(astro_token_raw.as_slice())
+        .unwrap();
+    assert_eq!(pending_astro, Uint128::from(1000u128));
+}
@@ -4,6 +4,12 @@ use crate::state::{
     pool_info_read, pool_info_store, read_config, read_state, rewards_read, rewards_store,
     state_store, Config, PoolInfo, RewardInfo, State,
 };
+use spectrum_protocol::fee::credit_withdraw_fee;
+use spectrum_protocol::incentive::{
+    self, incentive_to_bonder, incentive_to_pool, is_incentive_rewards_empty,
+    to_incentive_reward_items,
+};
+use spectrum_protocol::user_stat::{self, read_user_stat, user_stat_store, UserStatsResponse};
 
 use cw20::Cw20ExecuteMsg;
 
@@ -12,7 +18,8 @@ use astroport::generator::{
     Cw20HookMsg as AstroportCw20HookMsg, ExecuteMsg as AstroportExecuteMsg,
 };
 use spectrum_protocol::astroport_luna_ust_farm::{RewardInfoResponse, RewardInfoResponseItem};
-use spectrum_protocol::farm_helper::compute_deposit_time;
+use spectrum_protocol::farm_helper::{compute_deposit_time, unbond_lp_msg};
+use spectrum_protocol::staker::ZapOutParams;
 use spectrum_protocol::gov::{
     BalanceResponse as SpecBalanceResponse, ExecuteMsg as SpecExecuteMsg, QueryMsg as SpecQueryMsg,
 };
@@ -39,12 +46,20 @@ fn bond_internal(
         deposit_spec_reward(deps.as_ref(), env, &mut state, config, false)?;
         spec_reward_to_pool(&state, &mut pool_info, lp_balance)?;
     }
+    incentive_to_pool(&mut pool_info, env.block.time.seconds(), lp_balance)?;
 
     // withdraw reward to pending reward; before changing share
     let mut reward_info = rewards_read(deps.storage, &sender_addr_raw)
         .may_load(asset_denom.as_bytes())?
         .unwrap_or_else(|| RewardInfo::create(&pool_info));
     before_share_change(&pool_info, &mut reward_info);
+    let mut user_stat = read_user_stat(
+        deps.storage,
+        &sender_addr_raw,
+        asset_denom.as_bytes(),
+        pool_info.calc_user_auto_balance(lp_balance, reward_info.auto_bond_share)
+            + pool_info.calc_user_stake_balance(reward_info.stake_bond_share),
+    )?;
 
     if !reallocate &&
         reward_info.deposit_amount.is_zero() &&
@@ -57,7 +72,11 @@ fn bond_internal(
     }
 
     // increase bond_amount
-    let deposit_fee = if reallocate || sender_addr_raw == config.controller { Decimal::zero() } else { config.deposit_fee };
+    let deposit_fee = if reallocate {
+        Decimal::zero()
+    } else {
+        config.calc_deposit_fee(&sender_addr_raw, amount_to_auto + amount_to_stake)
+    };
     let deposit_fee_auto = amount_to_auto * deposit_fee;
     let deposit_fee_stake = amount_to_stake * deposit_fee;
     let auto_bond_amount = amount_to_auto.checked_sub(deposit_fee_auto)?;
@@ -95,6 +114,9 @@ fn bond_internal(
             reward_info.deposit_time,
             env.block.time.seconds(),
         )?;
+
+        user_stat.bond(new_deposit_amount, earned_deposit_fee);
+        user_stat_store(deps.storage, &sender_addr_raw).save(asset_denom.as_bytes(), &user_stat)?;
     }
 
     rewards_store(deps.storage, &sender_addr_raw)
@@ -271,6 +293,8 @@ fn before_share_change(pool_info: &PoolInfo, reward_info: &mut RewardInfo) {
     reward_info.spec_share += spec_share;
     reward_info.stake_spec_share_index = pool_info.stake_spec_share_index;
     reward_info.auto_spec_share_index = pool_info.auto_spec_share_index;
+
+    incentive_to_bonder(pool_info, reward_info);
 }
 
 // increase share amount in pool and reward info
@@ -334,7 +358,8 @@ fn unbond_internal(
     lp_balance: Uint128,
     config: &Config,
     reallocate: bool,
-) -> StdResult<PoolInfo> {
+    withdraw_fee: Decimal,
+) -> StdResult<(PoolInfo, Uint128)> {
     let mut state = read_state(deps.storage)?;
     let mut pool_info = pool_info_read(deps.storage).load(asset_denom.as_bytes())?;
     let mut reward_info =
@@ -352,6 +377,7 @@ fn unbond_internal(
     // distribute reward to pending reward; before changing share
     deposit_spec_reward(deps.as_ref(), env, &mut state, config, false)?;
     spec_reward_to_pool(&state, &mut pool_info, lp_balance)?;
+    incentive_to_pool(&mut pool_info, env.block.time.seconds(), lp_balance)?;
     before_share_change(&pool_info, &mut reward_info);
 
     // decrease bond amount
@@ -384,10 +410,22 @@ fn unbond_internal(
     reward_info.auto_bond_share = reward_info.auto_bond_share.checked_sub(auto_bond_share)?;
     reward_info.stake_bond_share = reward_info.stake_bond_share.checked_sub(stake_bond_share)?;
 
+    let withdraw_fee_amount = credit_withdraw_fee(
+        amount * withdraw_fee,
+        pool_info.total_auto_bond_share,
+        pool_info.total_stake_bond_share,
+        &mut pool_info.total_stake_bond_amount,
+    );
+
     if !reallocate {
         reward_info.deposit_amount = reward_info
             .deposit_amount
             .multiply_ratio(user_balance.checked_sub(amount)?, user_balance);
+
+        let mut user_stat =
+            read_user_stat(deps.storage, &staker_addr_raw, asset_denom.as_bytes(), user_balance)?;
+        user_stat.unbond(amount, user_balance, withdraw_fee_amount)?;
+        user_stat_store(deps.storage, &staker_addr_raw).save(asset_denom.as_bytes(), &user_stat)?;
     }
 
     // update rewards info
@@ -395,6 +433,7 @@ fn unbond_internal(
         && reward_info.farm_share.is_zero()
         && reward_info.auto_bond_share.is_zero()
         && reward_info.stake_bond_share.is_zero()
+        && is_incentive_rewards_empty(&reward_info.incentive_rewards)
         && !reallocate
     {
         rewards_store(deps.storage, &staker_addr_raw).remove(asset_denom.as_bytes());
@@ -407,7 +446,7 @@ fn unbond_internal(
     pool_info_store(deps.storage).save(asset_denom.as_bytes(), &pool_info)?;
     state_store(deps.storage).save(&state)?;
 
-    Ok(pool_info)
+    Ok((pool_info, withdraw_fee_amount))
 }
 
 pub fn unbond(
@@ -416,6 +455,7 @@ pub fn unbond(
     info: MessageInfo,
     asset_denom: String,
     amount: Uint128,
+    zap_out: Option<ZapOutParams>,
 ) -> StdResult<Response> {
     let staker_addr_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
 
@@ -429,7 +469,18 @@ pub fn unbond(
         &config.astroport_generator,
     )?;
 
-    let pool_info = unbond_internal(
+    let withdraw_fee = match rewards_read(deps.storage, &staker_addr_raw)
+        .may_load(asset_denom.as_bytes())?
+    {
+        Some(reward_info) => config.calc_withdraw_fee(
+            &staker_addr_raw,
+            reward_info.deposit_time,
+            env.block.time.seconds(),
+        ),
+        None => Decimal::zero(),
+    };
+
+    let (pool_info, withdraw_fee_amount) = unbond_internal(
         deps.branch(),
         &env,
         staker_addr_raw,
@@ -438,6 +489,17 @@ pub fn unbond(
         lp_balance,
         &config,
         false,
+        withdraw_fee,
+    )?;
+    let amount = amount.checked_sub(withdraw_fee_amount)?;
+
+    // send LP to user, or to spectrum_staker to withdraw liquidity and swap to target_asset
+    let transfer_msg = unbond_lp_msg(
+        deps.api,
+        config.spectrum_staker.as_ref(),
+        info.sender.to_string(),
+        amount,
+        zap_out,
     )?;
 
     Ok(Response::new()
@@ -458,10 +520,7 @@ pub fn unbond(
                     .api
                     .addr_humanize(&pool_info.staking_token)?
                     .to_string(),
-                msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                    recipient: info.sender.to_string(),
-                    amount,
-                })?,
+                msg: to_binary(&transfer_msg)?,
                 funds: vec![],
             }),
         ])
@@ -470,6 +529,7 @@ pub fn unbond(
             attr("staker_addr", info.sender),
             attr("asset_token", asset_denom),
             attr("amount", amount),
+            attr("withdraw_fee_amount", withdraw_fee_amount),
         ]))
 }
 
@@ -505,6 +565,7 @@ pub fn update_bond(
         lp_balance,
         &config,
         true,
+        Decimal::zero(),
     )?;
 
     bond_internal(
@@ -527,6 +588,108 @@ pub fn update_bond(
     ]))
 }
 
+fn query_lp_balance(deps: Deps, env: &Env, pool_info: &PoolInfo) -> StdResult<Uint128> {
+    let config = read_config(deps.storage)?;
+    query_astroport_pool_balance(
+        deps,
+        &pool_info.staking_token,
+        &env.contract.address,
+        &config.astroport_generator,
+    )
+}
+
+pub fn add_incentive(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    depositor: String,
+    asset_token: String,
+    amount: Uint128,
+    duration: u64,
+) -> StdResult<Response> {
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    let key = asset_token_raw.as_slice();
+    let mut pool_info = pool_info_read(deps.storage).load(key)?;
+
+    let response = incentive::add_incentive(
+        deps.branch(),
+        &env,
+        &info,
+        &mut pool_info,
+        |deps, pool_info| query_lp_balance(deps, &env, pool_info),
+        depositor,
+        asset_token.clone(),
+        amount,
+        duration,
+    )?;
+    pool_info_store(deps.storage).save(key, &pool_info)?;
+
+    Ok(response)
+}
+
+pub fn withdraw_incentives(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_token: String,
+    tokens: Option<Vec<String>>,
+) -> StdResult<Response> {
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    let key = asset_token_raw.as_slice();
+    let mut pool_info = pool_info_read(deps.storage).load(key)?;
+    let staker_addr = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut reward_info = rewards_read(deps.storage, &staker_addr).load(key)?;
+
+    let response = incentive::withdraw_incentives(
+        deps.branch(),
+        &env,
+        &info,
+        &mut pool_info,
+        &mut reward_info,
+        |deps, pool_info| query_lp_balance(deps, &env, pool_info),
+        asset_token.clone(),
+        tokens,
+    )?;
+    pool_info_store(deps.storage).save(key, &pool_info)?;
+    if reward_info.spec_share.is_zero()
+        && reward_info.farm_share.is_zero()
+        && reward_info.auto_bond_share.is_zero()
+        && reward_info.stake_bond_share.is_zero()
+        && is_incentive_rewards_empty(&reward_info.incentive_rewards)
+    {
+        rewards_store(deps.storage, &staker_addr).remove(key);
+    } else {
+        rewards_store(deps.storage, &staker_addr).save(key, &reward_info)?;
+    }
+
+    Ok(response)
+}
+
+pub fn refund_incentive(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_token: String,
+    token: String,
+) -> StdResult<Response> {
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    let key = asset_token_raw.as_slice();
+    let mut pool_info = pool_info_read(deps.storage).load(key)?;
+
+    let response = incentive::refund_incentive(
+        deps.branch(),
+        &env,
+        &info,
+        &mut pool_info,
+        |deps, pool_info| query_lp_balance(deps, &env, pool_info),
+        asset_token.clone(),
+        token,
+    )?;
+    pool_info_store(deps.storage).save(key, &pool_info)?;
+
+    Ok(response)
+}
+
 pub fn withdraw(
     mut deps: DepsMut,
     env: Env,
@@ -668,6 +831,7 @@ fn withdraw_reward(
         )?;
 
         spec_reward_to_pool(state, &mut pool_info, lp_balance)?;
+        incentive_to_pool(&mut pool_info, env.block.time.seconds(), lp_balance)?;
         before_share_change(&pool_info, &mut reward_info);
 
         // update withdraw
@@ -705,6 +869,17 @@ fn withdraw_reward(
             };
         spec_share += asset_spec_share;
         spec_amount += asset_spec_amount;
+
+        let mut user_stat = read_user_stat(
+            deps.storage,
+            staker_addr,
+            key,
+            pool_info.calc_user_auto_balance(lp_balance, reward_info.auto_bond_share)
+                + pool_info.calc_user_stake_balance(reward_info.stake_bond_share),
+        )?;
+        user_stat.farm_withdrawn += asset_farm_amount;
+        user_stat.spec_withdrawn += asset_spec_amount;
+        user_stat_store(deps.storage, staker_addr).save(key, &user_stat)?;
         pool_info.farm_share = pool_info.farm_share.checked_sub(asset_farm_share)?;
         reward_info.farm_share = reward_info.farm_share.checked_sub(asset_farm_share)?;
         reward_info.spec_share = reward_info.spec_share.checked_sub(asset_spec_share)?;
@@ -715,6 +890,7 @@ fn withdraw_reward(
             && reward_info.farm_share.is_zero()
             && reward_info.auto_bond_share.is_zero()
             && reward_info.stake_bond_share.is_zero()
+            && is_incentive_rewards_empty(&reward_info.incentive_rewards)
         {
             rewards_store(deps.storage, staker_addr).remove(key);
         } else {
@@ -837,6 +1013,7 @@ fn read_reward_infos(
             )?;
 
             spec_reward_to_pool(state, &mut pool_info, lp_balance)?;
+            incentive_to_pool(&mut pool_info, env.block.time.seconds(), lp_balance)?;
             before_share_change(&pool_info, &mut reward_info);
 
             let auto_bond_amount =
@@ -870,9 +1047,41 @@ fn read_reward_infos(
                 } else {
                     None
                 },
+                withdraw_fee: config.calc_withdraw_fee(
+                    staker_addr,
+                    reward_info.deposit_time,
+                    env.block.time.seconds(),
+                ),
+                incentive_rewards: to_incentive_reward_items(deps.api, &reward_info.incentive_rewards)?,
             })
         })
         .collect::<StdResult<Vec<RewardInfoResponseItem>>>()?;
 
     Ok(reward_infos)
 }
+
+pub fn query_user_stats(
+    deps: Deps,
+    env: Env,
+    staker_addr: String,
+) -> StdResult<UserStatsResponse> {
+    let staker_addr_raw = deps.api.addr_canonicalize(&staker_addr)?;
+    let asset_tokens = rewards_read(deps.storage, &staker_addr_raw)
+        .range(None, None, Order::Ascending)
+        .map(|item| Ok(item?.0))
+        .collect::<StdResult<Vec<Vec<u8>>>>()?;
+
+    user_stat::query_user_stats(deps, staker_addr, asset_tokens, |key| {
+        let asset_token = String::from_utf8(key.to_vec())?;
+        let pool_info = pool_info_read(deps.storage).load(key)?;
+        let lp_balance = query_lp_balance(deps, &env, &pool_info)?;
+        let reward_info = rewards_read(deps.storage, &staker_addr_raw)
+            .may_load(key)?
+            .unwrap_or_else(|| RewardInfo::create(&pool_info));
+        Ok((
+            asset_token,
+            pool_info.calc_user_auto_balance(lp_balance, reward_info.auto_bond_share)
+                + pool_info.calc_user_stake_balance(reward_info.stake_bond_share),
+        ))
+    })
+}
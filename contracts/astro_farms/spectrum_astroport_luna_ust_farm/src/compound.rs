@@ -1,10 +1,12 @@
-use cosmwasm_std::{attr, to_binary, Attribute, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128, WasmMsg, Decimal};
+use cosmwasm_std::{CanonicalAddr, attr, to_binary, Attribute, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128, WasmMsg, Decimal};
 
 use crate::{
     bond::deposit_farm_share,
     querier::{query_astroport_pending_token, query_astroport_pool_balance},
     state::{read_config, state_store},
 };
+use spectrum_protocol::fee::{fee_recipient_weights, split_fee, transfer_fee_msgs};
+use spectrum_protocol::incentive::query_free_token_balance;
 
 use cw20::Cw20ExecuteMsg;
 
@@ -17,7 +19,7 @@ use astroport::pair::{
     Cw20HookMsg as AstroportPairCw20HookMsg, ExecuteMsg as AstroportPairExecuteMsg,
 };
 
-use astroport::querier::{query_token_balance, simulate};
+use astroport::querier::{simulate};
 use moneymarket::market::ExecuteMsg as MoneyMarketExecuteMsg;
 use spectrum_protocol::anchor_farm::ExecuteMsg;
 use spectrum_protocol::farm_helper::deduct_tax;
@@ -68,7 +70,7 @@ pub fn compound(
     let controller_fee = config.controller_fee;
     let total_fee = community_fee + platform_fee + controller_fee;
 
-    let reward = query_token_balance(&deps.querier, astro_token.clone(), env.contract.address.clone())? + pending_token_response.pending;
+    let reward = query_free_token_balance(deps.storage, &deps.querier, astro_token.clone(), env.contract.address.clone())? + pending_token_response.pending;
 
     // calculate auto-compound, auto-stake, and commission in astro token
     let mut state = read_state(deps.storage)?;
@@ -305,7 +307,7 @@ pub fn stake(
     let pool_info: PoolInfo = pool_info_read(deps.storage).load(asset_denom.as_bytes())?;
     let staking_token = deps.api.addr_humanize(&pool_info.staking_token)?;
 
-    let amount = query_token_balance(&deps.querier, staking_token.clone(), env.contract.address)?;
+    let amount = query_free_token_balance(deps.storage, &deps.querier, staking_token.clone(), env.contract.address)?;
 
     Ok(Response::new()
         .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
@@ -337,51 +339,24 @@ pub fn send_fee(
     }
     let config = read_config(deps.storage)?;
     let aust_token = deps.api.addr_humanize(&config.aust_token)?;
-    let spectrum_gov = deps.api.addr_humanize(&config.spectrum_gov)?;
 
-    let aust_balance = query_token_balance(&deps.querier, aust_token.clone(), env.contract.address)?;
+    let aust_balance = query_free_token_balance(deps.storage, &deps.querier, aust_token.clone(), env.contract.address)?;
 
     let mut messages: Vec<CosmosMsg> = vec![];
-    let thousand = Uint128::from(1000u64);
-    let total_fee = config.community_fee + config.controller_fee + config.platform_fee;
-    let community_amount = aust_balance.multiply_ratio(thousand * config.community_fee, thousand * total_fee);
-    if !community_amount.is_zero() {
-        let transfer_community_fee = CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: aust_token.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: spectrum_gov.to_string(),
-                amount: community_amount,
-            })?,
-            funds: vec![],
-        });
-        messages.push(transfer_community_fee);
-    }
-
-    let platform_amount = aust_balance.multiply_ratio(thousand * config.platform_fee, thousand * total_fee);
-    if !platform_amount.is_zero() {
-        let stake_platform_fee = CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: aust_token.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: deps.api.addr_humanize(&config.platform)?.to_string(),
-                amount: platform_amount,
-            })?,
-            funds: vec![],
-        });
-        messages.push(stake_platform_fee);
-    }
-
-    let controller_amount = aust_balance.checked_sub(community_amount + platform_amount)?;
-    if !controller_amount.is_zero() {
-        let stake_controller_fee = CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: aust_token.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: deps.api.addr_humanize(&config.controller)?.to_string(),
-                amount: controller_amount,
-            })?,
-            funds: vec![],
-        });
-        messages.push(stake_controller_fee);
-    }
+    let mut fee_amounts: Vec<(CanonicalAddr, Uint128)> = vec![];
+    split_fee(
+        &mut fee_amounts,
+        fee_recipient_weights(
+            &config.fee_recipients,
+            vec![
+                (config.spectrum_gov.clone(), config.community_fee),
+                (config.platform.clone(), config.platform_fee),
+                (config.controller.clone(), config.controller_fee),
+            ],
+        ),
+        aust_balance,
+    )?;
+    messages.extend(transfer_fee_msgs(deps.api, &aust_token, fee_amounts)?);
     Ok(Response::new()
         .add_messages(messages))
 }
@@ -221,7 +221,7 @@ impl WasmMockQuerier {
                         let contract_addr = &ASTROPORT_GENERATOR.to_string();
                         let balance = self.read_token_balance(contract_addr, user.to_string());
                         SystemResult::Ok(ContractResult::from(to_binary(
-                            &Uint128::from(balance)
+                            &balance
                         )))
                     }
                     MockQueryMsg::Pair { asset_infos } => {
@@ -265,14 +265,14 @@ impl WasmMockQuerier {
                                 assets: [
                                     Asset {
                                         info: pair_info.asset_infos[0].clone(),
-                                        amount: Uint128::from(1_000_000_000000u128),
+                                        amount: Uint128::from(1_000_000_000_000u128),
                                     },
                                     Asset {
                                         info: pair_info.asset_infos[1].clone(),
-                                        amount: Uint128::from(1_000_000_000000u128),
+                                        amount: Uint128::from(1_000_000_000_000u128),
                                     }
                                 ],
-                                total_share: Uint128::from(1_000_000_000000u128),
+                                total_share: Uint128::from(1_000_000_000_000u128),
                             }
                         )))
                     }
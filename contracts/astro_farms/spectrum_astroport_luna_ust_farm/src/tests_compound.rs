@@ -123,7 +123,13 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         aust_token: AUST_TOKEN.to_string(),
         pair_contract: PAIR_CONTRACT.to_string(),
         farm_denom: FARM_DENOM.to_string(),
-        astro_ust_pair_contract: ASTRO_UST_PAIR_CONTRACT.to_string()
+        astro_ust_pair_contract: ASTRO_UST_PAIR_CONTRACT.to_string(),
+        spectrum_staker: None,
+        fee_recipients: vec![],
+        deposit_fee_tiers: vec![],
+        deposit_fee_exempt: vec![],
+        withdraw_fee: Decimal::zero(),
+        withdraw_fee_period: 0u64,
     };
 
     // success init
@@ -158,6 +164,7 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         platform_fee: None,
         controller_fee: None,
         deposit_fee: None,
+        spectrum_staker: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
     assert!(res.is_err());
@@ -210,6 +217,7 @@ fn test_register_asset(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
                 total_stake_bond_amount: Uint128::zero(),
                 total_stake_bond_share: Uint128::zero(),
                 total_auto_bond_share: Uint128::zero(),
+                incentives: vec![],
             }]
         }
     );
@@ -387,6 +395,7 @@ fn test_bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     let msg = ExecuteMsg::unbond {
         asset_token: FARM_DENOM.to_string(),
         amount: Uint128::from(3000u128),
+        zap_out: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
     assert!(res.is_ok());
@@ -691,7 +700,7 @@ fn test_compound_astro(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
                     contract: ASTRO_UST_PAIR_CONTRACT.to_string(),
                     amount: Uint128::from(4200u128),
                     msg: to_binary(&AstroportCw20HookMsg::Swap {
-                        max_spread: None,
+                        max_spread: Some(Decimal::percent(50)),
                         belief_price: None,
                         to: None,
                     }).unwrap()
@@ -705,7 +714,7 @@ fn test_compound_astro(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
                         info: AssetInfo::NativeToken { denom: "uusd".to_string() },
                         amount: Uint128::from(2055u128),
                     },
-                    max_spread: None,
+                    max_spread: Some(Decimal::percent(50)),
                     belief_price: None,
                     to: None,
                 }).unwrap(),
@@ -739,7 +748,7 @@ fn test_compound_astro(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
                             amount: Uint128::from(2049u128),
                         },
                     ],
-                    slippage_tolerance: None,
+                    slippage_tolerance: Some(Decimal::percent(50)),
                     auto_stake: Some(true),
                     receiver: None
                 }).unwrap(),
@@ -831,6 +840,7 @@ fn test_compound_astro_with_fees(deps: &mut OwnedDeps<MockStorage, MockApi, Wasm
         platform_fee: Some(Decimal::percent(1u64)),
         controller_fee: Some(Decimal::percent(1u64)),
         deposit_fee: None,
+        spectrum_staker: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg);
     assert!(res.is_ok());
@@ -898,7 +908,7 @@ fn test_compound_astro_with_fees(deps: &mut OwnedDeps<MockStorage, MockApi, Wasm
                     contract: ASTRO_UST_PAIR_CONTRACT.to_string(),
                     amount: Uint128::from(4690u128),
                     msg: to_binary(&AstroportCw20HookMsg::Swap {
-                        max_spread: None,
+                        max_spread: Some(Decimal::percent(50)),
                         belief_price: None,
                         to: None,
                     }).unwrap()
@@ -912,7 +922,7 @@ fn test_compound_astro_with_fees(deps: &mut OwnedDeps<MockStorage, MockApi, Wasm
                         info: AssetInfo::NativeToken { denom: "uusd".to_string() },
                         amount: Uint128::from(1999u128),
                     },
-                    max_spread: None,
+                    max_spread: Some(Decimal::percent(50)),
                     belief_price: None,
                     to: None,
                 }).unwrap(),
@@ -964,7 +974,7 @@ fn test_compound_astro_with_fees(deps: &mut OwnedDeps<MockStorage, MockApi, Wasm
                             amount: Uint128::from(1993u128),
                         },
                     ],
-                    slippage_tolerance: None,
+                    slippage_tolerance: Some(Decimal::percent(50)),
                     auto_stake: Some(true),
                     receiver: None
                 }).unwrap(),
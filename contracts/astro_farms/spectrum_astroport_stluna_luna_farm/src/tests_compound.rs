@@ -20,9 +20,6 @@ use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use spectrum_protocol::gov::ExecuteMsg as GovExecuteMsg;
-use spectrum_protocol::gov_proxy::{
-    Cw20HookMsg as GovProxyCw20HookMsg, ExecuteMsg as GovProxyExecuteMsg,
-};
 use std::fmt::Debug;
 use std::str;
 
@@ -32,7 +29,6 @@ const SPEC_TOKEN: &str = "spec_token";
 const WELDO_TOKEN: &str = "weldo_token";
 const STLUNA_TOKEN: &str = "stluna_token";
 const ASTROPORT_ROUTER: &str = "astroport_router";
-const GOV_PROXY: &str = "gov_proxy";
 const ASTROPORT_GENERATOR: &str = "astroport_generator";
 const STLUNA_WELDO_LP: &str = "stluna_weldo_lp";
 const TEST_CREATOR: &str = "creator";
@@ -52,8 +48,8 @@ const STLUNA_WELDO_PAIR_CONTRACT: &str = "stluna_weldo_pair_contract";
 const ULUNA_UUSD_LP: &str = "uluna_uusd_lp";
 const STLUNA_LUNA_LP: &str = "stluna_luna_lp";
 
-const uusd: &str = "uusd";
-const uluna: &str = "uluna";
+const UUSD: &str = "uusd";
+const ULUNA: &str = "uluna";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct RewardInfoResponse {
@@ -107,8 +103,8 @@ fn test() {
         &"ulunauusd".to_string(),
         &PairInfo {
             asset_infos: [
-                AssetInfo::NativeToken { denom: uluna.to_string() },
-                AssetInfo::NativeToken { denom: uusd.to_string() },
+                AssetInfo::NativeToken { denom: ULUNA.to_string() },
+                AssetInfo::NativeToken { denom: UUSD.to_string() },
             ],
             contract_addr: deps.api.addr_validate(ULUNA_UUSD_PAIR_CONTRACT).unwrap(),
             liquidity_token: deps.api.addr_validate(ULUNA_UUSD_LP).unwrap(),
@@ -159,7 +155,13 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         stluna_weldo_pair_contract: STLUNA_WELDO_PAIR_CONTRACT.to_string(),
         astroport_router: ASTROPORT_ROUTER.to_string(),
         stluna_token: STLUNA_TOKEN.to_string(),
-        uluna_uusd_pair_contract: ULUNA_UUSD_PAIR_CONTRACT.to_string()
+        uluna_uusd_pair_contract: ULUNA_UUSD_PAIR_CONTRACT.to_string(),
+        spectrum_staker: None,
+        fee_recipients: vec![],
+        deposit_fee_tiers: vec![],
+        deposit_fee_exempt: vec![],
+        withdraw_fee: Decimal::zero(),
+        withdraw_fee_period: 0u64,
     };
 
     // success init
@@ -195,6 +197,7 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         platform_fee: None,
         controller_fee: None,
         deposit_fee: None,
+        spectrum_staker: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
     assert!(res.is_err());
@@ -249,6 +252,7 @@ fn test_register_asset(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
                 total_stake_bond_share: Uint128::zero(),
                 total_auto_bond_share: Uint128::zero(),
                 farm2_share_index: Decimal::zero(),
+                incentives: vec![],
             }]
         }
     );
@@ -403,6 +407,7 @@ fn test_bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     let msg = ExecuteMsg::unbond {
         asset_token: STLUNA_TOKEN.to_string(),
         amount: Uint128::from(3000u128),
+        zap_out: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
     assert!(res.is_ok());
@@ -656,6 +661,7 @@ fn test_compound_farm_token_and_astro_with_fees(
         platform_fee: Some(Decimal::percent(1u64)),
         controller_fee: Some(Decimal::percent(1u64)),
         deposit_fee: None,
+        spectrum_staker: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg);
     assert!(res.is_ok());
@@ -687,8 +693,7 @@ fn test_compound_farm_token_and_astro_with_fees(
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-    let WELDO_TOKEN_ADDR = deps.api.addr_validate(&WELDO_TOKEN.to_string()).unwrap();
-    let STLUNA_TOKEN_ADDR = deps.api.addr_validate(&STLUNA_TOKEN.to_string()).unwrap();
+    let stluna_token_addr = deps.api.addr_validate(STLUNA_TOKEN).unwrap();
 
     assert_eq!(
         res.messages
@@ -727,18 +732,18 @@ fn test_compound_farm_token_and_astro_with_fees(
                         operations: vec![
                             SwapOperation::AstroSwap {
                                 offer_asset_info: AssetInfo::Token {
-                                    contract_addr: STLUNA_TOKEN_ADDR.clone(),
+                                    contract_addr: stluna_token_addr.clone(),
                                 },
                                 ask_asset_info: AssetInfo::NativeToken {
-                                    denom: uluna.to_string()
+                                    denom: ULUNA.to_string()
                                 },
                             },
                             SwapOperation::AstroSwap {
                                 offer_asset_info: AssetInfo::NativeToken {
-                                    denom: uluna.to_string()
+                                    denom: ULUNA.to_string()
                                 },
                                 ask_asset_info: AssetInfo::NativeToken {
-                                    denom: uusd.to_string()
+                                    denom: UUSD.to_string()
                                 },
                             },
                         ],
@@ -756,7 +761,7 @@ fn test_compound_farm_token_and_astro_with_fees(
                     contract: ASTRO_UST_PAIR_CONTRACT.to_string(),
                     amount: Uint128::from(6050u128),
                     msg: to_binary(&AstroportPairCw20HookMsg::Swap {
-                        max_spread: None,
+                        max_spread: Some(Decimal::percent(50)),
                         belief_price: None,
                         to: None,
                     }).unwrap() 
@@ -809,7 +814,7 @@ fn test_compound_farm_token_and_astro_with_fees(
             //                 amount: Uint128::from(5617u128),
             //             },
             //         ],
-            //         slippage_tolerance: None,
+            //         slippage_tolerance: Some(Decimal::percent(50)),
             //         auto_stake: Some(true),
             //         receiver: None
             //     })
@@ -863,7 +868,7 @@ fn test_compound_farm_token_and_astro_with_fees(
             &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100_000_000u128))],
         ),
         (
-            &uusd.to_string(),
+            &UUSD.to_string(),
             &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100_000_000u128))],
         ),
         (
@@ -871,7 +876,7 @@ fn test_compound_farm_token_and_astro_with_fees(
             &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100_000_000u128))],
         ),
         (
-            &uluna.to_string(),
+            &ULUNA.to_string(),
             &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100_000_000u128))],
         ),
     ]);
@@ -935,17 +940,17 @@ fn test_compound_farm_token_and_astro_with_fees(
                             amount: Uint128::from(100000000u128),
                         },
                         Asset {
-                            info: AssetInfo::NativeToken { denom: uluna.to_string() },
+                            info: AssetInfo::NativeToken { denom: ULUNA.to_string() },
                             amount: Uint128::from(100000000u128),
                         },
                     ],
-                    slippage_tolerance: None,
+                    slippage_tolerance: Some(Decimal::percent(50)),
                     auto_stake: Some(true),
                     receiver: None
                 })
                 .unwrap(),
                 funds: vec![Coin {
-                    denom: uluna.to_string(),
+                    denom: ULUNA.to_string(),
                     amount: Uint128::from(100000000u128),
                 }],
             }),
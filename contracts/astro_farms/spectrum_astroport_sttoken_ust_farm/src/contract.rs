@@ -13,7 +13,14 @@ use crate::{
 
 use cw20::Cw20ReceiveMsg;
 
-use crate::bond::{deposit_spec_reward, query_reward_info, unbond, update_bond, withdraw};
+use crate::bond::{add_incentive, deposit_spec_reward, query_reward_info, query_user_stats, unbond, update_bond, withdraw, refund_incentive, withdraw_incentives};
+use spectrum_protocol::fee::{
+    canonicalize_fee_exempt, humanize_fee_exempt, humanize_fee_recipients, update_fee_exempt,
+    validate_deposit_fee_tiers, validate_fee_recipients, DepositFeeTier, FeeRecipient,
+};
+use spectrum_protocol::incentive::{
+    self, query_incentive_tokens, to_incentive_items, IncentiveExecuteMsg,
+};
 use crate::state::{pool_info_read, pool_info_store, read_state};
 use crate::compound::send_fee;
 use crate::model::{ConfigInfo, Cw20HookMsg, ExecuteMsg, MigrateMsg, PoolItem, PoolsResponse, QueryMsg, StateInfo};
@@ -38,6 +45,10 @@ pub fn instantiate(
     validate_percentage(msg.platform_fee, "platform_fee")?;
     validate_percentage(msg.controller_fee, "controller_fee")?;
     validate_percentage(msg.deposit_fee, "deposit_fee")?;
+    validate_deposit_fee_tiers(&msg.deposit_fee_tiers)?;
+    validate_percentage(msg.withdraw_fee, "withdraw_fee")?;
+    let fee_recipients = validate_fee_recipients(deps.api, msg.fee_recipients)?;
+    let deposit_fee_exempt = canonicalize_fee_exempt(deps.api, &msg.deposit_fee_exempt)?;
 
     store_config(
         deps.storage,
@@ -67,6 +78,16 @@ pub fn instantiate(
             aust_token: deps.api.addr_canonicalize(&msg.aust_token)?,
             pair_contract: deps.api.addr_canonicalize(&msg.pair_contract)?,
             astro_ust_pair_contract: deps.api.addr_canonicalize(&msg.astro_ust_pair_contract)?,
+            spectrum_staker: if let Some(spectrum_staker) = msg.spectrum_staker {
+                Some(deps.api.addr_canonicalize(&spectrum_staker)?)
+            } else {
+                None
+            },
+            fee_recipients,
+            deposit_fee_tiers: msg.deposit_fee_tiers,
+            deposit_fee_exempt,
+            withdraw_fee: msg.withdraw_fee,
+            withdraw_fee_period: msg.withdraw_fee_period,
         },
     )?;
 
@@ -93,6 +114,7 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             platform_fee,
             controller_fee,
             deposit_fee,
+            spectrum_staker,
         } => update_config(
             deps,
             info,
@@ -102,6 +124,7 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             platform_fee,
             controller_fee,
             deposit_fee,
+            spectrum_staker,
         ),
         ExecuteMsg::register_asset {
             asset_token,
@@ -111,7 +134,8 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
         ExecuteMsg::unbond {
             asset_token,
             amount,
-        } => unbond(deps, env, info, asset_token, amount),
+            zap_out,
+        } => unbond(deps, env, info, asset_token, amount, zap_out),
         ExecuteMsg::withdraw {
             asset_token,
             spec_amount,
@@ -135,6 +159,34 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             amount_to_stake,
         ),
         ExecuteMsg::send_fee {} => send_fee(deps, env, info),
+        ExecuteMsg::update_fee_config {
+            fee_recipients,
+            deposit_fee_tiers,
+            insert_fee_exempt,
+            remove_fee_exempt,
+            withdraw_fee,
+            withdraw_fee_period,
+        } => update_fee_config(
+            deps,
+            info,
+            fee_recipients,
+            deposit_fee_tiers,
+            insert_fee_exempt,
+            remove_fee_exempt,
+            withdraw_fee,
+            withdraw_fee_period,
+        ),
+        ExecuteMsg::incentive(msg) => match msg {
+            IncentiveExecuteMsg::update_incentive_tokens { add, remove } => {
+                update_incentive_tokens(deps, info, add, remove)
+            }
+            IncentiveExecuteMsg::withdraw_incentives { asset_token, tokens } => {
+                withdraw_incentives(deps, env, info, asset_token, tokens)
+            }
+            IncentiveExecuteMsg::refund_incentive { asset_token, token } => {
+                refund_incentive(deps, env, info, asset_token, token)
+            }
+        },
     }
 }
 
@@ -158,6 +210,18 @@ fn receive_cw20(
             cw20_msg.amount,
             compound_rate,
         ),
+        Ok(Cw20HookMsg::add_incentive {
+            asset_token,
+            duration,
+        }) => add_incentive(
+            deps,
+            env,
+            info,
+            cw20_msg.sender,
+            asset_token,
+            cw20_msg.amount,
+            duration,
+        ),
         Err(_) => Err(StdError::generic_err("data should be given")),
     }
 }
@@ -172,6 +236,7 @@ pub fn update_config(
     platform_fee: Option<Decimal>,
     controller_fee: Option<Decimal>,
     deposit_fee: Option<Decimal>,
+    spectrum_staker: Option<String>,
 ) -> StdResult<Response> {
     let mut config: Config = read_config(deps.storage)?;
 
@@ -210,11 +275,78 @@ pub fn update_config(
         config.deposit_fee = deposit_fee;
     }
 
+    if let Some(spectrum_staker) = spectrum_staker {
+        config.spectrum_staker = Some(deps.api.addr_canonicalize(&spectrum_staker)?);
+    }
+
     store_config(deps.storage, &config)?;
 
     Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
 }
 
+fn update_incentive_tokens(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Option<Vec<String>>,
+    remove: Option<Vec<String>>,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    incentive::update_incentive_tokens(deps.api, deps.storage, add, remove)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "update_incentive_tokens")]))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_fee_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    fee_recipients: Option<Vec<FeeRecipient>>,
+    deposit_fee_tiers: Option<Vec<DepositFeeTier>>,
+    insert_fee_exempt: Option<Vec<String>>,
+    remove_fee_exempt: Option<Vec<String>>,
+    withdraw_fee: Option<Decimal>,
+    withdraw_fee_period: Option<u64>,
+) -> StdResult<Response> {
+    let mut config: Config = read_config(deps.storage)?;
+
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    if let Some(fee_recipients) = fee_recipients {
+        config.fee_recipients = validate_fee_recipients(deps.api, fee_recipients)?;
+    }
+
+    if let Some(deposit_fee_tiers) = deposit_fee_tiers {
+        validate_deposit_fee_tiers(&deposit_fee_tiers)?;
+        config.deposit_fee_tiers = deposit_fee_tiers;
+    }
+
+    update_fee_exempt(
+        deps.api,
+        &mut config.deposit_fee_exempt,
+        insert_fee_exempt,
+        remove_fee_exempt,
+    )?;
+
+    if let Some(withdraw_fee) = withdraw_fee {
+        validate_percentage(withdraw_fee, "withdraw_fee")?;
+        config.withdraw_fee = withdraw_fee;
+    }
+
+    if let Some(withdraw_fee_period) = withdraw_fee_period {
+        config.withdraw_fee_period = withdraw_fee_period;
+    }
+
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "update_fee_config")]))
+}
+
 fn register_asset(
     deps: DepsMut,
     env: Env,
@@ -256,6 +388,7 @@ fn register_asset(
             state_spec_share_index: state.spec_share_index,
             auto_spec_share_index: Decimal::zero(),
             stake_spec_share_index: Decimal::zero(),
+            incentives: vec![],
         });
     state.total_weight = state.total_weight + weight - pool_info.weight;
     pool_info.weight = weight;
@@ -277,6 +410,8 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_binary(&query_reward_info(deps, env, staker_addr)?)
         }
         QueryMsg::state {} => to_binary(&query_state(deps)?),
+        QueryMsg::user_stats { staker_addr } => to_binary(&query_user_stats(deps, env, staker_addr)?),
+        QueryMsg::incentive_tokens {} => to_binary(&query_incentive_tokens(deps.api, deps.storage)?),
     }
 }
 
@@ -315,6 +450,16 @@ fn query_config(deps: Deps) -> StdResult<ConfigInfo> {
         pair_contract: deps.api.addr_humanize(&config.pair_contract)?.to_string(),
         astro_ust_pair_contract: deps.api.addr_humanize(&config.astro_ust_pair_contract)?.to_string(),
         // reward_pair_contract: deps.api.addr_humanize(&config.reward_pair_contract)?.to_string(),
+        spectrum_staker: if let Some(spectrum_staker) = config.spectrum_staker {
+            Some(deps.api.addr_humanize(&spectrum_staker)?.to_string())
+        } else {
+            None
+        },
+        fee_recipients: humanize_fee_recipients(deps.api, &config.fee_recipients)?,
+        deposit_fee_tiers: config.deposit_fee_tiers,
+        deposit_fee_exempt: humanize_fee_exempt(deps.api, &config.deposit_fee_exempt)?,
+        withdraw_fee: config.withdraw_fee,
+        withdraw_fee_period: config.withdraw_fee_period,
     };
 
     Ok(resp)
@@ -345,6 +490,7 @@ fn query_pools(deps: Deps) -> StdResult<PoolsResponse> {
                 farm2_share_index: pool_info.farm2_share_index,
                 stake_spec_share_index: pool_info.stake_spec_share_index,
                 auto_spec_share_index: pool_info.auto_spec_share_index,
+                incentives: to_incentive_items(deps.api, &pool_info.incentives)?,
             })
         })
         .collect::<StdResult<Vec<PoolItem>>>()?;
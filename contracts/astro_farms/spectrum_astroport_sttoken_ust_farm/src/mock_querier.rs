@@ -208,7 +208,7 @@ impl WasmMockQuerier {
                 }
             }
             QueryRequest::Bank(BankQuery::Balance { address, denom }) => {
-                let amount = self.read_token_balance(&denom, address.clone());
+                let amount = self.read_token_balance(denom, address.clone());
                 SystemResult::Ok(ContractResult::from(to_binary(&BalanceResponse {
                     amount: Coin { denom: denom.clone(), amount }
                 })))
@@ -247,7 +247,7 @@ impl WasmMockQuerier {
                     MockQueryMsg::Deposit { lp_token: _, user } => {
                         let contract_addr = &ASTROPORT_GENERATOR.to_string();
                         let balance = self.read_token_balance(contract_addr, user.to_string());
-                        SystemResult::Ok(ContractResult::from(to_binary(&Uint128::from(balance))))
+                        SystemResult::Ok(ContractResult::from(to_binary(&balance)))
                     }
                     MockQueryMsg::Pair { asset_infos } => {
                         let key = asset_infos[0].to_string() + asset_infos[1].to_string().as_str();
@@ -292,19 +292,19 @@ impl WasmMockQuerier {
                             assets: [
                                 Asset {
                                     info: pair_info.asset_infos[0].clone(),
-                                    amount: Uint128::from(1_000_000_000000u128),
+                                    amount: Uint128::from(1_000_000_000_000u128),
                                 },
                                 Asset {
                                     info: pair_info.asset_infos[1].clone(),
-                                    amount: Uint128::from(1_000_000_000000u128),
+                                    amount: Uint128::from(1_000_000_000_000u128),
                                 },
                             ],
-                            total_share: Uint128::from(1_000_000_000000u128),
+                            total_share: Uint128::from(1_000_000_000_000u128),
                         })))
                     }
                     MockQueryMsg::SimulateSwapOperations {
                         offer_amount,
-                        operations,
+                        operations: _,
                     } => SystemResult::Ok(ContractResult::from(to_binary(
                         &SimulateSwapOperationsResponse {
                             amount: offer_amount,
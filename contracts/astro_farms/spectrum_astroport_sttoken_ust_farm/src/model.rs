@@ -4,6 +4,9 @@ use cosmwasm_std::{Decimal, Uint128};
 use cw20::Cw20ReceiveMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use spectrum_protocol::fee::{DepositFeeTier, FeeRecipient};
+use spectrum_protocol::incentive::{IncentiveExecuteMsg, IncentiveItem, IncentiveRewardItem};
+use spectrum_protocol::staker::ZapOutParams;
 
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -29,6 +32,12 @@ pub struct ConfigInfo {
     pub aust_token: String,
     pub pair_contract: String,
     pub astro_ust_pair_contract: String,
+    pub spectrum_staker: Option<String>,
+    #[serde(default)] pub fee_recipients: Vec<FeeRecipient>, // empty to split by community/platform/controller fee
+    #[serde(default)] pub deposit_fee_tiers: Vec<DepositFeeTier>,
+    #[serde(default)] pub deposit_fee_exempt: Vec<String>,
+    #[serde(default)] pub withdraw_fee: Decimal, // fee on unbond right after deposit, decays to zero
+    #[serde(default)] pub withdraw_fee_period: u64, // seconds after deposit_time until withdraw_fee is zero
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -42,11 +51,13 @@ pub enum ExecuteMsg {
         platform_fee: Option<Decimal>,
         controller_fee: Option<Decimal>,
         deposit_fee: Option<Decimal>,
+        spectrum_staker: Option<String>,
     },
-    // Unbond lp token
+    // Unbond lp token; if zap_out is given, LP is swapped to target_asset via spectrum_staker
     unbond {
         asset_token: String,
         amount: Uint128,
+        zap_out: Option<ZapOutParams>,
     },
     register_asset {
         asset_token: String,
@@ -73,6 +84,15 @@ pub enum ExecuteMsg {
         amount_to_auto: Uint128,
     },
     send_fee {},
+    update_fee_config {
+        fee_recipients: Option<Vec<FeeRecipient>>,
+        deposit_fee_tiers: Option<Vec<DepositFeeTier>>,
+        insert_fee_exempt: Option<Vec<String>>,
+        remove_fee_exempt: Option<Vec<String>>,
+        withdraw_fee: Option<Decimal>,
+        withdraw_fee_period: Option<u64>,
+    },
+    incentive(IncentiveExecuteMsg),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -82,6 +102,10 @@ pub enum Cw20HookMsg {
         asset_token: String,
         compound_rate: Option<Decimal>,
     },
+    add_incentive {
+        asset_token: String,
+        duration: u64,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -94,6 +118,11 @@ pub enum QueryMsg {
         staker_addr: String,
     },
     state {},
+    // get cumulative bond/unbond/withdraw stats and gain in LP terms
+    user_stats {
+        staker_addr: String,
+    },
+    incentive_tokens {},
 }
 
 // We define a custom struct for each query response
@@ -116,6 +145,7 @@ pub struct PoolItem {
     pub farm2_share_index: Decimal,       // per stake bond share
     pub stake_spec_share_index: Decimal, // per stake bond share
     pub auto_spec_share_index: Decimal,  // per auto bond share
+    #[serde(default)] pub incentives: Vec<IncentiveItem>,
 }
 
 // We define a custom struct for each query response
@@ -145,6 +175,8 @@ pub struct RewardInfoResponseItem {
     pub pending_spec_reward: Uint128,
     pub deposit_amount: Option<Uint128>,
     pub deposit_time: Option<u64>,
+    pub withdraw_fee: Decimal, // fee rate if unbond now
+    #[serde(default)] pub incentive_rewards: Vec<IncentiveRewardItem>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
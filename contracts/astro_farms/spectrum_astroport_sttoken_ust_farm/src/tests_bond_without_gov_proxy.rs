@@ -15,6 +15,8 @@ use astroport::generator::{
     ExecuteMsg as AstroportExecuteMsg,
 };
 
+use spectrum_protocol::staker::{Cw20HookMsg as StakerCw20HookMsg, ZapOutParams};
+use terraswap::asset::AssetInfo;
 use std::fmt::Debug;
 
 const SPEC_GOV: &str = "SPEC_GOV";
@@ -36,6 +38,7 @@ const PAIR_CONTRACT: &str = "pair_contract";
 const XASTRO_PROXY: &str = "xastro_proxy";
 const ASTRO_TOKEN: &str = "astro_token";
 const ASTRO_UST_PAIR_CONTRACT: &str = "astro_ust_pair_contract";
+const SPEC_STAKER: &str = "spec_staker";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct RewardInfoResponse {
@@ -73,6 +76,7 @@ fn test() {
     let _ = test_config(&mut deps);
     test_register_asset(&mut deps);
     test_bond(&mut deps);
+    test_unbond_zap_out(&mut deps);
     // test_deposit_fee(&mut deps);
     // test_staked_reward(&mut deps);
 }
@@ -102,7 +106,13 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         astro_ust_pair_contract: ASTRO_UST_PAIR_CONTRACT.to_string(),
         astroport_router: ASTROPORT_ROUTER.to_string(),
         stluna_token: STLUNA_TOKEN.to_string(),
-        stasset_token: STASSET_TOKEN.to_string()
+        stasset_token: STASSET_TOKEN.to_string(),
+        spectrum_staker: None,
+        fee_recipients: vec![],
+        deposit_fee_tiers: vec![],
+        deposit_fee_exempt: vec![],
+        withdraw_fee: Decimal::zero(),
+        withdraw_fee_period: 0u64,
     };
 
     // success init
@@ -138,6 +148,7 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         platform_fee: None,
         controller_fee: None,
         deposit_fee: None,
+        spectrum_staker: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
     assert!(res.is_err());
@@ -192,6 +203,7 @@ fn test_register_asset(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
                 total_stake_bond_share: Uint128::zero(),
                 total_auto_bond_share: Uint128::zero(),
                 farm2_share_index: Decimal::zero(),
+                incentives: vec![],
             }]
         }
     );
@@ -328,6 +340,7 @@ fn test_bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     let msg = ExecuteMsg::unbond {
         asset_token: STASSET_TOKEN.to_string(),
         amount: Uint128::from(3000u128),
+        zap_out: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
     assert!(res.is_ok());
@@ -757,3 +770,68 @@ fn test_bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
 //         ]
 //     );
 // }
+
+fn test_unbond_zap_out(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+    let zap_out = ZapOutParams {
+        sell_asset: AssetInfo::Token {
+            contract_addr: STASSET_TOKEN.to_string(),
+        },
+        sell_asset_b: None,
+        target_asset: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        belief_price: None,
+        belief_price_b: None,
+        max_spread: Decimal::percent(1),
+        swap_hints: None,
+        min_receive: Some(Uint128::from(190u128)),
+    };
+
+    // staker is not set
+    let info = mock_info(USER1, &[]);
+    let msg = ExecuteMsg::unbond {
+        asset_token: STASSET_TOKEN.to_string(),
+        amount: Uint128::from(100u128),
+        zap_out: Some(zap_out.clone()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert!(res.is_err());
+
+    let update_msg = ExecuteMsg::update_config {
+        owner: None,
+        controller: None,
+        community_fee: None,
+        platform_fee: None,
+        controller_fee: None,
+        deposit_fee: None,
+        spectrum_staker: Some(SPEC_STAKER.to_string()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(SPEC_GOV, &[]), update_msg);
+    assert!(res.is_ok());
+
+    // LP is sent to staker with user as receiver
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages.last().map(|it| it.msg.clone()),
+        Some(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ANC_LP.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: SPEC_STAKER.to_string(),
+                amount: Uint128::from(100u128),
+                msg: to_binary(&StakerCw20HookMsg::zap_to_unbond {
+                    sell_asset: zap_out.sell_asset,
+                    sell_asset_b: None,
+                    target_asset: zap_out.target_asset,
+                    belief_price: None,
+                    belief_price_b: None,
+                    max_spread: zap_out.max_spread,
+                    swap_hints: None,
+                    staker_addr: Some(USER1.to_string()),
+                    min_receive: zap_out.min_receive,
+                }).unwrap(),
+            }).unwrap(),
+        }))
+    );
+}
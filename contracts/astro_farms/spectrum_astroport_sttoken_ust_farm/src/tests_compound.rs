@@ -20,7 +20,6 @@ use crate::model::{
 use astroport::generator::{
     ExecuteMsg as AstroportExecuteMsg,
 };
-use spectrum_protocol::gov_proxy::{ExecuteMsg as GovProxyExecuteMsg, Cw20HookMsg as GovProxyCw20HookMsg};
 use std::str;
 use astroport::router::{SwapOperation, ExecuteMsg as AstroportRouterExecuteMsg};
 
@@ -31,7 +30,6 @@ const WELDO_TOKEN: &str = "weldo_token";
 const STLUNA_TOKEN: &str = "stluna_token";
 const STASSET_TOKEN: &str = "stasset_token";
 const ASTROPORT_ROUTER: &str = "astroport_router";
-const GOV_PROXY: &str = "gov_proxy";
 const ASTROPORT_GENERATOR: &str = "astroport_generator";
 const STASSET_UST_LP: &str = "stasset_ust_lp";
 const TEST_CREATOR: &str = "creator";
@@ -47,8 +45,8 @@ const XASTRO_PROXY: &str = "xastro_proxy";
 const ASTRO_TOKEN: &str = "astro_token";
 const ASTRO_UST_PAIR_CONTRACT: &str = "astro_ust_pair_contract";
 
-const uusd: &str = "uusd";
-const uluna: &str = "uluna";
+const UUSD: &str = "uusd";
+const ULUNA: &str = "uluna";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct RewardInfoResponse {
@@ -145,7 +143,13 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         astro_ust_pair_contract: ASTRO_UST_PAIR_CONTRACT.to_string(),
         astroport_router: ASTROPORT_ROUTER.to_string(),
         stluna_token: STLUNA_TOKEN.to_string(),
-        stasset_token: STASSET_TOKEN.to_string()
+        stasset_token: STASSET_TOKEN.to_string(),
+        spectrum_staker: None,
+        fee_recipients: vec![],
+        deposit_fee_tiers: vec![],
+        deposit_fee_exempt: vec![],
+        withdraw_fee: Decimal::zero(),
+        withdraw_fee_period: 0u64,
     };
 
     // success init
@@ -181,6 +185,7 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         platform_fee: None,
         controller_fee: None,
         deposit_fee: None,
+        spectrum_staker: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
     assert!(res.is_err());
@@ -235,6 +240,7 @@ fn test_register_asset(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
                 total_stake_bond_share: Uint128::zero(),
                 total_auto_bond_share: Uint128::zero(),
                 farm2_share_index: Decimal::zero(),
+                incentives: vec![],
             }]
         }
     );
@@ -304,8 +310,8 @@ fn test_compound_farm_token_and_astro_not_reach_threshold(deps: &mut OwnedDeps<M
 
     let msg = ExecuteMsg::compound { threshold_compound_astro: Some(Uint128::from(100_000u128)) };
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
-    let WELDO_TOKEN_ADDR = deps.api.addr_validate(&WELDO_TOKEN.to_string()).unwrap();
-    let STLUNA_TOKEN_ADDR = deps.api.addr_validate(&STLUNA_TOKEN.to_string()).unwrap();
+    let weldo_token_addr = deps.api.addr_validate(WELDO_TOKEN).unwrap();
+    let stluna_token_addr = deps.api.addr_validate(STLUNA_TOKEN).unwrap();
 
     assert_eq!(
         res.messages
@@ -330,26 +336,26 @@ fn test_compound_farm_token_and_astro_not_reach_threshold(deps: &mut OwnedDeps<M
                         operations: vec![
                             SwapOperation::AstroSwap {
                                 offer_asset_info: AssetInfo::Token {
-                                    contract_addr: WELDO_TOKEN_ADDR,
+                                    contract_addr: weldo_token_addr,
                                 },
                                 ask_asset_info: AssetInfo::Token {
-                                    contract_addr: STLUNA_TOKEN_ADDR.clone(),
+                                    contract_addr: stluna_token_addr.clone(),
                                 },
                             },
                             SwapOperation::AstroSwap {
                                 offer_asset_info: AssetInfo::Token {
-                                    contract_addr: STLUNA_TOKEN_ADDR,
+                                    contract_addr: stluna_token_addr,
                                 },
                                 ask_asset_info: AssetInfo::NativeToken {
-                                    denom: uluna.to_string()
+                                    denom: ULUNA.to_string()
                                },
                             },
                             SwapOperation::AstroSwap {
                                 offer_asset_info: AssetInfo::NativeToken {
-                                     denom: uluna.to_string()
+                                     denom: ULUNA.to_string()
                                 },
                                 ask_asset_info: AssetInfo::NativeToken {
-                                    denom: uusd.to_string()
+                                    denom: UUSD.to_string()
                                 },
                             },
                         ],
@@ -368,12 +374,12 @@ fn test_compound_farm_token_and_astro_not_reach_threshold(deps: &mut OwnedDeps<M
                     belief_price: None,
                     offer_asset: Asset {
                         info: AssetInfo::NativeToken {
-                            denom: uusd.to_string(),
+                            denom: UUSD.to_string(),
                         },
                         amount: Uint128::from(49_088_433u128),
                     }
                 }).unwrap(),
-                funds: vec![Coin { denom: uusd.to_string(), amount: Uint128::from(49_088_433u128) }]
+                funds: vec![Coin { denom: UUSD.to_string(), amount: Uint128::from(49_088_433u128) }]
             }),
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: STASSET_TOKEN.to_string(),
@@ -401,7 +407,7 @@ fn test_compound_farm_token_and_astro_not_reach_threshold(deps: &mut OwnedDeps<M
                             amount: Uint128::from(48_941_170u128),
                         },
                     ],
-                    slippage_tolerance: None,
+                    slippage_tolerance: Some(Decimal::percent(50)),
                     auto_stake: Some(true),
                     receiver: None
                 }).unwrap(),
@@ -436,8 +442,8 @@ fn test_compound_farm_token_and_astro(deps: &mut OwnedDeps<MockStorage, MockApi,
 
     let msg = ExecuteMsg::compound { threshold_compound_astro: Some(Uint128::from(100000u128)) };
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
-    let WELDO_TOKEN_ADDR = deps.api.addr_validate(&WELDO_TOKEN.to_string()).unwrap();
-    let STLUNA_TOKEN_ADDR = deps.api.addr_validate(&STLUNA_TOKEN.to_string()).unwrap();
+    let weldo_token_addr = deps.api.addr_validate(WELDO_TOKEN).unwrap();
+    let stluna_token_addr = deps.api.addr_validate(STLUNA_TOKEN).unwrap();
 
 
     assert_eq!(
@@ -476,26 +482,26 @@ fn test_compound_farm_token_and_astro(deps: &mut OwnedDeps<MockStorage, MockApi,
                         operations: vec![
                             SwapOperation::AstroSwap {
                                 offer_asset_info: AssetInfo::Token {
-                                    contract_addr: WELDO_TOKEN_ADDR,
+                                    contract_addr: weldo_token_addr,
                                 },
                                 ask_asset_info: AssetInfo::Token {
-                                    contract_addr: STLUNA_TOKEN_ADDR.clone(),
+                                    contract_addr: stluna_token_addr.clone(),
                                 },
                             },
                             SwapOperation::AstroSwap {
                                 offer_asset_info: AssetInfo::Token {
-                                    contract_addr: STLUNA_TOKEN_ADDR,
+                                    contract_addr: stluna_token_addr,
                                 },
                                 ask_asset_info: AssetInfo::NativeToken {
-                                    denom: uluna.to_string()
+                                    denom: ULUNA.to_string()
                                },
                             },
                             SwapOperation::AstroSwap {
                                 offer_asset_info: AssetInfo::NativeToken {
-                                     denom: uluna.to_string()
+                                     denom: ULUNA.to_string()
                                 },
                                 ask_asset_info: AssetInfo::NativeToken {
-                                    denom: uusd.to_string()
+                                    denom: UUSD.to_string()
                                 },
                             },
                         ],
@@ -514,12 +520,12 @@ fn test_compound_farm_token_and_astro(deps: &mut OwnedDeps<MockStorage, MockApi,
                     belief_price: None,
                     offer_asset: Asset {
                         info: AssetInfo::NativeToken {
-                            denom: uusd.to_string(),
+                            denom: UUSD.to_string(),
                         },
                         amount: Uint128::from(98_029_603u128),
                     }
                 }).unwrap(),
-                funds: vec![Coin { denom: uusd.to_string(), amount: Uint128::from(98_029_603u128) }]
+                funds: vec![Coin { denom: UUSD.to_string(), amount: Uint128::from(98_029_603u128) }]
             }),
 
 
@@ -557,7 +563,7 @@ fn test_compound_farm_token_and_astro(deps: &mut OwnedDeps<MockStorage, MockApi,
                             amount: Uint128::from(97_735_515u128),
                         },
                     ],
-                    slippage_tolerance: None,
+                    slippage_tolerance: Some(Decimal::percent(50)),
                     auto_stake: Some(true),
                     receiver: None
                 })
@@ -675,6 +681,7 @@ fn test_bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     let msg = ExecuteMsg::unbond {
         asset_token: STASSET_TOKEN.to_string(),
         amount: Uint128::from(3000u128),
+        zap_out: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
     assert!(res.is_ok());
@@ -919,6 +926,7 @@ fn test_compound_farm_token_and_astro_with_fees(deps: &mut OwnedDeps<MockStorage
         platform_fee: Some(Decimal::percent(1u64)),
         controller_fee: Some(Decimal::percent(1u64)),
         deposit_fee: None,
+        spectrum_staker: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg);
     assert!(res.is_ok());
@@ -962,8 +970,8 @@ fn test_compound_farm_token_and_astro_with_fees(deps: &mut OwnedDeps<MockStorage
     let msg = ExecuteMsg::compound { threshold_compound_astro: Some(Uint128::from(1u128)) };
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-    let WELDO_TOKEN_ADDR = deps.api.addr_validate(&WELDO_TOKEN.to_string()).unwrap();
-    let STLUNA_TOKEN_ADDR = deps.api.addr_validate(&STLUNA_TOKEN.to_string()).unwrap();
+    let weldo_token_addr = deps.api.addr_validate(WELDO_TOKEN).unwrap();
+    let stluna_token_addr = deps.api.addr_validate(STLUNA_TOKEN).unwrap();
 
     assert_eq!(
         res.messages
@@ -1001,26 +1009,26 @@ fn test_compound_farm_token_and_astro_with_fees(deps: &mut OwnedDeps<MockStorage
                         operations: vec![
                             SwapOperation::AstroSwap {
                                 offer_asset_info: AssetInfo::Token {
-                                    contract_addr: WELDO_TOKEN_ADDR,
+                                    contract_addr: weldo_token_addr,
                                 },
                                 ask_asset_info: AssetInfo::Token {
-                                    contract_addr: STLUNA_TOKEN_ADDR.clone(),
+                                    contract_addr: stluna_token_addr.clone(),
                                 },
                             },
                             SwapOperation::AstroSwap {
                                 offer_asset_info: AssetInfo::Token {
-                                    contract_addr: STLUNA_TOKEN_ADDR,
+                                    contract_addr: stluna_token_addr,
                                 },
                                 ask_asset_info: AssetInfo::NativeToken {
-                                    denom: uluna.to_string()
+                                    denom: ULUNA.to_string()
                                },
                             },
                             SwapOperation::AstroSwap {
                                 offer_asset_info: AssetInfo::NativeToken {
-                                     denom: uluna.to_string()
+                                     denom: ULUNA.to_string()
                                 },
                                 ask_asset_info: AssetInfo::NativeToken {
-                                    denom: uusd.to_string()
+                                    denom: UUSD.to_string()
                                 },
                             },
                         ],
@@ -1039,12 +1047,12 @@ fn test_compound_farm_token_and_astro_with_fees(deps: &mut OwnedDeps<MockStorage
                     belief_price: None,
                     offer_asset: Asset {
                         info: AssetInfo::NativeToken {
-                            denom: uusd.to_string(),
+                            denom: UUSD.to_string(),
                         },
                         amount: Uint128::from(5634u128),
                     }
                 }).unwrap(),
-                funds: vec![Coin { denom: uusd.to_string(), amount: Uint128::from(5634u128) }]
+                funds: vec![Coin { denom: UUSD.to_string(), amount: Uint128::from(5634u128) }]
             }),
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: STASSET_TOKEN.to_string(),
@@ -1072,7 +1080,7 @@ fn test_compound_farm_token_and_astro_with_fees(deps: &mut OwnedDeps<MockStorage
                             amount: Uint128::from(5617u128),
                         },
                     ],
-                    slippage_tolerance: None,
+                    slippage_tolerance: Some(Decimal::percent(50)),
                     auto_stake: Some(true),
                     receiver: None
                 }).unwrap(),
@@ -5,6 +5,10 @@ use cosmwasm_std::{CanonicalAddr, Decimal, StdResult, Storage, Uint128};
 use cosmwasm_storage::{
     bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, Singleton,
 };
+use spectrum_protocol::fee::{calc_decayed_withdraw_fee, calc_tier_deposit_fee, DepositFeeTier};
+use spectrum_protocol::incentive::{
+    create_incentive_rewards, Incentive, IncentiveBonder, IncentivePool, IncentiveReward,
+};
 
 pub fn default_addr() -> CanonicalAddr {
     CanonicalAddr::from(vec![])
@@ -33,6 +37,30 @@ pub struct Config {
     pub pair_contract: CanonicalAddr,
     pub luna_ust_pair_contract: CanonicalAddr,
     pub astro_ust_pair_contract: CanonicalAddr,
+    #[serde(default)] pub spectrum_staker: Option<CanonicalAddr>,
+    #[serde(default)] pub fee_recipients: Vec<(CanonicalAddr, u32)>,
+    #[serde(default)] pub deposit_fee_tiers: Vec<DepositFeeTier>,
+    #[serde(default)] pub deposit_fee_exempt: Vec<CanonicalAddr>,
+    #[serde(default)] pub withdraw_fee: Decimal,
+    #[serde(default)] pub withdraw_fee_period: u64,
+}
+
+impl Config {
+    pub fn calc_deposit_fee(&self, staker_addr: &CanonicalAddr, bond_amount: Uint128) -> Decimal {
+        if staker_addr == &self.controller || self.deposit_fee_exempt.contains(staker_addr) {
+            return Decimal::zero();
+        }
+
+        calc_tier_deposit_fee(self.deposit_fee, &self.deposit_fee_tiers, bond_amount)
+    }
+
+    pub fn calc_withdraw_fee(&self, staker_addr: &CanonicalAddr, deposit_time: u64, now: u64) -> Decimal {
+        if staker_addr == &self.controller {
+            return Decimal::zero();
+        }
+
+        calc_decayed_withdraw_fee(self.withdraw_fee, self.withdraw_fee_period, deposit_time, now)
+    }
 }
 
 pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
@@ -64,7 +92,7 @@ impl State {
     }
 }
 
-pub fn state_store(storage: &mut dyn Storage) -> Singleton<State> {
+pub fn state_store(storage: &mut dyn Storage) -> Singleton<'_, State> {
     singleton(storage, KEY_STATE)
 }
 
@@ -86,6 +114,7 @@ pub struct PoolInfo {
     pub farm_share_index: Decimal,
     pub auto_spec_share_index: Decimal,
     pub stake_spec_share_index: Decimal,
+    #[serde(default)] pub incentives: Vec<Incentive>,
 }
 
 impl PoolInfo {
@@ -130,11 +159,29 @@ impl PoolInfo {
     }
 }
 
-pub fn pool_info_store(storage: &mut dyn Storage) -> Bucket<PoolInfo> {
+impl IncentivePool for PoolInfo {
+    fn incentives(&self) -> &[Incentive] {
+        &self.incentives
+    }
+
+    fn incentives_mut(&mut self) -> &mut Vec<Incentive> {
+        &mut self.incentives
+    }
+
+    fn bond_totals(&self) -> (Uint128, Uint128, Uint128) {
+        (
+            self.total_stake_bond_amount,
+            self.total_auto_bond_share,
+            self.total_stake_bond_share,
+        )
+    }
+}
+
+pub fn pool_info_store(storage: &mut dyn Storage) -> Bucket<'_, PoolInfo> {
     bucket(storage, PREFIX_POOL_INFO)
 }
 
-pub fn pool_info_read(storage: &dyn Storage) -> ReadonlyBucket<PoolInfo> {
+pub fn pool_info_read(storage: &dyn Storage) -> ReadonlyBucket<'_, PoolInfo> {
     bucket_read(storage, PREFIX_POOL_INFO)
 }
 
@@ -151,6 +198,7 @@ pub struct RewardInfo {
     pub spec_share: Uint128,
     #[serde(default)] pub deposit_amount: Uint128,
     #[serde(default)] pub deposit_time: u64,
+    #[serde(default)] pub incentive_rewards: Vec<IncentiveReward>,
 }
 
 /// returns a bucket with all rewards owned by this owner (query it by owner)
@@ -182,6 +230,17 @@ impl RewardInfo {
             farm_share: Uint128::zero(),
             deposit_amount: Uint128::zero(),
             deposit_time: 0u64,
+            incentive_rewards: create_incentive_rewards(&pool_info.incentives),
         }
     }
 }
+
+impl IncentiveBonder for RewardInfo {
+    fn incentive_rewards_mut(&mut self) -> &mut Vec<IncentiveReward> {
+        &mut self.incentive_rewards
+    }
+
+    fn bond_shares(&self) -> (Uint128, Uint128) {
+        (self.auto_bond_share, self.stake_bond_share)
+    }
+}
@@ -20,9 +20,6 @@ use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use spectrum_protocol::gov::ExecuteMsg as GovExecuteMsg;
-use spectrum_protocol::gov_proxy::{
-    Cw20HookMsg as GovProxyCw20HookMsg, ExecuteMsg as GovProxyExecuteMsg,
-};
 use std::fmt::Debug;
 use std::str;
 
@@ -32,7 +29,6 @@ const SPEC_TOKEN: &str = "spec_token";
 const WELDO_TOKEN: &str = "weldo_token";
 const STLUNA_TOKEN: &str = "stluna_token";
 const ASTROPORT_ROUTER: &str = "astroport_router";
-const GOV_PROXY: &str = "gov_proxy";
 const ASTROPORT_GENERATOR: &str = "astroport_generator";
 const STLUNA_WELDO_LP: &str = "stluna_weldo_lp";
 const TEST_CREATOR: &str = "creator";
@@ -48,8 +44,8 @@ const XASTRO_PROXY: &str = "xastro_proxy";
 const ASTRO_TOKEN: &str = "astro_token";
 const ASTRO_UST_PAIR_CONTRACT: &str = "astro_ust_pair_contract";
 
-const uusd: &str = "uusd";
-const uluna: &str = "uluna";
+const UUSD: &str = "uusd";
+const ULUNA: &str = "uluna";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct RewardInfoResponse {
@@ -141,6 +137,12 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         astro_ust_pair_contract: ASTRO_UST_PAIR_CONTRACT.to_string(),
         astroport_router: ASTROPORT_ROUTER.to_string(),
         stluna_token: STLUNA_TOKEN.to_string(),
+        spectrum_staker: None,
+        fee_recipients: vec![],
+        deposit_fee_tiers: vec![],
+        deposit_fee_exempt: vec![],
+        withdraw_fee: Decimal::zero(),
+        withdraw_fee_period: 0u64,
     };
 
     // success init
@@ -176,6 +178,7 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         platform_fee: None,
         controller_fee: None,
         deposit_fee: None,
+        spectrum_staker: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
     assert!(res.is_err());
@@ -230,6 +233,7 @@ fn test_register_asset(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
                 total_stake_bond_share: Uint128::zero(),
                 total_auto_bond_share: Uint128::zero(),
                 farm2_share_index: Decimal::zero(),
+                incentives: vec![],
             }]
         }
     );
@@ -384,6 +388,7 @@ fn test_bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     let msg = ExecuteMsg::unbond {
         asset_token: STLUNA_TOKEN.to_string(),
         amount: Uint128::from(3000u128),
+        zap_out: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
     assert!(res.is_ok());
@@ -637,6 +642,7 @@ fn test_compound_farm_token_and_astro_with_fees(
         platform_fee: Some(Decimal::percent(1u64)),
         controller_fee: Some(Decimal::percent(1u64)),
         deposit_fee: None,
+        spectrum_staker: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg);
     assert!(res.is_ok());
@@ -668,8 +674,8 @@ fn test_compound_farm_token_and_astro_with_fees(
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-    let WELDO_TOKEN_ADDR = deps.api.addr_validate(&WELDO_TOKEN.to_string()).unwrap();
-    let STLUNA_TOKEN_ADDR = deps.api.addr_validate(&STLUNA_TOKEN.to_string()).unwrap();
+    let weldo_token_addr = deps.api.addr_validate(WELDO_TOKEN).unwrap();
+    let stluna_token_addr = deps.api.addr_validate(STLUNA_TOKEN).unwrap();
 
     assert_eq!(
         res.messages
@@ -694,26 +700,26 @@ fn test_compound_farm_token_and_astro_with_fees(
                         operations: vec![
                             SwapOperation::AstroSwap {
                                 offer_asset_info: AssetInfo::Token {
-                                    contract_addr: WELDO_TOKEN_ADDR,
+                                    contract_addr: weldo_token_addr,
                                 },
                                 ask_asset_info: AssetInfo::Token {
-                                    contract_addr: STLUNA_TOKEN_ADDR.clone(),
+                                    contract_addr: stluna_token_addr.clone(),
                                 },
                             },
                             SwapOperation::AstroSwap {
                                 offer_asset_info: AssetInfo::Token {
-                                    contract_addr: STLUNA_TOKEN_ADDR.clone(),
+                                    contract_addr: stluna_token_addr.clone(),
                                 },
                                 ask_asset_info: AssetInfo::NativeToken {
-                                    denom: uluna.to_string()
+                                    denom: ULUNA.to_string()
                                 },
                             },
                             SwapOperation::AstroSwap {
                                 offer_asset_info: AssetInfo::NativeToken {
-                                    denom: uluna.to_string()
+                                    denom: ULUNA.to_string()
                                 },
                                 ask_asset_info: AssetInfo::NativeToken {
-                                    denom: uusd.to_string()
+                                    denom: UUSD.to_string()
                                 },
                             },
                         ],
@@ -840,7 +846,7 @@ fn test_compound_farm_token_and_astro_with_fees(
             &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100_000_000u128))],
         ),
         (
-            &uusd.to_string(),
+            &UUSD.to_string(),
             &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100_000_000u128))],
         ),
     ]);
@@ -956,7 +962,7 @@ fn test_compound_farm_token_and_astro_with_fees(
                         },
                         SwapOperation::AstroSwap {
                             offer_asset_info: AssetInfo::NativeToken { denom: "uluna".to_string() },
-                            ask_asset_info: AssetInfo::Token { contract_addr: STLUNA_TOKEN_ADDR.clone() },
+                            ask_asset_info: AssetInfo::Token { contract_addr: stluna_token_addr.clone() },
                         },
                     ],
                     minimum_receive: None,
@@ -253,14 +253,14 @@ impl WasmMockQuerier {
                                 assets: [
                                     Asset {
                                         info: pair_info.asset_infos[0].clone(),
-                                        amount: Uint128::from(1_000_000_000000u128),
+                                        amount: Uint128::from(1_000_000_000_000u128),
                                     },
                                     Asset {
                                         info: pair_info.asset_infos[1].clone(),
-                                        amount: Uint128::from(1_000_000_000000u128),
+                                        amount: Uint128::from(1_000_000_000_000u128),
                                     }
                                 ],
-                                total_share: Uint128::from(1_000_000_000000u128),
+                                total_share: Uint128::from(1_000_000_000_000u128),
                             }
                         )))
                     }
@@ -15,6 +15,7 @@ use serde::{Deserialize, Serialize};
 use crate::model::{
     ConfigInfo, Cw20HookMsg, ExecuteMsg, PoolItem, PoolsResponse, QueryMsg, StateInfo,
 };
+use spectrum_protocol::fee::FeeRecipient;
 use spectrum_protocol::gov::{ExecuteMsg as GovExecuteMsg};
 use std::fmt::Debug;
 use terraswap::asset::{Asset, AssetInfo, PairInfo};
@@ -134,6 +135,12 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         anchor_market: ANC_MARKET.to_string(),
         aust_token: AUST_TOKEN.to_string(),
         pair_contract: GLOW_POOL.to_string(),
+        spectrum_staker: None,
+        fee_recipients: vec![],
+        deposit_fee_tiers: vec![],
+        deposit_fee_exempt: vec![],
+        withdraw_fee: Decimal::zero(),
+        withdraw_fee_period: 0u64,
     };
 
     // success init
@@ -168,6 +175,7 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         platform_fee: None,
         controller_fee: None,
         deposit_fee: None,
+        spectrum_staker: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
     assert!(res.is_err());
@@ -220,6 +228,7 @@ fn test_register_asset(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
                 total_stake_bond_amount: Uint128::zero(),
                 total_stake_bond_share: Uint128::zero(),
                 total_auto_bond_share: Uint128::zero(),
+                incentives: vec![],
             }]
         }
     );
@@ -441,6 +450,7 @@ fn test_bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     let msg = ExecuteMsg::unbond {
         asset_token: GLOW_TOKEN.to_string(),
         amount: Uint128::from(3000u128),
+        zap_out: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
     assert!(res.is_ok());
@@ -879,6 +889,7 @@ fn test_compound_glow_with_fees(deps: &mut OwnedDeps<MockStorage, MockApi, WasmM
         platform_fee: Some(Decimal::percent(1u64)),
         controller_fee: Some(Decimal::percent(1u64)),
         deposit_fee: None,
+        spectrum_staker: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg);
     assert!(res.is_ok());
@@ -1072,4 +1083,53 @@ fn test_compound_glow_with_fees(deps: &mut OwnedDeps<MockStorage, MockApi, WasmM
                 funds: vec![],
             }),
         ]);
+
+    // fee recipients replace community/platform/controller fee split
+    let msg = ExecuteMsg::update_fee_config {
+        fee_recipients: Some(vec![
+            FeeRecipient {
+                address: SPEC_GOV.to_string(),
+                weight: 1,
+            },
+            FeeRecipient {
+                address: SPEC_PLATFORM.to_string(),
+                weight: 3,
+            },
+        ]),
+        deposit_fee_tiers: None,
+        insert_fee_exempt: None,
+        remove_fee_exempt: None,
+        withdraw_fee: None,
+        withdraw_fee_period: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(TEST_CREATOR, &[]), msg.clone());
+    assert!(res.is_err());
+    let res = execute(deps.as_mut(), env.clone(), mock_info(SPEC_GOV, &[]), msg);
+    assert!(res.is_ok());
+
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::send_fee {}).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: AUST_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: SPEC_GOV.to_string(),
+                    amount: Uint128::from(147u128),
+                }).unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: AUST_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: SPEC_PLATFORM.to_string(),
+                    amount: Uint128::from(443u128),
+                }).unwrap(),
+                funds: vec![],
+            }),
+        ]);
 }
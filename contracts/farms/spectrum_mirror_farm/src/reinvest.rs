@@ -11,8 +11,9 @@ use mirror_protocol::staking::Cw20HookMsg as MirrorCw20HookMsg;
 use std::str::FromStr;
 use terraswap::asset::{Asset, AssetInfo};
 use terraswap::pair::{Cw20HookMsg as TerraswapCw20HookMsg, ExecuteMsg as TerraswapExecuteMsg, QueryMsg as TerraswapQueryMsg, PoolResponse};
-use terraswap::querier::{query_pair_info, query_token_balance, simulate};
+use terraswap::querier::{query_pair_info, simulate};
 use spectrum_protocol::farm_helper::{compute_provide_after_swap, deduct_tax};
+use spectrum_protocol::incentive::query_free_token_balance;
 
 const TERRASWAP_COMMISSION_RATE: &str = "0.003";
 
@@ -309,7 +310,7 @@ pub fn stake(
     let pool_info: PoolInfo = pool_info_read(deps.storage).load(asset_token_raw.as_slice())?;
     let staking_token = deps.api.addr_humanize(&pool_info.staking_token)?;
 
-    let amount = query_token_balance(&deps.querier, staking_token.clone(), env.contract.address)?;
+    let amount = query_free_token_balance(deps.storage, &deps.querier, staking_token.clone(), env.contract.address)?;
 
     Ok(Response::new()
         .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
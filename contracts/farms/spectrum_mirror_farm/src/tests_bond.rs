@@ -6,13 +6,16 @@ use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONT
 use cosmwasm_std::{from_binary, to_binary, CosmosMsg, Decimal, OwnedDeps, StdError, Uint128, WasmMsg, Storage};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use mirror_protocol::gov::ExecuteMsg as MirrorGovExecuteMsg;
+use mirror_protocol::staking::ExecuteMsg as MirrorStakingExecuteMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use spectrum_protocol::gov::ExecuteMsg as GovExecuteMsg;
 use spectrum_protocol::mirror_farm::{
-    ConfigInfo, Cw20HookMsg, ExecuteMsg, ExecuteMsg as MirrorStakingExecuteMsg, PoolItem,
+    ConfigInfo, Cw20HookMsg, ExecuteMsg, PoolItem,
     PoolsResponse, QueryMsg, StateInfo,
 };
+use spectrum_protocol::staker::{Cw20HookMsg as StakerCw20HookMsg, ZapOutParams};
+use terraswap::asset::AssetInfo;
 use std::fmt::Debug;
 
 const SPEC_GOV: &str = "spec_gov";
@@ -31,6 +34,7 @@ const SPY_LP: &str = "spy_lp";
 const INVALID_LP: &str = "invalid_lp";
 const ANC_MARKET: &str = "anc_market";
 const AUST_TOKEN: &str = "aust_token";
+const SPEC_STAKER: &str = "spec_staker";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct RewardInfoResponse {
@@ -56,6 +60,7 @@ fn test() {
     let _ = test_config(&mut deps);
     test_register_asset(&mut deps);
     test_bond(&mut deps);
+    test_unbond_zap_out(&mut deps);
     test_deposit_fee(&mut deps);
     test_staked_reward(&mut deps);
     test_reallocate(&mut deps);
@@ -83,6 +88,12 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         deposit_fee: Decimal::zero(),
         anchor_market: ANC_MARKET.to_string(),
         aust_token: AUST_TOKEN.to_string(),
+        spectrum_staker: None,
+        fee_recipients: vec![],
+        deposit_fee_tiers: vec![],
+        deposit_fee_exempt: vec![],
+        withdraw_fee: Decimal::zero(),
+        withdraw_fee_period: 0u64,
     };
 
     // success instantiate
@@ -117,6 +128,7 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         platform_fee: None,
         controller_fee: None,
         deposit_fee: None,
+        spectrum_staker: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
     assert!(res.is_err());
@@ -170,6 +182,7 @@ fn test_register_asset(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
                 total_stake_bond_share: Uint128::zero(),
                 total_auto_bond_share: Uint128::zero(),
                 reinvest_allowance: Uint128::zero(),
+                incentives: vec![],
             }]
         }
     );
@@ -202,6 +215,7 @@ fn test_register_asset(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
                 total_stake_bond_share: Uint128::zero(),
                 total_auto_bond_share: Uint128::zero(),
                 reinvest_allowance: Uint128::zero(),
+                incentives: vec![],
             }]
         }
     );
@@ -318,6 +332,7 @@ fn test_bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     let msg = ExecuteMsg::unbond {
         asset_token: MIR_TOKEN.to_string(),
         amount: Uint128::from(3000u128),
+        zap_out: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
     assert!(res.is_ok());
@@ -331,7 +346,7 @@ fn test_bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: MIR_STAKING.to_string(),
                 funds: vec![],
-                msg: to_binary(&MirrorStakingExecuteMsg::unbond {
+                msg: to_binary(&MirrorStakingExecuteMsg::Unbond {
                     amount: Uint128::from(3000u128),
                     asset_token: MIR_TOKEN.to_string(),
                 })
@@ -603,6 +618,7 @@ fn test_deposit_fee(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
         platform_fee: None,
         controller_fee: None,
         deposit_fee: Some(Decimal::percent(20)),
+        spectrum_staker: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg);
     assert!(res.is_ok());
@@ -936,6 +952,7 @@ fn test_deposit_fee(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
     let msg = ExecuteMsg::unbond {
         asset_token: MIR_TOKEN.to_string(),
         amount: Uint128::from(19996u128),
+        zap_out: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
     assert!(res.is_ok());
@@ -943,6 +960,7 @@ fn test_deposit_fee(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
     let msg = ExecuteMsg::unbond {
         asset_token: SPY_TOKEN.to_string(),
         amount: Uint128::from(14996u128),
+        zap_out: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
     assert!(res.is_ok());
@@ -974,6 +992,7 @@ fn test_staked_reward(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier
     let msg = ExecuteMsg::unbond {
         asset_token: MIR_TOKEN.to_string(),
         amount: Uint128::from(13201u128),
+        zap_out: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg);
     assert!(res.is_ok());
@@ -987,7 +1006,7 @@ fn test_staked_reward(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: MIR_STAKING.to_string(),
                 funds: vec![],
-                msg: to_binary(&MirrorStakingExecuteMsg::unbond {
+                msg: to_binary(&MirrorStakingExecuteMsg::Unbond {
                     amount: Uint128::from(13201u128),
                     asset_token: MIR_TOKEN.to_string(),
                 })
@@ -1282,7 +1301,7 @@ fn clone_storage(storage: &MockStorage) -> MockStorage {
     cloned
 }
 
-fn test_partial_withdraw(mut deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+fn test_partial_withdraw(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     let env = mock_env();
     let info = mock_info(USER1, &[]);
 
@@ -1394,3 +1413,72 @@ fn test_partial_withdraw(mut deps: &mut OwnedDeps<MockStorage, MockApi, WasmMock
         ]
     );
 }
+
+fn test_unbond_zap_out(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+    let zap_out = ZapOutParams {
+        sell_asset: AssetInfo::Token {
+            contract_addr: MIR_TOKEN.to_string(),
+        },
+        sell_asset_b: None,
+        target_asset: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        belief_price: None,
+        belief_price_b: None,
+        max_spread: Decimal::percent(1),
+        swap_hints: None,
+        min_receive: Some(Uint128::from(190u128)),
+    };
+
+    // restore storage after test, so later tests keep bond amounts
+    let old_storage = clone_storage(&deps.storage);
+
+    // staker is not set
+    let info = mock_info(USER1, &[]);
+    let msg = ExecuteMsg::unbond {
+        asset_token: MIR_TOKEN.to_string(),
+        amount: Uint128::from(100u128),
+        zap_out: Some(zap_out.clone()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert!(res.is_err());
+
+    let update_msg = ExecuteMsg::update_config {
+        owner: None,
+        controller: None,
+        community_fee: None,
+        platform_fee: None,
+        controller_fee: None,
+        deposit_fee: None,
+        spectrum_staker: Some(SPEC_STAKER.to_string()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(SPEC_GOV, &[]), update_msg);
+    assert!(res.is_ok());
+
+    // LP is sent to staker with user as receiver
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages.last().map(|it| it.msg.clone()),
+        Some(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MIR_LP.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: SPEC_STAKER.to_string(),
+                amount: Uint128::from(100u128),
+                msg: to_binary(&StakerCw20HookMsg::zap_to_unbond {
+                    sell_asset: zap_out.sell_asset,
+                    sell_asset_b: None,
+                    target_asset: zap_out.target_asset,
+                    belief_price: None,
+                    belief_price_b: None,
+                    max_spread: zap_out.max_spread,
+                    swap_hints: None,
+                    staker_addr: Some(USER1.to_string()),
+                    min_receive: zap_out.min_receive,
+                }).unwrap(),
+            }).unwrap(),
+        }))
+    );
+    deps.storage = old_storage;
+}
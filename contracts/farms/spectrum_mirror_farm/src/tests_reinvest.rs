@@ -6,12 +6,9 @@ use cosmwasm_std::{
     from_binary, to_binary, Api, Coin, CosmosMsg, Decimal, OwnedDeps, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
-use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
 use spectrum_protocol::mirror_farm::{
     ConfigInfo, ExecuteMsg, PoolItem, PoolsResponse, QueryMsg, StateInfo,
 };
-use std::fmt::Debug;
 use terraswap::asset::{Asset, AssetInfo, PairInfo};
 use terraswap::pair::{Cw20HookMsg as TerraswapCw20HookMsg, ExecuteMsg as TerraswapExecuteMsg};
 
@@ -29,22 +26,6 @@ const SPY_LP: &str = "spy_lp";
 const ANC_MARKET: &str = "anc_market";
 const AUST_TOKEN: &str = "aust_token";
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct RewardInfoResponse {
-    pub staker_addr: String,
-    pub reward_infos: Vec<RewardInfoResponseItem>,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct RewardInfoResponseItem {
-    pub asset_token: String,
-    pub bond_amount: Uint128,
-    pub auto_bond_amount: Uint128,
-    pub stake_bond_amount: Uint128,
-    pub pending_farm_reward: Uint128,
-    pub pending_spec_reward: Uint128,
-}
-
 #[test]
 fn test() {
     let mut deps = mock_dependencies(&[]);
@@ -116,6 +97,12 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         deposit_fee: Decimal::zero(),
         anchor_market: ANC_MARKET.to_string(),
         aust_token: AUST_TOKEN.to_string(),
+        spectrum_staker: None,
+        fee_recipients: vec![],
+        deposit_fee_tiers: vec![],
+        deposit_fee_exempt: vec![],
+        withdraw_fee: Decimal::zero(),
+        withdraw_fee_period: 0u64,
     };
 
     // success instantiate
@@ -150,6 +137,7 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         platform_fee: None,
         controller_fee: None,
         deposit_fee: None,
+        spectrum_staker: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
     assert!(res.is_err());
@@ -203,6 +191,7 @@ fn test_register_asset(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
                 total_stake_bond_share: Uint128::zero(),
                 total_auto_bond_share: Uint128::zero(),
                 reinvest_allowance: Uint128::zero(),
+                incentives: vec![],
             }]
         }
     );
@@ -327,7 +316,7 @@ fn test_reinvest_mir(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>
     let env = mock_env();
     let info = mock_info(TEST_CONTROLLER, &[]);
 
-    let asset_token_raw = deps.api.addr_canonicalize(&MIR_TOKEN.to_string()).unwrap();
+    let asset_token_raw = deps.api.addr_canonicalize(MIR_TOKEN).unwrap();
     let mut pool_info = pool_info_read(deps.as_ref().storage)
         .load(asset_token_raw.as_slice())
         .unwrap();
@@ -416,7 +405,7 @@ fn test_reinvest_spy(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>
     let env = mock_env();
     let info = mock_info(TEST_CONTROLLER, &[]);
 
-    let asset_token_raw = deps.api.addr_canonicalize(&SPY_TOKEN.to_string()).unwrap();
+    let asset_token_raw = deps.api.addr_canonicalize(SPY_TOKEN).unwrap();
     let mut pool_info = pool_info_read(deps.as_ref().storage)
         .load(asset_token_raw.as_slice())
         .unwrap();
@@ -4,6 +4,8 @@ use cosmwasm_std::{Decimal, Uint128};
 use cw20::Cw20ReceiveMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use spectrum_protocol::fee::{DepositFeeTier, FeeRecipient};
+use spectrum_protocol::incentive::{IncentiveExecuteMsg, IncentiveItem, IncentiveRewardItem};
 
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -26,6 +28,11 @@ pub struct ConfigInfo {
     pub aust_token: String,
     pub pair_contract: String,
     pub ust_pair_contract: String,
+    #[serde(default)] pub fee_recipients: Vec<FeeRecipient>, // empty to split by community/platform/controller fee
+    #[serde(default)] pub deposit_fee_tiers: Vec<DepositFeeTier>,
+    #[serde(default)] pub deposit_fee_exempt: Vec<String>,
+    #[serde(default)] pub withdraw_fee: Decimal, // fee on unbond right after deposit, decays to zero
+    #[serde(default)] pub withdraw_fee_period: u64, // seconds after deposit_time until withdraw_fee is zero
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -58,11 +65,20 @@ pub enum ExecuteMsg {
     },
     compound {},
     send_fee {},
+    update_fee_config {
+        fee_recipients: Option<Vec<FeeRecipient>>,
+        deposit_fee_tiers: Option<Vec<DepositFeeTier>>,
+        insert_fee_exempt: Option<Vec<String>>,
+        remove_fee_exempt: Option<Vec<String>>,
+        withdraw_fee: Option<Decimal>,
+        withdraw_fee_period: Option<u64>,
+    },
     update_bond {
         asset_token: String,
         amount_to_stake: Uint128,
         amount_to_auto: Uint128,
     },
+    incentive(IncentiveExecuteMsg),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -71,6 +87,10 @@ pub enum Cw20HookMsg {
         staker_addr: Option<String>,
         compound_rate: Option<Decimal>,
     },
+    add_incentive {
+        asset_token: String,
+        duration: u64,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -83,6 +103,11 @@ pub enum QueryMsg {
         staker_addr: String,
     },
     state {},
+    // get cumulative bond/unbond/withdraw stats and gain in LP terms
+    user_stats {
+        staker_addr: String,
+    },
+    incentive_tokens {},
 }
 
 // We define a custom struct for each query response
@@ -102,6 +127,7 @@ pub struct PoolItem {
     pub farm_share_index: Decimal,       // per stake bond share
     pub stake_spec_share_index: Decimal, // per stake bond share
     pub auto_spec_share_index: Decimal,  // per auto bond share
+    #[serde(default)] pub incentives: Vec<IncentiveItem>,
 }
 
 // We define a custom struct for each query response
@@ -128,6 +154,8 @@ pub struct RewardInfoResponseItem {
     pub pending_spec_reward: Uint128,
     pub deposit_amount: Option<Uint128>,
     pub deposit_time: Option<u64>,
+    pub withdraw_fee: Decimal, // fee rate if unbond now
+    #[serde(default)] pub incentive_rewards: Vec<IncentiveRewardItem>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
@@ -1,20 +1,23 @@
 use cosmwasm_std::{
-    attr, to_binary, CanonicalAddr, CosmosMsg, Decimal, Deps, DepsMut, MessageInfo, Order,
+    attr, to_binary, CanonicalAddr, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Order,
     QueryRequest, Response, StdError, StdResult, Storage, Uint128, WasmMsg, WasmQuery,
 };
 
 use crate::state::{
     pool_info_read, pool_info_store, read_config, read_state, rewards_read, rewards_store,
-    state_store, Config, PoolInfo, RewardInfo, State,
+    state_store, Config, EmissionReward, PoolInfo, RewardInfo, State,
 };
 
 use cw20::Cw20ExecuteMsg;
 use spectrum_protocol::gov::{BalanceResponse, ExecuteMsg, QueryMsg};
 use spectrum_protocol::math::UDec128;
-use spectrum_protocol::spec_farm::{RewardInfoResponse, RewardInfoResponseItem};
+use spectrum_protocol::spec_farm::{
+    EmissionRewardItem, RewardInfoResponse, RewardInfoResponseItem,
+};
 
 pub fn bond(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     staker_addr: String,
     asset_token: String,
@@ -37,6 +40,7 @@ pub fn bond(
         deposit_reward(deps.as_ref(), &mut state, &config, false)?;
         reward_to_pool(&state, &mut pool_info)?;
     }
+    emission_to_pool(&mut pool_info, env.block.height)?;
 
     let mut reward_info = rewards_read(deps.storage, &staker_addr_raw)
         .may_load(asset_token_raw.as_slice())?
@@ -44,6 +48,7 @@ pub fn bond(
             spec_share_index: pool_info.spec_share_index,
             bond_amount: Uint128::zero(),
             spec_share: Uint128::zero(),
+            emission_rewards: vec![],
         });
     before_share_change(&pool_info, &mut reward_info)?;
 
@@ -109,16 +114,73 @@ fn reward_to_pool(state: &State, pool_info: &mut PoolInfo) -> StdResult<()> {
     Ok(())
 }
 
+pub fn emission_to_pool(pool_info: &mut PoolInfo, height: u64) -> StdResult<()> {
+    let total_bond_amount = pool_info.total_bond_amount;
+    for emission in pool_info.emissions.iter_mut() {
+        let from_block = emission.last_block.max(emission.start_block);
+        let to_block = height.min(emission.end_block);
+        if to_block <= from_block {
+            continue;
+        }
+
+        // emission while nothing is bonded stays in the contract for the next program
+        if !total_bond_amount.is_zero() {
+            let amount = emission.amount.multiply_ratio(
+                to_block - from_block,
+                emission.end_block - emission.start_block,
+            );
+            emission.reward_index =
+                emission.reward_index + Decimal::from_ratio(amount, total_bond_amount);
+            emission.distributed_amount += amount;
+        }
+        emission.last_block = to_block;
+    }
+
+    Ok(())
+}
+
 fn before_share_change(pool_info: &PoolInfo, reward_info: &mut RewardInfo) -> StdResult<()> {
     let share =
         reward_info.bond_amount * (pool_info.spec_share_index - reward_info.spec_share_index);
     reward_info.spec_share += share;
     reward_info.spec_share_index = pool_info.spec_share_index;
+
+    let bond_amount = reward_info.bond_amount;
+    for emission in pool_info.emissions.iter() {
+        let emission_reward = match reward_info
+            .emission_rewards
+            .iter_mut()
+            .find(|it| it.token == emission.token)
+        {
+            Some(emission_reward) => emission_reward,
+            None => {
+                reward_info.emission_rewards.push(EmissionReward {
+                    token: emission.token.clone(),
+                    reward_index: Decimal::zero(),
+                    pending_reward: Uint128::zero(),
+                });
+                reward_info.emission_rewards.last_mut().unwrap()
+            }
+        };
+        emission_reward.pending_reward +=
+            bond_amount * (emission.reward_index - emission_reward.reward_index);
+        emission_reward.reward_index = emission.reward_index;
+    }
     Ok(())
 }
 
+fn is_reward_empty(reward_info: &RewardInfo) -> bool {
+    reward_info.spec_share.is_zero()
+        && reward_info.bond_amount.is_zero()
+        && reward_info
+            .emission_rewards
+            .iter()
+            .all(|it| it.pending_reward.is_zero())
+}
+
 pub fn unbond(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     asset_token: String,
     amount: Uint128,
@@ -140,6 +202,7 @@ pub fn unbond(
     let config = read_config(deps.storage)?;
     deposit_reward(deps.as_ref(), &mut state, &config, false)?;
     reward_to_pool(&state, &mut pool_info)?;
+    emission_to_pool(&mut pool_info, env.block.height)?;
     before_share_change(&pool_info, &mut reward_info)?;
 
     // Decrease bond amount
@@ -147,7 +210,7 @@ pub fn unbond(
     reward_info.bond_amount = reward_info.bond_amount.checked_sub(amount)?;
 
     // Update rewards info
-    if reward_info.spec_share.is_zero() && reward_info.bond_amount.is_zero() {
+    if is_reward_empty(&reward_info) {
         rewards_store(deps.storage, &staker_addr_raw).remove(asset_token_raw.as_slice());
     } else {
         rewards_store(deps.storage, &staker_addr_raw)
@@ -180,6 +243,7 @@ pub fn unbond(
 
 pub fn withdraw(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     asset_token: Option<String>,
     spec_amount: Option<Uint128>,
@@ -190,16 +254,32 @@ pub fn withdraw(
 
     let config = read_config(deps.storage)?;
     let staked = deposit_reward(deps.as_ref(), &mut state, &config, false)?;
-    let (amount, share) = withdraw_reward(
+    let (amount, share, emission_amounts) = withdraw_reward(
         deps.storage,
         &state,
         &staker_addr,
         &asset_token,
         &staked,
         spec_amount,
+        env.block.height,
     )?;
     state.previous_spec_share = state.previous_spec_share.checked_sub(share)?;
     state_store(deps.storage).save(&state)?;
+
+    let emission_messages = emission_amounts
+        .into_iter()
+        .map(|(token, amount)| {
+            Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: deps.api.addr_humanize(&token)?.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: info.sender.to_string(),
+                    amount,
+                })?,
+                funds: vec![],
+            }))
+        })
+        .collect::<StdResult<Vec<CosmosMsg>>>()?;
+
     Ok(Response::new()
         .add_messages(vec![
             CosmosMsg::Wasm(WasmMsg::Execute {
@@ -219,10 +299,11 @@ pub fn withdraw(
                 funds: vec![],
             }),
         ])
+        .add_messages(emission_messages)
         .add_attributes(vec![attr("action", "withdraw"), attr("amount", amount)]))
 }
 
-#[allow(clippy::too_many_arguments)]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn withdraw_reward(
     storage: &mut dyn Storage,
     state: &State,
@@ -230,7 +311,8 @@ fn withdraw_reward(
     asset_token: &Option<CanonicalAddr>,
     staked: &BalanceResponse,
     mut request_spec_amount: Option<Uint128>,
-) -> StdResult<(Uint128, Uint128)> {
+    height: u64,
+) -> StdResult<(Uint128, Uint128, Vec<(CanonicalAddr, Uint128)>)> {
     let rewards_bucket = rewards_read(storage, staker_addr);
 
     // single reward withdraw
@@ -254,6 +336,7 @@ fn withdraw_reward(
 
     let mut amount = Uint128::zero();
     let mut share = Uint128::zero();
+    let mut emission_amounts: Vec<(CanonicalAddr, Uint128)> = vec![];
     for reward_pair in reward_pairs {
         let (asset_token_raw, mut reward_info) = reward_pair;
 
@@ -261,6 +344,7 @@ fn withdraw_reward(
         let key = asset_token_raw.as_slice();
         let mut pool_info = pool_info_read(storage).load(key)?;
         reward_to_pool(state, &mut pool_info)?;
+        emission_to_pool(&mut pool_info, height)?;
         before_share_change(&pool_info, &mut reward_info)?;
 
         let (asset_spec_share, asset_spec_amount) = if let Some(request_amount) = request_spec_amount {
@@ -279,9 +363,25 @@ fn withdraw_reward(
         amount += asset_spec_amount;
         reward_info.spec_share = reward_info.spec_share.checked_sub(asset_spec_share)?;
 
+        // Emission rewards are always withdrawn in full
+        for emission_reward in reward_info.emission_rewards.iter_mut() {
+            if emission_reward.pending_reward.is_zero() {
+                continue;
+            }
+            match emission_amounts
+                .iter_mut()
+                .find(|(token, _)| *token == emission_reward.token)
+            {
+                Some((_, amount)) => *amount += emission_reward.pending_reward,
+                None => emission_amounts
+                    .push((emission_reward.token.clone(), emission_reward.pending_reward)),
+            }
+            emission_reward.pending_reward = Uint128::zero();
+        }
+
         // Update rewards info
         pool_info_store(storage).save(key, &pool_info)?;
-        if reward_info.spec_share.is_zero() && reward_info.bond_amount.is_zero() {
+        if is_reward_empty(&reward_info) {
             rewards_store(storage, staker_addr).remove(key);
         } else {
             rewards_store(storage, staker_addr).save(key, &reward_info)?;
@@ -294,7 +394,7 @@ fn withdraw_reward(
         }
     }
 
-    Ok((amount, share))
+    Ok((amount, share, emission_amounts))
 }
 
 fn calc_spec_balance(share: Uint128, staked: &BalanceResponse) -> Uint128 {
@@ -315,6 +415,7 @@ fn calc_spec_share(amount: Uint128, stated: &BalanceResponse) -> Uint128 {
 
 pub fn query_reward_info(
     deps: Deps,
+    env: Env,
     staker_addr: String,
     asset_token: Option<String>,
 ) -> StdResult<RewardInfoResponse> {
@@ -329,6 +430,7 @@ pub fn query_reward_info(
         &staker_addr_raw,
         &asset_token,
         &staked,
+        env.block.height,
     )?;
 
     Ok(RewardInfoResponse {
@@ -343,6 +445,7 @@ fn read_reward_infos(
     staker_addr: &CanonicalAddr,
     asset_token: &Option<String>,
     staked: &BalanceResponse,
+    height: u64,
 ) -> StdResult<Vec<RewardInfoResponseItem>> {
     let rewards_bucket = rewards_read(deps.storage, staker_addr);
     let reward_infos = if let Some(asset_token) = asset_token {
@@ -353,6 +456,7 @@ fn read_reward_infos(
             let mut pool_info = pool_info_read(deps.storage).load(key)?;
 
             reward_to_pool(state, &mut pool_info)?;
+            emission_to_pool(&mut pool_info, height)?;
             before_share_change(&pool_info, &mut reward_info)?;
 
             vec![RewardInfoResponseItem {
@@ -361,6 +465,7 @@ fn read_reward_infos(
                 spec_share: reward_info.spec_share,
                 pending_spec_reward: calc_spec_balance(reward_info.spec_share, staked),
                 spec_share_index,
+                emission_rewards: to_emission_reward_items(deps, &reward_info)?,
             }]
         } else {
             vec![]
@@ -377,6 +482,7 @@ fn read_reward_infos(
                 let mut pool_info =
                     pool_info_read(deps.storage).load(asset_token_raw.as_slice())?;
                 reward_to_pool(state, &mut pool_info)?;
+                emission_to_pool(&mut pool_info, height)?;
                 before_share_change(&pool_info, &mut reward_info)?;
 
                 Ok(RewardInfoResponseItem {
@@ -385,6 +491,7 @@ fn read_reward_infos(
                     spec_share: reward_info.spec_share,
                     pending_spec_reward: calc_spec_balance(reward_info.spec_share, staked),
                     spec_share_index,
+                    emission_rewards: to_emission_reward_items(deps, &reward_info)?,
                 })
             })
             .collect::<StdResult<Vec<RewardInfoResponseItem>>>()?
@@ -392,3 +499,19 @@ fn read_reward_infos(
 
     Ok(reward_infos)
 }
+
+fn to_emission_reward_items(
+    deps: Deps,
+    reward_info: &RewardInfo,
+) -> StdResult<Vec<EmissionRewardItem>> {
+    reward_info
+        .emission_rewards
+        .iter()
+        .map(|it| {
+            Ok(EmissionRewardItem {
+                token: deps.api.addr_humanize(&it.token)?.to_string(),
+                pending_reward: it.pending_reward,
+            })
+        })
+        .collect()
+}
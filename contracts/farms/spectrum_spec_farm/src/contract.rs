@@ -7,13 +7,14 @@ use cosmwasm_std::{
 
 use crate::state::{
     config_store, pool_info_read, pool_info_store, read_config, read_state, state_store, Config,
-    PoolInfo, State,
+    Emission, PoolInfo, State,
 };
 
-use crate::bond::{bond, deposit_reward, query_reward_info, unbond, withdraw};
+use crate::bond::{bond, deposit_reward, emission_to_pool, query_reward_info, unbond, withdraw};
 use cw20::Cw20ReceiveMsg;
 use spectrum_protocol::spec_farm::{
-    ConfigInfo, Cw20HookMsg, ExecuteMsg, MigrateMsg, PoolItem, PoolsResponse, QueryMsg, StateInfo,
+    ConfigInfo, Cw20HookMsg, EmissionItem, ExecuteMsg, MigrateMsg, PoolItem, PoolsResponse,
+    QueryMsg, StateInfo,
 };
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -41,19 +42,19 @@ pub fn instantiate(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(deps: DepsMut, _env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
     match msg {
-        ExecuteMsg::receive(msg) => receive_cw20(deps, info, msg),
+        ExecuteMsg::receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::register_asset {
             asset_token,
             staking_token,
             weight,
         } => register_asset(deps, info, asset_token, staking_token, weight),
-        ExecuteMsg::withdraw { asset_token, spec_amount } => withdraw(deps, info, asset_token, spec_amount),
+        ExecuteMsg::withdraw { asset_token, spec_amount } => withdraw(deps, env, info, asset_token, spec_amount),
         ExecuteMsg::unbond {
             asset_token,
             amount,
-        } => unbond(deps, info, asset_token, amount),
+        } => unbond(deps, env, info, asset_token, amount),
         ExecuteMsg::update_config {
             owner,
         } => update_config(deps, info, owner),
@@ -62,6 +63,7 @@ pub fn execute(deps: DepsMut, _env: Env, info: MessageInfo, msg: ExecuteMsg) ->
 
 fn receive_cw20(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
 ) -> StdResult<Response> {
@@ -71,11 +73,26 @@ fn receive_cw20(
             asset_token,
         }) => bond(
             deps,
+            env,
             info,
             staker_addr.unwrap_or(cw20_msg.sender),
             asset_token,
             cw20_msg.amount,
         ),
+        Ok(Cw20HookMsg::add_emission {
+            asset_token,
+            start_block,
+            end_block,
+        }) => add_emission(
+            deps,
+            env,
+            info,
+            cw20_msg.sender,
+            asset_token,
+            start_block,
+            end_block,
+            cw20_msg.amount,
+        ),
         Err(_) => Err(StdError::generic_err("data should be given")),
     }
 }
@@ -105,6 +122,7 @@ fn register_asset(
             weight: 0u32,
             state_spec_share_index: state.spec_share_index,
             spec_share_index: Decimal::zero(),
+            emissions: vec![],
         });
     state.total_weight = state.total_weight + weight - pool_info.weight;
     pool_info.weight = weight;
@@ -117,6 +135,65 @@ fn register_asset(
     ]))
 }
 
+#[allow(clippy::too_many_arguments)]
+fn add_emission(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: String,
+    asset_token: String,
+    start_block: u64,
+    end_block: u64,
+    amount: Uint128,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    if config.owner != deps.api.addr_canonicalize(&sender)? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    if start_block < env.block.height || end_block <= start_block {
+        return Err(StdError::generic_err("invalid emission period"));
+    }
+
+    let asset_token_raw = deps.api.addr_canonicalize(&asset_token)?;
+    let mut pool_info = pool_info_read(deps.storage).load(asset_token_raw.as_slice())?;
+    emission_to_pool(&mut pool_info, env.block.height)?;
+
+    // emission token is the cw20 contract sending this hook
+    let token = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if let Some(emission) = pool_info.emissions.iter_mut().find(|it| it.token == token) {
+        if env.block.height < emission.end_block {
+            return Err(StdError::generic_err("emission is still running"));
+        }
+
+        // reuse the reward index, undistributed amount is carried to the new program
+        let remaining_amount = emission.amount.checked_sub(emission.distributed_amount)?;
+        emission.start_block = start_block;
+        emission.end_block = end_block;
+        emission.last_block = start_block;
+        emission.amount = amount + remaining_amount;
+        emission.distributed_amount = Uint128::zero();
+    } else {
+        pool_info.emissions.push(Emission {
+            token,
+            start_block,
+            end_block,
+            last_block: start_block,
+            amount,
+            distributed_amount: Uint128::zero(),
+            reward_index: Decimal::zero(),
+        });
+    }
+
+    pool_info_store(deps.storage).save(asset_token_raw.as_slice(), &pool_info)?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "add_emission"),
+        attr("asset_token", asset_token),
+        attr("token", info.sender),
+        attr("amount", amount),
+    ]))
+}
+
 fn update_config(
     deps: DepsMut,
     info: MessageInfo,
@@ -141,14 +218,14 @@ fn update_config(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::config {} => to_binary(&query_config(deps)?),
         QueryMsg::pools {} => to_binary(&query_pools(deps)?),
         QueryMsg::reward_info {
             staker_addr,
             asset_token,
-        } => to_binary(&query_reward_info(deps, staker_addr, asset_token)?),
+        } => to_binary(&query_reward_info(deps, env, staker_addr, asset_token)?),
         QueryMsg::state {} => to_binary(&query_state(deps)?),
     }
 }
@@ -182,6 +259,20 @@ fn query_pools(deps: Deps) -> StdResult<PoolsResponse> {
                 total_bond_amount: pool_info.total_bond_amount,
                 state_spec_share_index: pool_info.state_spec_share_index,
                 spec_share_index: pool_info.spec_share_index,
+                emissions: pool_info
+                    .emissions
+                    .iter()
+                    .map(|it| {
+                        Ok(EmissionItem {
+                            token: deps.api.addr_humanize(&it.token)?.to_string(),
+                            start_block: it.start_block,
+                            end_block: it.end_block,
+                            amount: it.amount,
+                            distributed_amount: it.distributed_amount,
+                            reward_index: it.reward_index,
+                        })
+                    })
+                    .collect::<StdResult<Vec<EmissionItem>>>()?,
             })
         })
         .collect::<StdResult<Vec<PoolItem>>>()?;
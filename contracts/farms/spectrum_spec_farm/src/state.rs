@@ -15,7 +15,7 @@ pub struct Config {
     pub spectrum_gov: CanonicalAddr,
 }
 
-pub fn config_store(storage: &mut dyn Storage) -> Singleton<Config> {
+pub fn config_store(storage: &mut dyn Storage) -> Singleton<'_, Config> {
     singleton(storage, KEY_CONFIG)
 }
 
@@ -33,7 +33,7 @@ pub struct State {
     pub total_weight: u32,
 }
 
-pub fn state_store(storage: &mut dyn Storage) -> Singleton<State> {
+pub fn state_store(storage: &mut dyn Storage) -> Singleton<'_, State> {
     singleton(storage, KEY_STATE)
 }
 
@@ -50,13 +50,25 @@ pub struct PoolInfo {
     pub weight: u32,
     pub state_spec_share_index: Decimal,
     pub spec_share_index: Decimal, // per bond amount
+    #[serde(default)] pub emissions: Vec<Emission>,
 }
 
-pub fn pool_info_store(storage: &mut dyn Storage) -> Bucket<PoolInfo> {
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Emission {
+    pub token: CanonicalAddr,
+    pub start_block: u64,
+    pub end_block: u64,
+    pub last_block: u64,
+    pub amount: Uint128,
+    pub distributed_amount: Uint128,
+    pub reward_index: Decimal, // per bond amount
+}
+
+pub fn pool_info_store(storage: &mut dyn Storage) -> Bucket<'_, PoolInfo> {
     bucket(storage, PREFIX_POOL_INFO)
 }
 
-pub fn pool_info_read(storage: &dyn Storage) -> ReadonlyBucket<PoolInfo> {
+pub fn pool_info_read(storage: &dyn Storage) -> ReadonlyBucket<'_, PoolInfo> {
     bucket_read(storage, PREFIX_POOL_INFO)
 }
 
@@ -67,6 +79,14 @@ pub struct RewardInfo {
     pub spec_share_index: Decimal,
     pub bond_amount: Uint128,
     pub spec_share: Uint128,
+    #[serde(default)] pub emission_rewards: Vec<EmissionReward>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EmissionReward {
+    pub token: CanonicalAddr,
+    pub reward_index: Decimal,
+    pub pending_reward: Uint128,
 }
 
 pub fn rewards_store<'a>(
@@ -1,12 +1,12 @@
 use crate::contract::{execute, instantiate, query};
 use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
 use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{CosmosMsg, Decimal, OwnedDeps, StdError, Storage, Uint128, WasmMsg, from_binary, to_binary};
+use cosmwasm_std::{CosmosMsg, Decimal, Env, OwnedDeps, StdError, Storage, Uint128, WasmMsg, from_binary, to_binary};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use spectrum_protocol::gov::ExecuteMsg as GovExecuteMsg;
 use spectrum_protocol::spec_farm::{
-    ConfigInfo, Cw20HookMsg, ExecuteMsg, PoolItem, PoolsResponse, QueryMsg, RewardInfoResponse,
-    RewardInfoResponseItem, StateInfo,
+    ConfigInfo, Cw20HookMsg, EmissionItem, EmissionRewardItem, ExecuteMsg, PoolItem,
+    PoolsResponse, QueryMsg, RewardInfoResponse, RewardInfoResponseItem, StateInfo,
 };
 use std::str::FromStr;
 
@@ -16,6 +16,7 @@ const TEST_CREATOR: &str = "creator";
 const USER1: &str = "user1";
 const USER2: &str = "user2";
 const LP: &str = "lp_token";
+const REWARD: &str = "reward_token";
 
 #[test]
 fn test() {
@@ -24,6 +25,12 @@ fn test() {
     let _ = test_config(&mut deps);
     test_register_asset(&mut deps);
     test_bond(&mut deps);
+
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = test_config(&mut deps);
+    test_register_asset(&mut deps);
+    test_emission(&mut deps);
 }
 
 fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> ConfigInfo {
@@ -108,6 +115,7 @@ fn test_register_asset(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
                 state_spec_share_index: Decimal::zero(),
                 spec_share_index: Decimal::zero(),
                 weight: 1u32,
+                emissions: vec![],
             }]
         }
     );
@@ -127,7 +135,7 @@ fn clone_storage(storage: &MockStorage) -> MockStorage {
     cloned
 }
 
-fn test_bond(mut deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+fn test_bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     // bond err
     let mut env = mock_env();
     let info = mock_info(TEST_CREATOR, &[]);
@@ -174,6 +182,7 @@ fn test_bond(mut deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
             pending_spec_reward: Uint128::from(500u128),
             bond_amount: Uint128::from(100u128),
             spec_share_index: Decimal::zero(),
+            emission_rewards: vec![],
         },]
     );
 
@@ -314,6 +323,7 @@ fn test_bond(mut deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
             pending_spec_reward: Uint128::from(530u128),
             bond_amount: Uint128::from(80u128),
             spec_share_index: Decimal::from_str("5").unwrap(),
+            emission_rewards: vec![],
         },]
     );
 
@@ -332,6 +342,142 @@ fn test_bond(mut deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
             pending_spec_reward: Uint128::from(70u128),
             bond_amount: Uint128::from(70u128),
             spec_share_index: Decimal::from_str("10.625").unwrap(),
+            emission_rewards: vec![],
         },]
     );
 }
+
+fn query_emission_rewards(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    env: Env,
+    staker_addr: &str,
+) -> Vec<EmissionRewardItem> {
+    let msg = QueryMsg::reward_info {
+        staker_addr: staker_addr.to_string(),
+        asset_token: Some(TOKEN.to_string()),
+    };
+    let res: RewardInfoResponse =
+        from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+    res.reward_infos[0].emission_rewards.clone()
+}
+
+fn test_emission(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let mut env = mock_env();
+    env.block.height = 100u64;
+    deps.querier.with_token_balances(&[(
+        &GOV.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::zero())],
+    )]);
+
+    // only owner can add emission
+    let info = mock_info(REWARD, &[]);
+    let add_emission = |sender: &str, start_block: u64, end_block: u64| {
+        ExecuteMsg::receive(Cw20ReceiveMsg {
+            sender: sender.to_string(),
+            amount: Uint128::from(1000u128),
+            msg: to_binary(&Cw20HookMsg::add_emission {
+                asset_token: TOKEN.to_string(),
+                start_block,
+                end_block,
+            })
+            .unwrap(),
+        })
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), add_emission(USER1, 200, 300));
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    // end block must be after start block
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), add_emission(GOV, 200, 200));
+    assert_eq!(res, Err(StdError::generic_err("invalid emission period")));
+
+    // 10 reward per block from block 200 to 300
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), add_emission(GOV, 200, 300));
+    assert!(res.is_ok());
+
+    // bond user1 before emission start
+    env.block.height = 150u64;
+    let msg = ExecuteMsg::receive(Cw20ReceiveMsg {
+        sender: USER1.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::bond {
+            staker_addr: None,
+            asset_token: TOKEN.to_string(),
+        })
+        .unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), mock_info(LP, &[]), msg);
+    assert!(res.is_ok());
+
+    // bond user2 in the middle of emission
+    env.block.height = 250u64;
+    let msg = ExecuteMsg::receive(Cw20ReceiveMsg {
+        sender: USER2.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::bond {
+            staker_addr: None,
+            asset_token: TOKEN.to_string(),
+        })
+        .unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), mock_info(LP, &[]), msg);
+    assert!(res.is_ok());
+
+    let res: PoolsResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::pools {}).unwrap()).unwrap();
+    assert_eq!(
+        res.pools[0].emissions,
+        vec![EmissionItem {
+            token: REWARD.to_string(),
+            start_block: 200u64,
+            end_block: 300u64,
+            amount: Uint128::from(1000u128),
+            distributed_amount: Uint128::from(500u128),
+            reward_index: Decimal::from_str("5").unwrap(),
+        }]
+    );
+
+    // cannot add the same token while emission is running
+    let res = execute(deps.as_mut(), env.clone(), info, add_emission(GOV, 300, 400));
+    assert_eq!(res, Err(StdError::generic_err("emission is still running")));
+
+    // emission stops at end block
+    env.block.height = 350u64;
+    assert_eq!(
+        query_emission_rewards(deps, env.clone(), USER1),
+        vec![EmissionRewardItem {
+            token: REWARD.to_string(),
+            pending_reward: Uint128::from(750u128),
+        }]
+    );
+    assert_eq!(
+        query_emission_rewards(deps, env.clone(), USER2),
+        vec![EmissionRewardItem {
+            token: REWARD.to_string(),
+            pending_reward: Uint128::from(250u128),
+        }]
+    );
+
+    // withdraw pays emission reward
+    let info = mock_info(USER1, &[]);
+    let msg = ExecuteMsg::withdraw { asset_token: None, spec_amount: None };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages[2].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: REWARD.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: USER1.to_string(),
+                amount: Uint128::from(750u128),
+            })
+            .unwrap(),
+        })
+    );
+    assert_eq!(
+        query_emission_rewards(deps, env, USER1),
+        vec![EmissionRewardItem {
+            token: REWARD.to_string(),
+            pending_reward: Uint128::zero(),
+        }]
+    );
+}
@@ -2,7 +2,7 @@ use crate::contract::{execute, instantiate, query};
 use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
 use crate::state::{pool_info_read, pool_info_store, read_config, read_state, state_store};
 use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{from_binary, to_binary, CosmosMsg, Decimal, OwnedDeps, Uint128, WasmMsg};
+use cosmwasm_std::{from_binary, to_binary, CosmosMsg, Decimal, Env, OwnedDeps, Uint128, WasmMsg};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use terraworld_token::gov::{
     ExecuteMsg as TerraworldGovExecuteMsg,
@@ -12,9 +12,16 @@ use terraworld_token::staking::ExecuteMsg as TerraworldStakingExecuteMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use spectrum_protocol::gov::ExecuteMsg as GovExecuteMsg;
+use spectrum_protocol::incentive::{
+    IncentiveExecuteMsg, IncentiveItem, IncentiveRewardItem, IncentiveTokensResponse,
+};
 use spectrum_protocol::terraworld_farm::{
     ConfigInfo, Cw20HookMsg, ExecuteMsg, PoolItem, PoolsResponse, QueryMsg, StateInfo,
+    RewardInfoResponse as FarmRewardInfoResponse,
 };
+use spectrum_protocol::user_stat::{UserStatsResponse, UserStatsResponseItem};
+use spectrum_protocol::staker::{Cw20HookMsg as StakerCw20HookMsg, ZapOutParams};
+use terraswap::asset::AssetInfo;
 use std::fmt::Debug;
 use crate::bond::deposit_farm_share;
 
@@ -33,6 +40,8 @@ const TWD_FARM_CONTRACT: &str = "twd_farm_contract";
 const ANC_MARKET: &str = "anc_market";
 const AUST_TOKEN: &str = "aust_token";
 const TWD_POOL: &str = "twd_pool";
+const BRIBE_TOKEN: &str = "bribe_token";
+const SPEC_STAKER: &str = "spec_staker";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct RewardInfoResponse {
@@ -65,6 +74,9 @@ fn test() {
     let _ = test_config(&mut deps);
     test_register_asset(&mut deps);
     test_bond(&mut deps);
+    test_incentive(&mut deps);
+    test_unbond_zap_out(&mut deps);
+    test_unbond_withdraw_fee(&mut deps);
     // test_deposit_fee(&mut deps);
     // test_staked_reward(&mut deps);
 }
@@ -90,6 +102,12 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         anchor_market: ANC_MARKET.to_string(),
         aust_token: AUST_TOKEN.to_string(),
         pair_contract: TWD_POOL.to_string(),
+        spectrum_staker: None,
+        fee_recipients: vec![],
+        deposit_fee_tiers: vec![],
+        deposit_fee_exempt: vec![],
+        withdraw_fee: Decimal::zero(),
+        withdraw_fee_period: 0u64,
     };
 
     // success init
@@ -124,6 +142,7 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         platform_fee: None,
         controller_fee: None,
         deposit_fee: None,
+        spectrum_staker: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
     assert!(res.is_err());
@@ -176,6 +195,7 @@ fn test_register_asset(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerie
                 total_stake_bond_amount: Uint128::zero(),
                 total_stake_bond_share: Uint128::zero(),
                 total_auto_bond_share: Uint128::zero(),
+                incentives: vec![],
             }]
         }
     );
@@ -285,6 +305,7 @@ fn test_bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     let msg = ExecuteMsg::unbond {
         asset_token: TWD_TOKEN.to_string(),
         amount: Uint128::from(3000u128),
+        zap_out: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
     assert!(res.is_ok());
@@ -379,6 +400,32 @@ fn test_bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
         ),
     ]);
 
+    // query stats for user1
+    let msg = QueryMsg::user_stats {
+        staker_addr: USER1.to_string(),
+    };
+    let res: UserStatsResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res.user_stats,
+        vec![UserStatsResponseItem {
+            asset_token: TWD_TOKEN.to_string(),
+            bond_amount: Uint128::from(7000u128),
+            lp_bonded: Uint128::from(10000u128),
+            lp_unbonded: Uint128::from(3000u128),
+            farm_withdrawn: Uint128::from(1000u128),
+            spec_withdrawn: Uint128::from(2700u128),
+            deposit_fee_paid: Uint128::zero(),
+            cost_basis: Uint128::from(7000u128),
+            realized_gain: Uint128::zero(),
+            realized_loss: Uint128::zero(),
+            unrealized_gain: Uint128::zero(),
+            unrealized_loss: Uint128::zero(),
+            withdraw_fee_paid: Uint128::zero(),
+            farm2_withdrawn: Uint128::zero(),
+        },]
+    );
+
     // query balance for user2
     let msg = QueryMsg::reward_info {
         staker_addr: USER2.to_string(),
@@ -529,3 +576,357 @@ fn test_bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
         },]
     );
 }
+
+fn query_incentive_rewards(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    env: Env,
+    staker_addr: &str,
+) -> Vec<IncentiveRewardItem> {
+    let msg = QueryMsg::reward_info {
+        staker_addr: staker_addr.to_string(),
+    };
+    let res: FarmRewardInfoResponse =
+        from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+    res.reward_infos[0].incentive_rewards.clone()
+}
+
+fn test_incentive(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+    let info = mock_info(BRIBE_TOKEN, &[]);
+
+    // token not allowed
+    let msg = ExecuteMsg::receive(Cw20ReceiveMsg {
+        sender: USER2.to_string(),
+        amount: Uint128::from(7800u128),
+        msg: to_binary(&Cw20HookMsg::add_incentive {
+            asset_token: TWD_TOKEN.to_string(),
+            duration: 100u64,
+        })
+        .unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert!(res.is_err());
+
+    // only owner can allow token
+    let msg = ExecuteMsg::incentive(IncentiveExecuteMsg::update_incentive_tokens {
+        add: Some(vec![BRIBE_TOKEN.to_string()]),
+        remove: None,
+    });
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER2, &[]), msg.clone());
+    assert!(res.is_err());
+    let res = execute(deps.as_mut(), env.clone(), mock_info(SPEC_GOV, &[]), msg);
+    assert!(res.is_ok());
+
+    let msg = QueryMsg::incentive_tokens {};
+    let res: IncentiveTokensResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.tokens, vec![BRIBE_TOKEN.to_string()]);
+
+    // zero duration
+    let msg = ExecuteMsg::receive(Cw20ReceiveMsg {
+        sender: USER2.to_string(),
+        amount: Uint128::from(7800u128),
+        msg: to_binary(&Cw20HookMsg::add_incentive {
+            asset_token: TWD_TOKEN.to_string(),
+            duration: 0u64,
+        })
+        .unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert!(res.is_err());
+
+    // unknown pool
+    let msg = ExecuteMsg::receive(Cw20ReceiveMsg {
+        sender: USER2.to_string(),
+        amount: Uint128::from(7800u128),
+        msg: to_binary(&Cw20HookMsg::add_incentive {
+            asset_token: SPY_TOKEN.to_string(),
+            duration: 100u64,
+        })
+        .unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert!(res.is_err());
+
+    // stream 7800 BRIBE over 100 seconds
+    let msg = ExecuteMsg::receive(Cw20ReceiveMsg {
+        sender: USER2.to_string(),
+        amount: Uint128::from(7800u128),
+        msg: to_binary(&Cw20HookMsg::add_incentive {
+            asset_token: TWD_TOKEN.to_string(),
+            duration: 100u64,
+        })
+        .unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert!(res.is_ok());
+
+    // another depositor cannot take over a running stream
+    let msg = ExecuteMsg::receive(Cw20ReceiveMsg {
+        sender: USER1.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::add_incentive {
+            asset_token: TWD_TOKEN.to_string(),
+            duration: 100u64,
+        })
+        .unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_err());
+
+    let msg = QueryMsg::pools {};
+    let res: PoolsResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res.pools[0].incentives,
+        vec![IncentiveItem {
+            token: BRIBE_TOKEN.to_string(),
+            depositor: USER2.to_string(),
+            remaining_amount: Uint128::from(7800u128),
+            end_time: env.block.time.seconds() + 100u64,
+            auto_index: Decimal::zero(),
+            stake_index: Decimal::zero(),
+        }]
+    );
+
+    /*
+        USER1 7000 (auto 4200, stake 2800)
+        USER2 5000 (auto 0, stake 5000)
+        Total lp 12000, stake 7800
+        after 50 seconds, 3900 BRIBE streamed
+        auto-stake 3900 * 7800 / 12000 = 2535, auto-compound 1365
+        stake index = 2535 / 7800 = 0.325, auto index = 1365 / 4200 = 0.325
+        USER1 BRIBE = 7000 * 0.325 = 2275
+        USER2 BRIBE = 5000 * 0.325 = 1625
+    */
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(50);
+    assert_eq!(
+        query_incentive_rewards(deps, env.clone(), USER1),
+        vec![IncentiveRewardItem {
+            token: BRIBE_TOKEN.to_string(),
+            pending_reward: Uint128::from(2275u128),
+        }]
+    );
+    assert_eq!(
+        query_incentive_rewards(deps, env.clone(), USER2),
+        vec![IncentiveRewardItem {
+            token: BRIBE_TOKEN.to_string(),
+            pending_reward: Uint128::from(1625u128),
+        }]
+    );
+
+    // withdraw does not touch BRIBE
+    let info = mock_info(USER1, &[]);
+    let msg = ExecuteMsg::withdraw { asset_token: None, spec_amount: None, farm_amount: None };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert!(res.messages.iter().all(|it| match &it.msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => contract_addr != BRIBE_TOKEN,
+        _ => true,
+    }));
+
+    // skip BRIBE
+    let msg = ExecuteMsg::incentive(IncentiveExecuteMsg::withdraw_incentives {
+        asset_token: TWD_TOKEN.to_string(),
+        tokens: Some(vec![SPY_TOKEN.to_string()]),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert!(res.messages.is_empty());
+    assert_eq!(
+        query_incentive_rewards(deps, env.clone(), USER1),
+        vec![IncentiveRewardItem {
+            token: BRIBE_TOKEN.to_string(),
+            pending_reward: Uint128::from(2275u128),
+        }]
+    );
+
+    let msg = ExecuteMsg::incentive(IncentiveExecuteMsg::withdraw_incentives {
+        asset_token: TWD_TOKEN.to_string(),
+        tokens: None,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: BRIBE_TOKEN.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: USER1.to_string(),
+                amount: Uint128::from(2275u128),
+            })
+            .unwrap(),
+        })]
+    );
+    assert_eq!(
+        query_incentive_rewards(deps, env.clone(), USER1),
+        vec![IncentiveRewardItem {
+            token: BRIBE_TOKEN.to_string(),
+            pending_reward: Uint128::zero(),
+        }]
+    );
+
+    // cannot refund running stream
+    let msg = ExecuteMsg::incentive(IncentiveExecuteMsg::refund_incentive {
+        asset_token: TWD_TOKEN.to_string(),
+        token: BRIBE_TOKEN.to_string(),
+    });
+    let res = execute(deps.as_mut(), env, mock_info(USER2, &[]), msg);
+    assert!(res.is_err());
+
+    // stream ends, the rest is split the same way
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(200);
+    assert_eq!(
+        query_incentive_rewards(deps, env.clone(), USER1),
+        vec![IncentiveRewardItem {
+            token: BRIBE_TOKEN.to_string(),
+            pending_reward: Uint128::from(2275u128),
+        }]
+    );
+    assert_eq!(
+        query_incentive_rewards(deps, env.clone(), USER2),
+        vec![IncentiveRewardItem {
+            token: BRIBE_TOKEN.to_string(),
+            pending_reward: Uint128::from(3250u128),
+        }]
+    );
+
+    // all streamed, nothing left to refund
+    let msg = ExecuteMsg::incentive(IncentiveExecuteMsg::refund_incentive {
+        asset_token: TWD_TOKEN.to_string(),
+        token: BRIBE_TOKEN.to_string(),
+    });
+    let res = execute(deps.as_mut(), env, mock_info(USER2, &[]), msg);
+    assert!(res.is_err());
+}
+
+fn test_unbond_zap_out(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+    let zap_out = ZapOutParams {
+        sell_asset: AssetInfo::Token {
+            contract_addr: TWD_TOKEN.to_string(),
+        },
+        sell_asset_b: None,
+        target_asset: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        belief_price: None,
+        belief_price_b: None,
+        max_spread: Decimal::percent(1),
+        swap_hints: None,
+        min_receive: Some(Uint128::from(190u128)),
+    };
+
+    // staker is not set
+    let info = mock_info(USER1, &[]);
+    let msg = ExecuteMsg::unbond {
+        asset_token: TWD_TOKEN.to_string(),
+        amount: Uint128::from(100u128),
+        zap_out: Some(zap_out.clone()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert!(res.is_err());
+
+    let update_msg = ExecuteMsg::update_config {
+        owner: None,
+        controller: None,
+        community_fee: None,
+        platform_fee: None,
+        controller_fee: None,
+        deposit_fee: None,
+        spectrum_staker: Some(SPEC_STAKER.to_string()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(SPEC_GOV, &[]), update_msg);
+    assert!(res.is_ok());
+
+    // LP is sent to staker with user as receiver
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages.last().map(|it| it.msg.clone()),
+        Some(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: TWD_LP.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: SPEC_STAKER.to_string(),
+                amount: Uint128::from(100u128),
+                msg: to_binary(&StakerCw20HookMsg::zap_to_unbond {
+                    sell_asset: zap_out.sell_asset,
+                    sell_asset_b: None,
+                    target_asset: zap_out.target_asset,
+                    belief_price: None,
+                    belief_price_b: None,
+                    max_spread: zap_out.max_spread,
+                    swap_hints: None,
+                    staker_addr: Some(USER1.to_string()),
+                    min_receive: zap_out.min_receive,
+                }).unwrap(),
+            }).unwrap(),
+        }))
+    );
+}
+
+fn test_unbond_withdraw_fee(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let mut env = mock_env();
+    let msg = ExecuteMsg::update_fee_config {
+        fee_recipients: None,
+        deposit_fee_tiers: None,
+        insert_fee_exempt: None,
+        remove_fee_exempt: None,
+        withdraw_fee: Some(Decimal::percent(10)),
+        withdraw_fee_period: Some(1000u64),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER1, &[]), msg.clone());
+    assert!(res.is_err());
+    let res = execute(deps.as_mut(), env.clone(), mock_info(SPEC_GOV, &[]), msg);
+    assert!(res.is_ok());
+
+    // half of the period has passed
+    env.block.time = env.block.time.plus_seconds(500);
+    let res: FarmRewardInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::reward_info {
+                staker_addr: USER1.to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.reward_infos[0].withdraw_fee, Decimal::percent(5));
+
+    // fee stays staked for remaining bonders, user gets the net amount
+    let msg = ExecuteMsg::unbond {
+        asset_token: TWD_TOKEN.to_string(),
+        amount: Uint128::from(100u128),
+        zap_out: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER1, &[]), msg).unwrap();
+    assert_eq!(
+        res.messages.first().map(|it| it.msg.clone()),
+        Some(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: TWD_STAKING.to_string(),
+            funds: vec![],
+            msg: to_binary(&TerraworldStakingExecuteMsg::Unbond {
+                amount: Uint128::from(95u128),
+            }).unwrap(),
+        }))
+    );
+
+    // no fee after the period
+    env.block.time = env.block.time.plus_seconds(500);
+    let res: FarmRewardInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env,
+            QueryMsg::reward_info {
+                staker_addr: USER1.to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.reward_infos[0].withdraw_fee, Decimal::zero());
+}
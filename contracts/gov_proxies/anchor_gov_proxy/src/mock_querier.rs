@@ -10,7 +10,7 @@ use cosmwasm_std::{
 use std::collections::HashMap;
 use terra_cosmwasm::{TerraQueryWrapper};
 
-use anchor_token::gov::StakerResponse;
+use anchor_token::gov::{StakerResponse, VoteOption, VoterInfo};
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
 /// this uses our CustomQuerier.
@@ -31,6 +31,7 @@ pub fn mock_dependencies(
 pub struct WasmMockQuerier {
     base: MockQuerier<TerraQueryWrapper>,
     token_querier: TokenQuerier,
+    locked_balance: Uint128,
 }
 
 #[derive(Clone, Default)]
@@ -96,7 +97,14 @@ impl WasmMockQuerier {
                         SystemResult::Ok(ContractResult::from(to_binary(&StakerResponse {
                             balance,
                             share: balance,
-                            locked_balance: vec![],
+                            locked_balance: if self.locked_balance.is_zero() {
+                                vec![]
+                            } else {
+                                vec![(1u64, VoterInfo {
+                                    vote: VoteOption::Yes,
+                                    balance: self.locked_balance,
+                                })]
+                            },
                         })))
                     }
                 }
@@ -111,6 +119,7 @@ impl WasmMockQuerier {
         WasmMockQuerier {
             base,
             token_querier: TokenQuerier::default(),
+            locked_balance: Uint128::zero(),
         }
     }
 
@@ -119,6 +128,11 @@ impl WasmMockQuerier {
         self.token_querier = TokenQuerier::new(balances);
     }
 
+    // balance voted in active poll
+    pub fn with_locked_balance(&mut self, locked_balance: Uint128) {
+        self.locked_balance = locked_balance;
+    }
+
     pub fn read_token_balance(&self, contract_addr: &str, address: String) -> Uint128 {
         let balances: &HashMap<String, Uint128> =
             match self.token_querier.balances.get(contract_addr) {
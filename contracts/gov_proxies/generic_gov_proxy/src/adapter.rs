@@ -0,0 +1,299 @@
+use cosmwasm_std::{attr, to_binary, Attribute, Binary, CanonicalAddr, CosmosMsg, Deps, Env, QueryRequest, StdError, StdResult, Uint128, WasmMsg, WasmQuery};
+use cw20::Cw20ExecuteMsg;
+use astroport::querier::{query_supply, query_token_balance};
+use astroport::staking::{Cw20HookMsg as XAstroCw20HookMsg};
+use anchor_token::gov::{Cw20HookMsg as AnchorCw20HookMsg, ExecuteMsg as AnchorExecuteMsg, VoteOption as AnchorVoteOption};
+use mirror_protocol::gov::{Cw20HookMsg as MirrorCw20HookMsg, ExecuteMsg as MirrorExecuteMsg, VoteOption as MirrorVoteOption};
+use nexus_token::governance::{AnyoneMsg, Cw20HookMsg as NexusGovCw20HookMsg, ExecuteMsg as NexusGovExecuteMsg, VoteOption as NexusVoteOption};
+use spectrum_protocol::gov_proxy::{GovAdapter, VoteOption};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use crate::state::{Adapter, Config};
+
+impl Adapter {
+    pub fn from_msg(deps: Deps, msg: GovAdapter) -> StdResult<Adapter> {
+        Ok(match msg {
+            GovAdapter::Token {} => Adapter::Token {},
+            GovAdapter::XAstro { staking, xastro_token } => Adapter::XAstro {
+                staking: deps.api.addr_canonicalize(&staking)?,
+                xastro_token: deps.api.addr_canonicalize(&xastro_token)?,
+            },
+            GovAdapter::Anchor { gov } => Adapter::Anchor { gov: deps.api.addr_canonicalize(&gov)? },
+            GovAdapter::Mirror { gov } => Adapter::Mirror { gov: deps.api.addr_canonicalize(&gov)? },
+            GovAdapter::Nexus { gov } => Adapter::Nexus { gov: deps.api.addr_canonicalize(&gov)? },
+        })
+    }
+
+    pub fn to_msg(&self, deps: Deps) -> StdResult<GovAdapter> {
+        Ok(match self {
+            Adapter::Token {} => GovAdapter::Token {},
+            Adapter::XAstro { staking, xastro_token } => GovAdapter::XAstro {
+                staking: deps.api.addr_humanize(staking)?.to_string(),
+                xastro_token: deps.api.addr_humanize(xastro_token)?.to_string(),
+            },
+            Adapter::Anchor { gov } => GovAdapter::Anchor { gov: deps.api.addr_humanize(gov)?.to_string() },
+            Adapter::Mirror { gov } => GovAdapter::Mirror { gov: deps.api.addr_humanize(gov)?.to_string() },
+            Adapter::Nexus { gov } => GovAdapter::Nexus { gov: deps.api.addr_humanize(gov)?.to_string() },
+        })
+    }
+}
+
+// Anchor, Mirror and Nexus gov share the same staker query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum GovQueryMsg {
+    Staker {
+        address: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct GovStakerResponse {
+    pub balance: Uint128,
+    pub locked_balance: Vec<(u64, GovVoterInfo)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct GovVoterInfo {
+    pub balance: Uint128,
+}
+
+fn query_gov_staker(deps: Deps, gov: &CanonicalAddr, env: &Env) -> StdResult<GovStakerResponse> {
+    deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: deps.api.addr_humanize(gov)?.to_string(),
+        msg: to_binary(&GovQueryMsg::Staker {
+            address: env.contract.address.to_string(),
+        })?,
+    }))
+}
+
+struct XAstroResponse {
+    pub balance: Uint128,
+    pub total_share: Uint128,
+    pub total_balance: Uint128,
+}
+
+fn query_xastro(
+    deps: Deps,
+    config: &Config,
+    staking: &CanonicalAddr,
+    xastro_token: &CanonicalAddr,
+    env: &Env,
+) -> StdResult<XAstroResponse> {
+    let astro_token = deps.api.addr_humanize(&config.farm_token)?;
+    let staking = deps.api.addr_humanize(staking)?;
+    let xastro_token = deps.api.addr_humanize(xastro_token)?;
+
+    let total_share = query_supply(&deps.querier, xastro_token.clone())?;
+    let total_balance = query_token_balance(&deps.querier, astro_token, staking)?;
+    let share = query_token_balance(&deps.querier, xastro_token, env.contract.address.clone())?;
+
+    Ok(XAstroResponse {
+        balance: share.multiply_ratio(total_balance, total_share),
+        total_share,
+        total_balance,
+    })
+}
+
+pub fn query_total_balance(deps: Deps, env: &Env, config: &Config) -> StdResult<Uint128> {
+    match &config.adapter {
+        Adapter::Token {} => {
+            let farm_token = deps.api.addr_humanize(&config.farm_token)?;
+            query_token_balance(&deps.querier, farm_token, env.contract.address.clone())
+        },
+        Adapter::XAstro { staking, xastro_token } =>
+            Ok(query_xastro(deps, config, staking, xastro_token, env)?.balance),
+        Adapter::Anchor { gov } | Adapter::Mirror { gov } | Adapter::Nexus { gov } =>
+            Ok(query_gov_staker(deps, gov, env)?.balance),
+    }
+}
+
+// tokens voted in active polls are locked in upstream gov until poll ends
+pub fn query_withdrawable(deps: Deps, env: &Env, config: &Config) -> StdResult<Uint128> {
+    match &config.adapter {
+        Adapter::Token {} | Adapter::XAstro { .. } => query_total_balance(deps, env, config),
+        Adapter::Anchor { gov } | Adapter::Mirror { gov } | Adapter::Nexus { gov } => {
+            let gov_response = query_gov_staker(deps, gov, env)?;
+            let locked = gov_response.locked_balance
+                .iter()
+                .map(|(_, voter)| voter.balance)
+                .max()
+                .unwrap_or_default();
+            Ok(gov_response.balance.checked_sub(locked).unwrap_or_default())
+        },
+    }
+}
+
+pub fn stake_msgs(deps: Deps, config: &Config, amount: Uint128) -> StdResult<Vec<CosmosMsg>> {
+    let (contract, msg) = match &config.adapter {
+        Adapter::Token {} => return Ok(vec![]),
+        Adapter::XAstro { staking, .. } => (staking, to_binary(&XAstroCw20HookMsg::Enter {})?),
+        Adapter::Anchor { gov } => (gov, to_binary(&AnchorCw20HookMsg::StakeVotingTokens {})?),
+        Adapter::Mirror { gov } => (gov, to_binary(&MirrorCw20HookMsg::StakeVotingTokens {})?),
+        Adapter::Nexus { gov } => (gov, to_binary(&NexusGovCw20HookMsg::StakeVotingTokens {})?),
+    };
+
+    Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: deps.api.addr_humanize(&config.farm_token)?.to_string(),
+        funds: vec![],
+        msg: to_binary(&Cw20ExecuteMsg::Send {
+            contract: deps.api.addr_humanize(contract)?.to_string(),
+            msg,
+            amount,
+        })?,
+    })])
+}
+
+pub fn withdraw_msgs(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    recipient: String,
+    amount: Uint128,
+) -> StdResult<Vec<CosmosMsg>> {
+    let farm_token = deps.api.addr_humanize(&config.farm_token)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+
+    match &config.adapter {
+        Adapter::Token {} => {},
+        Adapter::XAstro { staking, xastro_token } => {
+            let xastro_response = query_xastro(deps, config, staking, xastro_token, env)?;
+            let astro_balance = query_token_balance(&deps.querier, farm_token.clone(), env.contract.address.clone())?;
+            let needed_astro = amount.checked_sub(astro_balance).unwrap_or_default();
+            let mut required_xastro = needed_astro.multiply_ratio(xastro_response.total_share, xastro_response.total_balance);
+            if required_xastro.multiply_ratio(xastro_response.total_balance, xastro_response.total_share) < needed_astro {
+                required_xastro += Uint128::from(1u128);
+            }
+            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: deps.api.addr_humanize(xastro_token)?.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: deps.api.addr_humanize(staking)?.to_string(),
+                    msg: to_binary(&XAstroCw20HookMsg::Leave {})?,
+                    amount: required_xastro,
+                })?,
+                funds: vec![],
+            }));
+        },
+        Adapter::Anchor { gov } => messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(gov)?.to_string(),
+            msg: to_binary(&AnchorExecuteMsg::WithdrawVotingTokens {
+                amount: Some(amount),
+            })?,
+            funds: vec![],
+        })),
+        Adapter::Mirror { gov } => messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(gov)?.to_string(),
+            msg: to_binary(&MirrorExecuteMsg::WithdrawVotingTokens {
+                amount: Some(amount),
+            })?,
+            funds: vec![],
+        })),
+        Adapter::Nexus { gov } => messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(gov)?.to_string(),
+            msg: to_binary(&NexusGovExecuteMsg::Anyone {
+                anyone_msg: AnyoneMsg::WithdrawVotingTokens {
+                    amount: Some(amount),
+                },
+            })?,
+            funds: vec![],
+        })),
+    }
+
+    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: farm_token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient,
+            amount,
+        })?,
+        funds: vec![],
+    }));
+
+    Ok(messages)
+}
+
+// Astroport assembly messages, voting power is xASTRO balance at proposal start
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AssemblyExecuteMsg {
+    CastVote {
+        proposal_id: u64,
+        vote: ProposalVoteOption,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalVoteOption {
+    For,
+    Against,
+}
+
+// upstream vote message with amount resolved by gov_proxy_helper::vote_amount
+pub fn vote_msg(
+    config: &Config,
+    poll_id: u64,
+    vote: VoteOption,
+    amount: Uint128,
+) -> StdResult<(Binary, Vec<Attribute>)> {
+    let (msg, vote_attr) = match (&config.adapter, vote) {
+        (Adapter::Token {}, _) => return Err(StdError::generic_err("voting is not supported")),
+        (Adapter::Mirror { .. }, vote) => {
+            let mirror_vote = match vote {
+                VoteOption::Yes => MirrorVoteOption::Yes,
+                VoteOption::No => MirrorVoteOption::No,
+                VoteOption::Abstain => MirrorVoteOption::Abstain,
+            };
+            (to_binary(&MirrorExecuteMsg::CastVote {
+                poll_id,
+                vote: mirror_vote.clone(),
+                amount,
+            })?, mirror_vote.to_string())
+        },
+        (_, VoteOption::Abstain) => return Err(StdError::generic_err("abstain is not supported")),
+        (Adapter::XAstro { .. }, vote) => {
+            let (assembly_vote, vote_attr) = if vote == VoteOption::Yes {
+                (ProposalVoteOption::For, "for")
+            } else {
+                (ProposalVoteOption::Against, "against")
+            };
+            (to_binary(&AssemblyExecuteMsg::CastVote {
+                proposal_id: poll_id,
+                vote: assembly_vote,
+            })?, vote_attr.to_string())
+        },
+        (Adapter::Anchor { .. }, vote) => {
+            let anchor_vote = if vote == VoteOption::Yes { AnchorVoteOption::Yes } else { AnchorVoteOption::No };
+            (to_binary(&AnchorExecuteMsg::CastVote {
+                poll_id,
+                vote: anchor_vote.clone(),
+                amount,
+            })?, anchor_vote.to_string())
+        },
+        (Adapter::Nexus { .. }, vote) => {
+            let nexus_vote = if vote == VoteOption::Yes { NexusVoteOption::Yes } else { NexusVoteOption::No };
+            (to_binary(&NexusGovExecuteMsg::Anyone {
+                anyone_msg: AnyoneMsg::CastVote {
+                    poll_id,
+                    vote: nexus_vote.clone(),
+                    amount,
+                },
+            })?, nexus_vote.to_string())
+        },
+    };
+
+    Ok((msg, vec![attr("vote", vote_attr), attr("amount", amount)]))
+}
+
+// staked rewards increase gov balance, so it is shared to all depositors by share
+pub fn stake_voting_rewards_msg(deps: Deps, config: &Config) -> StdResult<CosmosMsg> {
+    match &config.adapter {
+        Adapter::Mirror { gov } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(gov)?.to_string(),
+            msg: to_binary(&MirrorExecuteMsg::StakeVotingRewards {
+                poll_id: None,
+            })?,
+            funds: vec![],
+        })),
+        _ => Err(StdError::generic_err("voting rewards is not supported")),
+    }
+}
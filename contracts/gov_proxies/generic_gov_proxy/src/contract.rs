@@ -0,0 +1,145 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{from_binary, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128};
+
+use crate::{
+    state::{read_config, store_config, Adapter, Config},
+    proxy::{query_staker_info_gov, query_stakers, query_unbonding}
+};
+
+use cw20::Cw20ReceiveMsg;
+
+use spectrum_protocol::gov_proxy::{Cw20HookMsg, ExecuteMsg, GovAdapter, MigrateMsg, QueryMsg};
+use spectrum_protocol::gov_proxy_helper::{
+    canonicalize_opt, deregister_depositor, humanize_opt, read_proxy_config, read_state, register_depositor, set_vote_delegate, state_store, store_proxy_config, update_config, ProxyConfig, State,
+};
+use crate::proxy::{
+    cast_vote, claim_unstake, request_unstake, stake, stake_voting_rewards, unstake
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigInfo {
+    pub adapter: GovAdapter, // upstream, fixed at instantiate
+    pub farm_token: String,
+    #[serde(default)] pub vote_authority: Option<String>, // spectrum_gov, can update voting config
+    #[serde(default)] pub vote_delegate: Option<String>, // appointed by vote_authority to cast votes
+    #[serde(default)] pub vote_contract: Option<String>, // upstream contract to vote, default to adapter gov
+    pub owner: String, // can register depositors
+    #[serde(default)] pub unbonding_period: u64, // seconds before requested unstake can be claimed
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: ConfigInfo,
+) -> StdResult<Response> {
+    let adapter = Adapter::from_msg(deps.as_ref(), msg.adapter)?;
+    store_config(
+        deps.storage,
+        &Config {
+            adapter,
+            farm_token: deps.api.addr_canonicalize(&msg.farm_token)?,
+        },
+    )?;
+
+    store_proxy_config(
+        deps.storage,
+        &ProxyConfig {
+            vote_authority: canonicalize_opt(deps.api, msg.vote_authority)?,
+            vote_delegate: canonicalize_opt(deps.api, msg.vote_delegate)?,
+            vote_contract: canonicalize_opt(deps.api, msg.vote_contract)?,
+            owner: Some(deps.api.addr_canonicalize(&msg.owner)?),
+            unbonding_period: msg.unbonding_period,
+        },
+    )?;
+
+    state_store(deps.storage).save(&State {
+        total_share: Uint128::zero(),
+        total_pending: Uint128::zero(),
+    })?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::Unstake { amount} => unstake(deps, env, info, amount),
+        ExecuteMsg::RequestUnstake { amount } => request_unstake(deps, env, info, amount),
+        ExecuteMsg::ClaimUnstake {} => claim_unstake(deps, env, info),
+        ExecuteMsg::CastVote { poll_id, vote, amount } => cast_vote(deps, env, info, poll_id, vote, amount),
+        ExecuteMsg::StakeVotingRewards {} => stake_voting_rewards(deps),
+        ExecuteMsg::UpdateConfig { vote_authority, vote_contract } => update_config(deps, info, vote_authority, vote_contract),
+        ExecuteMsg::SetVoteDelegate { vote_delegate } => set_vote_delegate(deps, info, vote_delegate),
+        ExecuteMsg::RegisterDepositor { depositor, cap } => register_depositor(deps, info, depositor, cap),
+        ExecuteMsg::DeregisterDepositor { depositor } => deregister_depositor(deps, info, depositor),
+    }
+}
+
+fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw20ReceiveMsg,
+) -> StdResult<Response> {
+    match from_binary(&msg.msg) {
+        Ok(Cw20HookMsg::Stake {}) => stake(
+            deps,
+            env,
+            info,
+            msg.sender,
+            msg.amount,
+        ),
+        Err(_) => Err(StdError::generic_err("data should be given")),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::State {} => to_binary(&query_state(deps)?),
+        QueryMsg::Staker { address } => to_binary(&query_staker_info_gov(deps, env, address)?),
+        QueryMsg::Stakers { start_after, limit } => to_binary(&query_stakers(deps, env, start_after, limit)?),
+        QueryMsg::Unbonding { address } => to_binary(&query_unbonding(deps, env, address)?),
+    }
+}
+
+fn query_config(deps: Deps) -> StdResult<ConfigInfo> {
+    let config = read_config(deps.storage)?;
+    let proxy_config = read_proxy_config(deps.storage)?;
+    let resp = ConfigInfo {
+        adapter: config.adapter.to_msg(deps)?,
+        farm_token: deps.api.addr_humanize(&config.farm_token)?.to_string(),
+        vote_authority: humanize_opt(deps.api, &proxy_config.vote_authority)?,
+        vote_delegate: humanize_opt(deps.api, &proxy_config.vote_delegate)?,
+        vote_contract: humanize_opt(deps.api, &proxy_config.vote_contract)?,
+        owner: humanize_opt(deps.api, &proxy_config.owner)?.unwrap_or_default(),
+        unbonding_period: proxy_config.unbonding_period,
+    };
+    Ok(resp)
+}
+
+fn query_state(deps: Deps) -> StdResult<State> {
+    read_state(deps.storage)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    let mut proxy_config = read_proxy_config(deps.storage)?;
+    proxy_config.owner = Some(deps.api.addr_canonicalize(&msg.owner)?);
+    if let Some(vote_authority) = msg.vote_authority {
+        proxy_config.vote_authority = Some(deps.api.addr_canonicalize(&vote_authority)?);
+    }
+    if let Some(unbonding_period) = msg.unbonding_period {
+        proxy_config.unbonding_period = unbonding_period;
+    }
+    store_proxy_config(deps.storage, &proxy_config)?;
+
+    Ok(Response::default())
+}
@@ -0,0 +1,10 @@
+pub mod adapter;
+pub mod contract;
+pub mod proxy;
+pub mod state;
+
+#[cfg(test)]
+pub mod tests;
+
+#[cfg(test)]
+mod mock_querier;
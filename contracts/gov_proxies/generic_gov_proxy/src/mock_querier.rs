@@ -0,0 +1,170 @@
+#![allow(non_camel_case_types)]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Coin, ContractResult, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+};
+use std::collections::HashMap;
+use cw20::{BalanceResponse, TokenInfoResponse};
+use terra_cosmwasm::{TerraQueryWrapper};
+
+use mirror_protocol::gov::{StakerResponse, VoteOption, VoterInfo};
+
+/// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
+/// this uses our CustomQuerier.
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let contract_addr = MOCK_CONTRACT_ADDR.to_string();
+    let custom_querier: WasmMockQuerier =
+        WasmMockQuerier::new(MockQuerier::new(&[(&contract_addr, contract_balance)]));
+
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: custom_querier,
+    }
+}
+
+pub struct WasmMockQuerier {
+    base: MockQuerier<TerraQueryWrapper>,
+    token_querier: TokenQuerier,
+    locked_balance: Uint128,
+}
+
+#[derive(Clone, Default)]
+pub struct TokenQuerier {
+    // this lets us iterate over all pairs that match the first String
+    balances: HashMap<String, HashMap<String, Uint128>>,
+}
+
+impl TokenQuerier {
+    pub fn new(balances: &[(&String, &[(&String, &Uint128)])]) -> Self {
+        TokenQuerier {
+            balances: balances_to_map(balances),
+        }
+    }
+}
+
+pub(crate) fn balances_to_map(
+    balances: &[(&String, &[(&String, &Uint128)])],
+) -> HashMap<String, HashMap<String, Uint128>> {
+    let mut balances_map: HashMap<String, HashMap<String, Uint128>> = HashMap::new();
+    for (contract_addr, balances) in balances.iter() {
+        let mut contract_balances_map: HashMap<String, Uint128> = HashMap::new();
+        for (addr, balance) in balances.iter() {
+            contract_balances_map.insert(addr.to_string(), **balance);
+        }
+
+        balances_map.insert(contract_addr.to_string(), contract_balances_map);
+    }
+    balances_map
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        // MockQuerier doesn't support Custom, so we ignore it completely here
+        let request: QueryRequest<TerraQueryWrapper> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum MockQueryMsg {
+    Balance {
+        address: String,
+    },
+    TokenInfo {},
+    Staker {
+        address: String,
+    },
+}
+
+impl WasmMockQuerier {
+    pub fn handle_query(&self, request: &QueryRequest<TerraQueryWrapper>) -> QuerierResult {
+        match &request {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                match from_binary(msg).unwrap() {
+                    MockQueryMsg::Balance { address } => {
+                        let balance = self.read_token_balance(contract_addr, address);
+                        SystemResult::Ok(ContractResult::from(to_binary(&BalanceResponse {
+                            balance,
+                        })))
+                    },
+                    MockQueryMsg::TokenInfo {} => {
+                        let balance = self.read_token_balance(contract_addr, contract_addr.to_string());
+                        SystemResult::Ok(ContractResult::from(to_binary(&TokenInfoResponse {
+                            decimals: 6u8,
+                            name: "MOCK TOKEN".to_string(),
+                            symbol: "MOCK".to_string(),
+                            total_supply: balance,
+                        })))
+                    },
+                    MockQueryMsg::Staker { address } => {
+                        let balance = self.read_token_balance(contract_addr, address);
+                        SystemResult::Ok(ContractResult::from(to_binary(&StakerResponse {
+                            balance,
+                            share: balance,
+                            locked_balance: if self.locked_balance.is_zero() {
+                                vec![]
+                            } else {
+                                vec![(1u64, VoterInfo {
+                                    vote: VoteOption::Yes,
+                                    balance: self.locked_balance,
+                                })]
+                            },
+                            withdrawable_polls: vec![],
+                            pending_voting_rewards: Default::default()
+                        })))
+                    },
+                }
+            }
+            _ => self.base.handle_query(request),
+        }
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn new(base: MockQuerier<TerraQueryWrapper>) -> Self {
+        WasmMockQuerier {
+            base,
+            token_querier: TokenQuerier::default(),
+            locked_balance: Uint128::zero(),
+        }
+    }
+
+    // configure the mint whitelist mock querier
+    pub fn with_token_balances(&mut self, balances: &[(&String, &[(&String, &Uint128)])]) {
+        self.token_querier = TokenQuerier::new(balances);
+    }
+
+    // balance voted in active poll
+    pub fn with_locked_balance(&mut self, locked_balance: Uint128) {
+        self.locked_balance = locked_balance;
+    }
+
+    pub fn read_token_balance(&self, contract_addr: &str, address: String) -> Uint128 {
+        let balances: &HashMap<String, Uint128> =
+            match self.token_querier.balances.get(contract_addr) {
+                Some(balances) => balances,
+                None => return Uint128::zero(),
+            };
+
+        match balances.get(&address) {
+            Some(v) => *v,
+            None => Uint128::zero(),
+        }
+    }
+}
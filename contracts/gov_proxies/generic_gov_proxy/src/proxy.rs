@@ -0,0 +1,151 @@
+use cosmwasm_std::{Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128};
+use spectrum_protocol::gov_proxy::{StakerResponse, StakersResponse, UnbondingResponse, VoteOption};
+use spectrum_protocol::gov_proxy_helper;
+use crate::adapter::{query_total_balance, query_withdrawable, stake_msgs, stake_voting_rewards_msg, vote_msg, withdraw_msgs};
+use crate::state::{read_config, Adapter};
+
+pub fn query_staker_info_gov(
+    deps: Deps,
+    env: Env,
+    address: String,
+) -> StdResult<StakerResponse> {
+    let config = read_config(deps.storage)?;
+    let total_balance = query_total_balance(deps, &env, &config)?;
+    gov_proxy_helper::query_staker(deps, address, total_balance)
+}
+
+pub fn stake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: String,
+    amount: Uint128,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    if config.farm_token != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    // received token is already in proxy balance when upstream is the token itself
+    let mut total_balance = query_total_balance(deps.as_ref(), &env, &config)?;
+    if let Adapter::Token {} = config.adapter {
+        total_balance = total_balance.checked_sub(amount)?;
+    }
+    gov_proxy_helper::stake(deps.storage, &deps.api.addr_canonicalize(&sender)?, amount, total_balance)?;
+
+    Ok(Response::new()
+        .add_messages(stake_msgs(deps.as_ref(), &config, amount)?))
+}
+
+pub fn unstake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Option<Uint128>
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    let total_balance = query_total_balance(deps.as_ref(), &env, &config)?;
+    let withdrawable = query_withdrawable(deps.as_ref(), &env, &config)?;
+    let amount = gov_proxy_helper::unstake(
+        deps.storage,
+        &deps.api.addr_canonicalize(info.sender.as_str())?,
+        amount,
+        total_balance,
+        withdrawable,
+    )?;
+
+    Ok(Response::new().add_messages(withdraw_msgs(deps.as_ref(), &env, &config, info.sender.to_string(), amount)?))
+}
+
+pub fn cast_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: u64,
+    vote: VoteOption,
+    amount: Option<Uint128>,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    match (&config.adapter, amount) {
+        (Adapter::Token {}, _) => return Err(StdError::generic_err("voting is not supported")),
+        (Adapter::XAstro { .. }, Some(_)) =>
+            return Err(StdError::generic_err("amount is not supported, vote with all xASTRO balance")),
+        _ => {},
+    }
+
+    let total_balance = query_total_balance(deps.as_ref(), &env, &config)?;
+    let amount = gov_proxy_helper::vote_amount(deps.storage, amount, total_balance)?;
+    let (msg, attributes) = vote_msg(&config, poll_id, vote, amount)?;
+
+    gov_proxy_helper::cast_vote(
+        deps.as_ref(),
+        info,
+        config.adapter.default_vote_contract(),
+        poll_id,
+        msg,
+        attributes,
+    )
+}
+
+pub fn stake_voting_rewards(
+    deps: DepsMut,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    Ok(Response::new()
+        .add_message(stake_voting_rewards_msg(deps.as_ref(), &config)?))
+}
+
+pub fn query_stakers(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<StakersResponse> {
+    let config = read_config(deps.storage)?;
+    let total_balance = query_total_balance(deps, &env, &config)?;
+    gov_proxy_helper::query_stakers(deps, start_after, limit, total_balance)
+}
+
+pub fn request_unstake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Option<Uint128>,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    let total_balance = query_total_balance(deps.as_ref(), &env, &config)?;
+    gov_proxy_helper::request_unstake(
+        deps.storage,
+        &env,
+        &deps.api.addr_canonicalize(info.sender.as_str())?,
+        amount,
+        total_balance,
+    )
+}
+
+pub fn claim_unstake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    let withdrawable = query_withdrawable(deps.as_ref(), &env, &config)?;
+    let (amount, res) = gov_proxy_helper::claim_unstake(
+        deps.storage,
+        &env,
+        &deps.api.addr_canonicalize(info.sender.as_str())?,
+        withdrawable,
+    )?;
+
+    Ok(res.add_messages(withdraw_msgs(deps.as_ref(), &env, &config, info.sender.to_string(), amount)?))
+}
+
+pub fn query_unbonding(
+    deps: Deps,
+    env: Env,
+    address: String,
+) -> StdResult<UnbondingResponse> {
+    let config = read_config(deps.storage)?;
+    let withdrawable = query_withdrawable(deps, &env, &config)?;
+    gov_proxy_helper::query_unbonding(deps, &env, address, withdrawable)
+}
@@ -0,0 +1,50 @@
+use cosmwasm_storage::{singleton_read, singleton};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, Storage, StdResult};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Adapter {
+    Token {},
+    XAstro {
+        staking: CanonicalAddr,
+        xastro_token: CanonicalAddr,
+    },
+    Anchor {
+        gov: CanonicalAddr,
+    },
+    Mirror {
+        gov: CanonicalAddr,
+    },
+    Nexus {
+        gov: CanonicalAddr,
+    },
+}
+
+impl Adapter {
+    // poll based gov votes in gov itself, unless vote_contract is set
+    pub fn default_vote_contract(&self) -> Option<&CanonicalAddr> {
+        match self {
+            Adapter::Anchor { gov } | Adapter::Mirror { gov } | Adapter::Nexus { gov } => Some(gov),
+            Adapter::Token {} | Adapter::XAstro { .. } => None,
+        }
+    }
+}
+
+// vote, depositor and unbonding settings are kept in gov_proxy_helper
+static KEY_CONFIG: &[u8] = b"config";
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub adapter: Adapter,
+    pub farm_token: CanonicalAddr,
+}
+
+pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
+    singleton(storage, KEY_CONFIG).save(config)
+}
+
+pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
+    singleton_read(storage, KEY_CONFIG).load()
+}
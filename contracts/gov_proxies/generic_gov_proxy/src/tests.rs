@@ -0,0 +1,490 @@
+use crate::adapter::{AssemblyExecuteMsg, ProposalVoteOption};
+use crate::contract::{execute, instantiate, query, ConfigInfo};
+use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
+use astroport::staking::Cw20HookMsg as AstroportCw20HookMsg;
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{attr, from_binary, Binary, to_binary, CosmosMsg, OwnedDeps, Response, StdError, StdResult, Uint128, WasmMsg};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use mirror_protocol::gov::{Cw20HookMsg as MirrorCw20HookMsg, ExecuteMsg as MirrorExecuteMsg, VoteOption as MirrorVoteOption};
+use nexus_token::governance::{AnyoneMsg, Cw20HookMsg as NexusGovCw20HookMsg, ExecuteMsg as NexusGovExecuteMsg, VoteOption as NexusVoteOption};
+use spectrum_protocol::gov_proxy::{Cw20HookMsg, ExecuteMsg, GovAdapter, QueryMsg, StakerResponse, UnbondingInfo, UnbondingResponse, VoteOption};
+use spectrum_protocol::gov_proxy_helper::State;
+
+const TEST_CREATOR: &str = "creator";
+const FARM_CONTRACT: &str = "farm_contract";
+const FARM_TOKEN: &str = "farm_token";
+const FARM_GOV: &str = "farm_gov";
+const VOTE_AUTHORITY: &str = "vote_authority";
+const VOTE_CONTRACT: &str = "vote_contract";
+const XASTRO_TOKEN: &str = "xastro_token";
+
+#[test]
+fn test() {
+    let mut deps = mock_dependencies(&[]);
+
+    let _ = test_config(&mut deps);
+    test_depositor(&mut deps);
+    test_mirror(&mut deps);
+
+    test_token();
+    test_xastro();
+    test_nexus();
+}
+
+fn init(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, adapter: GovAdapter) -> ConfigInfo {
+    let env = mock_env();
+    let info = mock_info(TEST_CREATOR, &[]);
+    let config = ConfigInfo {
+        adapter,
+        farm_token: FARM_TOKEN.to_string(),
+        vote_authority: Some(VOTE_AUTHORITY.to_string()),
+        vote_delegate: None,
+        vote_contract: None,
+        owner: TEST_CREATOR.to_string(),
+        unbonding_period: 100,
+    };
+
+    let res = instantiate(deps.as_mut(), env.clone(), info.clone(), config.clone());
+    assert!(res.is_ok());
+
+    let msg = ExecuteMsg::RegisterDepositor {
+        depositor: FARM_CONTRACT.to_string(),
+        cap: None,
+    };
+    let res = execute(deps.as_mut(), env, info, msg);
+    assert!(res.is_ok());
+
+    config
+}
+
+fn stake(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, amount: u128) -> StdResult<Response> {
+    let info = mock_info(FARM_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: FARM_CONTRACT.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), info, msg)
+}
+
+fn unstake(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, amount: u128) -> StdResult<Response> {
+    let info = mock_info(FARM_CONTRACT, &[]);
+    let msg = ExecuteMsg::Unstake {
+        amount: Some(Uint128::from(amount)),
+    };
+    execute(deps.as_mut(), mock_env(), info, msg)
+}
+
+fn cast_vote(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, vote: VoteOption, amount: Option<u128>) -> StdResult<Response> {
+    let info = mock_info(VOTE_AUTHORITY, &[]);
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1u64,
+        vote,
+        amount: amount.map(Uint128::from),
+    };
+    execute(deps.as_mut(), mock_env(), info, msg)
+}
+
+fn query_staker(deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Uint128 {
+    let msg = QueryMsg::Staker {
+        address: FARM_CONTRACT.to_string(),
+    };
+    let res: StakerResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    res.balance
+}
+
+fn messages(res: Response) -> Vec<CosmosMsg> {
+    res.messages
+        .into_iter()
+        .map(|it| it.msg)
+        .collect()
+}
+
+fn transfer_msg(amount: u128) -> CosmosMsg {
+    CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: FARM_TOKEN.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: FARM_CONTRACT.to_string(),
+            amount: Uint128::from(amount),
+        })
+        .unwrap(),
+        funds: vec![],
+    })
+}
+
+fn send_msg(contract: &str, amount: u128, msg: Binary) -> CosmosMsg {
+    CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: FARM_TOKEN.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Send {
+            contract: contract.to_string(),
+            amount: Uint128::from(amount),
+            msg,
+        })
+        .unwrap(),
+        funds: vec![],
+    })
+}
+
+fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> ConfigInfo {
+    // test init & read config & read state
+    let env = mock_env();
+    let info = mock_info(TEST_CREATOR, &[]);
+    let config = ConfigInfo {
+        adapter: GovAdapter::Mirror {
+            gov: FARM_GOV.to_string(),
+        },
+        farm_token: FARM_TOKEN.to_string(),
+        vote_authority: Some(VOTE_AUTHORITY.to_string()),
+        vote_delegate: None,
+        vote_contract: None,
+        owner: TEST_CREATOR.to_string(),
+        unbonding_period: 100,
+    };
+
+    // success init
+    let res = instantiate(deps.as_mut(), env.clone(), info, config.clone());
+    assert!(res.is_ok());
+
+    // read config
+    let msg = QueryMsg::Config {};
+    let res: ConfigInfo = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res, config);
+
+    // read state
+    let msg = QueryMsg::State {};
+    let res: State = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        State {
+            total_share: Uint128::zero(),
+            total_pending: Uint128::zero(),
+        }
+    );
+
+    config
+}
+
+fn test_depositor(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+
+    // unregistered depositor cannot stake
+    let res = stake(deps, 10000u128);
+    assert_eq!(res, Err(StdError::generic_err("depositor is not allowed")));
+
+    // only owner can register depositor
+    let info = mock_info(FARM_CONTRACT, &[]);
+    let msg = ExecuteMsg::RegisterDepositor {
+        depositor: FARM_CONTRACT.to_string(),
+        cap: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    let info = mock_info(TEST_CREATOR, &[]);
+    let res = execute(deps.as_mut(), env, info, msg);
+    assert!(res.is_ok());
+}
+
+fn test_mirror(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    // stake into mirror gov
+    let res = stake(deps, 10000u128).unwrap();
+    assert_eq!(
+        messages(res),
+        vec![send_msg(FARM_GOV, 10000u128, to_binary(&MirrorCw20HookMsg::StakeVotingTokens {}).unwrap())]
+    );
+
+    // gov stake grows by 2000
+    deps.querier.with_token_balances(&[(
+        &FARM_GOV.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(12000u128))],
+    )]);
+    assert_eq!(query_staker(deps), Uint128::from(12000u128));
+
+    // voted balance is locked
+    deps.querier.with_locked_balance(Uint128::from(5000u128));
+    let res = unstake(deps, 8000u128);
+    assert_eq!(res, Err(StdError::generic_err("amount is locked in upstream gov, use request_unstake")));
+
+    let res = unstake(deps, 7000u128).unwrap();
+    assert_eq!(
+        messages(res),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: FARM_GOV.to_string(),
+                msg: to_binary(&MirrorExecuteMsg::WithdrawVotingTokens {
+                    amount: Some(Uint128::from(7000u128)),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            transfer_msg(7000u128),
+        ]
+    );
+    deps.querier.with_locked_balance(Uint128::zero());
+    deps.querier.with_token_balances(&[(
+        &FARM_GOV.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(5000u128))],
+    )]);
+
+    // mirror supports abstain, and vote with all balance by default
+    let res = cast_vote(deps, VoteOption::Abstain, None).unwrap();
+    assert_eq!(
+        messages(res),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: FARM_GOV.to_string(),
+            msg: to_binary(&MirrorExecuteMsg::CastVote {
+                poll_id: 1u64,
+                vote: MirrorVoteOption::Abstain,
+                amount: Uint128::from(5000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    // requested amount is excluded from vote
+    let mut env = mock_env();
+    let release_at = env.block.time.seconds() + 100;
+    let info = mock_info(FARM_CONTRACT, &[]);
+    let msg = ExecuteMsg::RequestUnstake {
+        amount: Some(Uint128::from(2000u128)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert!(res.messages.is_empty());
+    assert_eq!(query_staker(deps), Uint128::from(3000u128));
+
+    let res = cast_vote(deps, VoteOption::Yes, None).unwrap();
+    assert_eq!(res.attributes[3], attr("amount", "3000"));
+    let res = cast_vote(deps, VoteOption::Yes, Some(5000u128));
+    assert_eq!(res, Err(StdError::generic_err("amount exceeds voting balance")));
+
+    // matured, but only 1000 is not locked by poll
+    env.block.time = env.block.time.plus_seconds(100);
+    deps.querier.with_locked_balance(Uint128::from(4000u128));
+    let msg = QueryMsg::Unbonding {
+        address: FARM_CONTRACT.to_string(),
+    };
+    let res: UnbondingResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        UnbondingResponse {
+            requests: vec![UnbondingInfo {
+                amount: Uint128::from(2000u128),
+                release_at,
+            }],
+            pending: Uint128::from(2000u128),
+            matured: Uint128::from(2000u128),
+            claimable: Uint128::from(1000u128),
+        }
+    );
+
+    let info = mock_info(FARM_CONTRACT, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ClaimUnstake {}).unwrap();
+    assert_eq!(
+        messages(res),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: FARM_GOV.to_string(),
+                msg: to_binary(&MirrorExecuteMsg::WithdrawVotingTokens {
+                    amount: Some(Uint128::from(1000u128)),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            transfer_msg(1000u128),
+        ]
+    );
+    deps.querier.with_locked_balance(Uint128::zero());
+
+    let info = mock_info(TEST_CREATOR, &[]);
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::StakeVotingRewards {}).unwrap();
+    assert_eq!(
+        messages(res),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: FARM_GOV.to_string(),
+            msg: to_binary(&MirrorExecuteMsg::StakeVotingRewards {
+                poll_id: None,
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+}
+
+fn test_token() {
+    let mut deps = mock_dependencies(&[]);
+    let deps = &mut deps;
+    init(deps, GovAdapter::Token {});
+
+    // received token is already in balance, and stays in proxy
+    deps.querier.with_token_balances(&[(
+        &FARM_TOKEN.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(10000u128))],
+    )]);
+    let res = stake(deps, 10000u128).unwrap();
+    assert_eq!(messages(res), vec![]);
+    assert_eq!(query_staker(deps), Uint128::from(10000u128));
+
+    let res = unstake(deps, 4000u128).unwrap();
+    assert_eq!(messages(res), vec![transfer_msg(4000u128)]);
+
+    let res = cast_vote(deps, VoteOption::Yes, None);
+    assert_eq!(res, Err(StdError::generic_err("voting is not supported")));
+
+    let info = mock_info(TEST_CREATOR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::StakeVotingRewards {});
+    assert_eq!(res, Err(StdError::generic_err("voting rewards is not supported")));
+}
+
+fn test_xastro() {
+    let mut deps = mock_dependencies(&[]);
+    let deps = &mut deps;
+    init(deps, GovAdapter::XAstro {
+        staking: FARM_GOV.to_string(),
+        xastro_token: XASTRO_TOKEN.to_string(),
+    });
+
+    // enter staking
+    deps.querier.with_token_balances(&[
+        (
+            &XASTRO_TOKEN.to_string(),
+            &[(&XASTRO_TOKEN.to_string(), &Uint128::from(10_000_000u128))],
+        ),
+        (
+            &FARM_TOKEN.to_string(),
+            &[(&FARM_GOV.to_string(), &Uint128::from(20_000_000u128))],
+        ),
+    ]);
+    let res = stake(deps, 10000u128).unwrap();
+    assert_eq!(
+        messages(res),
+        vec![send_msg(FARM_GOV, 10000u128, to_binary(&AstroportCw20HookMsg::Enter {}).unwrap())]
+    );
+
+    // 5000 xASTRO is worth 10000 ASTRO
+    deps.querier.with_token_balances(&[
+        (
+            &XASTRO_TOKEN.to_string(),
+            &[
+                (&XASTRO_TOKEN.to_string(), &Uint128::from(10_000_000u128)),
+                (&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(5000u128)),
+            ],
+        ),
+        (
+            &FARM_TOKEN.to_string(),
+            &[(&FARM_GOV.to_string(), &Uint128::from(20_000_000u128))],
+        ),
+    ]);
+    assert_eq!(query_staker(deps), Uint128::from(10000u128));
+
+    // leave staking
+    let res = unstake(deps, 4000u128).unwrap();
+    assert_eq!(
+        messages(res),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: XASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: FARM_GOV.to_string(),
+                    amount: Uint128::from(2000u128),
+                    msg: to_binary(&AstroportCw20HookMsg::Leave {}).unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            transfer_msg(4000u128),
+        ]
+    );
+
+    // vote in assembly
+    let res = cast_vote(deps, VoteOption::Yes, None);
+    assert_eq!(res, Err(StdError::generic_err("vote_contract is not set")));
+
+    let info = mock_info(VOTE_AUTHORITY, &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        vote_authority: None,
+        vote_contract: Some(VOTE_CONTRACT.to_string()),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert!(res.is_ok());
+
+    let res = cast_vote(deps, VoteOption::Yes, Some(1000u128));
+    assert_eq!(res, Err(StdError::generic_err("amount is not supported, vote with all xASTRO balance")));
+
+    let res = cast_vote(deps, VoteOption::Abstain, None);
+    assert_eq!(res, Err(StdError::generic_err("abstain is not supported")));
+
+    let res = cast_vote(deps, VoteOption::No, None).unwrap();
+    assert_eq!(
+        messages(res),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: VOTE_CONTRACT.to_string(),
+            msg: to_binary(&AssemblyExecuteMsg::CastVote {
+                proposal_id: 1u64,
+                vote: ProposalVoteOption::Against,
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+}
+
+fn test_nexus() {
+    let mut deps = mock_dependencies(&[]);
+    let deps = &mut deps;
+    init(deps, GovAdapter::Nexus {
+        gov: FARM_GOV.to_string(),
+    });
+
+    let res = stake(deps, 10000u128).unwrap();
+    assert_eq!(
+        messages(res),
+        vec![send_msg(FARM_GOV, 10000u128, to_binary(&NexusGovCw20HookMsg::StakeVotingTokens {}).unwrap())]
+    );
+
+    deps.querier.with_token_balances(&[(
+        &FARM_GOV.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(10000u128))],
+    )]);
+    let res = unstake(deps, 3000u128).unwrap();
+    assert_eq!(
+        messages(res),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: FARM_GOV.to_string(),
+                msg: to_binary(&NexusGovExecuteMsg::Anyone {
+                    anyone_msg: AnyoneMsg::WithdrawVotingTokens {
+                        amount: Some(Uint128::from(3000u128)),
+                    },
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            transfer_msg(3000u128),
+        ]
+    );
+
+    let res = cast_vote(deps, VoteOption::Abstain, None);
+    assert_eq!(res, Err(StdError::generic_err("abstain is not supported")));
+
+    let res = cast_vote(deps, VoteOption::Yes, Some(1000u128)).unwrap();
+    assert_eq!(
+        messages(res),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: FARM_GOV.to_string(),
+            msg: to_binary(&NexusGovExecuteMsg::Anyone {
+                anyone_msg: AnyoneMsg::CastVote {
+                    poll_id: 1u64,
+                    vote: NexusVoteOption::Yes,
+                    amount: Uint128::from(1000u128),
+                },
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    let info = mock_info(TEST_CREATOR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::StakeVotingRewards {});
+    assert_eq!(res, Err(StdError::generic_err("voting rewards is not supported")));
+}
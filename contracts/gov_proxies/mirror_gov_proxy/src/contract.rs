@@ -4,7 +4,7 @@ use cosmwasm_std::{from_binary, to_binary, Binary, Deps, DepsMut, Env, MessageIn
 
 use crate::{
     state::{read_config, store_config, Config},
-    proxy::{query_staker_info_gov}
+    proxy::{query_staker_info_gov, query_stakers, query_unbonding}
 };
 
 use cw20::Cw20ReceiveMsg;
@@ -12,10 +12,12 @@ use cw20::Cw20ReceiveMsg;
 use spectrum_protocol::gov_proxy::{
     ConfigInfo, Cw20HookMsg, ExecuteMsg, MigrateMsg, QueryMsg,
 };
+use spectrum_protocol::gov_proxy_helper::{
+    canonicalize_opt, deregister_depositor, humanize_opt, read_proxy_config, read_state, register_depositor, set_vote_delegate, state_store, store_proxy_config, update_config, ProxyConfig, State,
+};
 use crate::proxy::{
-    stake, unstake
+    cast_vote, claim_unstake, request_unstake, stake, stake_voting_rewards, unstake
 };
-use crate::state::{read_state, State, state_store};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -32,8 +34,20 @@ pub fn instantiate(
         },
     )?;
 
+    store_proxy_config(
+        deps.storage,
+        &ProxyConfig {
+            vote_authority: canonicalize_opt(deps.api, msg.vote_authority)?,
+            vote_delegate: canonicalize_opt(deps.api, msg.vote_delegate)?,
+            vote_contract: canonicalize_opt(deps.api, msg.vote_contract)?,
+            owner: Some(deps.api.addr_canonicalize(&msg.owner)?),
+            unbonding_period: msg.unbonding_period,
+        },
+    )?;
+
     state_store(deps.storage).save(&State {
         total_share: Uint128::zero(),
+        total_pending: Uint128::zero(),
     })?;
 
     Ok(Response::default())
@@ -44,6 +58,14 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
     match msg {
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::Unstake { amount} => unstake(deps, env, info, amount),
+        ExecuteMsg::RequestUnstake { amount } => request_unstake(deps, env, info, amount),
+        ExecuteMsg::ClaimUnstake {} => claim_unstake(deps, env, info),
+        ExecuteMsg::CastVote { poll_id, vote, amount } => cast_vote(deps, env, info, poll_id, vote, amount),
+        ExecuteMsg::StakeVotingRewards {} => stake_voting_rewards(deps),
+        ExecuteMsg::UpdateConfig { vote_authority, vote_contract } => update_config(deps, info, vote_authority, vote_contract),
+        ExecuteMsg::SetVoteDelegate { vote_delegate } => set_vote_delegate(deps, info, vote_delegate),
+        ExecuteMsg::RegisterDepositor { depositor, cap } => register_depositor(deps, info, depositor, cap),
+        ExecuteMsg::DeregisterDepositor { depositor } => deregister_depositor(deps, info, depositor),
     }
 }
 
@@ -70,15 +92,23 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::State {} => to_binary(&query_state(deps)?),
-        QueryMsg::Staker { address } => to_binary(&query_staker_info_gov(deps, env, address)?)
+        QueryMsg::Staker { address } => to_binary(&query_staker_info_gov(deps, env, address)?),
+        QueryMsg::Stakers { start_after, limit } => to_binary(&query_stakers(deps, env, start_after, limit)?),
+        QueryMsg::Unbonding { address } => to_binary(&query_unbonding(deps, env, address)?),
     }
 }
 
 fn query_config(deps: Deps) -> StdResult<ConfigInfo> {
     let config = read_config(deps.storage)?;
+    let proxy_config = read_proxy_config(deps.storage)?;
     let resp = ConfigInfo {
         farm_token: deps.api.addr_humanize(&config.farm_token)?.to_string(),
         farm_gov: deps.api.addr_humanize(&config.farm_gov)?.to_string(),
+        vote_authority: humanize_opt(deps.api, &proxy_config.vote_authority)?,
+        vote_delegate: humanize_opt(deps.api, &proxy_config.vote_delegate)?,
+        vote_contract: humanize_opt(deps.api, &proxy_config.vote_contract)?,
+        owner: humanize_opt(deps.api, &proxy_config.owner)?.unwrap_or_default(),
+        unbonding_period: proxy_config.unbonding_period,
     };
     Ok(resp)
 }
@@ -88,6 +118,16 @@ fn query_state(deps: Deps) -> StdResult<State> {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    let mut proxy_config = read_proxy_config(deps.storage)?;
+    proxy_config.owner = Some(deps.api.addr_canonicalize(&msg.owner)?);
+    if let Some(vote_authority) = msg.vote_authority {
+        proxy_config.vote_authority = Some(deps.api.addr_canonicalize(&vote_authority)?);
+    }
+    if let Some(unbonding_period) = msg.unbonding_period {
+        proxy_config.unbonding_period = unbonding_period;
+    }
+    store_proxy_config(deps.storage, &proxy_config)?;
+
     Ok(Response::default())
 }
@@ -1,9 +1,10 @@
-use cosmwasm_std::{CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, to_binary, Uint128, WasmMsg};
+use cosmwasm_std::{attr, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, to_binary, Uint128, WasmMsg};
 use cw20::{Cw20ExecuteMsg};
-use spectrum_protocol::gov_proxy::StakerResponse;
+use spectrum_protocol::gov_proxy::{StakerResponse, StakersResponse, UnbondingResponse, VoteOption};
+use spectrum_protocol::gov_proxy_helper;
 use crate::querier::query_mirror_gov;
-use crate::state::{account_store, read_account, read_config, read_state, state_store};
-use mirror_protocol::gov::{Cw20HookMsg as MirrorCw20HookMsg, ExecuteMsg as MirrorExecuteMsg};
+use crate::state::{read_config, Config};
+use mirror_protocol::gov::{Cw20HookMsg as MirrorCw20HookMsg, ExecuteMsg as MirrorExecuteMsg, VoteOption as MirrorVoteOption};
 
 pub fn query_staker_info_gov(
     deps: Deps,
@@ -11,17 +12,8 @@ pub fn query_staker_info_gov(
     address: String,
 ) -> StdResult<StakerResponse> {
     let config = read_config(deps.storage)?;
-    let state = read_state(deps.storage)?;
-
-    let gov_response = query_mirror_gov(deps, &config.farm_gov, &env.contract.address)?;
-    let addr_raw = deps.api.addr_canonicalize(&address).unwrap();
-    let account = read_account(deps.storage, addr_raw.as_slice())?
-        .unwrap_or_default();
-    let balance = state.calc_balance(gov_response.balance, account.share);
-
-    Ok(StakerResponse {
-        balance,
-    })
+    let total_balance = query_total_balance(deps, &env, &config)?;
+    gov_proxy_helper::query_staker(deps, address, total_balance)
 }
 
 pub fn stake(
@@ -31,31 +23,13 @@ pub fn stake(
     sender: String,
     amount: Uint128,
 ) -> StdResult<Response> {
-
-    if amount.is_zero() {
-        return Err(StdError::generic_err("Insufficient funds sent"));
-    }
-
     let config = read_config(deps.storage)?;
     if config.farm_token != deps.api.addr_canonicalize(info.sender.as_str())? {
         return Err(StdError::generic_err("unauthorized"));
     }
 
-    let mut state = state_store(deps.storage).load()?;
-
-    let gov_response = query_mirror_gov(deps.as_ref(), &config.farm_gov, &env.contract.address)?;
-    let sender_address_raw = deps.api.addr_canonicalize(&sender)?;
-    let key = sender_address_raw.as_slice();
-    let mut account = account_store(deps.storage)
-        .may_load(key)?
-        .unwrap_or_default();
-
-    let share = state.calc_share(gov_response.balance, amount);
-    account.share += share;
-    state.total_share += share;
-
-    state_store(deps.storage).save(&state)?;
-    account_store(deps.storage).save(key, &account)?;
+    let total_balance = query_total_balance(deps.as_ref(), &env, &config)?;
+    gov_proxy_helper::stake(deps.storage, &deps.api.addr_canonicalize(&sender)?, amount, total_balance)?;
 
     let mirror_token = deps.api.addr_humanize(&config.farm_token)?;
     let mirror_gov = deps.api.addr_humanize(&config.farm_gov)?;
@@ -78,38 +52,31 @@ pub fn unstake(
     info: MessageInfo,
     amount: Option<Uint128>
 ) -> StdResult<Response> {
-
-    let sender_address_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
-    let key = sender_address_raw.as_slice();
-
     let config = read_config(deps.storage)?;
-    let mut state = state_store(deps.storage).load()?;
-
-    let gov_response = query_mirror_gov(deps.as_ref(), &config.farm_gov, &env.contract.address)?;
-    let mut account = account_store(deps.storage).load(key)?;
-    let user_balance = state.calc_balance(gov_response.balance, account.share);
-    let amount = amount.unwrap_or(user_balance);
-    if amount > user_balance {
-        return Err(StdError::generic_err(
-            "User is trying to withdraw too many tokens.",
-        ));
-    }
-
-    let mut withdraw_share = state.calc_share(gov_response.balance, amount);
-    if state.calc_balance(gov_response.balance, withdraw_share) < amount {
-        withdraw_share += Uint128::from(1u128);
-    }
-
-    account.share -= withdraw_share;
-    state.total_share -= withdraw_share;
-
-    account_store(deps.storage).save(key, &account)?;
-    state_store(deps.storage).save(&state)?;
+    let total_balance = query_total_balance(deps.as_ref(), &env, &config)?;
+    let withdrawable = query_withdrawable(deps.as_ref(), &env, &config)?;
+    let amount = gov_proxy_helper::unstake(
+        deps.storage,
+        &deps.api.addr_canonicalize(info.sender.as_str())?,
+        amount,
+        total_balance,
+        withdrawable,
+    )?;
+
+    Ok(Response::new().add_messages(withdraw_msgs(deps.as_ref(), &env, &config, info.sender.to_string(), amount)?))
+}
 
+fn withdraw_msgs(
+    deps: Deps,
+    _env: &Env,
+    config: &Config,
+    recipient: String,
+    amount: Uint128,
+) -> StdResult<Vec<CosmosMsg>> {
     let mirror_token = deps.api.addr_humanize(&config.farm_token)?;
     let mirror_gov = deps.api.addr_humanize(&config.farm_gov)?;
 
-    Ok(Response::new().add_messages(vec![
+    Ok(vec![
         CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: mirror_gov.to_string(),
             msg: to_binary(&MirrorExecuteMsg::WithdrawVotingTokens {
@@ -120,10 +87,132 @@ pub fn unstake(
         CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: mirror_token.to_string(),
             msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: info.sender.to_string(),
+                recipient,
                 amount
             })?,
             funds: vec![],
         })
-    ]))
+    ])
+}
+
+pub fn cast_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: u64,
+    vote: VoteOption,
+    amount: Option<Uint128>,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    let total_balance = query_total_balance(deps.as_ref(), &env, &config)?;
+    let amount = gov_proxy_helper::vote_amount(deps.storage, amount, total_balance)?;
+    let mirror_vote = match vote {
+        VoteOption::Yes => MirrorVoteOption::Yes,
+        VoteOption::No => MirrorVoteOption::No,
+        VoteOption::Abstain => MirrorVoteOption::Abstain,
+    };
+
+    gov_proxy_helper::cast_vote(
+        deps.as_ref(),
+        info,
+        Some(&config.farm_gov),
+        poll_id,
+        to_binary(&MirrorExecuteMsg::CastVote {
+            poll_id,
+            vote: mirror_vote.clone(),
+            amount,
+        })?,
+        vec![
+            attr("vote", mirror_vote.to_string()),
+            attr("amount", amount),
+        ],
+    )
+}
+
+pub fn stake_voting_rewards(
+    deps: DepsMut,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    let mirror_gov = deps.api.addr_humanize(&config.farm_gov)?;
+
+    // staked rewards increase gov balance, so it is shared to all depositors by share
+    Ok(Response::new()
+        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: mirror_gov.to_string(),
+            msg: to_binary(&MirrorExecuteMsg::StakeVotingRewards {
+                poll_id: None,
+            })?,
+            funds: vec![],
+        }))
+        .add_attributes(vec![attr("action", "stake_voting_rewards")]))
+}
+
+pub fn query_stakers(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<StakersResponse> {
+    let config = read_config(deps.storage)?;
+    let total_balance = query_total_balance(deps, &env, &config)?;
+    gov_proxy_helper::query_stakers(deps, start_after, limit, total_balance)
+}
+
+fn query_total_balance(deps: Deps, env: &Env, config: &Config) -> StdResult<Uint128> {
+    Ok(query_mirror_gov(deps, &config.farm_gov, &env.contract.address)?.balance)
+}
+
+// tokens voted in active polls are locked in upstream gov until poll ends
+fn query_withdrawable(deps: Deps, env: &Env, config: &Config) -> StdResult<Uint128> {
+    let gov_response = query_mirror_gov(deps, &config.farm_gov, &env.contract.address)?;
+    let locked = gov_response.locked_balance
+        .iter()
+        .map(|(_, voter)| voter.balance)
+        .max()
+        .unwrap_or_default();
+    Ok(gov_response.balance.checked_sub(locked).unwrap_or_default())
+}
+
+pub fn request_unstake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Option<Uint128>,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    let total_balance = query_total_balance(deps.as_ref(), &env, &config)?;
+    gov_proxy_helper::request_unstake(
+        deps.storage,
+        &env,
+        &deps.api.addr_canonicalize(info.sender.as_str())?,
+        amount,
+        total_balance,
+    )
+}
+
+pub fn claim_unstake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    let withdrawable = query_withdrawable(deps.as_ref(), &env, &config)?;
+    let (amount, res) = gov_proxy_helper::claim_unstake(
+        deps.storage,
+        &env,
+        &deps.api.addr_canonicalize(info.sender.as_str())?,
+        withdrawable,
+    )?;
+
+    Ok(res.add_messages(withdraw_msgs(deps.as_ref(), &env, &config, info.sender.to_string(), amount)?))
+}
+
+pub fn query_unbonding(
+    deps: Deps,
+    env: Env,
+    address: String,
+) -> StdResult<UnbondingResponse> {
+    let config = read_config(deps.storage)?;
+    let withdrawable = query_withdrawable(deps, &env, &config)?;
+    gov_proxy_helper::query_unbonding(deps, &env, address, withdrawable)
 }
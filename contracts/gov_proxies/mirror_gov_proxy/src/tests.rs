@@ -1,25 +1,32 @@
 use crate::contract::{execute, instantiate, query};
 use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
-use crate::state::State;
-use mirror_protocol::gov::{Cw20HookMsg as MirrorCw20HookMsg, ExecuteMsg as MirrorExecuteMsg};
+use spectrum_protocol::gov_proxy_helper::State;
+use mirror_protocol::gov::{Cw20HookMsg as MirrorCw20HookMsg, ExecuteMsg as MirrorExecuteMsg, VoteOption as MirrorVoteOption};
 use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{from_binary, to_binary, CosmosMsg, OwnedDeps, Uint128, WasmMsg};
+use cosmwasm_std::{attr, from_binary, to_binary, CosmosMsg, OwnedDeps, StdError, Uint128, WasmMsg};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
-use spectrum_protocol::gov_proxy::{ConfigInfo, Cw20HookMsg, ExecuteMsg, QueryMsg, StakerResponse};
+use spectrum_protocol::gov_proxy::{ConfigInfo, Cw20HookMsg, ExecuteMsg, QueryMsg, StakerInfo, StakerResponse, StakersResponse, UnbondingInfo, UnbondingResponse, VoteOption};
 
 const TEST_CREATOR: &str = "creator";
 const FARM_CONTRACT: &str = "farm_contract";
 const FARM_CONTRACT_2: &str = "farm_contract_2";
 const FARM_TOKEN: &str = "farm_token";
 const FARM_GOV: &str = "farm_gov";
+const VOTE_AUTHORITY: &str = "vote_authority";
+const VOTE_DELEGATE: &str = "vote_delegate";
+const VOTE_CONTRACT: &str = "vote_contract";
 
 #[test]
 fn test() {
     let mut deps = mock_dependencies(&[]);
 
     let _ = test_config(&mut deps);
+    test_depositor(&mut deps);
     test_stake(&mut deps);
     test_unstake(&mut deps);
+    test_stakers(&mut deps);
+    test_vote(&mut deps);
+    test_unbonding(&mut deps);
 }
 
 fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> ConfigInfo {
@@ -31,6 +38,11 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
     let config = ConfigInfo {
         farm_token: FARM_TOKEN.to_string(),
         farm_gov: FARM_GOV.to_string(),
+        vote_authority: Some(VOTE_AUTHORITY.to_string()),
+        vote_delegate: None,
+        vote_contract: None,
+        owner: TEST_CREATOR.to_string(),
+        unbonding_period: 100,
     };
 
     // success init
@@ -49,12 +61,48 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         res,
         State {
             total_share: Uint128::zero(),
+            total_pending: Uint128::zero(),
         }
     );
 
     config
 }
 
+fn test_depositor(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+
+    // unregistered depositor cannot stake
+    let info = mock_info(FARM_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: FARM_CONTRACT.to_string(),
+        amount: Uint128::from(10000u128),
+        msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("depositor is not allowed")));
+
+    // only owner can register depositor
+    let info = mock_info(FARM_CONTRACT, &[]);
+    let msg = ExecuteMsg::RegisterDepositor {
+        depositor: FARM_CONTRACT.to_string(),
+        cap: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    let info = mock_info(TEST_CREATOR, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::RegisterDepositor {
+        depositor: FARM_CONTRACT_2.to_string(),
+        cap: Some(Uint128::from(4000u128)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+}
+
 fn test_stake(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     let env = mock_env();
 
@@ -371,3 +419,327 @@ fn test_unstake(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
         }
     );
 }
+
+fn test_vote(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+
+    // only vote_authority can set delegate
+    let info = mock_info(VOTE_DELEGATE, &[]);
+    let msg = ExecuteMsg::SetVoteDelegate {
+        vote_delegate: Some(VOTE_DELEGATE.to_string()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert!(res.is_err());
+
+    let info = mock_info(VOTE_AUTHORITY, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    // only vote_authority or vote_delegate can vote
+    let info = mock_info(FARM_CONTRACT, &[]);
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1u64,
+        vote: VoteOption::Yes,
+        amount: Some(Uint128::from(1000u128)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert!(res.is_err());
+
+    let info = mock_info(VOTE_DELEGATE, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: FARM_GOV.to_string(),
+            msg: to_binary(&MirrorExecuteMsg::CastVote {
+                poll_id: 1u64,
+                vote: MirrorVoteOption::Yes,
+                amount: Uint128::from(1000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    // vote with all balance in new vote contract
+    let info = mock_info(VOTE_AUTHORITY, &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        vote_authority: None,
+        vote_contract: Some(VOTE_CONTRACT.to_string()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    let info = mock_info(VOTE_DELEGATE, &[]);
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 2u64,
+        vote: VoteOption::Abstain,
+        amount: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: VOTE_CONTRACT.to_string(),
+            msg: to_binary(&MirrorExecuteMsg::CastVote {
+                poll_id: 2u64,
+                vote: MirrorVoteOption::Abstain,
+                amount: Uint128::from(4620u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    // removed delegate cannot vote
+    let info = mock_info(VOTE_AUTHORITY, &[]);
+    let msg = ExecuteMsg::SetVoteDelegate { vote_delegate: None };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    let info = mock_info(VOTE_DELEGATE, &[]);
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 3u64,
+        vote: VoteOption::No,
+        amount: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_err());
+
+    // anyone can stake voting rewards
+    let info = mock_info(FARM_CONTRACT, &[]);
+    let msg = ExecuteMsg::StakeVotingRewards {};
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: FARM_GOV.to_string(),
+            msg: to_binary(&MirrorExecuteMsg::StakeVotingRewards { poll_id: None }).unwrap(),
+            funds: vec![],
+        })]
+    );
+}
+
+fn test_stakers(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+
+    let msg = QueryMsg::Stakers {
+        start_after: None,
+        limit: None,
+    };
+    let res: StakersResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        StakersResponse {
+            total_share: Uint128::from(3636u128),
+            total_balance: Uint128::from(4620u128),
+            stakers: vec![
+                StakerInfo {
+                    address: FARM_CONTRACT_2.to_string(),
+                    share: Uint128::from(3636u128),
+                    balance: Uint128::from(4620u128),
+                    allowed: true,
+                    cap: Some(Uint128::from(4000u128)),
+                },
+                StakerInfo {
+                    address: FARM_CONTRACT.to_string(),
+                    share: Uint128::zero(),
+                    balance: Uint128::zero(),
+                    allowed: true,
+                    cap: None,
+                },
+            ],
+        }
+    );
+
+    // paginate
+    let msg = QueryMsg::Stakers {
+        start_after: Some(FARM_CONTRACT_2.to_string()),
+        limit: Some(1),
+    };
+    let res: StakersResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.stakers.len(), 1);
+    assert_eq!(res.stakers[0].address, FARM_CONTRACT.to_string());
+
+    // already over cap
+    let info = mock_info(FARM_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: FARM_CONTRACT_2.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("exceed depositor cap")));
+
+    // deregistered depositor cannot stake
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::DeregisterDepositor {
+        depositor: FARM_CONTRACT.to_string(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    let info = mock_info(FARM_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: FARM_CONTRACT.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("depositor is not allowed")));
+}
+
+fn test_unbonding(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let mut env = mock_env();
+    let release_at = env.block.time.seconds() + 100;
+
+    // request 1000 of 4620
+    let info = mock_info(FARM_CONTRACT_2, &[]);
+    let msg = ExecuteMsg::RequestUnstake {
+        amount: Some(Uint128::from(1000u128)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert!(res.messages.is_empty());
+
+    // requested amount stops earning
+    let msg = QueryMsg::Staker {
+        address: FARM_CONTRACT_2.to_string(),
+    };
+    let res: StakerResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        StakerResponse {
+            balance: Uint128::from(3620u128),
+        }
+    );
+
+    // vote during active poll excludes requested amount, so it can be withdrawn after poll
+    let info = mock_info(VOTE_AUTHORITY, &[]);
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 3u64,
+        vote: VoteOption::Yes,
+        amount: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(res.attributes[3], attr("amount", "3620"));
+
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 3u64,
+        vote: VoteOption::Yes,
+        amount: Some(Uint128::from(4620u128)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("amount exceeds voting balance")));
+
+    // not matured
+    let info = mock_info(FARM_CONTRACT_2, &[]);
+    let msg = ExecuteMsg::ClaimUnstake {};
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("no matured unstake request")));
+
+    // matured, but only 620 is not locked by poll
+    env.block.time = env.block.time.plus_seconds(100);
+    deps.querier.with_locked_balance(Uint128::from(4000u128));
+    let msg = QueryMsg::Unbonding {
+        address: FARM_CONTRACT_2.to_string(),
+    };
+    let res: UnbondingResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        UnbondingResponse {
+            requests: vec![UnbondingInfo {
+                amount: Uint128::from(1000u128),
+                release_at,
+            }],
+            pending: Uint128::from(1000u128),
+            matured: Uint128::from(1000u128),
+            claimable: Uint128::from(620u128),
+        }
+    );
+
+    let info = mock_info(FARM_CONTRACT_2, &[]);
+    let msg = ExecuteMsg::ClaimUnstake {};
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: FARM_GOV.to_string(),
+                msg: to_binary(&MirrorExecuteMsg::WithdrawVotingTokens {
+                    amount: Some(Uint128::from(620u128)),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: FARM_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: FARM_CONTRACT_2.to_string(),
+                    amount: Uint128::from(620u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        ]
+    );
+    assert_eq!(res.attributes[2], attr("matured_remaining", "380"));
+
+    // all locked now, instant unstake fails clearly
+    deps.querier.with_token_balances(&[(
+        &FARM_GOV.to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(4000u128))],
+    )]);
+    let info = mock_info(FARM_CONTRACT_2, &[]);
+    let msg = ExecuteMsg::Unstake {
+        amount: Some(Uint128::from(100u128)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("amount is locked in upstream gov, use request_unstake")));
+
+    let info = mock_info(FARM_CONTRACT_2, &[]);
+    let msg = ExecuteMsg::ClaimUnstake {};
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("tokens are locked in upstream gov, try again later")));
+
+    // poll ended, claim the rest
+    deps.querier.with_locked_balance(Uint128::zero());
+    let info = mock_info(FARM_CONTRACT_2, &[]);
+    let msg = ExecuteMsg::ClaimUnstake {};
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(res.attributes[1], attr("amount", "380"));
+
+    let msg = QueryMsg::Unbonding {
+        address: FARM_CONTRACT_2.to_string(),
+    };
+    let res: UnbondingResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        UnbondingResponse {
+            requests: vec![],
+            pending: Uint128::zero(),
+            matured: Uint128::zero(),
+            claimable: Uint128::zero(),
+        }
+    );
+
+    let msg = QueryMsg::State {};
+    let res: State = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.total_pending, Uint128::zero());
+}
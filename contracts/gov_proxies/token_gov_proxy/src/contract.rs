@@ -4,7 +4,7 @@ use cosmwasm_std::{from_binary, to_binary, Binary, Deps, DepsMut, Env, MessageIn
 
 use crate::{
     state::{read_config, store_config, Config},
-    proxy::{query_staker_info_gov}
+    proxy::{query_staker_info_gov, query_stakers, query_unbonding}
 };
 
 use cw20::Cw20ReceiveMsg;
@@ -12,16 +12,20 @@ use cw20::Cw20ReceiveMsg;
 use spectrum_protocol::gov_proxy::{
     Cw20HookMsg, ExecuteMsg, MigrateMsg, QueryMsg,
 };
+use spectrum_protocol::gov_proxy_helper::{
+    deregister_depositor, humanize_opt, read_proxy_config, read_state, register_depositor, state_store, store_proxy_config, ProxyConfig, State,
+};
 use crate::proxy::{
-    stake, unstake
+    claim_unstake, request_unstake, stake, unstake
 };
-use crate::state::{read_state, State, state_store};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigInfo {
     pub farm_token: String,
+    pub owner: String, // can register depositors
+    #[serde(default)] pub unbonding_period: u64,
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -38,8 +42,18 @@ pub fn instantiate(
         },
     )?;
 
+    store_proxy_config(
+        deps.storage,
+        &ProxyConfig {
+            owner: Some(deps.api.addr_canonicalize(&msg.owner)?),
+            unbonding_period: msg.unbonding_period,
+            ..ProxyConfig::default()
+        },
+    )?;
+
     state_store(deps.storage).save(&State {
         total_share: Uint128::zero(),
+        total_pending: Uint128::zero(),
     })?;
 
     Ok(Response::default())
@@ -50,6 +64,14 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
     match msg {
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::Unstake { amount} => unstake(deps, env, info, amount),
+        ExecuteMsg::RequestUnstake { amount } => request_unstake(deps, env, info, amount),
+        ExecuteMsg::ClaimUnstake {} => claim_unstake(deps, env, info),
+        ExecuteMsg::CastVote { .. } |
+        ExecuteMsg::StakeVotingRewards { } |
+        ExecuteMsg::UpdateConfig { .. } |
+        ExecuteMsg::SetVoteDelegate { .. } => Err(StdError::generic_err("voting is not supported")),
+        ExecuteMsg::RegisterDepositor { depositor, cap } => register_depositor(deps, info, depositor, cap),
+        ExecuteMsg::DeregisterDepositor { depositor } => deregister_depositor(deps, info, depositor),
     }
 }
 
@@ -76,14 +98,19 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::State {} => to_binary(&query_state(deps)?),
-        QueryMsg::Staker { address } => to_binary(&query_staker_info_gov(deps, env, address)?)
+        QueryMsg::Staker { address } => to_binary(&query_staker_info_gov(deps, env, address)?),
+        QueryMsg::Stakers { start_after, limit } => to_binary(&query_stakers(deps, env, start_after, limit)?),
+        QueryMsg::Unbonding { address } => to_binary(&query_unbonding(deps, env, address)?),
     }
 }
 
 fn query_config(deps: Deps) -> StdResult<ConfigInfo> {
     let config = read_config(deps.storage)?;
+    let proxy_config = read_proxy_config(deps.storage)?;
     let resp = ConfigInfo {
         farm_token: deps.api.addr_humanize(&config.farm_token)?.to_string(),
+        owner: humanize_opt(deps.api, &proxy_config.owner)?.unwrap_or_default(),
+        unbonding_period: proxy_config.unbonding_period,
     };
     Ok(resp)
 }
@@ -93,6 +120,13 @@ fn query_state(deps: Deps) -> StdResult<State> {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    let mut proxy_config = read_proxy_config(deps.storage)?;
+    proxy_config.owner = Some(deps.api.addr_canonicalize(&msg.owner)?);
+    if let Some(unbonding_period) = msg.unbonding_period {
+        proxy_config.unbonding_period = unbonding_period;
+    }
+    store_proxy_config(deps.storage, &proxy_config)?;
+
     Ok(Response::default())
 }
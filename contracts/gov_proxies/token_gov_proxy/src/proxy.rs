@@ -1,8 +1,9 @@
 use cosmwasm_std::{CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, to_binary, Uint128, WasmMsg};
 use cw20::{Cw20ExecuteMsg};
 use terraswap::querier::query_token_balance;
-use spectrum_protocol::gov_proxy::StakerResponse;
-use crate::state::{account_store, read_account, read_config, read_state, state_store};
+use spectrum_protocol::gov_proxy::{StakerResponse, StakersResponse, UnbondingResponse};
+use spectrum_protocol::gov_proxy_helper;
+use crate::state::{read_config, Config};
 
 pub fn query_staker_info_gov(
     deps: Deps,
@@ -10,18 +11,8 @@ pub fn query_staker_info_gov(
     address: String,
 ) -> StdResult<StakerResponse> {
     let config = read_config(deps.storage)?;
-    let state = read_state(deps.storage)?;
-
-    let farm_token = deps.api.addr_humanize(&config.farm_token)?;
-    let total_balance = query_token_balance(&deps.querier, farm_token, env.contract.address)?;
-    let addr_raw = deps.api.addr_canonicalize(&address).unwrap();
-    let account = read_account(deps.storage, addr_raw.as_slice())?
-        .unwrap_or_default();
-    let balance = state.calc_balance(total_balance, account.share);
-
-    Ok(StakerResponse {
-        balance,
-    })
+    let total_balance = query_total_balance(deps, &env, &config)?;
+    gov_proxy_helper::query_staker(deps, address, total_balance)
 }
 
 pub fn stake(
@@ -31,33 +22,16 @@ pub fn stake(
     sender: String,
     amount: Uint128,
 ) -> StdResult<Response> {
-
-    if amount.is_zero() {
-        return Err(StdError::generic_err("Insufficient funds sent"));
-    }
-
     let config = read_config(deps.storage)?;
     if config.farm_token != deps.api.addr_canonicalize(info.sender.as_str())? {
         return Err(StdError::generic_err("unauthorized"));
     }
 
-    let mut state = state_store(deps.storage).load()?;
-
-    let farm_token = deps.api.addr_humanize(&config.farm_token)?;
-    let total_balance = query_token_balance(&deps.querier, farm_token, env.contract.address)?
-        .checked_sub(amount)?;
-    let sender_address_raw = deps.api.addr_canonicalize(&sender)?;
-    let key = sender_address_raw.as_slice();
-    let mut account = account_store(deps.storage)
-        .may_load(key)?
+    // received tokens are already held by proxy
+    let total_balance = query_total_balance(deps.as_ref(), &env, &config)?
+        .checked_sub(amount)
         .unwrap_or_default();
-
-    let share = state.calc_share(total_balance, amount);
-    account.share += share;
-    state.total_share += share;
-
-    state_store(deps.storage).save(&state)?;
-    account_store(deps.storage).save(key, &account)?;
+    gov_proxy_helper::stake(deps.storage, &deps.api.addr_canonicalize(&sender)?, amount, total_balance)?;
 
     Ok(Response::default())
 }
@@ -68,43 +42,101 @@ pub fn unstake(
     info: MessageInfo,
     amount: Option<Uint128>
 ) -> StdResult<Response> {
-
-    let sender_address_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
-    let key = sender_address_raw.as_slice();
-
     let config = read_config(deps.storage)?;
-    let mut state = state_store(deps.storage).load()?;
+    let total_balance = query_total_balance(deps.as_ref(), &env, &config)?;
+    let withdrawable = query_withdrawable(deps.as_ref(), &env, &config)?;
+    let amount = gov_proxy_helper::unstake(
+        deps.storage,
+        &deps.api.addr_canonicalize(info.sender.as_str())?,
+        amount,
+        total_balance,
+        withdrawable,
+    )?;
+
+    Ok(Response::new().add_messages(withdraw_msgs(deps.as_ref(), &env, &config, info.sender.to_string(), amount)?))
+}
 
+fn withdraw_msgs(
+    deps: Deps,
+    _env: &Env,
+    config: &Config,
+    recipient: String,
+    amount: Uint128,
+) -> StdResult<Vec<CosmosMsg>> {
     let farm_token = deps.api.addr_humanize(&config.farm_token)?;
-    let total_balance = query_token_balance(&deps.querier, farm_token.clone(), env.contract.address)?;
-    let mut account = account_store(deps.storage).load(key)?;
-    let user_balance = state.calc_balance(total_balance, account.share);
-    let amount = amount.unwrap_or(user_balance);
-    if amount > user_balance {
-        return Err(StdError::generic_err(
-            "User is trying to withdraw too many tokens.",
-        ));
-    }
-
-    let mut withdraw_share = state.calc_share(total_balance, amount);
-    if state.calc_balance(total_balance, withdraw_share) < amount {
-        withdraw_share += Uint128::from(1u128);
-    }
-
-    account.share -= withdraw_share;
-    state.total_share -= withdraw_share;
 
-    account_store(deps.storage).save(key, &account)?;
-    state_store(deps.storage).save(&state)?;
-
-    Ok(Response::new().add_messages(vec![
+    Ok(vec![
         CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: farm_token.to_string(),
             msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: info.sender.to_string(),
+                recipient,
                 amount
             })?,
             funds: vec![],
         })
-    ]))
+    ])
+}
+
+pub fn query_stakers(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<StakersResponse> {
+    let config = read_config(deps.storage)?;
+    let total_balance = query_total_balance(deps, &env, &config)?;
+    gov_proxy_helper::query_stakers(deps, start_after, limit, total_balance)
+}
+
+fn query_total_balance(deps: Deps, env: &Env, config: &Config) -> StdResult<Uint128> {
+    let farm_token = deps.api.addr_humanize(&config.farm_token)?;
+    query_token_balance(&deps.querier, farm_token, env.contract.address.clone())
+}
+
+fn query_withdrawable(deps: Deps, env: &Env, config: &Config) -> StdResult<Uint128> {
+    query_total_balance(deps, env, config)
+}
+
+pub fn request_unstake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Option<Uint128>,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    let total_balance = query_total_balance(deps.as_ref(), &env, &config)?;
+    gov_proxy_helper::request_unstake(
+        deps.storage,
+        &env,
+        &deps.api.addr_canonicalize(info.sender.as_str())?,
+        amount,
+        total_balance,
+    )
+}
+
+pub fn claim_unstake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    let withdrawable = query_withdrawable(deps.as_ref(), &env, &config)?;
+    let (amount, res) = gov_proxy_helper::claim_unstake(
+        deps.storage,
+        &env,
+        &deps.api.addr_canonicalize(info.sender.as_str())?,
+        withdrawable,
+    )?;
+
+    Ok(res.add_messages(withdraw_msgs(deps.as_ref(), &env, &config, info.sender.to_string(), amount)?))
+}
+
+pub fn query_unbonding(
+    deps: Deps,
+    env: Env,
+    address: String,
+) -> StdResult<UnbondingResponse> {
+    let config = read_config(deps.storage)?;
+    let withdrawable = query_withdrawable(deps, &env, &config)?;
+    gov_proxy_helper::query_unbonding(deps, &env, address, withdrawable)
 }
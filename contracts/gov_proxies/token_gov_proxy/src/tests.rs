@@ -1,10 +1,10 @@
 use crate::contract::{execute, instantiate, query, ConfigInfo};
 use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
-use crate::state::State;
+use spectrum_protocol::gov_proxy_helper::State;
 use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{from_binary, to_binary, CosmosMsg, OwnedDeps, Uint128, WasmMsg};
+use cosmwasm_std::{from_binary, to_binary, CosmosMsg, OwnedDeps, StdError, Uint128, WasmMsg};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
-use spectrum_protocol::gov_proxy::{Cw20HookMsg, ExecuteMsg, QueryMsg, StakerResponse};
+use spectrum_protocol::gov_proxy::{Cw20HookMsg, ExecuteMsg, QueryMsg, StakerInfo, StakerResponse, StakersResponse, UnbondingInfo, UnbondingResponse};
 
 const TEST_CREATOR: &str = "creator";
 const FARM_CONTRACT: &str = "farm_contract";
@@ -16,8 +16,11 @@ fn test() {
     let mut deps = mock_dependencies(&[]);
 
     let _ = test_config(&mut deps);
+    test_depositor(&mut deps);
     test_stake(&mut deps);
     test_unstake(&mut deps);
+    test_stakers(&mut deps);
+    test_unbonding(&mut deps);
 }
 
 fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> ConfigInfo {
@@ -28,6 +31,8 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
     let info = mock_info(TEST_CREATOR, &[]);
     let config = ConfigInfo {
         farm_token: FARM_TOKEN.to_string(),
+        owner: TEST_CREATOR.to_string(),
+        unbonding_period: 100,
     };
 
     // success init
@@ -46,12 +51,49 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         res,
         State {
             total_share: Uint128::zero(),
+            total_pending: Uint128::zero(),
         }
     );
 
     config
 }
 
+fn test_depositor(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+
+
+    // unregistered depositor cannot stake
+    let info = mock_info(FARM_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: FARM_CONTRACT.to_string(),
+        amount: Uint128::from(10000u128),
+        msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("depositor is not allowed")));
+
+    // only owner can register depositor
+    let info = mock_info(FARM_CONTRACT, &[]);
+    let msg = ExecuteMsg::RegisterDepositor {
+        depositor: FARM_CONTRACT.to_string(),
+        cap: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    let info = mock_info(TEST_CREATOR, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::RegisterDepositor {
+        depositor: FARM_CONTRACT_2.to_string(),
+        cap: Some(Uint128::from(4000u128)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+}
+
 fn test_stake(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     let env = mock_env();
     deps.querier.with_token_balances(&[(
@@ -306,3 +348,140 @@ fn test_unstake(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
         }
     );
 }
+
+fn test_stakers(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+
+    let msg = QueryMsg::Stakers {
+        start_after: None,
+        limit: None,
+    };
+    let res: StakersResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        StakersResponse {
+            total_share: Uint128::from(3636u128),
+            total_balance: Uint128::from(4620u128),
+            stakers: vec![
+                StakerInfo {
+                    address: FARM_CONTRACT_2.to_string(),
+                    share: Uint128::from(3636u128),
+                    balance: Uint128::from(4620u128),
+                    allowed: true,
+                    cap: Some(Uint128::from(4000u128)),
+                },
+                StakerInfo {
+                    address: FARM_CONTRACT.to_string(),
+                    share: Uint128::zero(),
+                    balance: Uint128::zero(),
+                    allowed: true,
+                    cap: None,
+                },
+            ],
+        }
+    );
+
+    // paginate
+    let msg = QueryMsg::Stakers {
+        start_after: Some(FARM_CONTRACT_2.to_string()),
+        limit: Some(1),
+    };
+    let res: StakersResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.stakers.len(), 1);
+    assert_eq!(res.stakers[0].address, FARM_CONTRACT.to_string());
+
+    // already over cap
+    let info = mock_info(FARM_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: FARM_CONTRACT_2.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("exceed depositor cap")));
+
+    // deregistered depositor cannot stake
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::DeregisterDepositor {
+        depositor: FARM_CONTRACT.to_string(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    let info = mock_info(FARM_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: FARM_CONTRACT.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("depositor is not allowed")));
+}
+
+fn test_unbonding(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let mut env = mock_env();
+    let release_at = env.block.time.seconds() + 100;
+
+    // request all balance
+    let info = mock_info(FARM_CONTRACT_2, &[]);
+    let msg = ExecuteMsg::RequestUnstake { amount: None };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    let msg = QueryMsg::Staker {
+        address: FARM_CONTRACT_2.to_string(),
+    };
+    let res: StakerResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        StakerResponse {
+            balance: Uint128::zero(),
+        }
+    );
+
+    let info = mock_info(FARM_CONTRACT_2, &[]);
+    let msg = ExecuteMsg::ClaimUnstake {};
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("no matured unstake request")));
+
+    env.block.time = env.block.time.plus_seconds(100);
+    let msg = QueryMsg::Unbonding {
+        address: FARM_CONTRACT_2.to_string(),
+    };
+    let res: UnbondingResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        UnbondingResponse {
+            requests: vec![UnbondingInfo {
+                amount: Uint128::from(4620u128),
+                release_at,
+            }],
+            pending: Uint128::from(4620u128),
+            matured: Uint128::from(4620u128),
+            claimable: Uint128::from(4620u128),
+        }
+    );
+
+    let info = mock_info(FARM_CONTRACT_2, &[]);
+    let msg = ExecuteMsg::ClaimUnstake {};
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: FARM_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: FARM_CONTRACT_2.to_string(),
+                amount: Uint128::from(4620u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+}
@@ -3,7 +3,8 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
-use spectrum_protocol::gov_proxy::{Cw20HookMsg, ExecuteMsg, MigrateMsg, QueryMsg};
+use spectrum_protocol::gov_proxy::Cw20HookMsg;
+use spectrum_protocol::xastro_gov_proxy::{ExecuteMsg, MigrateMsg, QueryMsg};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
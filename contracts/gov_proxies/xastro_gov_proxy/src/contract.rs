@@ -1,17 +1,21 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{from_binary, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128, CosmosMsg, WasmMsg, Empty};
+use cosmwasm_std::{from_binary, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128, CosmosMsg, WasmMsg};
 
 use crate::{
-    state::{read_config, store_config, Config, state_store, State, read_state},
-    proxy::{query_staker_info_gov}
+    state::{read_config, store_config, Config},
+    proxy::{query_exchange_rate, query_staker_info_gov, query_total_balance, query_staker_yield, query_stakers, query_unbonding}
 };
 
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 
-use spectrum_protocol::gov_proxy::{Cw20HookMsg, ExecuteMsg, QueryMsg};
+use spectrum_protocol::gov_proxy::Cw20HookMsg;
+use spectrum_protocol::xastro_gov_proxy::{ExecuteMsg, MigrateMsg, QueryMsg};
+use spectrum_protocol::gov_proxy_helper::{
+    account_store, canonicalize_opt, deregister_depositor, humanize_opt, read_accounts, read_proxy_config, read_state, register_depositor, set_vote_delegate, state_store, store_proxy_config, update_config, ProxyConfig, State,
+};
 use crate::proxy::{
-    stake, unstake
+    cast_vote, claim_unstake, compound, request_unstake, stake, stake_voting_rewards, unstake
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -23,6 +27,11 @@ pub struct ConfigInfo {
     pub xastro_token: String,
     pub farm_token: String,
     pub farm_gov: String,
+    #[serde(default)] pub vote_authority: Option<String>,
+    #[serde(default)] pub vote_delegate: Option<String>,
+    #[serde(default)] pub vote_contract: Option<String>,
+    pub owner: String,
+    #[serde(default)] pub unbonding_period: u64,
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -41,8 +50,20 @@ pub fn instantiate(
         },
     )?;
 
+    store_proxy_config(
+        deps.storage,
+        &ProxyConfig {
+            vote_authority: canonicalize_opt(deps.api, msg.vote_authority)?,
+            vote_delegate: canonicalize_opt(deps.api, msg.vote_delegate)?,
+            vote_contract: canonicalize_opt(deps.api, msg.vote_contract)?,
+            owner: Some(deps.api.addr_canonicalize(&msg.owner)?),
+            unbonding_period: msg.unbonding_period,
+        },
+    )?;
+
     state_store(deps.storage).save(&State {
         total_share: Uint128::zero(),
+        total_pending: Uint128::zero(),
     })?;
 
     Ok(Response::default())
@@ -53,6 +74,15 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
     match msg {
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::Unstake { amount} => unstake(deps, env, info, amount),
+        ExecuteMsg::RequestUnstake { amount } => request_unstake(deps, env, info, amount),
+        ExecuteMsg::ClaimUnstake {} => claim_unstake(deps, env, info),
+        ExecuteMsg::CastVote { poll_id, vote, amount } => cast_vote(deps, env, info, poll_id, vote, amount),
+        ExecuteMsg::StakeVotingRewards {} => stake_voting_rewards(deps),
+        ExecuteMsg::UpdateConfig { vote_authority, vote_contract } => update_config(deps, info, vote_authority, vote_contract),
+        ExecuteMsg::SetVoteDelegate { vote_delegate } => set_vote_delegate(deps, info, vote_delegate),
+        ExecuteMsg::RegisterDepositor { depositor, cap } => register_depositor(deps, info, depositor, cap),
+        ExecuteMsg::DeregisterDepositor { depositor } => deregister_depositor(deps, info, depositor),
+        ExecuteMsg::Compound {} => compound(deps, env),
     }
 }
 
@@ -79,16 +109,26 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::State {} => to_binary(&query_state(deps)?),
-        QueryMsg::Staker { address } => to_binary(&query_staker_info_gov(deps, env, address)?)
+        QueryMsg::Staker { address } => to_binary(&query_staker_info_gov(deps, env, address)?),
+        QueryMsg::Stakers { start_after, limit } => to_binary(&query_stakers(deps, env, start_after, limit)?),
+        QueryMsg::Unbonding { address } => to_binary(&query_unbonding(deps, env, address)?),
+        QueryMsg::ExchangeRate {} => to_binary(&query_exchange_rate(deps, env)?),
+        QueryMsg::StakerYield { address } => to_binary(&query_staker_yield(deps, env, address)?),
     }
 }
 
 fn query_config(deps: Deps) -> StdResult<ConfigInfo> {
     let config = read_config(deps.storage)?;
+    let proxy_config = read_proxy_config(deps.storage)?;
     let resp = ConfigInfo {
         xastro_token: deps.api.addr_humanize(&config.xastro_token)?.to_string(),
         farm_token: deps.api.addr_humanize(&config.farm_token)?.to_string(),
         farm_gov: deps.api.addr_humanize(&config.farm_gov)?.to_string(),
+        vote_authority: humanize_opt(deps.api, &proxy_config.vote_authority)?,
+        vote_delegate: humanize_opt(deps.api, &proxy_config.vote_delegate)?,
+        vote_contract: humanize_opt(deps.api, &proxy_config.vote_contract)?,
+        owner: humanize_opt(deps.api, &proxy_config.owner)?.unwrap_or_default(),
+        unbonding_period: proxy_config.unbonding_period,
     };
     Ok(resp)
 }
@@ -98,8 +138,37 @@ fn query_state(deps: Deps) -> StdResult<State> {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, env: Env, _msg: Empty) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> StdResult<Response> {
     let config = read_config(deps.storage)?;
+
+    let mut proxy_config = read_proxy_config(deps.storage)?;
+    proxy_config.owner = Some(deps.api.addr_canonicalize(&msg.owner)?);
+    if let Some(vote_authority) = msg.vote_authority {
+        proxy_config.vote_authority = Some(deps.api.addr_canonicalize(&vote_authority)?);
+    }
+    if let Some(unbonding_period) = msg.unbonding_period {
+        proxy_config.unbonding_period = unbonding_period;
+    }
+    store_proxy_config(deps.storage, &proxy_config)?;
+
+    // accounts staked before yield tracking start from current balance
+    let state = read_state(deps.storage)?;
+    let total_balance = query_total_balance(deps.as_ref(), &env, &config)?;
+    let mut start_after = None;
+    loop {
+        let accounts = read_accounts(deps.storage, start_after, None)?;
+        start_after = match accounts.last() {
+            Some((key, _)) => Some(key.clone()),
+            None => break,
+        };
+        for (key, mut account) in accounts {
+            if account.deposit.is_zero() && !account.share.is_zero() {
+                account.deposit = state.calc_balance(total_balance, account.share);
+                account_store(deps.storage).save(key.as_slice(), &account)?;
+            }
+        }
+    }
+
     let farm_token = deps.api.addr_humanize(&config.farm_token)?;
     let xastro_gov = deps.api.addr_humanize(&config.farm_gov)?;
 
@@ -107,15 +176,17 @@ pub fn migrate(deps: DepsMut, env: Env, _msg: Empty) -> StdResult<Response> {
     let astro_balance = query_token_balance(&deps.querier, farm_token.clone(), env.contract.address)?;
 
     // enter new staking
-    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: farm_token.to_string(),
-        funds: vec![],
-        msg: to_binary(&Cw20ExecuteMsg::Send {
-            contract: xastro_gov.to_string(),
-            msg: to_binary(&XAstroCw20HookMsg::Enter {})?,
-            amount: astro_balance,
-        })?,
-    }));
+    if !astro_balance.is_zero() {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: farm_token.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: xastro_gov.to_string(),
+                msg: to_binary(&XAstroCw20HookMsg::Enter {})?,
+                amount: astro_balance,
+            })?,
+        }));
+    }
 
     Ok(Response::new()
         .add_messages(messages))
@@ -1,10 +1,14 @@
-use cosmwasm_std::{CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, to_binary, Uint128, WasmMsg};
+use cosmwasm_std::{attr, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, to_binary, Uint128, WasmMsg};
 use cw20::{Cw20ExecuteMsg};
 use astroport::querier::query_token_balance;
-use spectrum_protocol::gov_proxy::StakerResponse;
+use spectrum_protocol::gov_proxy::{StakerResponse, StakersResponse, UnbondingResponse, VoteOption};
+use spectrum_protocol::xastro_gov_proxy::{ExchangeRateResponse, StakerYieldResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use astroport::staking::{Cw20HookMsg as XAstroCw20HookMsg};
+use spectrum_protocol::gov_proxy_helper::{self, read_account, read_state};
 use crate::querier::query_xastro_gov;
-use crate::state::{account_store, read_account, read_config, read_state, state_store};
+use crate::state::{read_config, Config};
 
 pub fn query_staker_info_gov(
     deps: Deps,
@@ -12,17 +16,8 @@ pub fn query_staker_info_gov(
     address: String,
 ) -> StdResult<StakerResponse> {
     let config = read_config(deps.storage)?;
-    let state = read_state(deps.storage)?;
-
-    let gov_response = query_xastro_gov(deps, &config, &env.contract.address)?;
-    let addr_raw = deps.api.addr_canonicalize(&address).unwrap();
-    let account = read_account(deps.storage, addr_raw.as_slice())?
-        .unwrap_or_default();
-    let balance = state.calc_balance(gov_response.balance, account.share);
-
-    Ok(StakerResponse {
-        balance,
-    })
+    let total_balance = query_total_balance(deps, &env, &config)?;
+    gov_proxy_helper::query_staker(deps, address, total_balance)
 }
 
 pub fn stake(
@@ -32,35 +27,18 @@ pub fn stake(
     sender: String,
     amount: Uint128,
 ) -> StdResult<Response> {
-
-    if amount.is_zero() {
-        return Err(StdError::generic_err("Insufficient funds sent"));
-    }
-
     let config = read_config(deps.storage)?;
     if config.farm_token != deps.api.addr_canonicalize(info.sender.as_str())? {
         return Err(StdError::generic_err("unauthorized"));
     }
 
-    let mut state = state_store(deps.storage).load()?;
-
-    let gov_response = query_xastro_gov(deps.as_ref(), &config, &env.contract.address)?;
-    let sender_address_raw = deps.api.addr_canonicalize(&sender)?;
-    let key = sender_address_raw.as_slice();
-    let mut account = account_store(deps.storage)
-        .may_load(key)?
-        .unwrap_or_default();
-
-    let share = state.calc_share(gov_response.balance, amount);
-    account.share += share;
-    state.total_share += share;
-
-    state_store(deps.storage).save(&state)?;
-    account_store(deps.storage).save(key, &account)?;
+    let total_balance = query_total_balance(deps.as_ref(), &env, &config)?;
+    gov_proxy_helper::stake(deps.storage, &deps.api.addr_canonicalize(&sender)?, amount, total_balance)?;
 
     let farm_token = deps.api.addr_humanize(&config.farm_token)?;
     let xastro_gov = deps.api.addr_humanize(&config.farm_gov)?;
 
+    // ASTRO held by proxy is entered by compound only, so it is not shared to new stake
     Ok(Response::new()
         .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: farm_token.to_string(),
@@ -79,46 +57,40 @@ pub fn unstake(
     info: MessageInfo,
     amount: Option<Uint128>
 ) -> StdResult<Response> {
-
-    let sender_address_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
-    let key = sender_address_raw.as_slice();
-
     let config = read_config(deps.storage)?;
-    let mut state = state_store(deps.storage).load()?;
-
-    let gov_response = query_xastro_gov(deps.as_ref(), &config, &env.contract.address)?;
-    let mut account = account_store(deps.storage).load(key)?;
-    let user_balance = state.calc_balance(gov_response.balance, account.share);
-    let amount = amount.unwrap_or(user_balance);
-    if amount > user_balance {
-        return Err(StdError::generic_err(
-            "User is trying to withdraw too many tokens.",
-        ));
-    }
-
-    let mut withdraw_share = state.calc_share(gov_response.balance, amount);
-    if state.calc_balance(gov_response.balance, withdraw_share) < amount {
-        withdraw_share += Uint128::from(1u128);
-    }
+    let total_balance = query_total_balance(deps.as_ref(), &env, &config)?;
+    let withdrawable = query_withdrawable(deps.as_ref(), &env, &config)?;
+    let amount = gov_proxy_helper::unstake(
+        deps.storage,
+        &deps.api.addr_canonicalize(info.sender.as_str())?,
+        amount,
+        total_balance,
+        withdrawable,
+    )?;
 
-    account.share -= withdraw_share;
-    state.total_share -= withdraw_share;
-
-    account_store(deps.storage).save(key, &account)?;
-    state_store(deps.storage).save(&state)?;
+    Ok(Response::new().add_messages(withdraw_msgs(deps.as_ref(), &env, &config, info.sender.to_string(), amount)?))
+}
 
+fn withdraw_msgs(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    recipient: String,
+    amount: Uint128,
+) -> StdResult<Vec<CosmosMsg>> {
+    let gov_response = query_xastro_gov(deps, config, &env.contract.address)?;
     let astro_token = deps.api.addr_humanize(&config.farm_token)?;
     let xastro_token = deps.api.addr_humanize(&config.xastro_token)?;
     let xastro_gov = deps.api.addr_humanize(&config.farm_gov)?;
 
-    let astro_balance = query_token_balance(&deps.querier, astro_token.clone(), env.contract.address)?;
+    let astro_balance = query_token_balance(&deps.querier, astro_token.clone(), env.contract.address.clone())?;
     let needed_astro = amount.checked_sub(astro_balance).unwrap_or_default();
     let mut required_xastro = needed_astro.multiply_ratio(gov_response.total_share, gov_response.total_balance);
     if required_xastro.multiply_ratio(gov_response.total_balance, gov_response.total_share) < needed_astro {
         required_xastro += Uint128::from(1u128);
     }
 
-    Ok(Response::new().add_messages(vec![
+    Ok(vec![
         CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: xastro_token.to_string(),
             msg: to_binary(&Cw20ExecuteMsg::Send {
@@ -131,10 +103,206 @@ pub fn unstake(
         CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: astro_token.to_string(),
             msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: info.sender.to_string(),
+                recipient,
                 amount,
             })?,
             funds: vec![],
         })
-    ]))
+    ])
+}
+
+// Astroport assembly messages, voting power is xASTRO balance at proposal start
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AssemblyExecuteMsg {
+    CastVote {
+        proposal_id: u64,
+        vote: ProposalVoteOption,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalVoteOption {
+    For,
+    Against,
+}
+
+pub fn cast_vote(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    poll_id: u64,
+    vote: VoteOption,
+    amount: Option<Uint128>,
+) -> StdResult<Response> {
+    if amount.is_some() {
+        return Err(StdError::generic_err("amount is not supported, vote with all xASTRO balance"));
+    }
+    let assembly_vote = match vote {
+        VoteOption::Yes => ProposalVoteOption::For,
+        VoteOption::No => ProposalVoteOption::Against,
+        VoteOption::Abstain => return Err(StdError::generic_err("abstain is not supported")),
+    };
+
+    gov_proxy_helper::cast_vote(
+        deps.as_ref(),
+        info,
+        None,
+        poll_id,
+        to_binary(&AssemblyExecuteMsg::CastVote {
+            proposal_id: poll_id,
+            vote: assembly_vote,
+        })?,
+        vec![],
+    )
+}
+
+pub fn stake_voting_rewards(
+    _deps: DepsMut,
+) -> StdResult<Response> {
+    Err(StdError::generic_err("voting rewards is not supported"))
+}
+
+pub fn query_stakers(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<StakersResponse> {
+    let config = read_config(deps.storage)?;
+    let total_balance = query_total_balance(deps, &env, &config)?;
+    gov_proxy_helper::query_stakers(deps, start_after, limit, total_balance)
+}
+
+// ASTRO held by proxy is not shared until entered to xASTRO
+pub(crate) fn query_total_balance(deps: Deps, env: &Env, config: &Config) -> StdResult<Uint128> {
+    Ok(query_xastro_gov(deps, config, &env.contract.address)?.balance)
+}
+
+fn query_withdrawable(deps: Deps, env: &Env, config: &Config) -> StdResult<Uint128> {
+    query_total_balance(deps, env, config)
+}
+
+pub fn request_unstake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Option<Uint128>,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    let total_balance = query_total_balance(deps.as_ref(), &env, &config)?;
+    gov_proxy_helper::request_unstake(
+        deps.storage,
+        &env,
+        &deps.api.addr_canonicalize(info.sender.as_str())?,
+        amount,
+        total_balance,
+    )
+}
+
+pub fn claim_unstake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    let withdrawable = query_withdrawable(deps.as_ref(), &env, &config)?;
+    let (amount, res) = gov_proxy_helper::claim_unstake(
+        deps.storage,
+        &env,
+        &deps.api.addr_canonicalize(info.sender.as_str())?,
+        withdrawable,
+    )?;
+
+    Ok(res.add_messages(withdraw_msgs(deps.as_ref(), &env, &config, info.sender.to_string(), amount)?))
+}
+
+pub fn query_unbonding(
+    deps: Deps,
+    env: Env,
+    address: String,
+) -> StdResult<UnbondingResponse> {
+    let config = read_config(deps.storage)?;
+    let withdrawable = query_withdrawable(deps, &env, &config)?;
+    gov_proxy_helper::query_unbonding(deps, &env, address, withdrawable)
+}
+
+pub fn compound(
+    deps: DepsMut,
+    env: Env,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+    let farm_token = deps.api.addr_humanize(&config.farm_token)?;
+    let xastro_gov = deps.api.addr_humanize(&config.farm_gov)?;
+
+    // ASTRO held by proxy is entered to xASTRO, so it is shared to all depositors by share
+    let amount = query_token_balance(&deps.querier, farm_token.clone(), env.contract.address)?;
+    if amount.is_zero() {
+        return Err(StdError::generic_err("nothing to compound"));
+    }
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: farm_token.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: xastro_gov.to_string(),
+                msg: to_binary(&XAstroCw20HookMsg::Enter {})?,
+                amount,
+            })?,
+        }))
+        .add_attributes(vec![
+            attr("action", "compound"),
+            attr("amount", amount),
+        ]))
+}
+
+pub fn query_exchange_rate(
+    deps: Deps,
+    env: Env,
+) -> StdResult<ExchangeRateResponse> {
+    let config = read_config(deps.storage)?;
+    let gov_response = query_xastro_gov(deps, &config, &env.contract.address)?;
+
+    // xASTRO is minted 1:1 when staking is empty
+    let exchange_rate = if gov_response.total_share.is_zero() {
+        Decimal::one()
+    } else {
+        Decimal::from_ratio(gov_response.total_balance, gov_response.total_share)
+    };
+
+    Ok(ExchangeRateResponse {
+        exchange_rate,
+        total_share: gov_response.total_share,
+        total_balance: gov_response.total_balance,
+    })
+}
+
+pub fn query_staker_yield(
+    deps: Deps,
+    env: Env,
+    address: String,
+) -> StdResult<StakerYieldResponse> {
+    let config = read_config(deps.storage)?;
+    let state = read_state(deps.storage)?;
+
+    let gov_response = query_xastro_gov(deps, &config, &env.contract.address)?;
+    let total_balance = query_total_balance(deps, &env, &config)?;
+    let addr_raw = deps.api.addr_canonicalize(&address)?;
+    let account = read_account(deps.storage, addr_raw.as_slice())?
+        .unwrap_or_default();
+    let balance = state.calc_balance(total_balance, account.share);
+    let upstream_share = if gov_response.total_balance.is_zero() {
+        Uint128::zero()
+    } else {
+        balance.multiply_ratio(gov_response.total_share, gov_response.total_balance)
+    };
+
+    Ok(StakerYieldResponse {
+        balance,
+        upstream_share,
+        deposit: account.deposit,
+        accumulated_yield: balance.checked_sub(account.deposit).unwrap_or_default(),
+    })
 }
@@ -26,7 +26,12 @@ pub fn query_xastro_gov(
     let total_balance = query_token_balance(&deps.querier, astro_token, xastro_gov)?;
     let share = query_token_balance(&deps.querier, xastro_token, staker.clone())?;
 
-    let balance = share.multiply_ratio(total_balance, total_share);
+    // xASTRO supply is empty before first enter
+    let balance = if total_share.is_zero() {
+        Uint128::zero()
+    } else {
+        share.multiply_ratio(total_balance, total_share)
+    };
     Ok(StakerResponse {
         balance,
         total_share,
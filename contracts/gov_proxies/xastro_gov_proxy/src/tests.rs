@@ -1,17 +1,23 @@
-use crate::contract::{execute, instantiate, query, ConfigInfo};
+use crate::contract::{execute, instantiate, migrate, query, ConfigInfo};
 use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
-use crate::state::State;
+use crate::proxy::{AssemblyExecuteMsg, ProposalVoteOption};
+use spectrum_protocol::gov_proxy_helper::{Account, State};
+use cosmwasm_storage::to_length_prefixed;
 use astroport::staking::Cw20HookMsg as AstroportCw20HookMsg;
 use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{from_binary, to_binary, CosmosMsg, OwnedDeps, Uint128, WasmMsg};
+use cosmwasm_std::{attr, from_binary, from_slice, to_binary, Api, CosmosMsg, Decimal, OwnedDeps, StdError, Storage, Uint128, WasmMsg};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
-use spectrum_protocol::gov_proxy::{Cw20HookMsg, ExecuteMsg, QueryMsg, StakerResponse};
+use spectrum_protocol::gov_proxy::{Cw20HookMsg, StakerInfo, StakerResponse, StakersResponse, UnbondingInfo, UnbondingResponse, VoteOption};
+use spectrum_protocol::xastro_gov_proxy::{ExchangeRateResponse, ExecuteMsg, MigrateMsg, QueryMsg, StakerYieldResponse};
 
 const TEST_CREATOR: &str = "creator";
 const FARM_CONTRACT: &str = "farm_contract";
 const FARM_CONTRACT_2: &str = "farm_contract_2";
 const FARM_TOKEN: &str = "farm_token";
 const FARM_GOV: &str = "farm_gov";
+const VOTE_AUTHORITY: &str = "vote_authority";
+const VOTE_DELEGATE: &str = "vote_delegate";
+const VOTE_CONTRACT: &str = "vote_contract";
 const XASTRO_TOKEN: &str = "xastro_token";
 
 #[test]
@@ -19,8 +25,13 @@ fn test() {
     let mut deps = mock_dependencies(&[]);
 
     let _ = test_config(&mut deps);
+    test_depositor(&mut deps);
     test_stake(&mut deps);
     test_unstake(&mut deps);
+    test_stakers(&mut deps);
+    test_vote(&mut deps);
+    test_compound(&mut deps);
+    test_unbonding(&mut deps);
 }
 
 fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> ConfigInfo {
@@ -33,6 +44,11 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         xastro_token: XASTRO_TOKEN.to_string(),
         farm_token: FARM_TOKEN.to_string(),
         farm_gov: FARM_GOV.to_string(),
+        vote_authority: Some(VOTE_AUTHORITY.to_string()),
+        vote_delegate: None,
+        vote_contract: None,
+        owner: TEST_CREATOR.to_string(),
+        unbonding_period: 100,
     };
 
     // success init
@@ -51,12 +67,48 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> C
         res,
         State {
             total_share: Uint128::zero(),
+            total_pending: Uint128::zero(),
         }
     );
 
     config
 }
 
+fn test_depositor(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+
+    // unregistered depositor cannot stake
+    let info = mock_info(FARM_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: FARM_CONTRACT.to_string(),
+        amount: Uint128::from(10000u128),
+        msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("depositor is not allowed")));
+
+    // only owner can register depositor
+    let info = mock_info(FARM_CONTRACT, &[]);
+    let msg = ExecuteMsg::RegisterDepositor {
+        depositor: FARM_CONTRACT.to_string(),
+        cap: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    let info = mock_info(TEST_CREATOR, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::RegisterDepositor {
+        depositor: FARM_CONTRACT_2.to_string(),
+        cap: Some(Uint128::from(4000u128)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+}
+
 fn test_stake(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     let env = mock_env();
     deps.querier.with_token_balances(&[
@@ -461,3 +513,455 @@ fn test_unstake(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
         }
     );
 }
+
+fn test_vote(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+
+    // only vote_authority can set delegate
+    let info = mock_info(VOTE_DELEGATE, &[]);
+    let msg = ExecuteMsg::SetVoteDelegate {
+        vote_delegate: Some(VOTE_DELEGATE.to_string()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert!(res.is_err());
+
+    let info = mock_info(VOTE_AUTHORITY, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    // only vote_authority or vote_delegate can vote
+    let info = mock_info(FARM_CONTRACT, &[]);
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1u64,
+        vote: VoteOption::Yes,
+        amount: Some(Uint128::from(1000u128)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert!(res.is_err());
+
+    // vote_contract must be set to vote in assembly
+    let info = mock_info(VOTE_DELEGATE, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_err());
+
+    let info = mock_info(VOTE_AUTHORITY, &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        vote_authority: None,
+        vote_contract: Some(VOTE_CONTRACT.to_string()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    // assembly votes with all xASTRO balance
+    let info = mock_info(VOTE_DELEGATE, &[]);
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1u64,
+        vote: VoteOption::Yes,
+        amount: Some(Uint128::from(1000u128)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_err());
+
+    let info = mock_info(VOTE_DELEGATE, &[]);
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 1u64,
+        vote: VoteOption::No,
+        amount: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: VOTE_CONTRACT.to_string(),
+            msg: to_binary(&AssemblyExecuteMsg::CastVote {
+                proposal_id: 1u64,
+                vote: ProposalVoteOption::Against,
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    // abstain is not supported
+    let info = mock_info(VOTE_DELEGATE, &[]);
+    let msg = ExecuteMsg::CastVote {
+        poll_id: 2u64,
+        vote: VoteOption::Abstain,
+        amount: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_err());
+
+    // voting rewards is not supported
+    let info = mock_info(FARM_CONTRACT, &[]);
+    let msg = ExecuteMsg::StakeVotingRewards {};
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_err());
+}
+
+fn test_stakers(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+
+    let msg = QueryMsg::Stakers {
+        start_after: None,
+        limit: None,
+    };
+    let res: StakersResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        StakersResponse {
+            total_share: Uint128::from(3636u128),
+            total_balance: Uint128::from(4619u128),
+            stakers: vec![
+                StakerInfo {
+                    address: FARM_CONTRACT_2.to_string(),
+                    share: Uint128::from(3636u128),
+                    balance: Uint128::from(4619u128),
+                    allowed: true,
+                    cap: Some(Uint128::from(4000u128)),
+                },
+                StakerInfo {
+                    address: FARM_CONTRACT.to_string(),
+                    share: Uint128::zero(),
+                    balance: Uint128::zero(),
+                    allowed: true,
+                    cap: None,
+                },
+            ],
+        }
+    );
+
+    // paginate
+    let msg = QueryMsg::Stakers {
+        start_after: Some(FARM_CONTRACT_2.to_string()),
+        limit: Some(1),
+    };
+    let res: StakersResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.stakers.len(), 1);
+    assert_eq!(res.stakers[0].address, FARM_CONTRACT.to_string());
+
+    // already over cap
+    let info = mock_info(FARM_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: FARM_CONTRACT_2.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("exceed depositor cap")));
+
+    // deregistered depositor cannot stake
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::DeregisterDepositor {
+        depositor: FARM_CONTRACT.to_string(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    let info = mock_info(FARM_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: FARM_CONTRACT.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("depositor is not allowed")));
+}
+
+fn test_compound(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+
+    // 1 xASTRO = 2.541 ASTRO
+    let msg = QueryMsg::ExchangeRate {};
+    let res: ExchangeRateResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        ExchangeRateResponse {
+            exchange_rate: Decimal::from_ratio(2541u128, 1000u128),
+            total_share: Uint128::from(10_000_000u128),
+            total_balance: Uint128::from(25_410_000u128),
+        }
+    );
+
+    let msg = QueryMsg::StakerYield {
+        address: FARM_CONTRACT_2.to_string(),
+    };
+    let res: StakerYieldResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg.clone()).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        StakerYieldResponse {
+            balance: Uint128::from(4619u128),
+            upstream_share: Uint128::from(1817u128),
+            deposit: Uint128::from(4000u128),
+            accumulated_yield: Uint128::from(619u128),
+        }
+    );
+
+    // nothing to compound
+    let info = mock_info(TEST_CREATOR, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::Compound {});
+    assert_eq!(res, Err(StdError::generic_err("nothing to compound")));
+
+    // ASTRO sent to proxy is not shared until compound
+    deps.querier.with_token_balances(&[
+        (
+            &XASTRO_TOKEN.to_string(),
+            &[
+                (&XASTRO_TOKEN.to_string(), &Uint128::from(10_000_000u128)),
+                (&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1818u128)),
+            ],
+        ),
+        (
+            &FARM_TOKEN.to_string(),
+            &[
+                (&FARM_GOV.to_string(), &Uint128::from(25_410_000u128)),
+                (&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1000u128)),
+            ],
+        ),
+    ]);
+    let res: StakerYieldResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg.clone()).unwrap()).unwrap();
+    assert_eq!(res.balance, Uint128::from(4619u128));
+
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Compound {}).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: FARM_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: FARM_GOV.to_string(),
+                amount: Uint128::from(1000u128),
+                msg: to_binary(&AstroportCw20HookMsg::Enter {}).unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    // account staked before yield tracking has no deposit, backfilled by migrate
+    let farm_raw = deps.api.addr_canonicalize(FARM_CONTRACT_2).unwrap();
+    let key = [to_length_prefixed(b"account"), farm_raw.as_slice().to_vec()].concat();
+    let account: Account = from_slice(&deps.storage.get(&key).unwrap()).unwrap();
+    deps.storage.set(&key, format!(r#"{{"share":"{}"}}"#, account.share).as_bytes());
+
+    let res = migrate(deps.as_mut(), env.clone(), MigrateMsg {
+        vote_authority: None,
+        owner: TEST_CREATOR.to_string(),
+        unbonding_period: None,
+    });
+    assert!(res.is_ok());
+
+    // only staked xASTRO is shared, yield starts from migrate
+    let res: StakerYieldResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        StakerYieldResponse {
+            balance: Uint128::from(4619u128),
+            upstream_share: Uint128::from(1817u128),
+            deposit: Uint128::from(4619u128),
+            accumulated_yield: Uint128::zero(),
+        }
+    );
+
+    // only received ASTRO is entered, ASTRO held by proxy waits for compound
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::RegisterDepositor {
+        depositor: FARM_CONTRACT.to_string(),
+        cap: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    deps.querier.with_token_balances(&[
+        (
+            &XASTRO_TOKEN.to_string(),
+            &[
+                (&XASTRO_TOKEN.to_string(), &Uint128::from(10_000_000u128)),
+                (&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1818u128)),
+            ],
+        ),
+        (
+            &FARM_TOKEN.to_string(),
+            &[
+                (&FARM_GOV.to_string(), &Uint128::from(25_410_000u128)),
+                (&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(3000u128)),
+            ],
+        ),
+    ]);
+    let info = mock_info(FARM_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: FARM_CONTRACT.to_string(),
+        amount: Uint128::from(2000u128),
+        msg: to_binary(&Cw20HookMsg::Stake {}).unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: FARM_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: FARM_GOV.to_string(),
+                amount: Uint128::from(2000u128),
+                msg: to_binary(&AstroportCw20HookMsg::Enter {}).unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    // 2000 ASTRO is entered as 787 xASTRO, then xASTRO grows by 5%
+    deps.querier.with_token_balances(&[
+        (
+            &XASTRO_TOKEN.to_string(),
+            &[
+                (&XASTRO_TOKEN.to_string(), &Uint128::from(10_000_000u128)),
+                (&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(2605u128)),
+            ],
+        ),
+        (
+            &FARM_TOKEN.to_string(),
+            &[(&FARM_GOV.to_string(), &Uint128::from(26_680_500u128))],
+        ),
+    ]);
+    let msg = QueryMsg::StakerYield {
+        address: FARM_CONTRACT.to_string(),
+    };
+    let res: StakerYieldResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        StakerYieldResponse {
+            balance: Uint128::from(2099u128),
+            upstream_share: Uint128::from(786u128),
+            deposit: Uint128::from(2000u128),
+            accumulated_yield: Uint128::from(99u128),
+        }
+    );
+}
+
+fn test_unbonding(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let mut env = mock_env();
+    let release_at = env.block.time.seconds() + 100;
+
+    let msg = QueryMsg::Staker {
+        address: FARM_CONTRACT_2.to_string(),
+    };
+    let res: StakerResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg.clone()).unwrap()).unwrap();
+    assert_eq!(res.balance, Uint128::from(4850u128));
+
+    // request 1000 of 4850
+    let info = mock_info(FARM_CONTRACT_2, &[]);
+    let unstake_msg = ExecuteMsg::RequestUnstake {
+        amount: Some(Uint128::from(1000u128)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, unstake_msg).unwrap();
+    assert!(res.messages.is_empty());
+    assert_eq!(res.attributes[2], attr("release_at", release_at.to_string()));
+
+    // requested amount stops earning
+    let res: StakerResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.balance, Uint128::from(3850u128));
+
+    // not matured
+    let msg = QueryMsg::Unbonding {
+        address: FARM_CONTRACT_2.to_string(),
+    };
+    let res: UnbondingResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg.clone()).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        UnbondingResponse {
+            requests: vec![UnbondingInfo {
+                amount: Uint128::from(1000u128),
+                release_at,
+            }],
+            pending: Uint128::from(1000u128),
+            matured: Uint128::zero(),
+            claimable: Uint128::zero(),
+        }
+    );
+
+    let info = mock_info(FARM_CONTRACT_2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ClaimUnstake {});
+    assert_eq!(res, Err(StdError::generic_err("no matured unstake request")));
+
+    // matured, xASTRO is not locked by vote
+    env.block.time = env.block.time.plus_seconds(100);
+    let res: UnbondingResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg.clone()).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        UnbondingResponse {
+            requests: vec![UnbondingInfo {
+                amount: Uint128::from(1000u128),
+                release_at,
+            }],
+            pending: Uint128::from(1000u128),
+            matured: Uint128::from(1000u128),
+            claimable: Uint128::from(1000u128),
+        }
+    );
+
+    let info = mock_info(FARM_CONTRACT_2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ClaimUnstake {}).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: XASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: FARM_GOV.to_string(),
+                    amount: Uint128::from(375u128),
+                    msg: to_binary(&AstroportCw20HookMsg::Leave {}).unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: FARM_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: FARM_CONTRACT_2.to_string(),
+                    amount: Uint128::from(1000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+    assert_eq!(res.attributes[2], attr("matured_remaining", "0"));
+
+    let res: UnbondingResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        UnbondingResponse {
+            requests: vec![],
+            pending: Uint128::zero(),
+            matured: Uint128::zero(),
+            claimable: Uint128::zero(),
+        }
+    );
+
+    let msg = QueryMsg::State {};
+    let res: State = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.total_pending, Uint128::zero());
+}
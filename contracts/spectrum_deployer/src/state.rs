@@ -17,7 +17,7 @@ pub struct Config {
     pub time_lock: u64,
 }
 
-pub fn config_store(storage: &mut dyn Storage) -> Singleton<Config> {
+pub fn config_store(storage: &mut dyn Storage) -> Singleton<'_, Config> {
     singleton(storage, KEY_CONFIG)
 }
 
@@ -45,6 +45,6 @@ pub fn read_codes<'a>(
         .collect()
 }
 
-pub fn code_store(storage: &mut dyn Storage, contract_addr: CanonicalAddr) -> Bucket<CodeInfo> {
+pub fn code_store(storage: &mut dyn Storage, contract_addr: CanonicalAddr) -> Bucket<'_, CodeInfo> {
     Bucket::multilevel(storage, &[KEY_CODE, contract_addr.as_slice()])
 }
@@ -373,8 +373,7 @@ fn query_state(deps: Deps, height: u64) -> StdResult<StateInfo> {
         vault_balances: state.vault_balances,
         vault_share_multiplier: state.vault_share_multiplier,
         pool_weight: state.pool_weight,
-        pools: vec![
-            vec![
+        pools: [vec![
                 StatePoolInfo {
                     days: 0u64,
                     total_share: state.total_share,
@@ -389,8 +388,7 @@ fn query_state(deps: Deps, height: u64) -> StdResult<StateInfo> {
                 total_balance: it.total_balance,
                 aust_index: it.aust_index,
                 weight: it.weight,
-            }).collect(),
-        ].concat(),
+            }).collect()].concat(),
     })
 }
 
@@ -306,7 +306,7 @@ pub fn update_stake(
 
     let to_share = state.calc_share(to_days, amount)?;
     let time = env.block.time.seconds();
-    let time_remain = if unlock < time { 0u64 } else { unlock - time };
+    let time_remain = unlock.saturating_sub(time);
     account.add_share(to_days, time, to_share, from_days * SEC_IN_DAY - time_remain, &state)?;
     state.add_share(to_days, to_share, amount)?;
 
@@ -613,8 +613,7 @@ pub fn query_balances(deps: Deps, address: String, height: u64) -> StdResult<Bal
             balance: account.calc_total_balance(&state)?,
             share: account.share,
             locked_balance: account.locked_balance,
-            pools: vec![
-                vec![
+            pools: [vec![
                     BalancePoolInfo {
                         days: 0u64,
                         share: account.share,
@@ -631,8 +630,7 @@ pub fn query_balances(deps: Deps, address: String, height: u64) -> StdResult<Bal
                     balance: state.calc_balance(it.days, it.share).unwrap(),
                     aust_index: it.aust_index,
                     pending_aust: it.pending_aust,
-                }).collect()
-            ].concat()
+                }).collect()].concat()
         })
     }
 }
@@ -38,7 +38,7 @@ pub struct Config {
     #[serde(default)] pub burnvault_ratio: Decimal,
 }
 
-pub fn config_store(storage: &mut dyn Storage) -> Singleton<Config> {
+pub fn config_store(storage: &mut dyn Storage) -> Singleton<'_, Config> {
     singleton(storage, KEY_CONFIG)
 }
 
@@ -154,7 +154,7 @@ impl State {
     }
 }
 
-pub fn state_store(storage: &mut dyn Storage) -> Singleton<State> {
+pub fn state_store(storage: &mut dyn Storage) -> Singleton<'_, State> {
     singleton(storage, KEY_STATE)
 }
 
@@ -180,7 +180,7 @@ pub struct Poll {
     pub total_balance_at_end_poll: Option<Uint128>,
 }
 
-pub fn poll_store(storage: &mut dyn Storage) -> Bucket<Poll> {
+pub fn poll_store(storage: &mut dyn Storage) -> Bucket<'_, Poll> {
     bucket(storage, PREFIX_POLL)
 }
 
@@ -314,7 +314,7 @@ impl Account {
     }
 }
 
-pub fn account_store(storage: &mut dyn Storage) -> Bucket<Account> {
+pub fn account_store(storage: &mut dyn Storage) -> Bucket<'_, Account> {
     bucket(storage, PREFIX_ACCOUNT)
 }
 
@@ -378,7 +378,7 @@ pub fn read_polls<'a>(
 
 static PREFIX_POLL_VOTER: &[u8] = b"poll_voter";
 
-pub fn poll_voter_store(storage: &mut dyn Storage, poll_id: u64) -> Bucket<VoterInfo> {
+pub fn poll_voter_store(storage: &mut dyn Storage, poll_id: u64) -> Bucket<'_, VoterInfo> {
     Bucket::multilevel(storage, &[PREFIX_POLL_VOTER, &poll_id.to_be_bytes()])
 }
 
@@ -424,7 +424,7 @@ pub struct Vault {
     #[serde(default)] pub balance: Uint128,
 }
 
-pub fn vault_store(storage: &mut dyn Storage) -> Bucket<Vault> {
+pub fn vault_store(storage: &mut dyn Storage) -> Bucket<'_, Vault> {
     bucket(storage, PREFIX_VAULT)
 }
 
@@ -222,9 +222,9 @@ pub fn poll_end(deps: DepsMut, env: Env, poll_id: u64) -> StdResult<Response> {
 
     Ok(Response::new().add_attributes(vec![
         attr("action", "end_poll"),
-        attr("poll_id", &poll_id.to_string()),
+        attr("poll_id", poll_id.to_string()),
         attr("rejected_reason", rejected_reason),
-        attr("passed", &passed.to_string()),
+        attr("passed", passed.to_string()),
     ]))
 }
 
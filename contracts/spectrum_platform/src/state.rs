@@ -23,7 +23,7 @@ pub struct Config {
     pub expiration_period: u64,
 }
 
-pub fn config_store(storage: &mut dyn Storage) -> Singleton<Config> {
+pub fn config_store(storage: &mut dyn Storage) -> Singleton<'_, Config> {
     singleton(storage, KEY_CONFIG)
 }
 
@@ -40,7 +40,7 @@ pub struct State {
     pub total_weight: u32,
 }
 
-pub fn state_store(storage: &mut dyn Storage) -> Singleton<State> {
+pub fn state_store(storage: &mut dyn Storage) -> Singleton<'_, State> {
     singleton(storage, KEY_STATE)
 }
 
@@ -65,7 +65,7 @@ pub struct Poll {
     pub total_balance_at_end_poll: Option<u32>,
 }
 
-pub fn poll_store(storage: &mut dyn Storage) -> Bucket<Poll> {
+pub fn poll_store(storage: &mut dyn Storage) -> Bucket<'_, Poll> {
     bucket(storage, PREFIX_POLL)
 }
 
@@ -129,7 +129,7 @@ pub fn read_polls<'a>(
 
 static PREFIX_POLL_VOTER: &[u8] = b"poll_voter";
 
-pub fn poll_voter_store(storage: &mut dyn Storage, poll_id: u64) -> Bucket<VoterInfo> {
+pub fn poll_voter_store(storage: &mut dyn Storage, poll_id: u64) -> Bucket<'_, VoterInfo> {
     Bucket::multilevel(storage, &[PREFIX_POLL_VOTER, &poll_id.to_be_bytes()])
 }
 
@@ -169,7 +169,7 @@ pub fn read_poll_voters<'a>(
 
 static PREFIX_BOARD: &[u8] = b"board";
 
-pub fn board_store(storage: &mut dyn Storage) -> Bucket<u32> {
+pub fn board_store(storage: &mut dyn Storage) -> Bucket<'_, u32> {
     bucket(storage, PREFIX_BOARD)
 }
 
@@ -3,7 +3,8 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 use spectrum_protocol::staker::{
-    ConfigInfo, Cw20HookMsg, ExecuteMsg, QueryMsg, SimulateZapToBondResponse,
+    ConfigInfo, Cw20HookMsg, ExecuteMsg, FarmInfo, FarmsResponse, LimitOrderInfo, LimitOrdersResponse, ScheduleInfo, SchedulesResponse, ZapIntentPayload, QueryMsg, SimulateBestRouteResponse,
+    SimulateZapToBondMultiResponse, SimulateZapToBondResponse,
 };
 
 fn main() {
@@ -14,6 +15,15 @@ fn main() {
 
     export_schema(&schema_for!(ConfigInfo), &out_dir);
     export_schema(&schema_for!(SimulateZapToBondResponse), &out_dir);
+    export_schema(&schema_for!(SimulateZapToBondMultiResponse), &out_dir);
+    export_schema(&schema_for!(SimulateBestRouteResponse), &out_dir);
+    export_schema(&schema_for!(FarmInfo), &out_dir);
+    export_schema(&schema_for!(FarmsResponse), &out_dir);
+    export_schema(&schema_for!(ZapIntentPayload), &out_dir);
+    export_schema(&schema_for!(ScheduleInfo), &out_dir);
+    export_schema(&schema_for!(SchedulesResponse), &out_dir);
+    export_schema(&schema_for!(LimitOrderInfo), &out_dir);
+    export_schema(&schema_for!(LimitOrdersResponse), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(Cw20HookMsg), &out_dir);
@@ -1,14 +1,16 @@
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
 
-use crate::state::{config_store, read_config, Config};
+use crate::dca::{cancel_schedule, create_schedule, deposit_schedule, execute_schedule_hook, execute_schedules, query_schedule, query_schedules, reply_schedule};
+use crate::order::{cancel_limit_order, create_limit_order, execute_limit_order, query_limit_order, query_limit_orders};
+use crate::state::{config_store, farm_read, farm_store, nonce_read, nonce_store, read_config, read_farms, read_schedule_count, Config, FarmRecord};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{attr, from_binary, to_binary, BankMsg, Binary, CanonicalAddr, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128, WasmMsg, QueryRequest, WasmQuery, Addr, QuerierWrapper, Fraction};
+use cosmwasm_std::{attr, from_binary, to_binary, to_vec, BankMsg, Binary, CanonicalAddr, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult, Uint128, WasmMsg, QueryRequest, WasmQuery, Addr, QuerierWrapper, Fraction};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
-use spectrum_protocol::mirror_farm::Cw20HookMsg as MirrorCw20HookMsg;
-use spectrum_protocol::staker::{ConfigInfo, Cw20HookMsg, ExecuteMsg, MigrateMsg, QueryMsg, SimulateZapToBondResponse};
+use spectrum_protocol::mirror_farm::{Cw20HookMsg as MirrorCw20HookMsg, ExecuteMsg as MirrorExecuteMsg};
+use spectrum_protocol::staker::{ConfigInfo, Cw20HookMsg, ExecuteMsg, FarmInfo, FarmsResponse, ZapIntent, ZapIntentPayload, MigrateMsg, QueryMsg, SimulateBestRouteResponse, SimulateZapToBondMultiItem, SimulateZapToBondMultiResponse, SimulateZapToBondResponse, ZapTarget};
 use terraswap::asset::{Asset, AssetInfo};
 use terraswap::pair::{Cw20HookMsg as PairCw20HookMsg, ExecuteMsg as PairExecuteMsg, PoolResponse, QueryMsg as PairQueryMsg};
 use terraswap::querier::{query_balance, query_token_balance, simulate};
@@ -19,8 +21,11 @@ use serde::{Deserialize, Serialize};
 use spectrum_protocol::farm_helper::deduct_tax;
 
 use spectrum_protocol::staker_single_asset::SwapOperation;
-use crate::math::{compute_d, U256};
+use crate::math::{calc_ask_amount, compute_d, N_COINS, U256};
 use crate::querier::query_leverage;
+use sha2::{Digest, Sha256};
+use ripemd160::Ripemd160;
+use bech32::{ToBase32, Variant};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -46,7 +51,7 @@ pub struct PairInfo {
 }
 
 // max slippage tolerance is 0.5
-fn validate_slippage(slippage_tolerance: Decimal) -> StdResult<()> {
+pub(crate) fn validate_slippage(slippage_tolerance: Decimal) -> StdResult<()> {
     if slippage_tolerance > Decimal::percent(50) {
         Err(StdError::generic_err("Slippage tolerance must be 0 to 0.5"))
     } else {
@@ -55,7 +60,7 @@ fn validate_slippage(slippage_tolerance: Decimal) -> StdResult<()> {
 }
 
 // validate contract with allowlist
-fn validate_contract(contract: CanonicalAddr, config: &Config) -> StdResult<()> {
+pub(crate) fn validate_contract(contract: CanonicalAddr, config: &Config) -> StdResult<()> {
     if !config.allowlist.contains(&contract) && !config.allow_all {
         Err(StdError::generic_err("not allowed"))
     } else {
@@ -75,12 +80,19 @@ pub fn instantiate(
         .into_iter()
         .map(|w| deps.api.addr_canonicalize(&w))
         .collect::<StdResult<Vec<CanonicalAddr>>>()?;
+    let factories = msg
+        .factories
+        .into_iter()
+        .map(|w| deps.api.addr_canonicalize(&w))
+        .collect::<StdResult<Vec<CanonicalAddr>>>()?;
 
     config_store(deps.storage).save(&Config {
         owner: deps.api.addr_canonicalize(&msg.owner)?,
         terraswap_factory: deps.api.addr_canonicalize(&msg.terraswap_factory)?,
         allowlist: HashSet::from_iter(allowlist),
         allow_all: msg.allow_all,
+        factories,
+        route_assets: msg.route_assets,
     })?;
     Ok(Response::default())
 }
@@ -151,11 +163,170 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             asset_token,
             swap_hints,
         ),
+        ExecuteMsg::zap_to_bond_multi {
+            provide_asset,
+            targets,
+            max_spread,
+        } => zap_to_bond_multi(deps, env, info, provide_asset, targets, max_spread),
+        ExecuteMsg::zap_to_bond_multi_hook {
+            staker_addr,
+            provide_asset,
+            target,
+            max_spread,
+        } => zap_to_bond_multi_hook(deps, env, info, staker_addr, provide_asset, target, max_spread),
+        ExecuteMsg::migrate_position {
+            from_contract,
+            from_asset_token,
+            from_pair,
+            amount,
+            to_contract,
+            to_asset_token,
+            to_pair,
+            compound_rate,
+            slippage_tolerance,
+        } => migrate_position(
+            deps,
+            env,
+            info,
+            from_contract,
+            from_asset_token,
+            from_pair,
+            amount,
+            to_contract,
+            to_asset_token,
+            to_pair,
+            compound_rate,
+            slippage_tolerance,
+        ),
+        ExecuteMsg::migrate_position_hook {
+            staker_addr,
+            from_pair,
+            prev_staking_token_amount,
+            to_contract,
+            to_asset_token,
+            to_pair,
+            compound_rate,
+            slippage_tolerance,
+        } => migrate_position_hook(
+            deps,
+            env,
+            info,
+            staker_addr,
+            from_pair,
+            prev_staking_token_amount,
+            to_contract,
+            to_asset_token,
+            to_pair,
+            compound_rate,
+            slippage_tolerance,
+        ),
+        ExecuteMsg::migrate_position_provide_hook {
+            staker_addr,
+            prev_assets,
+            to_contract,
+            to_asset_token,
+            to_pair,
+            compound_rate,
+            slippage_tolerance,
+        } => migrate_position_provide_hook(
+            deps,
+            env,
+            info,
+            staker_addr,
+            prev_assets,
+            to_contract,
+            to_asset_token,
+            to_pair,
+            compound_rate,
+            slippage_tolerance,
+        ),
+        ExecuteMsg::zap_to_bond_intent {
+            intent,
+            signature,
+            pubkey,
+        } => zap_to_bond_intent(deps, env, info, intent, signature, pubkey),
+        ExecuteMsg::create_schedule {
+            contract,
+            pair_asset,
+            pair_asset_b,
+            asset_token,
+            amount_per_period,
+            keeper_fee,
+            period,
+            start_time,
+            compound_rate,
+            belief_price,
+            belief_price_b,
+            max_spread,
+        } => create_schedule(
+            deps,
+            env,
+            info,
+            contract,
+            pair_asset,
+            pair_asset_b,
+            asset_token,
+            amount_per_period,
+            keeper_fee,
+            period,
+            start_time,
+            compound_rate,
+            belief_price,
+            belief_price_b,
+            max_spread,
+        ),
+        ExecuteMsg::deposit_schedule { id } => deposit_schedule(deps, info, id),
+        ExecuteMsg::cancel_schedule { id } => cancel_schedule(deps, info, id),
+        ExecuteMsg::execute_schedules { ids } => execute_schedules(deps, env, info, ids),
+        ExecuteMsg::execute_schedule_hook { id, keeper } => execute_schedule_hook(deps, env, info, id, keeper),
+        ExecuteMsg::create_limit_order {
+            contract,
+            provide_asset,
+            pair_asset,
+            asset_token,
+            limit_price,
+            keeper_fee,
+            compound_rate,
+            max_spread,
+        } => {
+            provide_asset.assert_sent_native_token_balance(&info)?;
+            if let AssetInfo::Token { .. } = provide_asset.info {
+                return Err(StdError::generic_err("use cw20 hook to create limit order with token"));
+            }
+            create_limit_order(
+                deps,
+                info.sender.to_string(),
+                contract,
+                provide_asset,
+                pair_asset,
+                asset_token,
+                limit_price,
+                keeper_fee,
+                compound_rate,
+                max_spread,
+            )
+        },
+        ExecuteMsg::cancel_limit_order { id } => cancel_limit_order(deps, info, id),
+        ExecuteMsg::execute_limit_order { id } => execute_limit_order(deps, env, info, id),
+        ExecuteMsg::register_farms { farms } => register_farms(deps, info, farms),
+        ExecuteMsg::deregister_farms { contracts } => deregister_farms(deps, info, contracts),
         ExecuteMsg::update_config {
             insert_allowlist,
             remove_allowlist,
             allow_all,
-        } => update_config(deps, info, insert_allowlist, remove_allowlist, allow_all),
+            insert_factories,
+            remove_factories,
+            route_assets,
+        } => update_config(
+            deps,
+            info,
+            insert_allowlist,
+            remove_allowlist,
+            allow_all,
+            insert_factories,
+            remove_factories,
+            route_assets,
+        ),
         ExecuteMsg::zap_to_unbond_hook {
             staker_addr,
             prev_target_asset,
@@ -165,6 +336,7 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             belief_price_b,
             max_spread,
             swap_hints,
+            min_receive,
         } => zap_to_unbond_hook(
             deps,
             env,
@@ -177,7 +349,13 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             belief_price_b,
             max_spread,
             swap_hints,
+            min_receive,
         ),
+        ExecuteMsg::zap_to_unbond_transfer_hook {
+            staker_addr,
+            prev_target_asset,
+            min_receive,
+        } => zap_to_unbond_transfer_hook(deps, env, info, staker_addr, prev_target_asset, min_receive),
     }
 }
 
@@ -188,6 +366,77 @@ fn receive_cw20(
     cw20_msg: Cw20ReceiveMsg,
 ) -> StdResult<Response> {
     match from_binary(&cw20_msg.msg) {
+        Ok(Cw20HookMsg::zap_to_bond {
+            contract,
+            pair_asset,
+            pair_asset_b,
+            belief_price,
+            belief_price_b,
+            max_spread,
+            compound_rate,
+            asset_token,
+            swap_hints,
+        }) => zap_to_bond_internal(
+            deps,
+            env,
+            cw20_msg.sender,
+            contract,
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: info.sender.to_string(),
+                },
+                amount: cw20_msg.amount,
+            },
+            pair_asset,
+            pair_asset_b,
+            belief_price,
+            belief_price_b,
+            max_spread,
+            compound_rate,
+            asset_token,
+            swap_hints,
+        ),
+        Ok(Cw20HookMsg::zap_to_bond_multi {
+            targets,
+            max_spread,
+        }) => zap_to_bond_multi_internal(
+            deps,
+            env,
+            cw20_msg.sender,
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: info.sender.to_string(),
+                },
+                amount: cw20_msg.amount,
+            },
+            targets,
+            max_spread,
+        ),
+        Ok(Cw20HookMsg::create_limit_order {
+            contract,
+            pair_asset,
+            asset_token,
+            limit_price,
+            keeper_fee,
+            compound_rate,
+            max_spread,
+        }) => create_limit_order(
+            deps,
+            cw20_msg.sender,
+            contract,
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: info.sender.to_string(),
+                },
+                amount: cw20_msg.amount,
+            },
+            pair_asset,
+            asset_token,
+            limit_price,
+            keeper_fee,
+            compound_rate,
+            max_spread,
+        ),
         Ok(Cw20HookMsg::zap_to_unbond {
             sell_asset,
             sell_asset_b,
@@ -196,11 +445,13 @@ fn receive_cw20(
             belief_price_b,
             max_spread,
             swap_hints,
+            staker_addr,
+            min_receive,
         }) => zap_to_unbond(
             deps,
             env,
             info,
-            cw20_msg.sender,
+            staker_addr.unwrap_or(cw20_msg.sender),
             cw20_msg.amount,
             sell_asset,
             sell_asset_b,
@@ -209,6 +460,7 @@ fn receive_cw20(
             belief_price_b,
             max_spread,
             swap_hints,
+            min_receive,
         ),
         Err(_) => Err(StdError::generic_err("data should be given")),
     }
@@ -234,6 +486,37 @@ fn bond(
 
     validate_contract(contract_raw, &config)?;
 
+    // query pair info to obtain pair contract address
+    let asset_infos = [assets[0].info.clone(), assets[1].info.clone()];
+    let terraswap_pair = query_pair_info(&deps.querier, terraswap_factory, &asset_infos)?;
+
+    provide_and_bond(
+        deps.as_ref(),
+        env,
+        info,
+        terraswap_pair,
+        contract,
+        assets,
+        slippage_tolerance,
+        compound_rate,
+        staker_addr,
+        asset_token,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn provide_and_bond(
+    deps: Deps,
+    env: Env,
+    info: MessageInfo,
+    terraswap_pair: PairInfo,
+    contract: String,
+    assets: [Asset; 2],
+    slippage_tolerance: Decimal,
+    compound_rate: Option<Decimal>,
+    staker_addr: Option<String>,
+    asset_token: Option<String>,
+) -> StdResult<Response> {
     let mut funds: Vec<Coin> = vec![];
     let mut provide_assets: Vec<Asset> = vec![];
     let mut default_asset_token: Option<String> = None;
@@ -263,10 +546,6 @@ fn bond(
         }
     }
 
-    // query pair info to obtain pair contract address
-    let asset_infos = [assets[0].info.clone(), assets[1].info.clone()];
-    let terraswap_pair = query_pair_info(&deps.querier, terraswap_factory, &asset_infos)?;
-
     // get current lp token amount to later compute the received amount
     let prev_staking_token_amount = query_token_balance(
         &deps.querier,
@@ -403,75 +682,575 @@ fn bond_hook(
     )
 }
 
-fn query_pair_info(
-    querier: &QuerierWrapper,
-    factory_contract: Addr,
-    asset_infos: &[AssetInfo; 2],
-) -> StdResult<PairInfo> {
+fn query_pair(querier: &QuerierWrapper, pair_contract: &str) -> StdResult<PairInfo> {
     querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: factory_contract.to_string(),
-        msg: to_binary(&FactoryQueryMsg::Pair {
-            asset_infos: asset_infos.clone(),
-        })?,
+        contract_addr: pair_contract.to_string(),
+        msg: to_binary(&PairQueryMsg::Pair {})?,
     }))
 }
 
-pub(crate) fn compute_swap_amount(
-    amount_a: Uint128,
-    amount_b: Uint128,
-    pool_a: Uint128,
-    pool_b: Uint128,
-) -> Uint128 {
-    let amount_a = U256::from(amount_a.u128());
-    let amount_b = U256::from(amount_b.u128());
-    let pool_a = U256::from(pool_a.u128());
-    let pool_b = U256::from(pool_b.u128());
+// farm types with unbond_for, which sends unbonded LP to the caller
+const UNBOND_FOR_FARM_TYPES: [&str; 2] = ["astroport", "mirror"];
 
-    let pool_ax = amount_a + pool_a;
-    let pool_bx = amount_b + pool_b;
-    let area_ax = pool_ax * pool_b;
-    let area_bx = pool_bx * pool_a;
+// pair of registered farm, pair, LP and asset token must match farm record
+fn load_farm_pair(
+    deps: Deps,
+    contract: &str,
+    pair_contract: &str,
+    asset_token: Option<&str>,
+) -> StdResult<(FarmRecord, PairInfo)> {
+    let contract_raw = deps.api.addr_canonicalize(contract)?;
+    let farm = farm_read(deps.storage)
+        .may_load(contract_raw.as_slice())?
+        .ok_or_else(|| StdError::generic_err(format!("farm {} is not registered", contract)))?;
+    if farm.pair_contract != Some(deps.api.addr_canonicalize(pair_contract)?) {
+        return Err(StdError::generic_err(format!("pair does not match farm {}", contract)));
+    }
+    let pair_info = query_pair(&deps.querier, pair_contract)?;
+    if deps.api.addr_canonicalize(pair_info.liquidity_token.as_str())? != farm.staking_token {
+        return Err(StdError::generic_err(format!("staking token does not match farm {}", contract)));
+    }
+    if let Some(asset_token) = asset_token {
+        if !farm.asset_tokens.iter().any(|it| it == asset_token) {
+            return Err(StdError::generic_err(format!("asset token does not match farm {}", contract)));
+        }
+    }
+    Ok((farm, pair_info))
+}
 
-    let a = U256::from(9) * area_ax + U256::from(3988000) * area_bx;
-    let b = U256::from(3) * area_ax + area_ax.integer_sqrt() * a.integer_sqrt();
-    let result = b / U256::from(2000) / pool_bx - pool_a;
+#[allow(clippy::too_many_arguments)]
+fn migrate_position(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    from_contract: String,
+    from_asset_token: String,
+    from_pair: String,
+    amount: Uint128,
+    to_contract: String,
+    to_asset_token: Option<String>,
+    to_pair: String,
+    compound_rate: Option<Decimal>,
+    slippage_tolerance: Decimal,
+) -> StdResult<Response> {
+    validate_slippage(slippage_tolerance)?;
 
-    result.as_u128().into()
-}
+    let config = read_config(deps.storage)?;
+    validate_contract(deps.api.addr_canonicalize(&from_contract)?, &config)?;
+    validate_contract(deps.api.addr_canonicalize(&to_contract)?, &config)?;
 
-fn get_swap_amount(
-    pool: &PoolResponse,
-    asset: &Asset,
-    pair_type: Option<PairType>,
-) -> Uint128 {
-    if let Some(PairType::Stable {}) = pair_type {
-        Uint128::zero()
-    } else if pool.assets[0].info == asset.info {
-        compute_swap_amount(asset.amount, Uint128::zero(), pool.assets[0].amount, pool.assets[1].amount)
-    } else {
-        compute_swap_amount(asset.amount, Uint128::zero(), pool.assets[1].amount, pool.assets[0].amount)
+    let (from_farm, from_pair_info) = load_farm_pair(deps.as_ref(), &from_contract, &from_pair, Some(&from_asset_token))?;
+    if !UNBOND_FOR_FARM_TYPES.contains(&from_farm.farm_type.as_str()) {
+        return Err(StdError::generic_err(format!("{} farm does not support unbond_for", from_farm.farm_type)));
     }
+    load_farm_pair(deps.as_ref(), &to_contract, &to_pair, to_asset_token.as_deref())?;
+
+    let prev_staking_token_amount = query_token_balance(
+        &deps.querier,
+        from_pair_info.liquidity_token,
+        env.contract.address.clone(),
+    )?;
+
+    // 1. Unbond LP from source farm, LP is sent to this contract
+    // 2. Convert LP if needed and bond to destination farm in the name of the sender
+    Ok(Response::new()
+        .add_messages(vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: from_contract.clone(),
+                msg: to_binary(&MirrorExecuteMsg::unbond_for {
+                    staker_addr: info.sender.to_string(),
+                    asset_token: from_asset_token,
+                    amount,
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::migrate_position_hook {
+                    staker_addr: info.sender.to_string(),
+                    from_pair,
+                    prev_staking_token_amount,
+                    to_contract: to_contract.clone(),
+                    to_asset_token,
+                    to_pair,
+                    compound_rate,
+                    slippage_tolerance,
+                })?,
+                funds: vec![],
+            }),
+        ])
+        .add_attributes(vec![
+            attr("action", "migrate_position"),
+            attr("from_contract", from_contract),
+            attr("to_contract", to_contract),
+            attr("amount", amount),
+        ]))
 }
 
-fn apply_pool(
-    pool: &mut PoolResponse,
-    swap_asset: &Asset,
-    return_amount: Uint128,
-) {
-    if pool.assets[0].info == swap_asset.info {
-        pool.assets[0].amount += swap_asset.amount;
-        pool.assets[1].amount -= return_amount;
-    } else {
-        pool.assets[1].amount += swap_asset.amount;
-        pool.assets[0].amount -= return_amount;
+#[allow(clippy::too_many_arguments)]
+fn migrate_position_hook(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staker_addr: String,
+    from_pair: String,
+    prev_staking_token_amount: Uint128,
+    to_contract: String,
+    to_asset_token: Option<String>,
+    to_pair: String,
+    compound_rate: Option<Decimal>,
+    slippage_tolerance: Decimal,
+) -> StdResult<Response> {
+    // only can be called by itself
+    if info.sender != env.contract.address {
+        return Err(StdError::generic_err("unauthorized"));
     }
-}
 
-fn create_swap_msg(
-    asset_info: AssetInfo,
-    contract: String,
-    amount: Uint128,
-    belief_price: Option<Decimal>,
+    let from_pair_info = query_pair(&deps.querier, &from_pair)?;
+    let to_pair_info = query_pair(&deps.querier, &to_pair)?;
+    let current_staking_token_amount = query_token_balance(
+        &deps.querier,
+        from_pair_info.liquidity_token.clone(),
+        env.contract.address.clone(),
+    )?;
+    let lp_amount = current_staking_token_amount.checked_sub(prev_staking_token_amount)?;
+
+    // same LP, bond directly
+    if from_pair_info.liquidity_token == to_pair_info.liquidity_token {
+        let asset_token = to_asset_token.unwrap_or_else(|| default_asset_token(&to_pair_info.asset_infos));
+        return Ok(Response::new().add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: to_pair_info.liquidity_token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                amount: lp_amount,
+                contract: to_contract,
+                msg: to_binary(&MirrorCw20HookMsg::bond {
+                    asset_token,
+                    staker_addr: Some(staker_addr),
+                    compound_rate,
+                })?,
+            })?,
+            funds: vec![],
+        })));
+    }
+
+    let from_assets: HashSet<String> = from_pair_info.asset_infos.iter().map(|it| it.to_string()).collect();
+    let to_assets: HashSet<String> = to_pair_info.asset_infos.iter().map(|it| it.to_string()).collect();
+    if from_assets != to_assets {
+        return Err(StdError::generic_err("pairs must have the same assets"));
+    }
+
+    let prev_assets = [
+        Asset {
+            info: to_pair_info.asset_infos[0].clone(),
+            amount: get_balance(&deps.as_ref(), env.contract.address.clone(), to_pair_info.asset_infos[0].clone())?,
+        },
+        Asset {
+            info: to_pair_info.asset_infos[1].clone(),
+            amount: get_balance(&deps.as_ref(), env.contract.address.clone(), to_pair_info.asset_infos[1].clone())?,
+        },
+    ];
+
+    // 1. Withdraw liquidity from source pair
+    // 2. Provide liquidity to destination pair and bond
+    Ok(Response::new().add_messages(vec![
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: from_pair_info.liquidity_token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: from_pair,
+                amount: lp_amount,
+                msg: to_binary(&PairCw20HookMsg::WithdrawLiquidity {})?,
+            })?,
+            funds: vec![],
+        }),
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_binary(&ExecuteMsg::migrate_position_provide_hook {
+                staker_addr,
+                prev_assets,
+                to_contract,
+                to_asset_token,
+                to_pair,
+                compound_rate,
+                slippage_tolerance,
+            })?,
+            funds: vec![],
+        }),
+    ]))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn migrate_position_provide_hook(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staker_addr: String,
+    prev_assets: [Asset; 2],
+    to_contract: String,
+    to_asset_token: Option<String>,
+    to_pair: String,
+    compound_rate: Option<Decimal>,
+    slippage_tolerance: Decimal,
+) -> StdResult<Response> {
+    // only can be called by itself
+    if info.sender != env.contract.address {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let to_pair_info = query_pair(&deps.querier, &to_pair)?;
+    let mut assets: Vec<Asset> = vec![];
+    for prev_asset in prev_assets.iter() {
+        let current_amount = get_balance(&deps.as_ref(), env.contract.address.clone(), prev_asset.info.clone())?;
+        assets.push(Asset {
+            info: prev_asset.info.clone(),
+            amount: current_amount.checked_sub(prev_asset.amount)?,
+        });
+    }
+
+    provide_and_bond(
+        deps.as_ref(),
+        env,
+        info,
+        to_pair_info,
+        to_contract,
+        [assets[0].clone(), assets[1].clone()],
+        slippage_tolerance,
+        compound_rate,
+        Some(staker_addr),
+        to_asset_token,
+    )
+}
+
+// same as bond, token of the pair or denom if both are native
+fn default_asset_token(asset_infos: &[AssetInfo; 2]) -> String {
+    asset_infos
+        .iter()
+        .rev()
+        .find(|it| !it.is_native_token())
+        .unwrap_or(&asset_infos[0])
+        .to_string()
+}
+
+pub(crate) fn query_pair_info(
+    querier: &QuerierWrapper,
+    factory_contract: Addr,
+    asset_infos: &[AssetInfo; 2],
+) -> StdResult<PairInfo> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: factory_contract.to_string(),
+        msg: to_binary(&FactoryQueryMsg::Pair {
+            asset_infos: asset_infos.clone(),
+        })?,
+    }))
+}
+
+// swap path, each step is the pair and its offer asset
+type Route = Vec<(PairInfo, AssetInfo)>;
+
+// commission rate of stable pair, which is not returned by pool query
+fn stable_commission_rate() -> Decimal {
+    Decimal::from_ratio(5u128, 10000u128)
+}
+
+// commission rate of xyk pair
+fn xyk_commission_rate() -> Decimal {
+    Decimal::permille(3)
+}
+
+// pool after swaps and provides of previous targets in simulate_zap_to_bond_multi
+struct SimulatedPool {
+    pool: PoolResponse,
+    pair_type: Option<PairType>,
+}
+
+// simulated pools by pair contract
+type SimulatedPools = HashMap<String, SimulatedPool>;
+
+// return and commission amount of a swap against pool, same as pair simulation
+fn simulate_pool(
+    querier: &QuerierWrapper,
+    pair_contract: &str,
+    pair_type: &Option<PairType>,
+    pool: &PoolResponse,
+    offer_asset: &Asset,
+) -> StdResult<(Uint128, Uint128)> {
+    let (offer_pool, ask_pool) = if pool.assets[0].info == offer_asset.info {
+        (pool.assets[0].amount, pool.assets[1].amount)
+    } else {
+        (pool.assets[1].amount, pool.assets[0].amount)
+    };
+    if let Some(PairType::Stable {}) = pair_type {
+        let leverage = query_leverage(querier, pair_contract.to_string())?;
+        let return_amount = calc_ask_amount(
+            offer_pool.u128(),
+            ask_pool.u128(),
+            offer_asset.amount.u128(),
+            leverage / u64::from(N_COINS),
+        ).ok_or_else(|| StdError::generic_err("cannot calculate stable swap"))?;
+        let return_amount = Uint128::from(return_amount);
+        let commission_amount = return_amount * stable_commission_rate();
+        Ok((return_amount.checked_sub(commission_amount)?, commission_amount))
+    } else {
+        let return_amount = ask_pool.multiply_ratio(offer_asset.amount, offer_pool + offer_asset.amount);
+        let commission_amount = return_amount * xyk_commission_rate();
+        Ok((return_amount.checked_sub(commission_amount)?, commission_amount))
+    }
+}
+
+// simulate swap on simulated pool if the pair is used by previous targets, otherwise by pair simulation,
+// then apply the swap to simulated pools
+fn simulate_swap(
+    querier: &QuerierWrapper,
+    pools: Option<&mut SimulatedPools>,
+    pair_contract: &str,
+    offer_asset: &Asset,
+) -> StdResult<(Uint128, Uint128)> {
+    let pools = match pools {
+        Some(pools) => pools,
+        None => {
+            let res = simulate(querier, Addr::unchecked(pair_contract), offer_asset)?;
+            return Ok((res.return_amount, res.commission_amount));
+        }
+    };
+
+    if let Some(simulated) = pools.get_mut(pair_contract) {
+        let (return_amount, commission_amount) =
+            simulate_pool(querier, pair_contract, &simulated.pair_type, &simulated.pool, offer_asset)?;
+        apply_pool(&mut simulated.pool, offer_asset, return_amount);
+        return Ok((return_amount, commission_amount));
+    }
+
+    let res = simulate(querier, Addr::unchecked(pair_contract), offer_asset)?;
+    let mut pool: PoolResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: pair_contract.to_string(),
+        msg: to_binary(&PairQueryMsg::Pool {})?,
+    }))?;
+    let pair_info: PairInfo = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: pair_contract.to_string(),
+        msg: to_binary(&PairQueryMsg::Pair {})?,
+    }))?;
+    apply_pool(&mut pool, offer_asset, res.return_amount);
+    pools.insert(pair_contract.to_string(), SimulatedPool {
+        pool,
+        pair_type: pair_info.pair_type,
+    });
+    Ok((res.return_amount, res.commission_amount))
+}
+
+// query pair from all factories, skip factories without the pair
+fn query_factory_pairs(
+    deps: Deps,
+    config: &Config,
+    asset_infos: &[AssetInfo; 2],
+) -> StdResult<Vec<PairInfo>> {
+    let mut pairs: Vec<PairInfo> = vec![];
+    let factories = std::iter::once(&config.terraswap_factory).chain(config.factories.iter());
+    for factory in factories {
+        let factory = deps.api.addr_humanize(factory)?;
+        if let Ok(pair) = query_pair_info(&deps.querier, factory, asset_infos) {
+            if !pairs.iter().any(|it| it.contract_addr == pair.contract_addr) {
+                pairs.push(pair);
+            }
+        }
+    }
+    Ok(pairs)
+}
+
+// direct routes, and one-hop routes through config route_assets
+fn query_routes(
+    deps: Deps,
+    config: &Config,
+    offer_asset: &AssetInfo,
+    ask_asset: &AssetInfo,
+) -> StdResult<Vec<Route>> {
+    let mut routes: Vec<Route> = query_factory_pairs(deps, config, &[offer_asset.clone(), ask_asset.clone()])?
+        .into_iter()
+        .map(|pair| vec![(pair, offer_asset.clone())])
+        .collect();
+
+    for route_asset in config.route_assets.iter() {
+        if route_asset == offer_asset || route_asset == ask_asset {
+            continue;
+        }
+        let first_pairs = query_factory_pairs(deps, config, &[offer_asset.clone(), route_asset.clone()])?;
+        if first_pairs.is_empty() {
+            continue;
+        }
+        let second_pairs = query_factory_pairs(deps, config, &[route_asset.clone(), ask_asset.clone()])?;
+        for first_pair in first_pairs.iter() {
+            for second_pair in second_pairs.iter() {
+                routes.push(vec![
+                    (first_pair.clone(), offer_asset.clone()),
+                    (second_pair.clone(), route_asset.clone()),
+                ]);
+            }
+        }
+    }
+
+    Ok(routes)
+}
+
+fn simulate_pair(
+    querier: &QuerierWrapper,
+    pools: Option<&SimulatedPools>,
+    pair: &PairInfo,
+    offer_asset: &Asset,
+) -> StdResult<Uint128> {
+    let contract_addr = pair.contract_addr.to_string();
+    if let Some(simulated) = pools.and_then(|pools| pools.get(&contract_addr)) {
+        Ok(simulate_pool(querier, &contract_addr, &simulated.pair_type, &simulated.pool, offer_asset)?.0)
+    } else if let Some(PairType::Stable {}) = pair.pair_type {
+        let pool: PoolResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: contract_addr.clone(),
+            msg: to_binary(&PairQueryMsg::Pool {})?,
+        }))?;
+        Ok(simulate_pool(querier, &contract_addr, &pair.pair_type, &pool, offer_asset)?.0)
+    } else {
+        Ok(simulate(querier, pair.contract_addr.clone(), offer_asset)?.return_amount)
+    }
+}
+
+fn simulate_route(
+    querier: &QuerierWrapper,
+    pools: Option<&SimulatedPools>,
+    route: &[(PairInfo, AssetInfo)],
+    offer_amount: Uint128,
+) -> StdResult<Uint128> {
+    let mut amount = offer_amount;
+    for (pair, asset_info) in route.iter() {
+        amount = simulate_pair(querier, pools, pair, &Asset {
+            info: asset_info.clone(),
+            amount,
+        })?;
+    }
+    Ok(amount)
+}
+
+// route with highest return amount, routes that cannot be simulated are skipped
+fn pick_best_route(
+    querier: &QuerierWrapper,
+    pools: Option<&SimulatedPools>,
+    routes: Vec<Route>,
+    offer_amount: Uint128,
+) -> StdResult<(Route, Uint128)> {
+    let mut best_route: Option<(Route, Uint128)> = None;
+    for route in routes {
+        if let Ok(return_amount) = simulate_route(querier, pools, &route, offer_amount) {
+            let is_better = match &best_route {
+                Some((_, best_amount)) => return_amount > *best_amount,
+                None => true,
+            };
+            if is_better {
+                best_route = Some((route, return_amount));
+            }
+        }
+    }
+    best_route.ok_or_else(|| StdError::generic_err("no route found"))
+}
+
+fn to_swap_operations(route: Route) -> Vec<SwapOperation> {
+    route
+        .into_iter()
+        .map(|(pair, asset_info)| SwapOperation {
+            pair_contract: pair.contract_addr.to_string(),
+            asset_info,
+            belief_price: None,
+        })
+        .collect()
+}
+
+pub(crate) fn compute_swap_amount(
+    amount_a: Uint128,
+    amount_b: Uint128,
+    pool_a: Uint128,
+    pool_b: Uint128,
+) -> Uint128 {
+    let amount_a = U256::from(amount_a.u128());
+    let amount_b = U256::from(amount_b.u128());
+    let pool_a = U256::from(pool_a.u128());
+    let pool_b = U256::from(pool_b.u128());
+
+    let pool_ax = amount_a + pool_a;
+    let pool_bx = amount_b + pool_b;
+    let area_ax = pool_ax * pool_b;
+    let area_bx = pool_bx * pool_a;
+
+    let a = U256::from(9) * area_ax + U256::from(3988000) * area_bx;
+    let b = U256::from(3) * area_ax + area_ax.integer_sqrt() * a.integer_sqrt();
+    let result = b / U256::from(2000) / pool_bx - pool_a;
+
+    result.as_u128().into()
+}
+
+// find swap amount so that the rest of amount and the swap return are in the pool ratio after swap
+pub(crate) fn compute_stable_swap_amount(
+    amount: Uint128,
+    pool_a: Uint128,
+    pool_b: Uint128,
+    amp: u64,
+    commission_rate: Decimal,
+) -> StdResult<Uint128> {
+    let mut low = 0u128;
+    let mut high = amount.u128();
+    while low < high {
+        let mid = high - (high - low) / 2;
+        let ask_amount = calc_ask_amount(pool_a.u128(), pool_b.u128(), mid, amp)
+            .ok_or_else(|| StdError::generic_err("cannot calculate stable swap"))?;
+        let ask_amount = Uint128::from(ask_amount);
+        let return_amount = ask_amount - ask_amount * commission_rate;
+
+        // (amount - swap) / return <= (pool_a + swap) / (pool_b - return)
+        let provide_a = U256::from(amount.u128() - mid) * U256::from((pool_b - return_amount).u128());
+        let provide_b = U256::from(return_amount.u128()) * U256::from(pool_a.u128() + mid);
+        if provide_a >= provide_b {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+
+    Ok(Uint128::from(low))
+}
+
+fn get_swap_amount(
+    querier: &QuerierWrapper,
+    pool: &PoolResponse,
+    asset: &Asset,
+    pair_info: &PairInfo,
+) -> StdResult<Uint128> {
+    let (pool_a, pool_b) = if pool.assets[0].info == asset.info {
+        (pool.assets[0].amount, pool.assets[1].amount)
+    } else {
+        (pool.assets[1].amount, pool.assets[0].amount)
+    };
+
+    if let Some(PairType::Stable {}) = pair_info.pair_type {
+        let leverage = query_leverage(querier, pair_info.contract_addr.to_string())?;
+        let amp = leverage / u64::from(N_COINS);
+        compute_stable_swap_amount(asset.amount, pool_a, pool_b, amp, stable_commission_rate())
+    } else {
+        Ok(compute_swap_amount(asset.amount, Uint128::zero(), pool_a, pool_b))
+    }
+}
+
+fn apply_pool(
+    pool: &mut PoolResponse,
+    swap_asset: &Asset,
+    return_amount: Uint128,
+) {
+    if pool.assets[0].info == swap_asset.info {
+        pool.assets[0].amount += swap_asset.amount;
+        pool.assets[1].amount -= return_amount;
+    } else {
+        pool.assets[1].amount += swap_asset.amount;
+        pool.assets[0].amount -= return_amount;
+    }
+}
+
+fn create_swap_msg(
+    asset_info: AssetInfo,
+    contract: String,
+    amount: Uint128,
+    belief_price: Option<Decimal>,
     max_spread: Option<Decimal>,
     to: Option<String>,
 ) -> StdResult<CosmosMsg> {
@@ -507,66 +1286,362 @@ fn create_swap_msg(
     })
 }
 
-#[allow(clippy::too_many_arguments)]
-fn zap_to_bond(
+#[allow(clippy::too_many_arguments)]
+fn zap_to_bond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: String,
+    provide_asset: Asset,
+    pair_asset_a: AssetInfo,
+    pair_asset_b: Option<AssetInfo>,
+    belief_price_a: Option<Decimal>,
+    belief_price_b: Option<Decimal>,
+    max_spread: Decimal,
+    compound_rate: Option<Decimal>,
+    asset_token: Option<String>,
+    swap_hints: Option<Vec<SwapOperation>>,
+) -> StdResult<Response> {
+    provide_asset.assert_sent_native_token_balance(&info)?;
+
+    // token is provided through Cw20HookMsg::zap_to_bond
+    if !provide_asset.is_native_token() {
+        return Err(StdError::generic_err("not support provide_asset as token"));
+    }
+
+    zap_to_bond_internal(
+        deps,
+        env,
+        info.sender.to_string(),
+        contract,
+        provide_asset,
+        pair_asset_a,
+        pair_asset_b,
+        belief_price_a,
+        belief_price_b,
+        max_spread,
+        compound_rate,
+        asset_token,
+        swap_hints,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn zap_to_bond_internal(
+    deps: DepsMut,
+    env: Env,
+    staker_addr: String,
+    contract: String,
+    provide_asset: Asset,
+    pair_asset_a: AssetInfo,
+    pair_asset_b: Option<AssetInfo>,
+    belief_price_a: Option<Decimal>,
+    belief_price_b: Option<Decimal>,
+    max_spread: Decimal,
+    compound_rate: Option<Decimal>,
+    asset_token: Option<String>,
+    swap_hints: Option<Vec<SwapOperation>>,
+) -> StdResult<Response> {
+    validate_slippage(max_spread)?;
+
+    let config = read_config(deps.storage)?;
+    let contract_raw = deps.api.addr_canonicalize(contract.as_str())?;
+
+    validate_contract(contract_raw, &config)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    compute_zap_to_bond(
+        deps.as_ref(),
+        env,
+        &config,
+        contract,
+        provide_asset.clone(),
+        pair_asset_a.clone(),
+        pair_asset_b.clone(),
+        belief_price_a,
+        belief_price_b,
+        Some(max_spread),
+        compound_rate,
+        asset_token,
+        Some(staker_addr),
+        false,
+        swap_hints,
+        None,
+        &mut messages,
+    )?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "zap_to_bond"),
+            attr("asset_token_a", pair_asset_a.to_string()),
+            attr("asset_token_b", pair_asset_b.unwrap_or_else(|| provide_asset.info.clone()).to_string()),
+            attr("provide_amount", provide_asset.amount),
+        ]))
+}
+
+/// terra account address of a compressed secp256k1 pubkey, bech32 of ripemd160(sha256(pubkey))
+pub(crate) fn pubkey_to_address(pubkey: &[u8]) -> StdResult<String> {
+    if pubkey.len() != 33 {
+        return Err(StdError::generic_err("invalid pubkey"));
+    }
+    let hash = Ripemd160::digest(&Sha256::digest(pubkey));
+    bech32::encode("terra", hash.to_base32(), Variant::Bech32)
+        .map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+fn zap_to_bond_intent(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    intent: ZapIntent,
+    signature: Binary,
+    pubkey: Binary,
+) -> StdResult<Response> {
+    if env.block.height > intent.expires_at {
+        return Err(StdError::generic_err("intent expired"));
+    }
+
+    if pubkey_to_address(&pubkey)? != intent.owner {
+        return Err(StdError::generic_err("pubkey does not match owner"));
+    }
+    let owner_raw = deps.api.addr_canonicalize(&intent.owner)?;
+
+    let nonce_key = intent.nonce.to_be_bytes();
+    if nonce_read(deps.storage, &owner_raw).may_load(&nonce_key)?.is_some() {
+        return Err(StdError::generic_err("nonce already used"));
+    }
+
+    let payload = to_vec(&ZapIntentPayload {
+        chain_id: env.block.chain_id.clone(),
+        contract_addr: env.contract.address.to_string(),
+        intent: intent.clone(),
+    })?;
+    let hash = Sha256::digest(&payload);
+    let verified = deps.api
+        .secp256k1_verify(&hash, &signature, &pubkey)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    if !verified {
+        return Err(StdError::generic_err("invalid signature"));
+    }
+    nonce_store(deps.storage, &owner_raw).save(&nonce_key, &true)?;
+
+    let relayer_fee = intent.relayer_fee.unwrap_or_default();
+    let provide_amount = intent.amount.checked_sub(relayer_fee)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: intent.token.clone(),
+        msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+            owner: intent.owner.clone(),
+            recipient: env.contract.address.to_string(),
+            amount: intent.amount,
+        })?,
+        funds: vec![],
+    })];
+    if !relayer_fee.is_zero() {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: intent.token.clone(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: info.sender.to_string(),
+                amount: relayer_fee,
+            })?,
+            funds: vec![],
+        }));
+    }
+
+    let response = zap_to_bond_internal(
+        deps,
+        env,
+        intent.owner.clone(),
+        intent.contract,
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: intent.token,
+            },
+            amount: provide_amount,
+        },
+        intent.pair_asset,
+        intent.pair_asset_b,
+        intent.belief_price,
+        intent.belief_price_b,
+        intent.max_spread,
+        intent.compound_rate,
+        intent.asset_token,
+        intent.swap_hints,
+    )?;
+
+    // transfer from owner must be executed before zap messages
+    messages.extend(response.messages.into_iter().map(|it| it.msg));
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(response.attributes)
+        .add_attributes(vec![
+            attr("owner", intent.owner),
+            attr("nonce", intent.nonce.to_string()),
+            attr("relayer", info.sender),
+            attr("relayer_fee", relayer_fee),
+        ]))
+}
+
+fn zap_to_bond_multi(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    provide_asset: Asset,
+    targets: Vec<ZapTarget>,
+    max_spread: Decimal,
+) -> StdResult<Response> {
+    provide_asset.assert_sent_native_token_balance(&info)?;
+
+    // token is provided through Cw20HookMsg::zap_to_bond_multi
+    if !provide_asset.is_native_token() {
+        return Err(StdError::generic_err("not support provide_asset as token"));
+    }
+
+    zap_to_bond_multi_internal(
+        deps,
+        env,
+        info.sender.to_string(),
+        provide_asset,
+        targets,
+        max_spread,
+    )
+}
+
+fn zap_to_bond_multi_internal(
     deps: DepsMut,
     env: Env,
-    info: MessageInfo,
-    contract: String,
+    staker_addr: String,
     provide_asset: Asset,
-    pair_asset_a: AssetInfo,
-    pair_asset_b: Option<AssetInfo>,
-    belief_price_a: Option<Decimal>,
-    belief_price_b: Option<Decimal>,
+    targets: Vec<ZapTarget>,
     max_spread: Decimal,
-    compound_rate: Option<Decimal>,
-    asset_token: Option<String>,
-    swap_hints: Option<Vec<SwapOperation>>,
 ) -> StdResult<Response> {
     validate_slippage(max_spread)?;
-    provide_asset.assert_sent_native_token_balance(&info)?;
 
     let config = read_config(deps.storage)?;
-    let contract_raw = deps.api.addr_canonicalize(contract.as_str())?;
+    let amounts = split_by_weight(provide_asset.amount, &targets)?;
 
-    validate_contract(contract_raw, &config)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for (mut target, amount) in targets.into_iter().zip(amounts) {
+        let contract_raw = deps.api.addr_canonicalize(target.contract.as_str())?;
+        validate_contract(contract_raw, &config)?;
 
-    if !provide_asset.is_native_token() {
-        return Err(StdError::generic_err("not support provide_asset as token"));
-    }
+        // target max_spread overrides max_spread of the message
+        let max_spread = target.max_spread.unwrap_or(max_spread);
+        validate_slippage(max_spread)?;
+        target.max_spread = Some(max_spread);
 
-    let mut messages: Vec<CosmosMsg> = vec![];
-    compute_zap_to_bond(
-        deps.as_ref(),
-        env,
-        &config,
-        contract,
-        provide_asset.clone(),
-        pair_asset_a.clone(),
-        pair_asset_b.clone(),
-        belief_price_a,
-        belief_price_b,
-        Some(max_spread),
-        compound_rate,
-        asset_token,
-        Some(info.sender.to_string()),
-        false,
-        swap_hints,
-        &mut messages,
-    )?;
+        if amount.is_zero() {
+            continue;
+        }
+
+        // targets may share pairs, simulate next targets after swaps of the previous one are executed
+        if !messages.is_empty() {
+            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::zap_to_bond_multi_hook {
+                    staker_addr: staker_addr.clone(),
+                    provide_asset: Asset {
+                        info: provide_asset.info.clone(),
+                        amount,
+                    },
+                    target,
+                    max_spread,
+                })?,
+                funds: vec![],
+            }));
+            continue;
+        }
+
+        compute_zap_to_bond(
+            deps.as_ref(),
+            env.clone(),
+            &config,
+            target.contract,
+            Asset {
+                info: provide_asset.info.clone(),
+                amount,
+            },
+            target.pair_asset,
+            target.pair_asset_b,
+            target.belief_price,
+            target.belief_price_b,
+            Some(max_spread),
+            target.compound_rate,
+            target.asset_token,
+            Some(staker_addr.clone()),
+            false,
+            target.swap_hints,
+            None,
+            &mut messages,
+        )?;
+    }
 
     Ok(Response::new()
         .add_messages(messages)
         .add_attributes(vec![
-            attr("action", "zap_to_bond"),
-            attr("asset_token_a", pair_asset_a.to_string()),
-            attr("asset_token_b", pair_asset_b.unwrap_or_else(|| provide_asset.info.clone()).to_string()),
+            attr("action", "zap_to_bond_multi"),
+            attr("provide_asset", provide_asset.info.to_string()),
             attr("provide_amount", provide_asset.amount),
         ]))
 }
 
+fn zap_to_bond_multi_hook(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staker_addr: String,
+    provide_asset: Asset,
+    target: ZapTarget,
+    max_spread: Decimal,
+) -> StdResult<Response> {
+    // only can be called by itself
+    if info.sender != env.contract.address {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    zap_to_bond_internal(
+        deps,
+        env,
+        staker_addr,
+        target.contract,
+        provide_asset,
+        target.pair_asset,
+        target.pair_asset_b,
+        target.belief_price,
+        target.belief_price_b,
+        target.max_spread.unwrap_or(max_spread),
+        target.compound_rate,
+        target.asset_token,
+        target.swap_hints,
+    )
+}
+
+// split amount by target weight, last target takes the remainder
+fn split_by_weight(amount: Uint128, targets: &[ZapTarget]) -> StdResult<Vec<Uint128>> {
+    let total_weight: u32 = targets.iter().map(|target| target.weight).sum();
+    if total_weight == 0 {
+        return Err(StdError::generic_err("total weight must be more than 0"));
+    }
+
+    let mut remaining = amount;
+    let mut amounts: Vec<Uint128> = targets
+        .iter()
+        .take(targets.len() - 1)
+        .map(|target| {
+            let portion = amount.multiply_ratio(target.weight, total_weight);
+            remaining = remaining.checked_sub(portion)?;
+            Ok(portion)
+        })
+        .collect::<StdResult<Vec<Uint128>>>()?;
+    amounts.push(remaining);
+
+    Ok(amounts)
+}
+
 fn do_swap(
     querier: &QuerierWrapper,
+    mut pools: Option<&mut SimulatedPools>,
     swaps: Vec<SwapOperation>,
     offer_amount: Uint128,
     max_spread: Option<Decimal>,
@@ -580,9 +1655,10 @@ fn do_swap(
     let mut prices: Vec<Decimal> = vec![];
     for swap in swaps {
         i += 1;
-        let simulate = simulate(
+        let (return_amount, commission_amount) = simulate_swap(
             querier,
-            Addr::unchecked(swap.pair_contract.clone()),
+            pools.as_deref_mut(),
+            &swap.pair_contract,
             &Asset {
                 info: swap.asset_info.clone(),
                 amount,
@@ -597,10 +1673,10 @@ fn do_swap(
                 if i < len { None } else { to.clone() },
             )?,
         );
-        let swap_price = Decimal::from_ratio(amount, simulate.return_amount + simulate.commission_amount);
+        let swap_price = Decimal::from_ratio(amount, return_amount + commission_amount);
         price = Decimal::from_ratio(price.numerator(), swap_price.inv().unwrap().numerator());
         prices.push(swap_price);
-        amount = simulate.return_amount;
+        amount = return_amount;
     }
 
     Ok((amount, price, prices))
@@ -631,6 +1707,7 @@ fn compute_zap_to_bond(
     staker_addr: Option<String>,
     simulation_mode: bool,
     swap_hints: Option<Vec<SwapOperation>>,
+    mut pools: Option<&mut SimulatedPools>,
     messages: &mut Vec<CosmosMsg>,
 ) -> StdResult<Option<SimulateZapToBondResponse>> {
 
@@ -644,20 +1721,25 @@ fn compute_zap_to_bond(
         // swap ust -> A
         let swaps = match swap_hints {
             None => {
-                let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
-                let asset_pair_a = [provide_asset.info.clone(), pair_asset_a.clone()];
-                let terraswap_pair_a = query_pair_info(&deps.querier, terraswap_factory, &asset_pair_a)?;
+                let mut routes = query_routes(deps, config, &provide_asset.info, &pair_asset_a)?;
+                let route = if routes.len() > 1 {
+                    pick_best_route(&deps.querier, pools.as_deref(), routes, offer_amount)?.0
+                } else {
+                    routes.pop().ok_or_else(|| StdError::generic_err("no route found"))?
+                };
 
-                vec![SwapOperation {
-                    pair_contract: terraswap_pair_a.contract_addr.to_string(),
-                    asset_info: provide_asset.info.clone(),
-                    belief_price: belief_price_a,
-                }]
+                // belief price is only applicable to direct swap
+                let mut swaps = to_swap_operations(route);
+                if swaps.len() == 1 {
+                    swaps[0].belief_price = belief_price_a;
+                }
+                swaps
             },
             Some(swaps) => swaps
         };
         let (return_amount, belief_price, swap_hint_prices) = do_swap(
             &deps.querier,
+            pools.as_deref_mut(),
             swaps,
             offer_amount,
             max_spread,
@@ -690,6 +1772,7 @@ fn compute_zap_to_bond(
             staker_addr,
             simulation_mode,
             None,
+            pools,
             messages,
         )?;
 
@@ -713,11 +1796,15 @@ fn compute_zap_to_bond(
         let asset_pair_a = [provide_asset.info.clone(), pair_asset_a.clone()];
         let terraswap_pair_a = query_pair_info(&deps.querier, terraswap_factory, &asset_pair_a)?;
 
-        let mut pool: PoolResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: terraswap_pair_a.contract_addr.to_string(),
-            msg: to_binary(&PairQueryMsg::Pool {})?,
-        }))?;
-        let swap_amount = get_swap_amount(&pool, &provide_asset, terraswap_pair_a.pair_type.clone());
+        let pair_contract_a = terraswap_pair_a.contract_addr.to_string();
+        let mut pool: PoolResponse = match pools.as_deref().and_then(|pools| pools.get(&pair_contract_a)) {
+            Some(simulated) => simulated.pool.clone(),
+            None => deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: pair_contract_a.clone(),
+                msg: to_binary(&PairQueryMsg::Pool {})?,
+            }))?,
+        };
+        let swap_amount = get_swap_amount(&deps.querier, &pool, &provide_asset, &terraswap_pair_a)?;
         let bond_asset = Asset {
             info: provide_asset.info.clone(),
             amount: provide_asset.amount.checked_sub(swap_amount)?,
@@ -734,15 +1821,18 @@ fn compute_zap_to_bond(
             };
 
             // swap ust -> A
-            let simulate_a = simulate(
-                &deps.querier,
-                terraswap_pair_a.contract_addr.clone(),
-                &swap_asset)?;
-            apply_pool(&mut pool, &swap_asset, simulate_a.return_amount);
-            let price_a = Decimal::from_ratio(swap_asset.amount, simulate_a.return_amount + simulate_a.commission_amount);
+            let (return_amount, commission_amount) = match pools.as_deref().and_then(|pools| pools.get(&pair_contract_a)) {
+                Some(simulated) => simulate_pool(&deps.querier, &pair_contract_a, &simulated.pair_type, &pool, &swap_asset)?,
+                None => {
+                    let simulate_a = simulate(&deps.querier, terraswap_pair_a.contract_addr.clone(), &swap_asset)?;
+                    (simulate_a.return_amount, simulate_a.commission_amount)
+                }
+            };
+            apply_pool(&mut pool, &swap_asset, return_amount);
+            let price_a = Decimal::from_ratio(swap_asset.amount, return_amount + commission_amount);
             let asset_a = Asset {
                 info: pair_asset_a.clone(),
-                amount: simulate_a.return_amount,
+                amount: return_amount,
             };
             let amount_a = safe_deduct_tax(&asset_a, &deps.querier)?;
             messages.push(
@@ -779,6 +1869,21 @@ fn compute_zap_to_bond(
 
         if simulation_mode {
             let lp_amount = calculate_lp(&deps.querier, &pool, &assets, &terraswap_pair_a)?;
+
+            // next targets see the swap and the provided liquidity
+            if let Some(pools) = pools {
+                for asset in assets.iter() {
+                    let pool_asset = pool.assets.iter_mut().find(|it| it.info == asset.info);
+                    if let Some(pool_asset) = pool_asset {
+                        pool_asset.amount += asset.amount;
+                    }
+                }
+                pool.total_share += lp_amount;
+                pools.insert(pair_contract_a, SimulatedPool {
+                    pool,
+                    pair_type: terraswap_pair_a.pair_type.clone(),
+                });
+            }
             Ok(Some(SimulateZapToBondResponse {
                 lp_amount,
                 belief_price: price_a,
@@ -797,7 +1902,7 @@ fn compute_zap_to_bond(
     }
 }
 
-fn calculate_lp(
+pub(crate) fn calculate_lp(
     querier: &QuerierWrapper,
     pool: &PoolResponse,
     assets: &[Asset; 2],
@@ -813,14 +1918,17 @@ fn calculate_lp(
     if let Some(PairType::Stable {}) = pair_info.pair_type {
         let leverage = query_leverage(querier, pair_info.contract_addr.to_string())?;
 
-        let d_before_addition_liquidity =
-            compute_d(leverage, pool_a.u128(), pool_b.u128()).unwrap();
+        let d_before_addition_liquidity = compute_d(leverage, pool_a.u128(), pool_b.u128())
+            .ok_or_else(|| StdError::generic_err("cannot calculate stable swap liquidity"))?;
+        if d_before_addition_liquidity == 0 {
+            return Err(StdError::generic_err("pool is empty"));
+        }
 
         pool_a += assets[0].amount;
         pool_b += assets[1].amount;
 
-        let d_after_addition_liquidity =
-            compute_d(leverage, pool_a.u128(), pool_b.u128()).unwrap();
+        let d_after_addition_liquidity = compute_d(leverage, pool_a.u128(), pool_b.u128())
+            .ok_or_else(|| StdError::generic_err("cannot calculate stable swap liquidity"))?;
 
         Ok(pool.total_share.multiply_ratio(
             d_after_addition_liquidity.saturating_sub(d_before_addition_liquidity),
@@ -835,12 +1943,16 @@ fn calculate_lp(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn update_config(
     deps: DepsMut,
     info: MessageInfo,
     insert_allowlist: Option<Vec<String>>,
     remove_allowlist: Option<Vec<String>>,
     allow_all: Option<bool>,
+    insert_factories: Option<Vec<String>>,
+    remove_factories: Option<Vec<String>>,
+    route_assets: Option<Vec<AssetInfo>>,
 ) -> StdResult<Response> {
     let mut config = read_config(deps.storage)?;
 
@@ -856,7 +1968,9 @@ fn update_config(
 
     if let Some(remove_allowlist) = remove_allowlist {
         for contract in remove_allowlist.iter() {
-            config.allowlist.remove(&deps.api.addr_canonicalize(contract)?);
+            let contract_raw = deps.api.addr_canonicalize(contract)?;
+            config.allowlist.remove(&contract_raw);
+            farm_store(deps.storage).remove(contract_raw.as_slice());
         }
     }
 
@@ -864,11 +1978,90 @@ fn update_config(
         config.allow_all = allow_all;
     }
 
+    if let Some(insert_factories) = insert_factories {
+        for factory in insert_factories.iter() {
+            let factory_raw = deps.api.addr_canonicalize(factory)?;
+            if factory_raw != config.terraswap_factory && !config.factories.contains(&factory_raw) {
+                config.factories.push(factory_raw);
+            }
+        }
+    }
+
+    if let Some(remove_factories) = remove_factories {
+        for factory in remove_factories.iter() {
+            let factory_raw = deps.api.addr_canonicalize(factory)?;
+            config.factories.retain(|it| it != &factory_raw);
+        }
+    }
+
+    if let Some(route_assets) = route_assets {
+        config.route_assets = route_assets;
+    }
+
     config_store(deps.storage).save(&config)?;
 
     Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
 }
 
+fn register_farms(
+    deps: DepsMut,
+    info: MessageInfo,
+    farms: Vec<FarmInfo>,
+) -> StdResult<Response> {
+    let mut config = read_config(deps.storage)?;
+
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    for farm in farms.iter() {
+        let contract_raw = deps.api.addr_canonicalize(&farm.contract)?;
+        farm_store(deps.storage).save(contract_raw.as_slice(), &FarmRecord {
+            farm_type: farm.farm_type.clone(),
+            asset_tokens: farm.asset_tokens.clone(),
+            staking_token: deps.api.addr_canonicalize(&farm.staking_token)?,
+            pair_contract: if let Some(pair_contract) = &farm.pair_contract {
+                Some(deps.api.addr_canonicalize(pair_contract)?)
+            } else {
+                None
+            },
+        })?;
+        config.allowlist.insert(contract_raw);
+    }
+
+    config_store(deps.storage).save(&config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "register_farms"),
+        attr("count", farms.len().to_string()),
+    ]))
+}
+
+fn deregister_farms(
+    deps: DepsMut,
+    info: MessageInfo,
+    contracts: Vec<String>,
+) -> StdResult<Response> {
+    let mut config = read_config(deps.storage)?;
+
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    for contract in contracts.iter() {
+        let contract_raw = deps.api.addr_canonicalize(contract)?;
+        farm_store(deps.storage).remove(contract_raw.as_slice());
+        config.allowlist.remove(&contract_raw);
+    }
+
+    config_store(deps.storage).save(&config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "deregister_farms"),
+        attr("count", contracts.len().to_string()),
+    ]))
+}
+
 fn get_balance(
     deps: &Deps,
     account_addr: Addr,
@@ -898,14 +2091,10 @@ fn zap_to_unbond(
     belief_price_b: Option<Decimal>,
     max_spread: Decimal,
     swap_hints: Option<Vec<SwapOperation>>,
+    min_receive: Option<Uint128>,
 ) -> StdResult<Response> {
     validate_slippage(max_spread)?;
 
-    let denom = match target_asset.clone() {
-        AssetInfo::NativeToken { denom } => denom,
-        _ => return Err(StdError::generic_err("not support target_asset as token")),
-    };
-
     let config = read_config(deps.storage)?;
     let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
     let asset_infos = if let Some(asset_info) = sell_asset_b.clone() {
@@ -919,7 +2108,7 @@ fn zap_to_unbond(
         return Err(StdError::generic_err("invalid lp token"));
     }
 
-    let current_denom_amount = query_balance(&deps.querier, env.contract.address.clone(), denom)?;
+    let current_target_amount = get_balance(&deps.as_ref(), env.contract.address.clone(), target_asset.clone())?;
     let current_token_a_amount = get_balance(&deps.as_ref(), env.contract.address.clone(), sell_asset_a.clone())?;
     let current_token_b_asset = match sell_asset_b {
         Some(sell_asset_b) => {
@@ -947,7 +2136,7 @@ fn zap_to_unbond(
             msg: to_binary(&ExecuteMsg::zap_to_unbond_hook {
                 staker_addr,
                 prev_target_asset: Asset {
-                    amount: current_denom_amount,
+                    amount: current_target_amount,
                     info: target_asset,
                 },
                 prev_asset_a: Asset {
@@ -959,6 +2148,7 @@ fn zap_to_unbond(
                 belief_price_b,
                 max_spread,
                 swap_hints,
+                min_receive,
             })?,
             funds: vec![],
         }),
@@ -978,6 +2168,7 @@ fn zap_to_unbond_hook(
     belief_price_b: Option<Decimal>,
     max_spread: Decimal,
     swap_hints: Option<Vec<SwapOperation>>,
+    min_receive: Option<Uint128>,
 ) -> StdResult<Response> {
     // only can be called by itself
     if info.sender != env.contract.address {
@@ -987,31 +2178,27 @@ fn zap_to_unbond_hook(
     let config = read_config(deps.storage)?;
     let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
     if let Some(prev_asset_b) = prev_asset_b {
-        let asset_token_b = match prev_asset_b.info.clone() {
-            AssetInfo::Token { contract_addr } => contract_addr,
-            _ => return Err(StdError::generic_err("not support sell_asset as native coin")),
-        };
         let asset_infos = [prev_asset_b.info.clone(), prev_asset_a.info.clone()];
         let terraswap_pair = query_pair_info(&deps.querier, terraswap_factory, &asset_infos)?;
-        let current_token_b_amount = query_token_balance(
-            &deps.querier,
-            deps.api.addr_validate(&asset_token_b)?,
-            env.contract.address.clone())?;
+        let current_token_b_amount = get_balance(
+            &deps.as_ref(),
+            env.contract.address.clone(),
+            prev_asset_b.info.clone(),
+        )?;
+        let swap_amount = safe_deduct_tax(&Asset {
+            info: prev_asset_b.info.clone(),
+            amount: current_token_b_amount.checked_sub(prev_asset_b.amount)?,
+        }, &deps.querier)?;
 
         Ok(Response::new().add_messages(vec![
-            CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: asset_token_b,
-                msg: to_binary(&Cw20ExecuteMsg::Send {
-                    contract: terraswap_pair.contract_addr.to_string(),
-                    amount: current_token_b_amount.checked_sub(prev_asset_b.amount)?,
-                    msg: to_binary(&PairCw20HookMsg::Swap {
-                        to: None,
-                        belief_price: belief_price_b,
-                        max_spread: Some(max_spread),
-                    })?,
-                })?,
-                funds: vec![],
-            }),
+            create_swap_msg(
+                prev_asset_b.info,
+                terraswap_pair.contract_addr.to_string(),
+                swap_amount,
+                belief_price_b,
+                Some(max_spread),
+                None,
+            )?,
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: env.contract.address.to_string(),
                 msg: to_binary(&ExecuteMsg::zap_to_unbond_hook {
@@ -1023,41 +2210,19 @@ fn zap_to_unbond_hook(
                     belief_price_b: None,
                     max_spread,
                     swap_hints,
+                    min_receive,
                 })?,
                 funds: vec![],
             }),
         ]))
     } else {
-        let denom = match prev_target_asset.info.clone() {
-            AssetInfo::NativeToken { denom } => denom,
-            _ => return Err(StdError::generic_err("not support target_asset as token")),
-        };
         let current_token_a_amount = get_balance(
             &deps.as_ref(),
             env.contract.address.clone(),
             prev_asset_a.info.clone(),
         )?;
-        let current_denom_amount = deps
-            .querier
-            .query_balance(env.contract.address.to_string(), denom.clone())?
-            .amount;
-
-        let transfer_asset = Asset {
-            info: prev_target_asset.info.clone(),
-            amount: current_denom_amount.checked_sub(prev_target_asset.amount)?,
-        };
-        let mut messages: Vec<CosmosMsg> = vec![];
-        if !transfer_asset.amount.is_zero() {
-            let after_tax = safe_deduct_tax(&transfer_asset, &deps.querier)?;
-            messages.push(CosmosMsg::Bank(BankMsg::Send {
-                to_address: staker_addr.clone(),
-                amount: vec![Coin {
-                    denom,
-                    amount: after_tax,
-                }],
-            }));
-        }
 
+        let mut messages: Vec<CosmosMsg> = vec![];
         let offer_amount = safe_deduct_tax(&Asset {
             info: prev_asset_a.info.clone(),
             amount: current_token_a_amount.checked_sub(prev_asset_a.amount)?
@@ -1065,7 +2230,7 @@ fn zap_to_unbond_hook(
 
         let swaps = match swap_hints {
             None => {
-                let asset_infos = [prev_target_asset.info, prev_asset_a.info.clone()];
+                let asset_infos = [prev_target_asset.info.clone(), prev_asset_a.info.clone()];
                 let terraswap_pair = query_pair_info(&deps.querier, terraswap_factory, &asset_infos)?;
                 vec![SwapOperation {
                     pair_contract: terraswap_pair.contract_addr.to_string(),
@@ -1077,21 +2242,117 @@ fn zap_to_unbond_hook(
         };
         do_swap(
             &deps.querier,
+            None,
             swaps,
             offer_amount,
             Some(max_spread),
-            Some(staker_addr),
+            None,
             &mut messages,
         )?;
 
+        // min_receive is checked against actual balance after swaps
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_binary(&ExecuteMsg::zap_to_unbond_transfer_hook {
+                staker_addr,
+                prev_target_asset,
+                min_receive,
+            })?,
+            funds: vec![],
+        }));
+
         Ok(Response::new().add_messages(messages))
     }
 }
 
+fn zap_to_unbond_transfer_hook(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staker_addr: String,
+    prev_target_asset: Asset,
+    min_receive: Option<Uint128>,
+) -> StdResult<Response> {
+    // only can be called by itself
+    if info.sender != env.contract.address {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let current_target_amount = get_balance(
+        &deps.as_ref(),
+        env.contract.address.clone(),
+        prev_target_asset.info.clone(),
+    )?;
+    let transfer_asset = Asset {
+        info: prev_target_asset.info,
+        amount: current_target_amount.checked_sub(prev_target_asset.amount)?,
+    };
+    let receive_amount = safe_deduct_tax(&transfer_asset, &deps.querier)?;
+
+    if let Some(min_receive) = min_receive {
+        if receive_amount < min_receive {
+            return Err(StdError::generic_err("receive amount is less than min_receive"));
+        }
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !receive_amount.is_zero() {
+        messages.push(match transfer_asset.info {
+            AssetInfo::NativeToken { denom } => CosmosMsg::Bank(BankMsg::Send {
+                to_address: staker_addr.clone(),
+                amount: vec![Coin {
+                    denom,
+                    amount: receive_amount,
+                }],
+            }),
+            AssetInfo::Token { contract_addr } => CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: staker_addr.clone(),
+                    amount: receive_amount,
+                })?,
+                funds: vec![],
+            }),
+        });
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "zap_to_unbond"),
+            attr("staker_addr", staker_addr),
+            attr("receive_amount", receive_amount),
+        ]))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+    // only schedules are dispatched as submessages, reply id is schedule id
+    if msg.id == 0 || msg.id > read_schedule_count(deps.storage)? {
+        return Err(StdError::generic_err("unknown reply id"));
+    }
+    reply_schedule(deps, env, msg)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::config {} => to_binary(&query_config(deps)?),
+        QueryMsg::is_nonce_used { owner, nonce } => to_binary(&query_is_nonce_used(deps, owner, nonce)?),
+        QueryMsg::schedule { id } => to_binary(&query_schedule(deps, id)?),
+        QueryMsg::schedules {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&query_schedules(deps, owner, start_after, limit)?),
+        QueryMsg::limit_order { id } => to_binary(&query_limit_order(deps, id)?),
+        QueryMsg::limit_orders {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&query_limit_orders(deps, owner, start_after, limit)?),
+        QueryMsg::farm { contract } => to_binary(&query_farm(deps, contract)?),
+        QueryMsg::farms { start_after, limit } => to_binary(&query_farms(deps, start_after, limit)?),
         QueryMsg::simulate_zap_to_bond {
             provide_asset,
             pair_asset,
@@ -1105,6 +2366,14 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             pair_asset_b,
             swap_hints,
         )?),
+        QueryMsg::simulate_zap_to_bond_multi {
+            provide_asset,
+            targets,
+        } => to_binary(&simulate_zap_to_bond_multi(deps, env, provide_asset, targets)?),
+        QueryMsg::simulate_best_route {
+            offer_asset,
+            ask_asset,
+        } => to_binary(&simulate_best_route(deps, offer_asset, ask_asset)?),
     }
 }
 
@@ -1122,11 +2391,60 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigInfo> {
             .map(|w| deps.api.addr_humanize(&w).map(|addr| addr.to_string()))
             .collect::<StdResult<Vec<String>>>()?,
         allow_all: config.allow_all,
+        factories: config
+            .factories
+            .iter()
+            .map(|w| deps.api.addr_humanize(w).map(|addr| addr.to_string()))
+            .collect::<StdResult<Vec<String>>>()?,
+        route_assets: config.route_assets,
     };
 
     Ok(resp)
 }
 
+fn query_is_nonce_used(deps: Deps, owner: String, nonce: u64) -> StdResult<bool> {
+    let owner_raw = deps.api.addr_canonicalize(&owner)?;
+    Ok(nonce_read(deps.storage, &owner_raw).may_load(&nonce.to_be_bytes())?.is_some())
+}
+
+fn to_farm_info(deps: Deps, contract: CanonicalAddr, farm: FarmRecord) -> StdResult<FarmInfo> {
+    Ok(FarmInfo {
+        contract: deps.api.addr_humanize(&contract)?.to_string(),
+        farm_type: farm.farm_type,
+        asset_tokens: farm.asset_tokens,
+        staking_token: deps.api.addr_humanize(&farm.staking_token)?.to_string(),
+        pair_contract: if let Some(pair_contract) = farm.pair_contract {
+            Some(deps.api.addr_humanize(&pair_contract)?.to_string())
+        } else {
+            None
+        },
+    })
+}
+
+fn query_farm(deps: Deps, contract: String) -> StdResult<FarmInfo> {
+    let contract_raw = deps.api.addr_canonicalize(&contract)?;
+    let farm = farm_read(deps.storage).load(contract_raw.as_slice())?;
+    to_farm_info(deps, contract_raw, farm)
+}
+
+fn query_farms(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<FarmsResponse> {
+    let start_after = if let Some(start_after) = start_after {
+        Some(deps.api.addr_canonicalize(&start_after)?)
+    } else {
+        None
+    };
+    let farms = read_farms(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|(contract, farm)| to_farm_info(deps, contract, farm))
+        .collect::<StdResult<Vec<FarmInfo>>>()?;
+
+    Ok(FarmsResponse { farms })
+}
+
 fn simulate_zap_to_bond(
     deps: Deps,
     env: Env,
@@ -1154,12 +2472,79 @@ fn simulate_zap_to_bond(
         None,
         true,
         swap_hints,
+        None,
         &mut messages,
     )?;
 
     Ok(res.unwrap())
 }
 
+fn simulate_zap_to_bond_multi(
+    deps: Deps,
+    env: Env,
+    provide_asset: Asset,
+    targets: Vec<ZapTarget>,
+) -> StdResult<SimulateZapToBondMultiResponse> {
+    let config = read_config(deps.storage)?;
+    let amounts = split_by_weight(provide_asset.amount, &targets)?;
+
+    // targets are executed in order, so each target is simulated after swaps and provides of previous targets
+    let mut pools = SimulatedPools::new();
+    let results = targets
+        .into_iter()
+        .zip(amounts)
+        .map(|(target, amount)| {
+            let mut messages: Vec<CosmosMsg> = vec![];
+            let res = compute_zap_to_bond(
+                deps,
+                env.clone(),
+                &config,
+                target.contract.clone(),
+                Asset {
+                    info: provide_asset.info.clone(),
+                    amount,
+                },
+                target.pair_asset,
+                target.pair_asset_b,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                true,
+                target.swap_hints,
+                Some(&mut pools),
+                &mut messages,
+            )?;
+            Ok(SimulateZapToBondMultiItem {
+                contract: target.contract,
+                asset_token: target.asset_token,
+                provide_amount: amount,
+                simulation: res.unwrap(),
+            })
+        })
+        .collect::<StdResult<Vec<SimulateZapToBondMultiItem>>>()?;
+
+    Ok(SimulateZapToBondMultiResponse { results })
+}
+
+fn simulate_best_route(
+    deps: Deps,
+    offer_asset: Asset,
+    ask_asset: AssetInfo,
+) -> StdResult<SimulateBestRouteResponse> {
+    let config = read_config(deps.storage)?;
+
+    let routes = query_routes(deps, &config, &offer_asset.info, &ask_asset)?;
+    let (route, return_amount) = pick_best_route(&deps.querier, None, routes, offer_asset.amount)?;
+
+    Ok(SimulateBestRouteResponse {
+        return_amount,
+        swap_operations: to_swap_operations(route),
+    })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
     Ok(Response::default())
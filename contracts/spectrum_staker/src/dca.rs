@@ -0,0 +1,338 @@
+use cosmwasm_std::{attr, to_binary, BankMsg, Coin, ContractResult, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult, SubMsg, Uint128, WasmMsg};
+use spectrum_protocol::farm_helper::deduct_tax;
+use spectrum_protocol::staker::{ExecuteMsg, ScheduleInfo, SchedulesResponse};
+use terraswap::asset::{Asset, AssetInfo};
+
+use crate::contract::{validate_contract, zap_to_bond_internal};
+use crate::state::{read_config, read_schedule_count, read_schedules, schedule_count_store, schedule_owner_store, schedule_read, schedule_store, Schedule};
+
+// schedules run unattended over a long time, so spread is capped tighter than zap_to_bond
+const MAX_SCHEDULE_SPREAD: u64 = 5u64;
+
+fn get_sent_amount(info: &MessageInfo, denom: Option<&str>) -> StdResult<Coin> {
+    if info.funds.len() != 1 {
+        return Err(StdError::generic_err("must send exactly one native token"));
+    }
+    let coin = info.funds[0].clone();
+    if let Some(denom) = denom {
+        if coin.denom != denom {
+            return Err(StdError::generic_err("denom not matched"));
+        }
+    }
+    if coin.amount.is_zero() {
+        return Err(StdError::generic_err("amount must be more than 0"));
+    }
+    Ok(coin)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_schedule(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: String,
+    pair_asset: AssetInfo,
+    pair_asset_b: Option<AssetInfo>,
+    asset_token: Option<String>,
+    amount_per_period: Uint128,
+    keeper_fee: Uint128,
+    period: u64,
+    start_time: Option<u64>,
+    compound_rate: Option<Decimal>,
+    belief_price: Decimal,
+    belief_price_b: Option<Decimal>,
+    max_spread: Decimal,
+) -> StdResult<Response> {
+    if max_spread > Decimal::percent(MAX_SCHEDULE_SPREAD) {
+        return Err(StdError::generic_err("max_spread must be 0 to 0.05"));
+    }
+    if belief_price.is_zero() || belief_price_b.map(|it| it.is_zero()).unwrap_or(false) {
+        return Err(StdError::generic_err("belief_price must be more than 0"));
+    }
+    if pair_asset_b.is_some() != belief_price_b.is_some() {
+        return Err(StdError::generic_err("belief_price_b must be set only with pair_asset_b"));
+    }
+
+    let config = read_config(deps.storage)?;
+    let contract_raw = deps.api.addr_canonicalize(&contract)?;
+    validate_contract(contract_raw.clone(), &config)?;
+
+    if period == 0 {
+        return Err(StdError::generic_err("period must be more than 0"));
+    }
+    if keeper_fee >= amount_per_period {
+        return Err(StdError::generic_err("keeper_fee must be less than amount_per_period"));
+    }
+    let now = env.block.time.seconds();
+    if let Some(start_time) = start_time {
+        if start_time < now {
+            return Err(StdError::generic_err("start_time must not be in the past"));
+        }
+    }
+
+    let coin = get_sent_amount(&info, None)?;
+    let owner_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let id = read_schedule_count(deps.storage)? + 1;
+    schedule_count_store(deps.storage).save(&id)?;
+
+    let schedule = Schedule {
+        owner: owner_raw.clone(),
+        contract: contract_raw,
+        pair_asset,
+        pair_asset_b,
+        asset_token,
+        denom: coin.denom,
+        amount_per_period,
+        keeper_fee,
+        period,
+        next_execution: start_time.unwrap_or(now),
+        compound_rate,
+        belief_price,
+        belief_price_b,
+        max_spread,
+        balance: coin.amount,
+    };
+    schedule_store(deps.storage).save(&id.to_be_bytes(), &schedule)?;
+    schedule_owner_store(deps.storage, &owner_raw).save(&id.to_be_bytes(), &true)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "create_schedule"),
+        attr("id", id.to_string()),
+        attr("owner", info.sender),
+        attr("balance", schedule.balance),
+    ]))
+}
+
+pub fn deposit_schedule(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> StdResult<Response> {
+    let mut schedule = schedule_read(deps.storage).load(&id.to_be_bytes())?;
+    let coin = get_sent_amount(&info, Some(&schedule.denom))?;
+
+    schedule.balance += coin.amount;
+    schedule_store(deps.storage).save(&id.to_be_bytes(), &schedule)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "deposit_schedule"),
+        attr("id", id.to_string()),
+        attr("amount", coin.amount),
+    ]))
+}
+
+pub fn cancel_schedule(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> StdResult<Response> {
+    let schedule = schedule_read(deps.storage).load(&id.to_be_bytes())?;
+    let owner_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if schedule.owner != owner_raw {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    schedule_store(deps.storage).remove(&id.to_be_bytes());
+    schedule_owner_store(deps.storage, &owner_raw).remove(&id.to_be_bytes());
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !schedule.balance.is_zero() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin {
+                denom: schedule.denom.clone(),
+                amount: deduct_tax(&deps.querier, schedule.balance, schedule.denom)?,
+            }],
+        }));
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "cancel_schedule"),
+            attr("id", id.to_string()),
+            attr("refund_amount", schedule.balance),
+        ]))
+}
+
+fn is_due(schedule: &Schedule, now: u64) -> bool {
+    schedule.next_execution <= now && schedule.balance >= schedule.amount_per_period
+}
+
+pub fn execute_schedules(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ids: Vec<u64>,
+) -> StdResult<Response> {
+    let now = env.block.time.seconds();
+    let mut sub_messages: Vec<SubMsg> = vec![];
+    let mut executed: Vec<String> = vec![];
+    for id in ids {
+        // skip unknown or cancelled schedules, and schedules not due or out of balance,
+        // so keeper can execute in batch
+        let schedule = match schedule_read(deps.storage).may_load(&id.to_be_bytes())? {
+            Some(schedule) => schedule,
+            None => continue,
+        };
+        if !is_due(&schedule, now) || executed.contains(&id.to_string()) {
+            continue;
+        }
+
+        // each schedule is a submessage, so it simulates after the swaps of previous schedules,
+        // and a failed schedule is reverted without failing the others
+        sub_messages.push(SubMsg::reply_on_error(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::execute_schedule_hook {
+                    id,
+                    keeper: info.sender.to_string(),
+                })?,
+                funds: vec![],
+            }),
+            id,
+        ));
+        executed.push(id.to_string());
+    }
+
+    if executed.is_empty() {
+        return Err(StdError::generic_err("no schedule to execute"));
+    }
+
+    Ok(Response::new()
+        .add_submessages(sub_messages)
+        .add_attributes(vec![
+            attr("action", "execute_schedules"),
+            attr("ids", executed.join(",")),
+            attr("keeper", info.sender),
+        ]))
+}
+
+pub fn execute_schedule_hook(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    keeper: String,
+) -> StdResult<Response> {
+    // only can be called by itself
+    if info.sender != env.contract.address {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let now = env.block.time.seconds();
+    let mut schedule = schedule_read(deps.storage).load(&id.to_be_bytes())?;
+    if !is_due(&schedule, now) {
+        return Err(StdError::generic_err("schedule is not due"));
+    }
+
+    // skip missed periods, so late execution does not run several periods at once
+    let missed_periods = (now - schedule.next_execution) / schedule.period;
+    schedule.balance = schedule.balance.checked_sub(schedule.amount_per_period)?;
+    schedule.next_execution += (missed_periods + 1) * schedule.period;
+    schedule_store(deps.storage).save(&id.to_be_bytes(), &schedule)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !schedule.keeper_fee.is_zero() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: keeper,
+            amount: vec![Coin {
+                denom: schedule.denom.clone(),
+                amount: deduct_tax(&deps.querier, schedule.keeper_fee, schedule.denom.clone())?,
+            }],
+        }));
+    }
+
+    let owner = deps.api.addr_humanize(&schedule.owner)?.to_string();
+    let contract = deps.api.addr_humanize(&schedule.contract)?.to_string();
+    let response = zap_to_bond_internal(
+        deps,
+        env,
+        owner,
+        contract,
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: schedule.denom.clone(),
+            },
+            amount: schedule.amount_per_period.checked_sub(schedule.keeper_fee)?,
+        },
+        schedule.pair_asset,
+        schedule.pair_asset_b,
+        Some(schedule.belief_price),
+        schedule.belief_price_b,
+        schedule.max_spread,
+        schedule.compound_rate,
+        schedule.asset_token,
+        None,
+    )?;
+    messages.extend(response.messages.into_iter().map(|it| it.msg));
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "execute_schedule"),
+            attr("id", id.to_string()),
+        ]))
+}
+
+pub fn reply_schedule(
+    _deps: DepsMut,
+    _env: Env,
+    msg: Reply,
+) -> StdResult<Response> {
+    // schedule state is reverted, it can be executed again by next keeper
+    let error = match msg.result {
+        ContractResult::Err(error) => error,
+        ContractResult::Ok(_) => return Err(StdError::generic_err("unexpected reply")),
+    };
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "execute_schedule_failed"),
+        attr("id", msg.id.to_string()),
+        attr("error", error),
+    ]))
+}
+
+fn to_schedule_info(deps: Deps, id: u64, schedule: Schedule) -> StdResult<ScheduleInfo> {
+    Ok(ScheduleInfo {
+        id,
+        owner: deps.api.addr_humanize(&schedule.owner)?.to_string(),
+        contract: deps.api.addr_humanize(&schedule.contract)?.to_string(),
+        pair_asset: schedule.pair_asset,
+        pair_asset_b: schedule.pair_asset_b,
+        asset_token: schedule.asset_token,
+        denom: schedule.denom,
+        amount_per_period: schedule.amount_per_period,
+        keeper_fee: schedule.keeper_fee,
+        period: schedule.period,
+        next_execution: schedule.next_execution,
+        compound_rate: schedule.compound_rate,
+        belief_price: schedule.belief_price,
+        belief_price_b: schedule.belief_price_b,
+        max_spread: schedule.max_spread,
+        balance: schedule.balance,
+    })
+}
+
+pub fn query_schedule(deps: Deps, id: u64) -> StdResult<ScheduleInfo> {
+    let schedule = schedule_read(deps.storage).load(&id.to_be_bytes())?;
+    to_schedule_info(deps, id, schedule)
+}
+
+pub fn query_schedules(
+    deps: Deps,
+    owner: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<SchedulesResponse> {
+    let owner = if let Some(owner) = owner {
+        Some(deps.api.addr_canonicalize(&owner)?)
+    } else {
+        None
+    };
+    let schedules = read_schedules(deps.storage, owner, start_after, limit)?
+        .into_iter()
+        .map(|(id, schedule)| to_schedule_info(deps, id, schedule))
+        .collect::<StdResult<Vec<ScheduleInfo>>>()?;
+
+    Ok(SchedulesResponse { schedules })
+}
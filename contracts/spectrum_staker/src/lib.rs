@@ -1,4 +1,6 @@
 pub mod contract;
+pub mod dca;
+pub mod order;
 pub mod state;
 pub mod math;
 pub mod querier;
@@ -1,5 +1,6 @@
 #![allow(clippy::assign_op_pattern)]
 #![allow(clippy::ptr_offset_with_cast)]
+#![allow(clippy::manual_div_ceil)]
 
 use std::convert::TryFrom;
 use uint::construct_uint;
@@ -36,7 +37,7 @@ pub fn calc_ask_amount(
     let leverage = amp.checked_mul(u64::from(N_COINS)).unwrap();
     let new_offer_pool = offer_pool.checked_add(offer_amount)?;
 
-    let d = compute_d(leverage, offer_pool, ask_pool).unwrap();
+    let d = compute_d(leverage, offer_pool, ask_pool)?;
 
     let new_ask_pool = compute_new_balance(leverage, new_offer_pool, d)?;
 
@@ -63,7 +64,7 @@ pub fn calc_offer_amount(
     let leverage = amp.checked_mul(u64::from(N_COINS)).unwrap();
     let new_ask_pool = ask_pool.checked_sub(ask_amount)?;
 
-    let d = compute_d(leverage, offer_pool, ask_pool).unwrap();
+    let d = compute_d(leverage, offer_pool, ask_pool)?;
 
     let new_offer_pool = compute_new_balance(leverage, new_ask_pool, d)?;
 
@@ -11,6 +11,7 @@ use terraswap::pair::{PoolResponse, SimulationResponse};
 
 use spectrum_protocol::gov::BalanceResponse as SpecBalanceResponse;
 use crate::contract::PairInfo;
+use crate::querier::{ConfigResponse, StablePoolConfig};
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
 /// this uses our CustomQuerier.
@@ -101,6 +102,8 @@ pub struct TerraswapFactoryQuerier {
 #[derive(Clone, Default)]
 pub struct TerraswapPairQuerier {
     pools: HashMap<String, PoolResponse>,
+    commission_rates: HashMap<String, Decimal>,
+    pair_infos: HashMap<String, PairInfo>,
 }
 
 impl TerraswapFactoryQuerier {
@@ -115,6 +118,8 @@ impl TerraswapPairQuerier {
     pub fn new(pools: &[(&String, &PoolResponse)]) -> Self {
         TerraswapPairQuerier {
             pools: pools_to_map(pools),
+            commission_rates: HashMap::new(),
+            pair_infos: HashMap::new(),
         }
     }
 }
@@ -158,12 +163,14 @@ enum MockQueryMsg {
         address: String,
     },
     Pair {
-        asset_infos: [AssetInfo; 2],
+        #[serde(default)]
+        asset_infos: Option<[AssetInfo; 2]>,
     },
     Simulation {
         offer_asset: Asset,
     },
     Pool {},
+    Config {},
 }
 
 impl WasmMockQuerier {
@@ -212,8 +219,23 @@ impl WasmMockQuerier {
                             pools: vec![],
                         })))
                     },
-                    MockQueryMsg::Pair { asset_infos } => {
+                    MockQueryMsg::Pair { asset_infos: None } => {
+                        // pair query to pair contract
+                        match self.terraswap_pair_querier.pair_infos.get(contract_addr) {
+                            Some(v) => SystemResult::Ok(ContractResult::from(to_binary(&v))),
+                            None => SystemResult::Err(SystemError::InvalidRequest {
+                                error: "No pair info exists".to_string(),
+                                request: msg.as_slice().into(),
+                            }),
+                        }
+                    },
+                    MockQueryMsg::Pair { asset_infos: Some(asset_infos) } => {
+                        // pair registered for a specific factory, then pair for all factories
+                        let factory_key = contract_addr.to_string() + asset_infos[0].to_string().as_str() + asset_infos[1].to_string().as_str();
                         let key = asset_infos[0].to_string() + asset_infos[1].to_string().as_str();
+                        if let Some(v) = self.terraswap_factory_querier.pairs.get(&factory_key) {
+                            return SystemResult::Ok(ContractResult::from(to_binary(&v)));
+                        }
                         match self.terraswap_factory_querier.pairs.get(&key) {
                             Some(v) => SystemResult::Ok(ContractResult::from(to_binary(&v))),
                             None => {
@@ -229,7 +251,11 @@ impl WasmMockQuerier {
                         }
                     },
                     MockQueryMsg::Simulation { offer_asset } => {
-                        let commission_amount = offer_asset.amount.multiply_ratio(3u128, 1000u128);
+                        let commission_rate = self.terraswap_pair_querier.commission_rates
+                            .get(contract_addr)
+                            .copied()
+                            .unwrap_or_else(|| Decimal::permille(3));
+                        let commission_amount = offer_asset.amount * commission_rate;
                         let return_amount = offer_asset.amount.checked_sub(commission_amount);
                         match return_amount {
                             Ok(amount) => SystemResult::Ok(ContractResult::from(to_binary(
@@ -251,6 +277,14 @@ impl WasmMockQuerier {
                             }),
                         }
                     },
+                    MockQueryMsg::Config {} => {
+                        SystemResult::Ok(ContractResult::from(to_binary(&ConfigResponse {
+                            block_time_last: 0u64,
+                            params: Some(to_binary(&StablePoolConfig {
+                                amp: Decimal::from_ratio(100u128, 1u128),
+                            }).unwrap()),
+                        })))
+                    },
                 }
             }
             _ => self.base.handle_query(request),
@@ -306,4 +340,23 @@ impl WasmMockQuerier {
     pub fn with_terraswap_pairs(&mut self, pools: &[(&String, &PoolResponse)]) {
         self.terraswap_pair_querier = TerraswapPairQuerier::new(pools);
     }
+
+    // update pool of a pair, other pairs are kept
+    pub fn with_terraswap_pool(&mut self, pair: &str, pool: PoolResponse) {
+        self.terraswap_pair_querier.pools.insert(pair.to_string(), pool);
+    }
+
+    // configure pair info returned by pair contract
+    pub fn with_pair_infos(&mut self, pair_infos: &[&PairInfo]) {
+        for pair_info in pair_infos.iter() {
+            self.terraswap_pair_querier.pair_infos.insert(pair_info.contract_addr.to_string(), (*pair_info).clone());
+        }
+    }
+
+    // configure commission rate of pair simulation, default to 0.3%
+    pub fn with_commission_rates(&mut self, commission_rates: &[(&String, &Decimal)]) {
+        for (pair, rate) in commission_rates.iter() {
+            self.terraswap_pair_querier.commission_rates.insert(pair.to_string(), **rate);
+        }
+    }
 }
@@ -0,0 +1,207 @@
+use cosmwasm_std::{attr, to_binary, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, QueryRequest, Response, StdError, StdResult, Uint128, WasmQuery};
+use spectrum_protocol::staker::{LimitOrderInfo, LimitOrdersResponse};
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::pair::{PoolResponse, QueryMsg as PairQueryMsg};
+
+use crate::contract::{query_pair_info, validate_contract, validate_slippage, zap_to_bond_internal};
+use crate::state::{limit_order_count_store, limit_order_owner_store, limit_order_read, limit_order_store, read_config, read_limit_order_count, read_limit_orders, LimitOrder};
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_limit_order(
+    deps: DepsMut,
+    owner: String,
+    contract: String,
+    provide_asset: Asset,
+    pair_asset: AssetInfo,
+    asset_token: Option<String>,
+    limit_price: Decimal,
+    keeper_fee: Uint128,
+    compound_rate: Option<Decimal>,
+    max_spread: Decimal,
+) -> StdResult<Response> {
+    validate_slippage(max_spread)?;
+
+    let config = read_config(deps.storage)?;
+    let contract_raw = deps.api.addr_canonicalize(&contract)?;
+    validate_contract(contract_raw.clone(), &config)?;
+
+    if limit_price.is_zero() {
+        return Err(StdError::generic_err("limit_price must be more than 0"));
+    }
+    if keeper_fee >= provide_asset.amount {
+        return Err(StdError::generic_err("keeper_fee must be less than provide amount"));
+    }
+
+    let owner_raw = deps.api.addr_canonicalize(&owner)?;
+    let id = read_limit_order_count(deps.storage)? + 1;
+    limit_order_count_store(deps.storage).save(&id)?;
+
+    let order = LimitOrder {
+        owner: owner_raw.clone(),
+        contract: contract_raw,
+        provide_asset,
+        pair_asset,
+        asset_token,
+        limit_price,
+        keeper_fee,
+        compound_rate,
+        max_spread,
+    };
+    limit_order_store(deps.storage).save(&id.to_be_bytes(), &order)?;
+    limit_order_owner_store(deps.storage, &owner_raw).save(&id.to_be_bytes(), &true)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "create_limit_order"),
+        attr("id", id.to_string()),
+        attr("owner", owner),
+        attr("provide_amount", order.provide_asset.amount),
+        attr("limit_price", order.limit_price.to_string()),
+    ]))
+}
+
+fn remove_limit_order(deps: DepsMut, id: u64, order: &LimitOrder) {
+    limit_order_store(deps.storage).remove(&id.to_be_bytes());
+    limit_order_owner_store(deps.storage, &order.owner).remove(&id.to_be_bytes());
+}
+
+pub fn cancel_limit_order(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> StdResult<Response> {
+    let order = limit_order_read(deps.storage).load(&id.to_be_bytes())?;
+    if order.owner != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let refund = order.provide_asset.clone().into_msg(&deps.querier, info.sender)?;
+    remove_limit_order(deps, id, &order);
+
+    Ok(Response::new()
+        .add_message(refund)
+        .add_attributes(vec![
+            attr("action", "cancel_limit_order"),
+            attr("id", id.to_string()),
+            attr("refund_amount", order.provide_asset.amount),
+        ]))
+}
+
+// price of pair_asset in provide_asset, same unit as belief_price
+fn query_limit_order_price(deps: Deps, order: &LimitOrder) -> StdResult<Decimal> {
+    let config = read_config(deps.storage)?;
+    let terraswap_factory = deps.api.addr_humanize(&config.terraswap_factory)?;
+    let pair_info = query_pair_info(
+        &deps.querier,
+        terraswap_factory,
+        &[order.provide_asset.info.clone(), order.pair_asset.clone()],
+    )?;
+    let pool: PoolResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: pair_info.contract_addr.to_string(),
+        msg: to_binary(&PairQueryMsg::Pool {})?,
+    }))?;
+
+    let (offer_pool, ask_pool) = if pool.assets[0].info == order.provide_asset.info {
+        (pool.assets[0].amount, pool.assets[1].amount)
+    } else {
+        (pool.assets[1].amount, pool.assets[0].amount)
+    };
+    if ask_pool.is_zero() {
+        return Err(StdError::generic_err("pool is empty"));
+    }
+
+    Ok(Decimal::from_ratio(offer_pool, ask_pool))
+}
+
+pub fn execute_limit_order(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> StdResult<Response> {
+    let order = limit_order_read(deps.storage).load(&id.to_be_bytes())?;
+
+    let price = query_limit_order_price(deps.as_ref(), &order)?;
+    if price > order.limit_price {
+        return Err(StdError::generic_err("limit price not reached"));
+    }
+
+    remove_limit_order(deps.branch(), id, &order);
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !order.keeper_fee.is_zero() {
+        messages.push(Asset {
+            info: order.provide_asset.info.clone(),
+            amount: order.keeper_fee,
+        }.into_msg(&deps.querier, info.sender.clone())?);
+    }
+
+    let owner = deps.api.addr_humanize(&order.owner)?.to_string();
+    let contract = deps.api.addr_humanize(&order.contract)?.to_string();
+    let response = zap_to_bond_internal(
+        deps,
+        env,
+        owner,
+        contract,
+        Asset {
+            info: order.provide_asset.info.clone(),
+            amount: order.provide_asset.amount.checked_sub(order.keeper_fee)?,
+        },
+        order.pair_asset,
+        None,
+        Some(order.limit_price),
+        None,
+        order.max_spread,
+        order.compound_rate,
+        order.asset_token,
+        None,
+    )?;
+    messages.extend(response.messages.into_iter().map(|it| it.msg));
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "execute_limit_order"),
+            attr("id", id.to_string()),
+            attr("price", price.to_string()),
+            attr("keeper", info.sender),
+        ]))
+}
+
+fn to_limit_order_info(deps: Deps, id: u64, order: LimitOrder) -> StdResult<LimitOrderInfo> {
+    Ok(LimitOrderInfo {
+        id,
+        owner: deps.api.addr_humanize(&order.owner)?.to_string(),
+        contract: deps.api.addr_humanize(&order.contract)?.to_string(),
+        provide_asset: order.provide_asset,
+        pair_asset: order.pair_asset,
+        asset_token: order.asset_token,
+        limit_price: order.limit_price,
+        keeper_fee: order.keeper_fee,
+        compound_rate: order.compound_rate,
+        max_spread: order.max_spread,
+    })
+}
+
+pub fn query_limit_order(deps: Deps, id: u64) -> StdResult<LimitOrderInfo> {
+    let order = limit_order_read(deps.storage).load(&id.to_be_bytes())?;
+    to_limit_order_info(deps, id, order)
+}
+
+pub fn query_limit_orders(
+    deps: Deps,
+    owner: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<LimitOrdersResponse> {
+    let owner = if let Some(owner) = owner {
+        Some(deps.api.addr_canonicalize(&owner)?)
+    } else {
+        None
+    };
+    let orders = read_limit_orders(deps.storage, owner, start_after, limit)?
+        .into_iter()
+        .map(|(id, order)| to_limit_order_info(deps, id, order))
+        .collect::<StdResult<Vec<LimitOrderInfo>>>()?;
+
+    Ok(LimitOrdersResponse { orders })
+}
@@ -36,10 +36,11 @@ pub fn query_leverage(
         contract_addr,
         msg: to_binary(&QueryMsg::Config {})?,
     }))?;
-    let config: StablePoolConfig = from_binary(&res.params.unwrap())?;
+    let params = res.params.ok_or_else(|| StdError::generic_err("stable pool params not found"))?;
+    let config: StablePoolConfig = from_binary(&params)?;
     let leverage = u64::try_from((config.amp * Uint128::from(AMP_PRECISION)).u128())
         .map_err(|_| StdError::generic_err("overflow"))?
         .checked_mul(u64::from(N_COINS))
-        .unwrap();
+        .ok_or_else(|| StdError::generic_err("overflow"))?;
     Ok(leverage)
 }
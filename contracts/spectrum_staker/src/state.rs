@@ -3,8 +3,10 @@ use std::collections::HashSet;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{CanonicalAddr, StdResult, Storage};
-use cosmwasm_storage::{singleton, singleton_read, Singleton};
+use cosmwasm_std::{CanonicalAddr, Decimal, Order, StdResult, Storage, Uint128};
+use cosmwasm_storage::{bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, Singleton};
+use spectrum_protocol::common::{calc_range_start, calc_range_start_addr};
+use terraswap::asset::{Asset, AssetInfo};
 
 static KEY_CONFIG: &[u8] = b"config";
 
@@ -14,12 +16,218 @@ pub struct Config {
     pub terraswap_factory: CanonicalAddr,
     pub allowlist: HashSet<CanonicalAddr>,
     #[serde(default)] pub allow_all: bool,
+    #[serde(default)] pub factories: Vec<CanonicalAddr>,
+    #[serde(default)] pub route_assets: Vec<AssetInfo>,
 }
 
-pub fn config_store(storage: &mut dyn Storage) -> Singleton<Config> {
+pub fn config_store(storage: &mut dyn Storage) -> Singleton<'_, Config> {
     singleton(storage, KEY_CONFIG)
 }
 
 pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
     singleton_read(storage, KEY_CONFIG).load()
 }
+
+static PREFIX_FARM: &[u8] = b"farm";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FarmRecord {
+    pub farm_type: String,
+    pub asset_tokens: Vec<String>,
+    pub staking_token: CanonicalAddr,
+    pub pair_contract: Option<CanonicalAddr>,
+}
+
+pub fn farm_store(storage: &mut dyn Storage) -> Bucket<'_, FarmRecord> {
+    bucket(storage, PREFIX_FARM)
+}
+
+pub fn farm_read(storage: &dyn Storage) -> ReadonlyBucket<'_, FarmRecord> {
+    bucket_read(storage, PREFIX_FARM)
+}
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+pub fn read_farms(
+    storage: &dyn Storage,
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(CanonicalAddr, FarmRecord)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start_addr(start_after);
+
+    farm_read(storage)
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, v) = item?;
+            Ok((CanonicalAddr::from(k), v))
+        })
+        .collect()
+}
+
+static PREFIX_NONCE: &[u8] = b"nonce";
+
+pub fn nonce_store<'a>(storage: &'a mut dyn Storage, owner: &CanonicalAddr) -> Bucket<'a, bool> {
+    Bucket::multilevel(storage, &[PREFIX_NONCE, owner.as_slice()])
+}
+
+pub fn nonce_read<'a>(storage: &'a dyn Storage, owner: &CanonicalAddr) -> ReadonlyBucket<'a, bool> {
+    ReadonlyBucket::multilevel(storage, &[PREFIX_NONCE, owner.as_slice()])
+}
+
+static KEY_SCHEDULE_COUNT: &[u8] = b"schedule_count";
+static PREFIX_SCHEDULE: &[u8] = b"schedule";
+static PREFIX_SCHEDULE_OWNER: &[u8] = b"schedule_owner";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Schedule {
+    pub owner: CanonicalAddr,
+    pub contract: CanonicalAddr,
+    pub pair_asset: AssetInfo,
+    pub pair_asset_b: Option<AssetInfo>,
+    pub asset_token: Option<String>,
+    pub denom: String,
+    pub amount_per_period: Uint128,
+    pub keeper_fee: Uint128,
+    pub period: u64,
+    pub next_execution: u64,
+    pub compound_rate: Option<Decimal>,
+    pub belief_price: Decimal,
+    pub belief_price_b: Option<Decimal>,
+    pub max_spread: Decimal,
+    pub balance: Uint128,
+}
+
+pub fn schedule_count_store(storage: &mut dyn Storage) -> Singleton<'_, u64> {
+    singleton(storage, KEY_SCHEDULE_COUNT)
+}
+
+pub fn read_schedule_count(storage: &dyn Storage) -> StdResult<u64> {
+    Ok(singleton_read(storage, KEY_SCHEDULE_COUNT).may_load()?.unwrap_or_default())
+}
+
+pub fn schedule_store(storage: &mut dyn Storage) -> Bucket<'_, Schedule> {
+    bucket(storage, PREFIX_SCHEDULE)
+}
+
+pub fn schedule_read(storage: &dyn Storage) -> ReadonlyBucket<'_, Schedule> {
+    bucket_read(storage, PREFIX_SCHEDULE)
+}
+
+pub fn schedule_owner_store<'a>(storage: &'a mut dyn Storage, owner: &CanonicalAddr) -> Bucket<'a, bool> {
+    Bucket::multilevel(storage, &[PREFIX_SCHEDULE_OWNER, owner.as_slice()])
+}
+
+pub fn schedule_owner_read<'a>(storage: &'a dyn Storage, owner: &CanonicalAddr) -> ReadonlyBucket<'a, bool> {
+    ReadonlyBucket::multilevel(storage, &[PREFIX_SCHEDULE_OWNER, owner.as_slice()])
+}
+
+pub fn read_schedules(
+    storage: &dyn Storage,
+    owner: Option<CanonicalAddr>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(u64, Schedule)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after);
+
+    if let Some(owner) = owner {
+        let schedules = schedule_read(storage);
+        schedule_owner_read(storage, &owner)
+            .range(start.as_deref(), None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                let (k, _) = item?;
+                Ok((to_u64(&k), schedules.load(&k)?))
+            })
+            .collect()
+    } else {
+        schedule_read(storage)
+            .range(start.as_deref(), None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                let (k, v) = item?;
+                Ok((to_u64(&k), v))
+            })
+            .collect()
+    }
+}
+
+static KEY_LIMIT_ORDER_COUNT: &[u8] = b"limit_order_count";
+static PREFIX_LIMIT_ORDER: &[u8] = b"limit_order";
+static PREFIX_LIMIT_ORDER_OWNER: &[u8] = b"limit_order_owner";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LimitOrder {
+    pub owner: CanonicalAddr,
+    pub contract: CanonicalAddr,
+    pub provide_asset: Asset,
+    pub pair_asset: AssetInfo,
+    pub asset_token: Option<String>,
+    pub limit_price: Decimal,
+    pub keeper_fee: Uint128,
+    pub compound_rate: Option<Decimal>,
+    pub max_spread: Decimal,
+}
+
+pub fn limit_order_count_store(storage: &mut dyn Storage) -> Singleton<'_, u64> {
+    singleton(storage, KEY_LIMIT_ORDER_COUNT)
+}
+
+pub fn read_limit_order_count(storage: &dyn Storage) -> StdResult<u64> {
+    Ok(singleton_read(storage, KEY_LIMIT_ORDER_COUNT).may_load()?.unwrap_or_default())
+}
+
+pub fn limit_order_store(storage: &mut dyn Storage) -> Bucket<'_, LimitOrder> {
+    bucket(storage, PREFIX_LIMIT_ORDER)
+}
+
+pub fn limit_order_read(storage: &dyn Storage) -> ReadonlyBucket<'_, LimitOrder> {
+    bucket_read(storage, PREFIX_LIMIT_ORDER)
+}
+
+pub fn limit_order_owner_store<'a>(storage: &'a mut dyn Storage, owner: &CanonicalAddr) -> Bucket<'a, bool> {
+    Bucket::multilevel(storage, &[PREFIX_LIMIT_ORDER_OWNER, owner.as_slice()])
+}
+
+pub fn limit_order_owner_read<'a>(storage: &'a dyn Storage, owner: &CanonicalAddr) -> ReadonlyBucket<'a, bool> {
+    ReadonlyBucket::multilevel(storage, &[PREFIX_LIMIT_ORDER_OWNER, owner.as_slice()])
+}
+
+pub fn read_limit_orders(
+    storage: &dyn Storage,
+    owner: Option<CanonicalAddr>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(u64, LimitOrder)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after);
+
+    if let Some(owner) = owner {
+        let orders = limit_order_read(storage);
+        limit_order_owner_read(storage, &owner)
+            .range(start.as_deref(), None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                let (k, _) = item?;
+                Ok((to_u64(&k), orders.load(&k)?))
+            })
+            .collect()
+    } else {
+        limit_order_read(storage)
+            .range(start.as_deref(), None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                let (k, v) = item?;
+                Ok((to_u64(&k), v))
+            })
+            .collect()
+    }
+}
+
+fn to_u64(key: &[u8]) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(key);
+    u64::from_be_bytes(bytes)
+}
@@ -2,18 +2,22 @@ use std::collections::HashSet;
 use std::iter::FromIterator;
 use std::str::FromStr;
 
-use crate::contract::{execute, compute_swap_amount, instantiate, query, PairInfo, PairType};
+use crate::contract::{execute, calculate_lp, compute_stable_swap_amount, compute_swap_amount, instantiate, pubkey_to_address, query, reply, PairInfo, PairType};
 use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
+use crate::state::{farm_store, FarmRecord};
 use cosmwasm_std::testing::{MOCK_CONTRACT_ADDR, mock_env, mock_info, MockApi, MockStorage};
-use cosmwasm_std::{from_binary, to_binary, Coin, CosmosMsg, Decimal, OwnedDeps, StdError, Uint128, WasmMsg, Addr, BankMsg};
+use cosmwasm_std::{attr, from_binary, Api, QuerierWrapper, to_binary, to_vec, Binary, Coin, ContractResult, CosmosMsg, Decimal, OwnedDeps, Reply, StdError, SubMsg, SubMsgExecutionResponse, Uint128, WasmMsg, Addr, BankMsg};
+use spectrum_protocol::mirror_farm::{Cw20HookMsg as MirrorCw20HookMsg, ExecuteMsg as MirrorExecuteMsg};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
-use spectrum_protocol::staker::{ConfigInfo, Cw20HookMsg, ExecuteMsg, QueryMsg};
+use spectrum_protocol::staker::{ConfigInfo, Cw20HookMsg, ExecuteMsg, QueryMsg, SimulateBestRouteResponse, SimulateZapToBondMultiResponse, SimulateZapToBondResponse, ZapTarget, FarmInfo, FarmsResponse, ZapIntent, ZapIntentPayload, ScheduleInfo, SchedulesResponse, LimitOrderInfo, LimitOrdersResponse};
 use terraswap::asset::{Asset, AssetInfo};
 use terraswap::pair::{Cw20HookMsg as TerraswapCw20HookMsg, ExecuteMsg as TerraswapExecuteMsg, PoolResponse};
 use spectrum_protocol::staker_single_asset::SwapOperation;
+use k256::ecdsa::{signature::Signer, Signature, SigningKey};
 
 const TOKEN: &str = "token";
 const USER1: &str = "user1";
+const USER2: &str = "user2";
 const TEST_CREATOR: &str = "creator";
 const LP: &str = "lp_token";
 const PAIR: &str = "pair0001";
@@ -26,7 +30,11 @@ const FARM1: &str = "farm1";
 const FARM2: &str = "farm2";
 const FARM3: &str = "farm3";
 const FARM4: &str = "farm4";
+const FARM5: &str = "farm5";
 const TERRA_SWAP: &str = "terra_swap";
+const ASTRO_FACTORY: &str = "astro_factory";
+const PAIR_A: &str = "pair_astro";
+const LP_A: &str = "lp_token_astro";
 
 #[test]
 fn test() {
@@ -89,6 +97,20 @@ fn test() {
              liquidity_token: Addr::unchecked(LP),
              pair_type: Some(PairType::Stable {})
          }),
+        (&"astro_factoryuusdtoken".to_string(),
+         &PairInfo {
+             asset_infos: [
+                 AssetInfo::NativeToken {
+                     denom: "uusd".to_string(),
+                 },
+                 AssetInfo::Token {
+                     contract_addr: TOKEN.to_string(),
+                 },
+             ],
+             contract_addr: Addr::unchecked(PAIR_A),
+             liquidity_token: Addr::unchecked(LP_A),
+             pair_type: Some(PairType::Xyk {})
+         }),
     ]);
     deps.querier.with_terraswap_pairs(&[
         (&PAIR.to_string(),
@@ -138,6 +160,14 @@ fn test() {
     test_zap_unbond2(&mut deps);
     test_zap_unbond3(&mut deps);
     test_native_assets(&mut deps);
+    test_best_route(&mut deps);
+    test_zap_token(&mut deps);
+    test_zap_bond_multi(&mut deps);
+    test_migrate_position(&mut deps);
+    test_farms(&mut deps);
+    test_zap_intent(&mut deps);
+    test_dca(&mut deps);
+    test_limit_order(&mut deps);
 }
 
 fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
@@ -148,7 +178,9 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
         owner: TEST_CREATOR.to_string(),
         terraswap_factory: TERRA_SWAP.to_string(),
         allowlist: vec![FARM3.to_string()],
-        allow_all: false
+        allow_all: false,
+        factories: vec![],
+        route_assets: vec![],
     };
 
     // success instantiate
@@ -166,6 +198,9 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
         insert_allowlist: Some(vec![FARM1.to_string()]),
         remove_allowlist: Some(vec![FARM3.to_string()]),
         allow_all: None,
+        insert_factories: None,
+        remove_factories: None,
+        route_assets: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
     assert_eq!(res, Err(StdError::generic_err("unauthorized")));
@@ -184,7 +219,9 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
             owner: TEST_CREATOR.to_string(),
             terraswap_factory: TERRA_SWAP.to_string(),
             allowlist: vec![FARM1.to_string()],
-            allow_all: false
+            allow_all: false,
+            factories: vec![],
+            route_assets: vec![],
         }
     );
 
@@ -194,6 +231,9 @@ fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
         insert_allowlist: Some(vec![FARM1.to_string(), FARM2.to_string()]),
         remove_allowlist: Some(vec![FARM4.to_string()]),
         allow_all: None,
+        insert_factories: None,
+        remove_factories: None,
+        route_assets: None,
     };
 
     // success
@@ -949,6 +989,7 @@ fn test_zap_unbond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
         prev_asset_b: None,
         belief_price_b: None,
         swap_hints: None,
+        min_receive: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
     assert_eq!(res, Err(StdError::generic_err("unauthorized")));
@@ -969,6 +1010,8 @@ fn test_zap_unbond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
             sell_asset_b: None,
             belief_price_b: None,
             swap_hints: None,
+            staker_addr: None,
+            min_receive: None,
         })
         .unwrap(),
     });
@@ -997,6 +1040,8 @@ fn test_zap_unbond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
             sell_asset_b: None,
             belief_price_b: None,
             swap_hints: None,
+            staker_addr: None,
+            min_receive: None,
         })
         .unwrap(),
     });
@@ -1022,6 +1067,8 @@ fn test_zap_unbond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
             sell_asset_b: None,
             belief_price_b: None,
             swap_hints: None,
+            staker_addr: None,
+            min_receive: None,
         })
         .unwrap(),
     });
@@ -1063,6 +1110,7 @@ fn test_zap_unbond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
                     prev_asset_b: None,
                     belief_price_b: None,
                     swap_hints: None,
+                    min_receive: None,
                 }).unwrap(),
                 funds: vec![],
             }),
@@ -1093,6 +1141,7 @@ fn test_zap_unbond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
         prev_asset_b: None,
         belief_price_b: None,
         swap_hints: None,
+        min_receive: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
     assert_eq!(
@@ -1101,12 +1150,6 @@ fn test_zap_unbond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
             .map(|it| it.msg)
             .collect::<Vec<CosmosMsg>>(),
         vec![
-            CosmosMsg::Bank(BankMsg::Send {
-                to_address: USER1.to_string(),
-                amount: vec![
-                    Coin { denom: "uusd".to_string(), amount: Uint128::from(456u128) },
-                ],
-            }),
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: TOKEN.to_string(),
                 msg: to_binary(&Cw20ExecuteMsg::Send {
@@ -1115,13 +1158,61 @@ fn test_zap_unbond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
                     msg: to_binary(&TerraswapCw20HookMsg::Swap {
                         belief_price: Some(Decimal::one()),
                         max_spread: Some(Decimal::percent(1u64)),
-                        to: Some(USER1.to_string()),
+                        to: None,
                     }).unwrap(),
                 }).unwrap(),
                 funds: vec![],
             }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::zap_to_unbond_transfer_hook {
+                    staker_addr: USER1.to_string(),
+                    prev_target_asset: Asset {
+                        amount: Uint128::zero(),
+                        info: AssetInfo::NativeToken {
+                            denom: "uusd".to_string(),
+                        },
+                    },
+                    min_receive: None,
+                }).unwrap(),
+                funds: vec![],
+            }),
         ]
     );
+
+    // withdrawn and swapped uusd is sent after swap, min_receive is checked against actual amount
+    deps.querier.with_token_balances(&[
+        (&"uusd".to_string(), &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1456u128))]),
+    ]);
+    let transfer_hook = |min_receive: Option<u128>| ExecuteMsg::zap_to_unbond_transfer_hook {
+        staker_addr: USER1.to_string(),
+        prev_target_asset: Asset {
+            amount: Uint128::from(1000u128),
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        },
+        min_receive: min_receive.map(Uint128::from),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER1, &[]), transfer_hook(None));
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), transfer_hook(Some(457u128)));
+    assert_eq!(res, Err(StdError::generic_err("receive amount is less than min_receive")));
+
+    let res = execute(deps.as_mut(), env, info, transfer_hook(Some(456u128))).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Bank(BankMsg::Send {
+            to_address: USER1.to_string(),
+            amount: vec![
+                Coin { denom: "uusd".to_string(), amount: Uint128::from(456u128) },
+            ],
+        })]
+    );
 }
 
 fn test_zap_unbond2(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
@@ -1146,6 +1237,8 @@ fn test_zap_unbond2(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
             },
             belief_price_b: Some(Decimal::from_ratio(6u128, 5u128)),
             swap_hints: None,
+            staker_addr: None,
+            min_receive: None,
         }).unwrap(),
     });
 
@@ -1191,6 +1284,7 @@ fn test_zap_unbond2(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
                         },
                     }),
                     swap_hints: None,
+                    min_receive: None,
                 }).unwrap(),
                 funds: vec![],
             }),
@@ -1227,6 +1321,7 @@ fn test_zap_unbond2(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
             },
         }),
         swap_hints: None,
+        min_receive: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
     assert_eq!(
@@ -1269,6 +1364,7 @@ fn test_zap_unbond2(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
                     belief_price_b: None,
                     prev_asset_b: None,
                     swap_hints: None,
+                    min_receive: None,
                 }).unwrap(),
                 funds: vec![],
             })
@@ -1294,6 +1390,7 @@ fn test_zap_unbond2(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
         belief_price_b: None,
         prev_asset_b: None,
         swap_hints: None,
+        min_receive: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
     assert_eq!(
@@ -1302,12 +1399,6 @@ fn test_zap_unbond2(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
             .map(|it| it.msg)
             .collect::<Vec<CosmosMsg>>(),
         vec![
-            CosmosMsg::Bank(BankMsg::Send {
-                to_address: USER1.to_string(),
-                amount: vec![
-                    Coin { denom: "uusd".to_string(), amount: Uint128::from(456u128) },
-                ],
-            }),
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: TOKEN.to_string(),
                 msg: to_binary(&Cw20ExecuteMsg::Send {
@@ -1316,11 +1407,25 @@ fn test_zap_unbond2(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
                     msg: to_binary(&TerraswapCw20HookMsg::Swap {
                         belief_price: Some(Decimal::one()),
                         max_spread: Some(Decimal::percent(1u64)),
-                        to: Some(USER1.to_string()),
+                        to: None,
                     }).unwrap(),
                 }).unwrap(),
                 funds: vec![],
             }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::zap_to_unbond_transfer_hook {
+                    staker_addr: USER1.to_string(),
+                    prev_target_asset: Asset {
+                        amount: Uint128::zero(),
+                        info: AssetInfo::NativeToken {
+                            denom: "uusd".to_string(),
+                        },
+                    },
+                    min_receive: None,
+                }).unwrap(),
+                funds: vec![],
+            }),
         ]
     );
 }
@@ -1362,6 +1467,8 @@ fn test_zap_unbond3(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
                     belief_price: Some(Decimal::from_ratio(1u128, 2u128)),
                 },
             ]),
+            staker_addr: None,
+            min_receive: None,
         }).unwrap(),
     });
 
@@ -1422,6 +1529,7 @@ fn test_zap_unbond3(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
                             belief_price: Some(Decimal::from_ratio(1u128, 2u128)),
                         },
                     ]),
+                    min_receive: None,
                 }).unwrap(),
                 funds: vec![],
             }),
@@ -1473,6 +1581,7 @@ fn test_zap_unbond3(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
                 belief_price: Some(Decimal::from_ratio(1u128, 2u128)),
             },
         ]),
+        min_receive: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
     assert_eq!(
@@ -1530,6 +1639,7 @@ fn test_zap_unbond3(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
                             belief_price: Some(Decimal::from_ratio(1u128, 2u128)),
                         },
                     ]),
+                    min_receive: None,
                 }).unwrap(),
                 funds: vec![],
             })
@@ -1570,6 +1680,7 @@ fn test_zap_unbond3(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
                 belief_price: Some(Decimal::from_ratio(1u128, 2u128)),
             },
         ]),
+        min_receive: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
     assert_eq!(
@@ -1578,12 +1689,6 @@ fn test_zap_unbond3(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
             .map(|it| it.msg)
             .collect::<Vec<CosmosMsg>>(),
         vec![
-            CosmosMsg::Bank(BankMsg::Send {
-                to_address: USER1.to_string(),
-                amount: vec![
-                    Coin { denom: "uusd".to_string(), amount: Uint128::from(456u128) },
-                ],
-            }),
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: TOKEN.to_string(),
                 msg: to_binary(&Cw20ExecuteMsg::Send {
@@ -1606,12 +1711,26 @@ fn test_zap_unbond3(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
                     },
                     belief_price: Some(Decimal::from_ratio(1u128, 2u128)),
                     max_spread: Some(Decimal::percent(1u64)),
-                    to: Some(USER1.to_string()),
+                    to: None,
                 }).unwrap(),
                 funds: vec![
                     Coin { denom: "uluna".to_string(), amount: Uint128::from(123u128) }
                 ],
             }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::zap_to_unbond_transfer_hook {
+                    staker_addr: USER1.to_string(),
+                    prev_target_asset: Asset {
+                        amount: Uint128::zero(),
+                        info: AssetInfo::NativeToken {
+                            denom: "uusd".to_string(),
+                        },
+                    },
+                    min_receive: None,
+                }).unwrap(),
+                funds: vec![],
+            }),
         ]
     );
 }
@@ -1626,6 +1745,69 @@ fn test_get_swap_amount() {
     assert_eq!(swap_a, Uint128::from(1192_872692u128));
 }
 
+#[test]
+fn test_get_stable_swap_amount() {
+    let pool_a = Uint128::from(1000_000000u128);
+    let pool_b = Uint128::from(1000_000000u128);
+    let amount_a = Uint128::from(100_000000u128);
+
+    let xyk_swap = compute_swap_amount(amount_a, Uint128::zero(), pool_a, pool_b);
+    assert_eq!(xyk_swap, Uint128::from(48_885752u128));
+
+    // amp 1 has price impact close to constant product
+    let stable_swap_low = compute_stable_swap_amount(amount_a, pool_a, pool_b, 100, Decimal::permille(3)).unwrap();
+    assert_eq!(stable_swap_low, Uint128::from(48_289811u128));
+
+    // with amp 100 price is close to 1:1, swap amount approaches amount * pool_a / (2 * pool_a + amount)
+    let stable_swap = compute_stable_swap_amount(amount_a, pool_a, pool_b, 10000, Decimal::permille(3)).unwrap();
+    assert_eq!(stable_swap, Uint128::from(47_705801u128));
+    assert!(stable_swap > Uint128::from(47_619047u128));
+    assert!(stable_swap < stable_swap_low);
+}
+
+#[test]
+fn test_calculate_stable_lp() {
+    let deps = mock_dependencies(&[]);
+    let querier = QuerierWrapper::new(&deps.querier);
+    let pair_info = PairInfo {
+        asset_infos: [
+            AssetInfo::NativeToken { denom: "uusd".to_string() },
+            AssetInfo::Token { contract_addr: TOKEN.to_string() },
+        ],
+        contract_addr: Addr::unchecked(PAIR),
+        liquidity_token: Addr::unchecked(LP),
+        pair_type: Some(PairType::Stable {}),
+    };
+    let assets = [
+        Asset {
+            info: AssetInfo::NativeToken { denom: "uusd".to_string() },
+            amount: Uint128::from(100_000000u128),
+        },
+        Asset {
+            info: AssetInfo::Token { contract_addr: TOKEN.to_string() },
+            amount: Uint128::from(100_000000u128),
+        },
+    ];
+    let pool = |amount: u128, total_share: u128| PoolResponse {
+        assets: [
+            Asset { info: assets[0].info.clone(), amount: Uint128::from(amount) },
+            Asset { info: assets[1].info.clone(), amount: Uint128::from(amount) },
+        ],
+        total_share: Uint128::from(total_share),
+    };
+
+    // balanced provide to balanced pool mints pro rata
+    let lp = calculate_lp(&querier, &pool(1000_000000u128, 2000_000000u128), &assets, &pair_info).unwrap();
+    assert_eq!(lp, Uint128::from(200_000000u128));
+
+    // invariant overflow is an error instead of a panic
+    let res = calculate_lp(&querier, &pool(u128::MAX / 2, 1u128), &assets, &pair_info);
+    assert_eq!(res, Err(StdError::generic_err("cannot calculate stable swap liquidity")));
+
+    let res = calculate_lp(&querier, &pool(0u128, 0u128), &assets, &pair_info);
+    assert_eq!(res, Err(StdError::generic_err("pool is empty")));
+}
+
 fn test_native_assets(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
     let env = mock_env();
     let info = mock_info(TEST_CREATOR, &[Coin {
@@ -1676,6 +1858,21 @@ fn test_native_assets(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier
                     Coin { denom: "uusd".to_string(), amount: Uint128::from(100_000_000u128) }
                 ],
             }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: PAIR.to_string(),
+                msg: to_binary(&TerraswapExecuteMsg::Swap {
+                    offer_asset: Asset {
+                        info: AssetInfo::NativeToken { denom: "uluna".to_string() },
+                        amount: Uint128::from(45365286u128),
+                    },
+                    belief_price: Some(Decimal::from_str("1").unwrap()),
+                    max_spread: Some(Decimal::percent(1)),
+                    to: None,
+                }).unwrap(),
+                funds: vec![
+                    Coin { denom: "uluna".to_string(), amount: Uint128::from(45365286u128) }
+                ],
+            }),
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: MOCK_CONTRACT_ADDR.to_string(),
                 msg: to_binary(&ExecuteMsg::bond {
@@ -1683,11 +1880,11 @@ fn test_native_assets(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier
                     assets: [
                         Asset {
                             info: AssetInfo::Token { contract_addr: TOKEN.to_string() },
-                            amount: Uint128::zero(),
+                            amount: Uint128::from(45229191u128),
                         },
                         Asset {
                             info: AssetInfo::NativeToken { denom: "uluna".to_string() },
-                            amount: Uint128::from(99700000u128),
+                            amount: Uint128::from(54334714u128),
                         },
                     ],
                     slippage_tolerance: Decimal::percent(1),
@@ -1700,7 +1897,28 @@ fn test_native_assets(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier
         ]
     );
 
-    // skip stable swap
+    // stable pair is simulated with the same swap amount
+    let msg = QueryMsg::simulate_zap_to_bond {
+        provide_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(100_000_000u128),
+        },
+        pair_asset: AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        },
+        pair_asset_b: Some(AssetInfo::Token {
+            contract_addr: TOKEN.to_string(),
+        }),
+        swap_hints: None,
+    };
+    let res: SimulateZapToBondResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.swap_a, Some(Uint128::from(45365286u128)));
+    assert_eq!(res.provide_a, Uint128::from(54334714u128));
+    assert_eq!(res.provide_b, Uint128::from(45229191u128));
+
+    // stable swap through PAIR_U route without belief price
     let msg = ExecuteMsg::zap_to_bond {
         contract: FARM1.to_string(),
         compound_rate: Some(Decimal::percent(100u64)),
@@ -1708,7 +1926,7 @@ fn test_native_assets(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier
             info: AssetInfo::NativeToken {
                 denom: "uusd".to_string(),
             },
-            amount: Uint128::from(100_000_000u128),
+            amount: Uint128::from(10_000_000u128),
         },
         pair_asset: AssetInfo::NativeToken {
             denom: "uluna".to_string(),
@@ -1716,13 +1934,17 @@ fn test_native_assets(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier
         pair_asset_b: Some(AssetInfo::Token {
             contract_addr: TOKEN.to_string(),
         }),
-        belief_price: Some(Decimal::from_ratio(1u128, 1u128)),
-        belief_price_b: Some(Decimal::from_ratio(1u128, 1u128)),
+        belief_price: None,
+        belief_price_b: None,
         max_spread: Decimal::percent(1u64),
         asset_token: None,
         swap_hints: None,
     };
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    let info = mock_info(TEST_CREATOR, &[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(10_000_000u128),
+    }]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
     assert_eq!(
         res.messages
             .into_iter()
@@ -1734,14 +1956,29 @@ fn test_native_assets(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier
                 msg: to_binary(&TerraswapExecuteMsg::Swap {
                     offer_asset: Asset {
                         info: AssetInfo::NativeToken { denom: "uusd".to_string() },
-                        amount: Uint128::from(100_000_000u128),
+                        amount: Uint128::from(10000000u128),
                     },
-                    belief_price: Some(Decimal::from_str("1").unwrap()),
+                    belief_price: None,
                     max_spread: Some(Decimal::percent(1)),
                     to: None,
                 }).unwrap(),
                 funds: vec![
-                    Coin { denom: "uusd".to_string(), amount: Uint128::from(100_000_000u128) }
+                    Coin { denom: "uusd".to_string(), amount: Uint128::from(10000000u128) }
+                ],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: PAIR.to_string(),
+                msg: to_binary(&TerraswapExecuteMsg::Swap {
+                    offer_asset: Asset {
+                        info: AssetInfo::NativeToken { denom: "uluna".to_string() },
+                        amount: Uint128::from(4937279u128),
+                    },
+                    belief_price: None,
+                    max_spread: Some(Decimal::percent(1)),
+                    to: None,
+                }).unwrap(),
+                funds: vec![
+                    Coin { denom: "uluna".to_string(), amount: Uint128::from(4937279u128) }
                 ],
             }),
             CosmosMsg::Wasm(WasmMsg::Execute {
@@ -1751,11 +1988,11 @@ fn test_native_assets(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier
                     assets: [
                         Asset {
                             info: AssetInfo::Token { contract_addr: TOKEN.to_string() },
-                            amount: Uint128::zero(),
+                            amount: Uint128::from(4922468u128),
                         },
                         Asset {
                             info: AssetInfo::NativeToken { denom: "uluna".to_string() },
-                            amount: Uint128::from(99700000u128),
+                            amount: Uint128::from(5032721u128),
                         },
                     ],
                     slippage_tolerance: Decimal::percent(1),
@@ -1767,4 +2004,1492 @@ fn test_native_assets(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier
             }),
         ]
     );
+
+    // stable pair without pair_asset_b
+    let msg = ExecuteMsg::zap_to_bond {
+        contract: FARM1.to_string(),
+        compound_rate: Some(Decimal::percent(100u64)),
+        provide_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            amount: Uint128::from(100_000_000u128),
+        },
+        pair_asset: AssetInfo::Token {
+            contract_addr: TOKEN.to_string(),
+        },
+        pair_asset_b: None,
+        belief_price: None,
+        belief_price_b: None,
+        max_spread: Decimal::percent(1u64),
+        asset_token: None,
+        swap_hints: None,
+    };
+    let info = mock_info(TEST_CREATOR, &[Coin {
+        denom: "uluna".to_string(),
+        amount: Uint128::from(100_000_000u128),
+    }]);
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: PAIR.to_string(),
+                msg: to_binary(&TerraswapExecuteMsg::Swap {
+                    offer_asset: Asset {
+                        info: AssetInfo::NativeToken { denom: "uluna".to_string() },
+                        amount: Uint128::from(45489452u128),
+                    },
+                    belief_price: None,
+                    max_spread: Some(Decimal::percent(1)),
+                    to: None,
+                }).unwrap(),
+                funds: vec![
+                    Coin { denom: "uluna".to_string(), amount: Uint128::from(45489452u128) }
+                ],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::bond {
+                    contract: FARM1.to_string(),
+                    assets: [
+                        Asset {
+                            info: AssetInfo::Token { contract_addr: TOKEN.to_string() },
+                            amount: Uint128::from(45352984u128),
+                        },
+                        Asset {
+                            info: AssetInfo::NativeToken { denom: "uluna".to_string() },
+                            amount: Uint128::from(54510548u128),
+                        },
+                    ],
+                    slippage_tolerance: Decimal::percent(1),
+                    compound_rate: Some(Decimal::one()),
+                    staker_addr: Some(TEST_CREATOR.to_string()),
+                    asset_token: None,
+                }).unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+}
+
+fn test_best_route(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+    deps.querier.with_commission_rates(&[
+        (&PAIR_A.to_string(), &Decimal::permille(1)),
+    ]);
+
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::update_config {
+        insert_allowlist: None,
+        remove_allowlist: None,
+        allow_all: None,
+        insert_factories: Some(vec![ASTRO_FACTORY.to_string()]),
+        remove_factories: None,
+        route_assets: Some(vec![AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        }]),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    /*
+    direct terraswap 1000000 uusd -> 997000 token
+    direct astroport 1000000 uusd -> 999000 token
+    one-hop 1000000 uusd -> 997000 uluna -> stable pair < 997000 token
+    */
+    let msg = QueryMsg::simulate_best_route {
+        offer_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(1_000_000u128),
+        },
+        ask_asset: AssetInfo::Token {
+            contract_addr: TOKEN.to_string(),
+        },
+    };
+    let res: SimulateBestRouteResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        SimulateBestRouteResponse {
+            return_amount: Uint128::from(999_000u128),
+            swap_operations: vec![SwapOperation {
+                pair_contract: PAIR_A.to_string(),
+                asset_info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                belief_price: None,
+            }],
+        }
+    );
+
+    // zap without hints uses best route
+    let info = mock_info(
+        USER1,
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100_000_000u128),
+        }],
+    );
+    let msg = ExecuteMsg::zap_to_bond {
+        contract: FARM1.to_string(),
+        compound_rate: None,
+        provide_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(100_000_000u128),
+        },
+        pair_asset: AssetInfo::Token {
+            contract_addr: TOKEN.to_string(),
+        },
+        pair_asset_b: Some(AssetInfo::Token {
+            contract_addr: TOKEN_B.to_string(),
+        }),
+        belief_price: Some(Decimal::one()),
+        belief_price_b: None,
+        max_spread: Decimal::percent(1u64),
+        asset_token: None,
+        swap_hints: None,
+    };
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: PAIR_A.to_string(),
+            msg: to_binary(&TerraswapExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uusd".to_string(),
+                    },
+                    amount: Uint128::from(100_000_000u128),
+                },
+                max_spread: Some(Decimal::percent(1u64)),
+                belief_price: Some(Decimal::one()),
+                to: None,
+            }).unwrap(),
+            funds: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(100_000_000u128),
+            }],
+        })
+    );
+}
+
+fn test_zap_token(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+
+    // zap token to LP through cw20 hook
+    let info = mock_info(TOKEN, &[]);
+    let msg = ExecuteMsg::receive(Cw20ReceiveMsg {
+        sender: USER1.to_string(),
+        amount: Uint128::from(100_000_000u128),
+        msg: to_binary(&Cw20HookMsg::zap_to_bond {
+            contract: FARM1.to_string(),
+            pair_asset: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            pair_asset_b: None,
+            belief_price: Some(Decimal::from_ratio(1u128, 1u128)),
+            belief_price_b: None,
+            max_spread: Decimal::percent(1u64),
+            compound_rate: Some(Decimal::percent(55u64)),
+            asset_token: None,
+            swap_hints: None,
+        })
+        .unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: PAIR.to_string(),
+                    amount: Uint128::from(47801096u128),
+                    msg: to_binary(&TerraswapCw20HookMsg::Swap {
+                        belief_price: Some(Decimal::one()),
+                        max_spread: Some(Decimal::percent(1u64)),
+                        to: None,
+                    }).unwrap(),
+                }).unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::bond {
+                    contract: FARM1.to_string(),
+                    assets: [
+                        Asset {
+                            info: AssetInfo::NativeToken {
+                                denom: "uusd".to_string(),
+                            },
+                            amount: Uint128::from(47657693u128),
+                        },
+                        Asset {
+                            info: AssetInfo::Token {
+                                contract_addr: TOKEN.to_string(),
+                            },
+                            amount: Uint128::from(52198904u128),
+                        },
+                    ],
+                    staker_addr: Some(USER1.to_string()),
+                    slippage_tolerance: Decimal::percent(1u64),
+                    compound_rate: Some(Decimal::percent(55u64)),
+                    asset_token: None,
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+
+    // zap out to token
+    deps.querier.with_token_balances(&[
+        (&TOKEN.to_string(), &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(123u128))]),
+        (&"uusd".to_string(), &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(456u128))]),
+    ]);
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let msg = ExecuteMsg::zap_to_unbond_hook {
+        staker_addr: USER1.to_string(),
+        prev_asset_a: Asset {
+            amount: Uint128::zero(),
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        },
+        prev_target_asset: Asset {
+            amount: Uint128::zero(),
+            info: AssetInfo::Token {
+                contract_addr: TOKEN.to_string(),
+            },
+        },
+        belief_price_a: Some(Decimal::from_ratio(1u128, 1u128)),
+        max_spread: Decimal::percent(1u64),
+        prev_asset_b: None,
+        belief_price_b: None,
+        swap_hints: None,
+        min_receive: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: PAIR.to_string(),
+                msg: to_binary(&TerraswapExecuteMsg::Swap {
+                    offer_asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "uusd".to_string(),
+                        },
+                        amount: Uint128::from(456u128),
+                    },
+                    belief_price: Some(Decimal::one()),
+                    max_spread: Some(Decimal::percent(1u64)),
+                    to: None,
+                }).unwrap(),
+                funds: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(456u128),
+                }],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::zap_to_unbond_transfer_hook {
+                    staker_addr: USER1.to_string(),
+                    prev_target_asset: Asset {
+                        amount: Uint128::zero(),
+                        info: AssetInfo::Token {
+                            contract_addr: TOKEN.to_string(),
+                        },
+                    },
+                    min_receive: None,
+                }).unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+
+    // token balance after swap is transferred
+    deps.querier.with_token_balances(&[
+        (&TOKEN.to_string(), &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(578u128))]),
+    ]);
+    let msg = ExecuteMsg::zap_to_unbond_transfer_hook {
+        staker_addr: USER1.to_string(),
+        prev_target_asset: Asset {
+            amount: Uint128::zero(),
+            info: AssetInfo::Token {
+                contract_addr: TOKEN.to_string(),
+            },
+        },
+        min_receive: Some(Uint128::from(578u128)),
+    };
+    let res = execute(deps.as_mut(), env, mock_info(MOCK_CONTRACT_ADDR, &[]), msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: USER1.to_string(),
+                    amount: Uint128::from(578u128),
+                }).unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+}
+
+fn test_zap_bond_multi(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+    let info = mock_info(
+        USER1,
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100_000_000u128),
+        }],
+    );
+    let provide_asset = Asset {
+        info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        amount: Uint128::from(100_000_000u128),
+    };
+    let target = |contract: &str, weight: u32| ZapTarget {
+        contract: contract.to_string(),
+        asset_token: None,
+        pair_asset: AssetInfo::Token {
+            contract_addr: TOKEN.to_string(),
+        },
+        pair_asset_b: None,
+        belief_price: None,
+        belief_price_b: None,
+        compound_rate: Some(Decimal::percent(55u64)),
+        swap_hints: None,
+        weight,
+        max_spread: None,
+    };
+
+    // zero weight
+    let msg = ExecuteMsg::zap_to_bond_multi {
+        provide_asset: provide_asset.clone(),
+        targets: vec![target(FARM1, 0), target(FARM2, 0)],
+        max_spread: Decimal::percent(1u64),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_eq!(res, Err(StdError::generic_err("total weight must be more than 0")));
+
+    // contract not in allowlist
+    let msg = ExecuteMsg::zap_to_bond_multi {
+        provide_asset: provide_asset.clone(),
+        targets: vec![target(FARM1, 1), target(FARM3, 1)],
+        max_spread: Decimal::percent(1u64),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_eq!(res, Err(StdError::generic_err("not allowed")));
+
+    // target max_spread is validated
+    let msg = ExecuteMsg::zap_to_bond_multi {
+        provide_asset: provide_asset.clone(),
+        targets: vec![
+            ZapTarget { max_spread: Some(Decimal::percent(60u64)), ..target(FARM1, 3) },
+            target(FARM2, 1),
+        ],
+        max_spread: Decimal::percent(1u64),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_eq!(res, Err(StdError::generic_err("Slippage tolerance must be 0 to 0.5")));
+
+    let msg = ExecuteMsg::zap_to_bond_multi {
+        provide_asset: provide_asset.clone(),
+        targets: vec![
+            ZapTarget { max_spread: Some(Decimal::percent(2u64)), ..target(FARM1, 3) },
+            target(FARM2, 1),
+        ],
+        max_spread: Decimal::percent(1u64),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let self_messages = |res: cosmwasm_std::Response| res.messages
+        .into_iter()
+        .filter_map(|it| match it.msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) if contract_addr == MOCK_CONTRACT_ADDR => Some(from_binary(&msg).unwrap()),
+            _ => None,
+        })
+        .collect::<Vec<ExecuteMsg>>();
+    let bonds = self_messages(res);
+    let bond = |contract: &str, amount_a: u128, amount_b: u128, max_spread: u64| ExecuteMsg::bond {
+        contract: contract.to_string(),
+        assets: [
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: TOKEN.to_string(),
+                },
+                amount: Uint128::from(amount_a),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::from(amount_b),
+            },
+        ],
+        staker_addr: Some(USER1.to_string()),
+        slippage_tolerance: Decimal::percent(max_spread),
+        compound_rate: Some(Decimal::percent(55u64)),
+        asset_token: None,
+    };
+    // both targets swap through PAIR, FARM2 is zapped after FARM1 swap and provide
+    let hook_msg = ExecuteMsg::zap_to_bond_multi_hook {
+        staker_addr: USER1.to_string(),
+        provide_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(25_000_000u128),
+        },
+        target: ZapTarget { max_spread: Some(Decimal::percent(1u64)), ..target(FARM2, 1) },
+        max_spread: Decimal::percent(1u64),
+    };
+    assert_eq!(bonds, vec![
+        bond(FARM1, 36139824u128, 38751431u128, 2u64),
+        hook_msg.clone(),
+    ]);
+
+    // hook only by itself
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER1, &[]), hook_msg.clone());
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    // simulate with pool after FARM1 zap
+    let pool = |amount_a: u128, amount_b: u128, total_share: u128| PoolResponse {
+        total_share: Uint128::from(total_share),
+        assets: [
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: TOKEN.to_string(),
+                },
+                amount: Uint128::from(amount_a),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::from(amount_b),
+            },
+        ],
+    };
+    deps.querier.with_terraswap_pool(PAIR, pool(500_000_000u128, 575_000_000u128, 537_500_000u128));
+    let res = execute(deps.as_mut(), env.clone(), mock_info(MOCK_CONTRACT_ADDR, &[]), hook_msg).unwrap();
+    assert_eq!(self_messages(res), vec![bond(FARM2, 12348826u128, 12614017u128, 1u64)]);
+    deps.querier.with_terraswap_pool(PAIR, pool(500_000_000u128, 500_000_000u128, 500_000_000u128));
+
+    let msg = QueryMsg::simulate_zap_to_bond_multi {
+        provide_asset,
+        targets: vec![target(FARM1, 3), target(FARM2, 1)],
+    };
+    let res: SimulateZapToBondMultiResponse = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+    assert_eq!(
+        res.results.iter().map(|it| (it.contract.clone(), it.provide_amount, it.simulation.lp_amount)).collect::<Vec<_>>(),
+        vec![
+            (FARM1.to_string(), Uint128::from(75_000_000u128), Uint128::from(36131966u128)),
+            // simulated on pool after FARM1 swap and provide, token price is higher
+            (FARM2.to_string(), Uint128::from(25_000_000u128), Uint128::from(11513343u128)),
+        ]
+    );
+
+    // swap hops shared by targets are also simulated on updated pools
+    deps.querier.with_pair_infos(&[
+        &PairInfo {
+            asset_infos: [
+                AssetInfo::Token { contract_addr: TOKEN.to_string() },
+                AssetInfo::NativeToken { denom: "uusd".to_string() },
+            ],
+            contract_addr: Addr::unchecked(PAIR),
+            liquidity_token: Addr::unchecked(LP),
+            pair_type: None,
+        },
+        &PairInfo {
+            asset_infos: [
+                AssetInfo::NativeToken { denom: "uusd".to_string() },
+                AssetInfo::Token { contract_addr: TOKEN.to_string() },
+            ],
+            contract_addr: Addr::unchecked(PAIR_A),
+            liquidity_token: Addr::unchecked(LP_A),
+            pair_type: Some(PairType::Xyk {}),
+        },
+    ]);
+    let target_b = |contract: &str| ZapTarget {
+        pair_asset_b: Some(AssetInfo::Token {
+            contract_addr: TOKEN_B.to_string(),
+        }),
+        swap_hints: Some(vec![SwapOperation {
+            pair_contract: PAIR.to_string(),
+            asset_info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            belief_price: None,
+        }]),
+        ..target(contract, 1)
+    };
+    let msg = QueryMsg::simulate_zap_to_bond_multi {
+        provide_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(100_000_000u128),
+        },
+        targets: vec![target_b(FARM1), target_b(FARM2)],
+    };
+    let res: SimulateZapToBondMultiResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res.results.iter().map(|it| it.simulation.receive_a).collect::<Vec<_>>(),
+        vec![Uint128::from(49_850_000u128), Uint128::from(37_399_963u128)],
+    );
+}
+
+fn test_migrate_position(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+    let uusd = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+    let token = AssetInfo::Token {
+        contract_addr: TOKEN.to_string(),
+    };
+    deps.querier.with_pair_infos(&[
+        &PairInfo {
+            asset_infos: [token.clone(), uusd.clone()],
+            contract_addr: Addr::unchecked(PAIR),
+            liquidity_token: Addr::unchecked(LP),
+            pair_type: None,
+        },
+        &PairInfo {
+            asset_infos: [uusd.clone(), token.clone()],
+            contract_addr: Addr::unchecked(PAIR_A),
+            liquidity_token: Addr::unchecked(LP_A),
+            pair_type: Some(PairType::Xyk {}),
+        },
+        &PairInfo {
+            asset_infos: [token.clone(), AssetInfo::Token {
+                contract_addr: TOKEN_B.to_string(),
+            }],
+            contract_addr: Addr::unchecked(PAIR_B),
+            liquidity_token: Addr::unchecked(LP_B),
+            pair_type: None,
+        },
+    ]);
+    deps.querier.with_token_balances(&[]);
+
+    // contract not in allowlist
+    let info = mock_info(USER1, &[]);
+    let msg = ExecuteMsg::migrate_position {
+        from_contract: FARM1.to_string(),
+        from_asset_token: TOKEN.to_string(),
+        from_pair: PAIR.to_string(),
+        amount: Uint128::from(100u128),
+        to_contract: FARM3.to_string(),
+        to_asset_token: None,
+        to_pair: PAIR_A.to_string(),
+        compound_rate: Some(Decimal::percent(50u64)),
+        slippage_tolerance: Decimal::percent(1u64),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_eq!(res, Err(StdError::generic_err("not allowed")));
+
+    let migrate = |from_asset_token: &str, from_pair: &str, to_pair: &str| ExecuteMsg::migrate_position {
+        from_contract: FARM1.to_string(),
+        from_asset_token: from_asset_token.to_string(),
+        from_pair: from_pair.to_string(),
+        amount: Uint128::from(100u128),
+        to_contract: FARM2.to_string(),
+        to_asset_token: None,
+        to_pair: to_pair.to_string(),
+        compound_rate: Some(Decimal::percent(50u64)),
+        slippage_tolerance: Decimal::percent(1u64),
+    };
+
+    // farms must be registered
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), migrate(TOKEN, PAIR, PAIR_A));
+    assert_eq!(res, Err(StdError::generic_err("farm farm1 is not registered")));
+
+    let api = deps.api;
+    let farm_record = move |farm_type: &str, staking_token: &str, pair_contract: &str| FarmRecord {
+        farm_type: farm_type.to_string(),
+        asset_tokens: vec![TOKEN.to_string()],
+        staking_token: api.addr_canonicalize(staking_token).unwrap(),
+        pair_contract: Some(api.addr_canonicalize(pair_contract).unwrap()),
+    };
+    let farm1_raw = deps.api.addr_canonicalize(FARM1).unwrap();
+    let farm2_raw = deps.api.addr_canonicalize(FARM2).unwrap();
+    let farm1_pylon = farm_record("pylon_liquid", LP, PAIR);
+    let farm1 = farm_record("mirror", LP, PAIR);
+    let farm2 = farm_record("astroport", LP_A, PAIR_A);
+    farm_store(&mut deps.storage).save(farm1_raw.as_slice(), &farm1_pylon).unwrap();
+    farm_store(&mut deps.storage).save(farm2_raw.as_slice(), &farm2).unwrap();
+
+    // source farm must support unbond_for
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), migrate(TOKEN, PAIR, PAIR_A));
+    assert_eq!(res, Err(StdError::generic_err("pylon_liquid farm does not support unbond_for")));
+    farm_store(&mut deps.storage).save(farm1_raw.as_slice(), &farm1).unwrap();
+
+    // pairs and asset token must match farm records
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), migrate(TOKEN, PAIR_A, PAIR_A));
+    assert_eq!(res, Err(StdError::generic_err("pair does not match farm farm1")));
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), migrate(TOKEN, PAIR, PAIR));
+    assert_eq!(res, Err(StdError::generic_err("pair does not match farm farm2")));
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), migrate(TOKEN_B, PAIR, PAIR_A));
+    assert_eq!(res, Err(StdError::generic_err("asset token does not match farm farm1")));
+
+    // registered LP must be the LP of the pair
+    farm_store(&mut deps.storage).save(farm2_raw.as_slice(), &farm_record("astroport", LP, PAIR_A)).unwrap();
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), migrate(TOKEN, PAIR, PAIR_A));
+    assert_eq!(res, Err(StdError::generic_err("staking token does not match farm farm2")));
+    farm_store(&mut deps.storage).save(farm2_raw.as_slice(), &farm2).unwrap();
+
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), migrate(TOKEN, PAIR, PAIR_A)).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: FARM1.to_string(),
+                msg: to_binary(&MirrorExecuteMsg::unbond_for {
+                    staker_addr: USER1.to_string(),
+                    asset_token: TOKEN.to_string(),
+                    amount: Uint128::from(100u128),
+                }).unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::migrate_position_hook {
+                    staker_addr: USER1.to_string(),
+                    from_pair: PAIR.to_string(),
+                    prev_staking_token_amount: Uint128::zero(),
+                    to_contract: FARM2.to_string(),
+                    to_asset_token: None,
+                    to_pair: PAIR_A.to_string(),
+                    compound_rate: Some(Decimal::percent(50u64)),
+                    slippage_tolerance: Decimal::percent(1u64),
+                }).unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+
+    // unauthorized
+    let hook = |to_pair: &str| ExecuteMsg::migrate_position_hook {
+        staker_addr: USER1.to_string(),
+        from_pair: PAIR.to_string(),
+        prev_staking_token_amount: Uint128::zero(),
+        to_contract: FARM2.to_string(),
+        to_asset_token: None,
+        to_pair: to_pair.to_string(),
+        compound_rate: Some(Decimal::percent(50u64)),
+        slippage_tolerance: Decimal::percent(1u64),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, hook(PAIR_A));
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    // LP is received from FARM1
+    deps.querier.with_token_balances(&[
+        (&LP.to_string(), &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(100u128))]),
+    ]);
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+
+    // same LP is bonded directly
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), hook(PAIR)).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: LP.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: FARM2.to_string(),
+                amount: Uint128::from(100u128),
+                msg: to_binary(&MirrorCw20HookMsg::bond {
+                    asset_token: TOKEN.to_string(),
+                    staker_addr: Some(USER1.to_string()),
+                    compound_rate: Some(Decimal::percent(50u64)),
+                }).unwrap(),
+            }).unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    // pair assets mismatch
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), hook(PAIR_B));
+    assert_eq!(res, Err(StdError::generic_err("pairs must have the same assets")));
+
+    // LP is converted to destination pair
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), hook(PAIR_A)).unwrap();
+    let provide_hook = ExecuteMsg::migrate_position_provide_hook {
+        staker_addr: USER1.to_string(),
+        prev_assets: [
+            Asset {
+                info: uusd.clone(),
+                amount: Uint128::zero(),
+            },
+            Asset {
+                info: token.clone(),
+                amount: Uint128::zero(),
+            },
+        ],
+        to_contract: FARM2.to_string(),
+        to_asset_token: None,
+        to_pair: PAIR_A.to_string(),
+        compound_rate: Some(Decimal::percent(50u64)),
+        slippage_tolerance: Decimal::percent(1u64),
+    };
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: LP.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: PAIR.to_string(),
+                    amount: Uint128::from(100u128),
+                    msg: to_binary(&TerraswapCw20HookMsg::WithdrawLiquidity {}).unwrap(),
+                }).unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&provide_hook).unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+
+    // withdrawn assets are provided to destination pair
+    deps.querier.with_token_balances(&[
+        (&TOKEN.to_string(), &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(98u128))]),
+        (&"uusd".to_string(), &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(102u128))]),
+    ]);
+    let res = execute(deps.as_mut(), env.clone(), info, provide_hook).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: PAIR_A.to_string(),
+                    amount: Uint128::from(98u128),
+                    expires: None,
+                }).unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: PAIR_A.to_string(),
+                msg: to_binary(&TerraswapExecuteMsg::ProvideLiquidity {
+                    assets: [
+                        Asset {
+                            info: uusd,
+                            amount: Uint128::from(102u128),
+                        },
+                        Asset {
+                            info: token,
+                            amount: Uint128::from(98u128),
+                        },
+                    ],
+                    slippage_tolerance: Some(Decimal::percent(1u64)),
+                    receiver: None,
+                }).unwrap(),
+                funds: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(102u128),
+                }],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::bond_hook {
+                    contract: FARM2.to_string(),
+                    asset_token: TOKEN.to_string(),
+                    staking_token: LP_A.to_string(),
+                    staker_addr: USER1.to_string(),
+                    prev_staking_token_amount: Uint128::zero(),
+                    compound_rate: Some(Decimal::percent(50u64)),
+                }).unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+
+    farm_store(&mut deps.storage).remove(farm1_raw.as_slice());
+    farm_store(&mut deps.storage).remove(farm2_raw.as_slice());
+}
+
+fn test_farms(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+
+    let farm4 = FarmInfo {
+        contract: FARM4.to_string(),
+        farm_type: "astroport".to_string(),
+        asset_tokens: vec![TOKEN.to_string()],
+        staking_token: LP_A.to_string(),
+        pair_contract: Some(PAIR_A.to_string()),
+    };
+    let farm5 = FarmInfo {
+        contract: FARM5.to_string(),
+        farm_type: "pylon_liquid".to_string(),
+        asset_tokens: vec![TOKEN_B.to_string()],
+        staking_token: TOKEN_B.to_string(),
+        pair_contract: None,
+    };
+
+    // register, validate owner
+    let info = mock_info(USER1, &[]);
+    let msg = ExecuteMsg::register_farms {
+        farms: vec![farm5.clone(), farm4.clone()],
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    // success
+    let info = mock_info(TEST_CREATOR, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    let msg = QueryMsg::config {};
+    let res: ConfigInfo = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert!(res.allowlist.contains(&FARM4.to_string()));
+    assert!(res.allowlist.contains(&FARM5.to_string()));
+
+    let msg = QueryMsg::farm {
+        contract: FARM5.to_string(),
+    };
+    let res: FarmInfo = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res, farm5);
+
+    // paginate
+    let msg = QueryMsg::farms {
+        start_after: None,
+        limit: Some(1),
+    };
+    let res: FarmsResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.farms, vec![farm4.clone()]);
+
+    let msg = QueryMsg::farms {
+        start_after: Some(FARM4.to_string()),
+        limit: None,
+    };
+    let res: FarmsResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.farms, vec![farm5]);
+
+    // removing from allowlist also removes metadata
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::update_config {
+        insert_allowlist: None,
+        remove_allowlist: Some(vec![FARM5.to_string()]),
+        allow_all: None,
+        insert_factories: None,
+        remove_factories: None,
+        route_assets: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    // deregister
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::deregister_farms {
+        contracts: vec![FARM4.to_string()],
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    let msg = QueryMsg::farms {
+        start_after: None,
+        limit: None,
+    };
+    let res: FarmsResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.farms, vec![]);
+
+    let msg = QueryMsg::config {};
+    let res: ConfigInfo = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+    assert!(!res.allowlist.contains(&FARM4.to_string()));
+    assert!(!res.allowlist.contains(&FARM5.to_string()));
+}
+
+fn sign_intent(key: &SigningKey, intent: &ZapIntent) -> Binary {
+    let env = mock_env();
+    let payload = to_vec(&ZapIntentPayload {
+        chain_id: env.block.chain_id,
+        contract_addr: env.contract.address.to_string(),
+        intent: intent.clone(),
+    }).unwrap();
+    let signature: Signature = key.sign(&payload);
+    Binary::from(signature.as_ref())
+}
+
+fn test_zap_intent(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+    let key = SigningKey::from_bytes(&[7u8; 32]).unwrap();
+    let other_key = SigningKey::from_bytes(&[8u8; 32]).unwrap();
+    let pubkey = Binary::from(key.verifying_key().to_bytes().as_slice());
+    let other_pubkey = Binary::from(other_key.verifying_key().to_bytes().as_slice());
+    let owner = pubkey_to_address(&pubkey).unwrap();
+    assert_eq!(owner, "terra150rtrmj2f8vl9tem8qpfw36ylw5jg9j248sfme");
+
+    let intent = ZapIntent {
+        owner: owner.clone(),
+        nonce: 1u64,
+        expires_at: env.block.height + 10,
+        token: TOKEN.to_string(),
+        amount: Uint128::from(100_000_000u128),
+        relayer_fee: Some(Uint128::from(1_000_000u128)),
+        contract: FARM1.to_string(),
+        pair_asset: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        pair_asset_b: None,
+        belief_price: Some(Decimal::one()),
+        belief_price_b: None,
+        max_spread: Decimal::percent(1u64),
+        compound_rate: Some(Decimal::percent(55u64)),
+        asset_token: None,
+        swap_hints: None,
+    };
+
+    // signed by key of other address
+    let info = mock_info(USER2, &[]);
+    let msg = ExecuteMsg::zap_to_bond_intent {
+        intent: intent.clone(),
+        signature: sign_intent(&other_key, &intent),
+        pubkey: other_pubkey.clone(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("pubkey does not match owner")));
+
+    // signed by other key
+    let info = mock_info(USER2, &[]);
+    let msg = ExecuteMsg::zap_to_bond_intent {
+        intent: intent.clone(),
+        signature: sign_intent(&other_key, &intent),
+        pubkey: pubkey.clone(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("invalid signature")));
+
+    // only compressed pubkey derives account address
+    let info = mock_info(USER2, &[]);
+    let msg = ExecuteMsg::zap_to_bond_intent {
+        intent: intent.clone(),
+        signature: sign_intent(&key, &intent),
+        pubkey: Binary::from(vec![4u8; 65]),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("invalid pubkey")));
+
+    // intent is altered after signed
+    let mut altered_intent = intent.clone();
+    altered_intent.relayer_fee = Some(Uint128::from(50_000_000u128));
+    let info = mock_info(USER2, &[]);
+    let msg = ExecuteMsg::zap_to_bond_intent {
+        intent: altered_intent,
+        signature: sign_intent(&key, &intent),
+        pubkey: pubkey.clone(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("invalid signature")));
+
+    // expired
+    let mut expired_env = env.clone();
+    expired_env.block.height = intent.expires_at + 1;
+    let info = mock_info(USER2, &[]);
+    let msg = ExecuteMsg::zap_to_bond_intent {
+        intent: intent.clone(),
+        signature: sign_intent(&key, &intent),
+        pubkey: pubkey.clone(),
+    };
+    let res = execute(deps.as_mut(), expired_env, info, msg.clone());
+    assert_eq!(res, Err(StdError::generic_err("intent expired")));
+
+    // success, zap is the same as cw20 hook zap with amount after relayer fee
+    let info = mock_info(USER2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
+
+    let info = mock_info(TOKEN, &[]);
+    let hook_msg = ExecuteMsg::receive(Cw20ReceiveMsg {
+        sender: owner.clone(),
+        amount: Uint128::from(99_000_000u128),
+        msg: to_binary(&Cw20HookMsg::zap_to_bond {
+            contract: FARM1.to_string(),
+            pair_asset: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            pair_asset_b: None,
+            belief_price: Some(Decimal::one()),
+            belief_price_b: None,
+            max_spread: Decimal::percent(1u64),
+            compound_rate: Some(Decimal::percent(55u64)),
+            asset_token: None,
+            swap_hints: None,
+        })
+        .unwrap(),
+    });
+    let hook_res = execute(deps.as_mut(), env.clone(), info, hook_msg).unwrap();
+
+    let mut expected = vec![
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: owner.clone(),
+                recipient: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Uint128::from(100_000_000u128),
+            }).unwrap(),
+            funds: vec![],
+        }),
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: USER2.to_string(),
+                amount: Uint128::from(1_000_000u128),
+            }).unwrap(),
+            funds: vec![],
+        }),
+    ];
+    expected.extend(hook_res.messages.into_iter().map(|it| it.msg));
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        expected
+    );
+
+    let msg_nonce = QueryMsg::is_nonce_used {
+        owner,
+        nonce: 1u64,
+    };
+    let res: bool = from_binary(&query(deps.as_ref(), env.clone(), msg_nonce).unwrap()).unwrap();
+    assert!(res);
+
+    // replay
+    let info = mock_info(USER2, &[]);
+    let res = execute(deps.as_mut(), env, info, msg);
+    assert_eq!(res, Err(StdError::generic_err("nonce already used")));
+}
+
+fn test_dca(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+    let now = env.block.time.seconds();
+    let msg = ExecuteMsg::create_schedule {
+        contract: FARM1.to_string(),
+        pair_asset: AssetInfo::Token {
+            contract_addr: TOKEN.to_string(),
+        },
+        pair_asset_b: None,
+        asset_token: None,
+        amount_per_period: Uint128::from(10_100_000u128),
+        keeper_fee: Uint128::from(100_000u128),
+        period: 86400u64,
+        start_time: None,
+        compound_rate: Some(Decimal::percent(55u64)),
+        belief_price: Decimal::one(),
+        belief_price_b: None,
+        max_spread: Decimal::percent(1u64),
+    };
+
+    // no fund
+    let info = mock_info(USER1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_eq!(res, Err(StdError::generic_err("must send exactly one native token")));
+
+    // start_time in the past
+    let mut past_msg = msg.clone();
+    if let ExecuteMsg::create_schedule { start_time, .. } = &mut past_msg {
+        *start_time = Some(now - 1);
+    }
+    let info = mock_info(USER1, &[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(15_000_000u128),
+    }]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), past_msg);
+    assert_eq!(res, Err(StdError::generic_err("start_time must not be in the past")));
+
+    // spread is capped for unattended execution
+    let mut spread_msg = msg.clone();
+    if let ExecuteMsg::create_schedule { max_spread, .. } = &mut spread_msg {
+        *max_spread = Decimal::percent(6u64);
+    }
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), spread_msg);
+    assert_eq!(res, Err(StdError::generic_err("max_spread must be 0 to 0.05")));
+
+    // belief_price_b without pair_asset_b
+    let mut price_msg = msg.clone();
+    if let ExecuteMsg::create_schedule { belief_price_b, .. } = &mut price_msg {
+        *belief_price_b = Some(Decimal::one());
+    }
+    let res = execute(deps.as_mut(), env.clone(), info, price_msg);
+    assert_eq!(res, Err(StdError::generic_err("belief_price_b must be set only with pair_asset_b")));
+
+    // success
+    let info = mock_info(USER1, &[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(15_000_000u128),
+    }]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    // top up
+    let info = mock_info(USER1, &[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(10_000_000u128),
+    }]);
+    let msg = ExecuteMsg::deposit_schedule { id: 1u64 };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    let msg = QueryMsg::schedules {
+        owner: Some(USER1.to_string()),
+        start_after: None,
+        limit: None,
+    };
+    let res: SchedulesResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.schedules, vec![ScheduleInfo {
+        id: 1u64,
+        owner: USER1.to_string(),
+        contract: FARM1.to_string(),
+        pair_asset: AssetInfo::Token {
+            contract_addr: TOKEN.to_string(),
+        },
+        pair_asset_b: None,
+        asset_token: None,
+        denom: "uusd".to_string(),
+        amount_per_period: Uint128::from(10_100_000u128),
+        keeper_fee: Uint128::from(100_000u128),
+        period: 86400u64,
+        next_execution: now,
+        compound_rate: Some(Decimal::percent(55u64)),
+        belief_price: Decimal::one(),
+        belief_price_b: None,
+        max_spread: Decimal::percent(1u64),
+        balance: Uint128::from(25_000_000u128),
+    }]);
+
+    // keeper executes, each schedule is a submessage, unknown id is skipped
+    let info = mock_info(USER2, &[]);
+    let msg = ExecuteMsg::execute_schedules { ids: vec![1u64, 1u64, 9u64] };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    let hook_msg = ExecuteMsg::execute_schedule_hook {
+        id: 1u64,
+        keeper: USER2.to_string(),
+    };
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_error(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&hook_msg).unwrap(),
+                funds: vec![],
+            }),
+            1u64,
+        )]
+    );
+
+    // failed schedule does not fail the batch
+    let res = reply(deps.as_mut(), env.clone(), Reply {
+        id: 1u64,
+        result: ContractResult::Err("slippage".to_string()),
+    }).unwrap();
+    assert_eq!(res.attributes, vec![
+        attr("action", "execute_schedule_failed"),
+        attr("id", "1"),
+        attr("error", "slippage"),
+    ]);
+
+    let res = reply(deps.as_mut(), env.clone(), Reply {
+        id: 1u64,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: None,
+        }),
+    });
+    assert_eq!(res, Err(StdError::generic_err("unexpected reply")));
+
+    let res = reply(deps.as_mut(), env.clone(), Reply {
+        id: 9u64,
+        result: ContractResult::Err("slippage".to_string()),
+    });
+    assert_eq!(res, Err(StdError::generic_err("unknown reply id")));
+
+    // hook only by itself
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), hook_msg.clone());
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    // same as zap_to_bond with amount after keeper fee
+    let self_info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), env.clone(), self_info.clone(), hook_msg.clone()).unwrap();
+
+    let zap_info = mock_info(USER1, &[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(10_000_000u128),
+    }]);
+    let zap_msg = ExecuteMsg::zap_to_bond {
+        contract: FARM1.to_string(),
+        provide_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(10_000_000u128),
+        },
+        pair_asset: AssetInfo::Token {
+            contract_addr: TOKEN.to_string(),
+        },
+        pair_asset_b: None,
+        belief_price: Some(Decimal::one()),
+        belief_price_b: None,
+        max_spread: Decimal::percent(1u64),
+        compound_rate: Some(Decimal::percent(55u64)),
+        asset_token: None,
+        swap_hints: None,
+    };
+    let zap_res = execute(deps.as_mut(), env.clone(), zap_info, zap_msg).unwrap();
+
+    let mut expected = vec![CosmosMsg::Bank(BankMsg::Send {
+        to_address: USER2.to_string(),
+        amount: vec![Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100_000u128),
+        }],
+    })];
+    expected.extend(zap_res.messages.into_iter().map(|it| it.msg));
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        expected
+    );
+
+    // not due
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_eq!(res, Err(StdError::generic_err("no schedule to execute")));
+
+    let res = execute(deps.as_mut(), env.clone(), self_info.clone(), hook_msg.clone());
+    assert_eq!(res, Err(StdError::generic_err("schedule is not due")));
+
+    // late execution skips missed periods
+    let mut next_env = env.clone();
+    next_env.block.time = next_env.block.time.plus_seconds(86400u64 * 2 + 100);
+    let res = execute(deps.as_mut(), next_env.clone(), info.clone(), msg.clone());
+    assert!(res.is_ok());
+    let res = execute(deps.as_mut(), next_env.clone(), self_info, hook_msg);
+    assert!(res.is_ok());
+
+    // out of balance
+    next_env.block.time = next_env.block.time.plus_seconds(86400u64);
+    let res = execute(deps.as_mut(), next_env, info, msg);
+    assert_eq!(res, Err(StdError::generic_err("no schedule to execute")));
+
+    let msg = QueryMsg::schedule { id: 1u64 };
+    let res: ScheduleInfo = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.balance, Uint128::from(4_800_000u128));
+    assert_eq!(res.next_execution, now + 86400u64 * 3);
+
+    // cancel, validate owner
+    let info = mock_info(USER2, &[]);
+    let msg = ExecuteMsg::cancel_schedule { id: 1u64 };
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    let info = mock_info(USER1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Bank(BankMsg::Send {
+            to_address: USER1.to_string(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(4_800_000u128),
+            }],
+        })]
+    );
+
+    let msg = QueryMsg::schedules {
+        owner: None,
+        start_after: None,
+        limit: None,
+    };
+    let res: SchedulesResponse = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+    assert_eq!(res.schedules, vec![]);
+}
+
+fn test_limit_order(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let env = mock_env();
+    let uusd = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+    let token = AssetInfo::Token {
+        contract_addr: TOKEN.to_string(),
+    };
+
+    // order 1 with native token, price is 1 uusd per token
+    let info = mock_info(USER1, &[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(10_000_000u128),
+    }]);
+    let msg = ExecuteMsg::create_limit_order {
+        contract: FARM1.to_string(),
+        provide_asset: Asset {
+            info: uusd.clone(),
+            amount: Uint128::from(10_000_000u128),
+        },
+        pair_asset: token.clone(),
+        asset_token: None,
+        limit_price: Decimal::percent(90u64),
+        keeper_fee: Uint128::from(100_000u128),
+        compound_rate: Some(Decimal::percent(55u64)),
+        max_spread: Decimal::percent(1u64),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    // order 2 with token through cw20 hook
+    let info = mock_info(TOKEN, &[]);
+    let msg = ExecuteMsg::receive(Cw20ReceiveMsg {
+        sender: USER1.to_string(),
+        amount: Uint128::from(10_000_000u128),
+        msg: to_binary(&Cw20HookMsg::create_limit_order {
+            contract: FARM1.to_string(),
+            pair_asset: uusd.clone(),
+            asset_token: None,
+            limit_price: Decimal::percent(110u64),
+            keeper_fee: Uint128::from(100_000u128),
+            compound_rate: Some(Decimal::percent(55u64)),
+            max_spread: Decimal::percent(1u64),
+        }).unwrap(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    let msg = QueryMsg::limit_orders {
+        owner: Some(USER1.to_string()),
+        start_after: None,
+        limit: None,
+    };
+    let res: LimitOrdersResponse = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.orders.len(), 2);
+    assert_eq!(res.orders[1], LimitOrderInfo {
+        id: 2u64,
+        owner: USER1.to_string(),
+        contract: FARM1.to_string(),
+        provide_asset: Asset {
+            info: token.clone(),
+            amount: Uint128::from(10_000_000u128),
+        },
+        pair_asset: uusd.clone(),
+        asset_token: None,
+        limit_price: Decimal::percent(110u64),
+        keeper_fee: Uint128::from(100_000u128),
+        compound_rate: Some(Decimal::percent(55u64)),
+        max_spread: Decimal::percent(1u64),
+    });
+
+    // price not reached
+    let info = mock_info(USER2, &[]);
+    let msg = ExecuteMsg::execute_limit_order { id: 1u64 };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(StdError::generic_err("limit price not reached")));
+
+    // execute order 2, same as zap_to_bond with amount after keeper fee
+    let info = mock_info(USER2, &[]);
+    let msg = ExecuteMsg::execute_limit_order { id: 2u64 };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let info = mock_info(TOKEN, &[]);
+    let zap_msg = ExecuteMsg::receive(Cw20ReceiveMsg {
+        sender: USER1.to_string(),
+        amount: Uint128::from(9_900_000u128),
+        msg: to_binary(&Cw20HookMsg::zap_to_bond {
+            contract: FARM1.to_string(),
+            pair_asset: uusd.clone(),
+            pair_asset_b: None,
+            belief_price: Some(Decimal::percent(110u64)),
+            belief_price_b: None,
+            max_spread: Decimal::percent(1u64),
+            compound_rate: Some(Decimal::percent(55u64)),
+            asset_token: None,
+            swap_hints: None,
+        }).unwrap(),
+    });
+    let zap_res = execute(deps.as_mut(), env.clone(), info, zap_msg).unwrap();
+
+    let mut expected = vec![CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: TOKEN.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: USER2.to_string(),
+            amount: Uint128::from(100_000u128),
+        }).unwrap(),
+        funds: vec![],
+    })];
+    expected.extend(zap_res.messages.into_iter().map(|it| it.msg));
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        expected
+    );
+
+    // executed order is removed
+    let info = mock_info(USER2, &[]);
+    let msg = ExecuteMsg::execute_limit_order { id: 2u64 };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_err());
+
+    // cancel order 1, validate owner
+    let info = mock_info(USER2, &[]);
+    let msg = ExecuteMsg::cancel_limit_order { id: 1u64 };
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    let info = mock_info(USER1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Bank(BankMsg::Send {
+            to_address: USER1.to_string(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(10_000_000u128),
+            }],
+        })]
+    );
+
+    let msg = QueryMsg::limit_orders {
+        owner: None,
+        start_after: None,
+        limit: None,
+    };
+    let res: LimitOrdersResponse = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+    assert_eq!(res.orders, vec![]);
 }
@@ -2,7 +2,7 @@ use std::env::current_dir;
 use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
-use spectrum_protocol::staker_single_asset::{ConfigInfo, ExecuteMsg, QueryMsg};
+use spectrum_protocol::staker_single_asset::{BondHook, ConfigInfo, ExecuteMsg, QueryMsg, SimulateZapToBondResponse};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -11,6 +11,8 @@ fn main() {
     remove_schemas(&out_dir).unwrap();
 
     export_schema(&schema_for!(ConfigInfo), &out_dir);
+    export_schema(&schema_for!(BondHook), &out_dir);
+    export_schema(&schema_for!(SimulateZapToBondResponse), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
 }
@@ -1,16 +1,19 @@
 use std::collections::HashSet;
 use std::iter::FromIterator;
 
-use crate::state::{config_store, read_config, Config};
+use crate::state::{bond_hook_store, config_store, read_bond_hook, read_config, Config};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{attr, to_binary, Binary, CanonicalAddr, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128, WasmMsg, Addr};
 use cw20::{Cw20ExecuteMsg};
+use spectrum_protocol::gov::Cw20HookMsg as GovCw20HookMsg;
 use spectrum_protocol::pylon_liquid_farm::Cw20HookMsg as PylonLiquidCw20HookMsg;
-use spectrum_protocol::staker_single_asset::{ConfigInfo, ExecuteMsg, MigrateMsg, QueryMsg, SwapOperation};
+use spectrum_protocol::spec_farm::Cw20HookMsg as SpecCw20HookMsg;
+use spectrum_protocol::staker::{FarmInfo, QueryMsg as StakerQueryMsg};
+use spectrum_protocol::staker_single_asset::{BondHook, ConfigInfo, ExecuteMsg, MigrateMsg, QueryMsg, SimulateZapToBondResponse, SwapOperation};
 use terraswap::asset::{Asset, AssetInfo};
 use terraswap::pair::{ExecuteMsg as PairExecuteMsg, Cw20HookMsg as PairCw20HookMsg};
-use terraswap::querier::{query_balance, query_token_balance};
+use terraswap::querier::{query_balance, query_token_balance, simulate};
 
 // max slippage tolerance is 0.5
 fn validate_slippage(slippage_tolerance: Decimal) -> StdResult<()> {
@@ -21,13 +24,23 @@ fn validate_slippage(slippage_tolerance: Decimal) -> StdResult<()> {
     }
 }
 
-// validate contract with allowlist
-fn validate_contract(contract: CanonicalAddr, allowlist: &HashSet<CanonicalAddr>) -> StdResult<()> {
-    if !allowlist.contains(&contract) {
-        Err(StdError::generic_err("not allowed"))
-    } else {
-        Ok(())
+// validate contract with allowlist, or single asset farm registered in farm_registry
+fn validate_contract(deps: Deps, contract: CanonicalAddr, config: &Config) -> StdResult<()> {
+    if config.allowlist.contains(&contract) {
+        return Ok(());
+    }
+    if let Some(farm_registry) = &config.farm_registry {
+        let farm: StdResult<FarmInfo> = deps.querier.query_wasm_smart(
+            deps.api.addr_humanize(farm_registry)?,
+            &StakerQueryMsg::farm {
+                contract: deps.api.addr_humanize(&contract)?.to_string(),
+            },
+        );
+        if let Ok(FarmInfo { pair_contract: None, .. }) = farm {
+            return Ok(());
+        }
     }
+    Err(StdError::generic_err("not allowed"))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -46,6 +59,11 @@ pub fn instantiate(
     config_store(deps.storage).save(&Config {
         owner: deps.api.addr_canonicalize(&msg.owner)?,
         allowlist: HashSet::from_iter(allowlist),
+        farm_registry: if let Some(farm_registry) = msg.farm_registry {
+            Some(deps.api.addr_canonicalize(&farm_registry)?)
+        } else {
+            None
+        },
     })?;
     Ok(Response::default())
 }
@@ -90,7 +108,12 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
         ExecuteMsg::update_config {
             insert_allowlist,
             remove_allowlist,
-        } => update_config(deps, info, insert_allowlist, remove_allowlist),
+            farm_registry,
+        } => update_config(deps, info, insert_allowlist, remove_allowlist, farm_registry),
+        ExecuteMsg::set_bond_hook {
+            contract,
+            bond_hook,
+        } => set_bond_hook(deps, info, contract, bond_hook),
     }
 }
 
@@ -167,24 +190,39 @@ fn swap_operation(
     let amount = provide_asset.amount.checked_sub(tax)?;
     match splitted {
         None => {
-            let contract_addr = match provide_asset.info {
-                AssetInfo::Token { contract_addr } => contract_addr,
-                AssetInfo::NativeToken { .. } => return Err(StdError::generic_err("native token not supported")),
+            let contract_raw = deps.api.addr_canonicalize(&contract)?;
+            let bond_msg = match read_bond_hook(deps.storage, &contract_raw)? {
+                BondHook::farm_bond {} => to_binary(&PylonLiquidCw20HookMsg::bond {
+                    staker_addr: Some(staker_addr),
+                    compound_rate,
+                })?,
+                BondHook::spec_farm_bond { asset_token } => to_binary(&SpecCw20HookMsg::bond {
+                    staker_addr: Some(staker_addr),
+                    asset_token,
+                })?,
+                BondHook::gov_stake { days } => to_binary(&GovCw20HookMsg::stake_tokens {
+                    staker_addr: Some(staker_addr),
+                    days,
+                })?,
             };
-            Ok(Response::new().add_message(
-                CosmosMsg::Wasm(WasmMsg::Execute {
+            let message = match provide_asset.info {
+                AssetInfo::Token { contract_addr } => CosmosMsg::Wasm(WasmMsg::Execute {
                     contract_addr,
                     msg: to_binary(&Cw20ExecuteMsg::Send {
                         amount,
                         contract,
-                        msg: to_binary(&PylonLiquidCw20HookMsg::bond {
-                            staker_addr: Some(staker_addr),
-                            compound_rate,
-                        })?,
+                        msg: bond_msg,
                     })?,
                     funds: vec![],
-                })
-            ))
+                }),
+                // native asset is sent as funds with the same bond message
+                AssetInfo::NativeToken { denom } => CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: contract,
+                    msg: bond_msg,
+                    funds: vec![Coin { denom, amount }],
+                }),
+            };
+            Ok(Response::new().add_message(message))
         },
         Some((swap, tails)) => {
             let prev_balance = query_asset_balance(
@@ -258,7 +296,7 @@ fn zap_to_bond(
     let config = read_config(deps.storage)?;
     let contract_raw = deps.api.addr_canonicalize(contract.as_str())?;
 
-    validate_contract(contract_raw, &config.allowlist)?;
+    validate_contract(deps.as_ref(), contract_raw, &config)?;
 
     if let AssetInfo::Token { .. } = provide_asset.info {
         return Err(StdError::generic_err("not support provide_asset as token"));
@@ -281,6 +319,7 @@ fn update_config(
     info: MessageInfo,
     insert_allowlist: Option<Vec<String>>,
     remove_allowlist: Option<Vec<String>>,
+    farm_registry: Option<String>,
 ) -> StdResult<Response> {
     let mut config = read_config(deps.storage)?;
 
@@ -296,22 +335,98 @@ fn update_config(
 
     if let Some(remove_allowlist) = remove_allowlist {
         for contract in remove_allowlist.iter() {
-            config.allowlist.remove(&deps.api.addr_canonicalize(contract)?);
+            let contract_raw = deps.api.addr_canonicalize(contract)?;
+            config.allowlist.remove(&contract_raw);
+            bond_hook_store(deps.storage).remove(contract_raw.as_slice());
         }
     }
 
+    if let Some(farm_registry) = farm_registry {
+        config.farm_registry = Some(deps.api.addr_canonicalize(&farm_registry)?);
+    }
+
     config_store(deps.storage).save(&config)?;
 
     Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
 }
 
+fn set_bond_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: String,
+    bond_hook: Option<BondHook>,
+) -> StdResult<Response> {
+    let config = read_config(deps.storage)?;
+
+    if deps.api.addr_canonicalize(info.sender.as_str())? != config.owner {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let contract_raw = deps.api.addr_canonicalize(&contract)?;
+    if let Some(bond_hook) = bond_hook {
+        bond_hook_store(deps.storage).save(contract_raw.as_slice(), &bond_hook)?;
+    } else {
+        bond_hook_store(deps.storage).remove(contract_raw.as_slice());
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_bond_hook"),
+        attr("contract", contract),
+    ]))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::config {} => to_binary(&query_config(deps)?),
+        QueryMsg::bond_hook { contract } => to_binary(&query_bond_hook(deps, contract)?),
+        QueryMsg::simulate_zap_to_bond {
+            provide_asset,
+            swap_operations,
+        } => to_binary(&simulate_zap_to_bond(deps, provide_asset, swap_operations)?),
     }
 }
 
+fn query_bond_hook(deps: Deps, contract: String) -> StdResult<BondHook> {
+    read_bond_hook(deps.storage, &deps.api.addr_canonicalize(&contract)?)
+}
+
+// simulate swap operations with the same tax deduction as swap_operation
+fn simulate_zap_to_bond(
+    deps: Deps,
+    provide_asset: Asset,
+    swap_operations: Vec<SwapOperation>,
+) -> StdResult<SimulateZapToBondResponse> {
+    let mut asset = provide_asset;
+    let mut belief_prices: Vec<Decimal> = vec![];
+    for swap in swap_operations {
+        let tax = asset.compute_tax(&deps.querier)?;
+        let offer_amount = asset.amount.checked_sub(tax)?;
+        let simulation = simulate(
+            &deps.querier,
+            deps.api.addr_validate(&swap.pair_contract)?,
+            &Asset {
+                info: asset.info,
+                amount: offer_amount,
+            },
+        )?;
+        belief_prices.push(Decimal::from_ratio(
+            offer_amount,
+            simulation.return_amount + simulation.commission_amount,
+        ));
+        asset = Asset {
+            info: swap.asset_info,
+            amount: simulation.return_amount,
+        };
+    }
+    let tax = asset.compute_tax(&deps.querier)?;
+
+    Ok(SimulateZapToBondResponse {
+        receive_amount: asset.amount.checked_sub(tax)?,
+        belief_prices,
+    })
+}
+
 pub fn query_config(deps: Deps) -> StdResult<ConfigInfo> {
     let config = read_config(deps.storage)?;
     let resp = ConfigInfo {
@@ -321,6 +436,11 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigInfo> {
             .into_iter()
             .map(|w| deps.api.addr_humanize(&w).map(|addr| addr.to_string()))
             .collect::<StdResult<Vec<String>>>()?,
+        farm_registry: if let Some(farm_registry) = config.farm_registry {
+            Some(deps.api.addr_humanize(&farm_registry)?.to_string())
+        } else {
+            None
+        },
     };
 
     Ok(resp)
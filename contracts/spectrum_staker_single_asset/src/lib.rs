@@ -1,2 +1,8 @@
 pub mod contract;
 pub mod state;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(test)]
+mod mock_querier;
@@ -0,0 +1,149 @@
+#![allow(non_camel_case_types)]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{from_binary, from_slice, to_binary, BalanceResponse, BankQuery, Coin, ContractResult, Decimal, OwnedDeps, Querier, QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery};
+use cw20::BalanceResponse as Cw20BalanceResponse;
+use spectrum_protocol::staker::FarmInfo;
+use std::collections::HashMap;
+use terra_cosmwasm::{TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper, TerraRoute};
+
+/// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
+/// this uses our CustomQuerier.
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let contract_addr = MOCK_CONTRACT_ADDR.to_string();
+    let custom_querier: WasmMockQuerier =
+        WasmMockQuerier::new(MockQuerier::new(&[(&contract_addr, contract_balance)]));
+
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: custom_querier,
+    }
+}
+
+pub struct WasmMockQuerier {
+    base: MockQuerier<TerraQueryWrapper>,
+    balances: HashMap<String, HashMap<String, Uint128>>,
+    tax_rate: Decimal,
+    tax_caps: HashMap<String, Uint128>,
+    farms: HashMap<String, FarmInfo>,
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        // MockQuerier doesn't support Custom, so we ignore it completely here
+        let request: QueryRequest<TerraQueryWrapper> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.execute_query(&request)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+enum MockQueryMsg {
+    balance {
+        address: String,
+    },
+    farm {
+        contract: String,
+    },
+}
+
+impl WasmMockQuerier {
+    pub fn execute_query(&self, request: &QueryRequest<TerraQueryWrapper>) -> QuerierResult {
+        match &request {
+            QueryRequest::Bank(BankQuery::Balance { address, denom }) => {
+                let amount = self.read_balance(denom, address);
+                SystemResult::Ok(ContractResult::from(to_binary(&BalanceResponse {
+                    amount: Coin { denom: denom.clone(), amount }
+                })))
+            },
+            QueryRequest::Custom(TerraQueryWrapper { route, query_data }) => {
+                if &TerraRoute::Treasury == route {
+                    match query_data {
+                        TerraQuery::TaxRate {} => {
+                            let res = TaxRateResponse {
+                                rate: self.tax_rate,
+                            };
+                            SystemResult::Ok(ContractResult::from(to_binary(&res)))
+                        }
+                        TerraQuery::TaxCap { denom } => {
+                            let cap = self.tax_caps.get(denom).copied().unwrap_or_default();
+                            let res = TaxCapResponse { cap };
+                            SystemResult::Ok(ContractResult::from(to_binary(&res)))
+                        }
+                        _ => panic!("DO NOT ENTER HERE"),
+                    }
+                } else {
+                    panic!("DO NOT ENTER HERE")
+                }
+            }
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                match from_binary(msg).unwrap() {
+                    MockQueryMsg::balance { address } => {
+                        let balance = self.read_balance(contract_addr, &address);
+                        SystemResult::Ok(ContractResult::from(to_binary(&Cw20BalanceResponse {
+                            balance,
+                        })))
+                    },
+                    MockQueryMsg::farm { contract } => match self.farms.get(&contract) {
+                        Some(v) => SystemResult::Ok(ContractResult::from(to_binary(&v))),
+                        None => SystemResult::Ok(ContractResult::Err("farm not found".to_string())),
+                    },
+                }
+            }
+            _ => self.base.handle_query(request),
+        }
+    }
+}
+
+impl WasmMockQuerier {
+    #![allow(dead_code)]
+    pub fn new(base: MockQuerier<TerraQueryWrapper>) -> Self {
+        WasmMockQuerier {
+            base,
+            balances: HashMap::new(),
+            tax_rate: Decimal::zero(),
+            tax_caps: HashMap::new(),
+            farms: HashMap::new(),
+        }
+    }
+
+    // balance of token contract or native denom
+    pub fn with_balance(&mut self, token: &str, address: &str, amount: Uint128) {
+        self.balances
+            .entry(token.to_string())
+            .or_default()
+            .insert(address.to_string(), amount);
+    }
+
+    fn read_balance(&self, token: &str, address: &str) -> Uint128 {
+        self.balances
+            .get(token)
+            .and_then(|balances| balances.get(address))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn with_tax(&mut self, rate: Decimal, caps: &[(&String, &Uint128)]) {
+        self.tax_rate = rate;
+        self.tax_caps = caps.iter().map(|(denom, cap)| (denom.to_string(), **cap)).collect();
+    }
+
+    // configure farms registered in farm registry
+    pub fn with_farms(&mut self, farms: &[FarmInfo]) {
+        for farm in farms.iter() {
+            self.farms.insert(farm.contract.clone(), farm.clone());
+        }
+    }
+}
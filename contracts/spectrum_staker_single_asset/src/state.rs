@@ -4,7 +4,8 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::{CanonicalAddr, StdResult, Storage};
-use cosmwasm_storage::{singleton, singleton_read, Singleton};
+use cosmwasm_storage::{bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, Singleton};
+use spectrum_protocol::staker_single_asset::BondHook;
 
 static KEY_CONFIG: &[u8] = b"config";
 
@@ -12,12 +13,27 @@ static KEY_CONFIG: &[u8] = b"config";
 pub struct Config {
     pub owner: CanonicalAddr,
     pub allowlist: HashSet<CanonicalAddr>,
+    #[serde(default)] pub farm_registry: Option<CanonicalAddr>,
 }
 
-pub fn config_store(storage: &mut dyn Storage) -> Singleton<Config> {
+pub fn config_store(storage: &mut dyn Storage) -> Singleton<'_, Config> {
     singleton(storage, KEY_CONFIG)
 }
 
 pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
     singleton_read(storage, KEY_CONFIG).load()
 }
+
+static PREFIX_BOND_HOOK: &[u8] = b"bond_hook";
+
+pub fn bond_hook_store(storage: &mut dyn Storage) -> Bucket<'_, BondHook> {
+    bucket(storage, PREFIX_BOND_HOOK)
+}
+
+pub fn bond_hook_read(storage: &dyn Storage) -> ReadonlyBucket<'_, BondHook> {
+    bucket_read(storage, PREFIX_BOND_HOOK)
+}
+
+pub fn read_bond_hook(storage: &dyn Storage, contract: &CanonicalAddr) -> StdResult<BondHook> {
+    Ok(bond_hook_read(storage).may_load(contract.as_slice())?.unwrap_or_default())
+}
@@ -0,0 +1,347 @@
+use crate::contract::{execute, instantiate};
+use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{to_binary, Coin, CosmosMsg, Decimal, OwnedDeps, StdError, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+use spectrum_protocol::gov::Cw20HookMsg as GovCw20HookMsg;
+use spectrum_protocol::pylon_liquid_farm::Cw20HookMsg as PylonLiquidCw20HookMsg;
+use spectrum_protocol::staker::FarmInfo;
+use spectrum_protocol::staker_single_asset::{BondHook, ConfigInfo, ExecuteMsg, SwapOperation};
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::pair::ExecuteMsg as PairExecuteMsg;
+
+const TEST_CREATOR: &str = "creator";
+const USER1: &str = "user1";
+const TOKEN: &str = "token";
+const PAIR: &str = "pair0001";
+const FARM: &str = "farm1";
+const GOV: &str = "gov";
+const FARM_REGISTRY: &str = "farm_registry";
+const SINGLE_FARM: &str = "single_farm";
+const PAIR_FARM: &str = "pair_farm";
+const UNKNOWN_FARM: &str = "unknown_farm";
+
+#[test]
+fn test() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    test_config(&mut deps);
+    test_zap_to_bond_token(&mut deps);
+    test_zap_to_bond_native(&mut deps);
+    test_farm_registry(&mut deps);
+}
+
+fn test_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let msg = ConfigInfo {
+        owner: TEST_CREATOR.to_string(),
+        allowlist: vec![FARM.to_string(), GOV.to_string()],
+        farm_registry: None,
+    };
+    let res = instantiate(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg);
+    assert!(res.is_ok());
+
+    let msg = ExecuteMsg::set_bond_hook {
+        contract: GOV.to_string(),
+        bond_hook: Some(BondHook::gov_stake { days: Some(30u64) }),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg);
+    assert!(res.is_ok());
+}
+
+fn uusd_asset(amount: u128) -> Asset {
+    Asset {
+        info: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        amount: Uint128::from(amount),
+    }
+}
+
+fn test_zap_to_bond_token(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let swap_operations = vec![SwapOperation {
+        pair_contract: PAIR.to_string(),
+        asset_info: AssetInfo::Token {
+            contract_addr: TOKEN.to_string(),
+        },
+        belief_price: None,
+    }];
+
+    // swap uusd to token, then bond by hook
+    let msg = ExecuteMsg::zap_to_bond {
+        contract: FARM.to_string(),
+        provide_asset: uusd_asset(10100u128),
+        swap_operations: swap_operations.clone(),
+        max_spread: Decimal::percent(1),
+        compound_rate: Some(Decimal::percent(50)),
+    };
+    let info = mock_info(USER1, &[Coin::new(10100u128, "uusd")]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: PAIR.to_string(),
+                msg: to_binary(&PairExecuteMsg::Swap {
+                    to: None,
+                    max_spread: Some(Decimal::percent(1)),
+                    belief_price: None,
+                    offer_asset: uusd_asset(10000u128),
+                })
+                .unwrap(),
+                funds: vec![Coin::new(10000u128, "uusd")],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::zap_to_bond_hook {
+                    contract: FARM.to_string(),
+                    prev_asset: Asset {
+                        info: AssetInfo::Token {
+                            contract_addr: TOKEN.to_string(),
+                        },
+                        amount: Uint128::zero(),
+                    },
+                    staker_addr: USER1.to_string(),
+                    swap_operations: vec![],
+                    max_spread: Decimal::percent(1),
+                    compound_rate: Some(Decimal::percent(50)),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+
+    // hook can be called by contract only
+    let hook_msg = |contract: &str| ExecuteMsg::zap_to_bond_hook {
+        contract: contract.to_string(),
+        prev_asset: Asset {
+            info: AssetInfo::Token {
+                contract_addr: TOKEN.to_string(),
+            },
+            amount: Uint128::zero(),
+        },
+        staker_addr: USER1.to_string(),
+        swap_operations: vec![],
+        max_spread: Decimal::percent(1),
+        compound_rate: Some(Decimal::percent(50)),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(USER1, &[]), hook_msg(FARM));
+    assert_eq!(res, Err(StdError::generic_err("unauthorized")));
+
+    // swapped token is sent to farm with default farm bond
+    deps.querier.with_balance(TOKEN, MOCK_CONTRACT_ADDR, Uint128::from(9900u128));
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), hook_msg(FARM)).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: FARM.to_string(),
+                amount: Uint128::from(9900u128),
+                msg: to_binary(&PylonLiquidCw20HookMsg::bond {
+                    staker_addr: Some(USER1.to_string()),
+                    compound_rate: Some(Decimal::percent(50)),
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    // gov uses stake_tokens hook
+    let res = execute(deps.as_mut(), mock_env(), info, hook_msg(GOV)).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: GOV.to_string(),
+                amount: Uint128::from(9900u128),
+                msg: to_binary(&GovCw20HookMsg::stake_tokens {
+                    staker_addr: Some(USER1.to_string()),
+                    days: Some(30u64),
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+}
+
+fn test_zap_to_bond_native(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let uluna = AssetInfo::NativeToken {
+        denom: "uluna".to_string(),
+    };
+
+    // swap uusd to uluna, then bond by hook
+    let msg = ExecuteMsg::zap_to_bond {
+        contract: FARM.to_string(),
+        provide_asset: uusd_asset(10100u128),
+        swap_operations: vec![SwapOperation {
+            pair_contract: PAIR.to_string(),
+            asset_info: uluna.clone(),
+            belief_price: None,
+        }],
+        max_spread: Decimal::percent(1),
+        compound_rate: None,
+    };
+    let info = mock_info(USER1, &[Coin::new(10100u128, "uusd")]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    let hook_msg = ExecuteMsg::zap_to_bond_hook {
+        contract: FARM.to_string(),
+        prev_asset: Asset {
+            info: uluna,
+            amount: Uint128::zero(),
+        },
+        staker_addr: USER1.to_string(),
+        swap_operations: vec![],
+        max_spread: Decimal::percent(1),
+        compound_rate: None,
+    };
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: PAIR.to_string(),
+                msg: to_binary(&PairExecuteMsg::Swap {
+                    to: None,
+                    max_spread: Some(Decimal::percent(1)),
+                    belief_price: None,
+                    offer_asset: uusd_asset(10000u128),
+                })
+                .unwrap(),
+                funds: vec![Coin::new(10000u128, "uusd")],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&hook_msg).unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+
+    // swapped uluna is sent as funds with the bond message
+    deps.querier.with_balance("uluna", MOCK_CONTRACT_ADDR, Uint128::from(5000u128));
+    let res = execute(deps.as_mut(), mock_env(), mock_info(MOCK_CONTRACT_ADDR, &[]), hook_msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: FARM.to_string(),
+            msg: to_binary(&PylonLiquidCw20HookMsg::bond {
+                staker_addr: Some(USER1.to_string()),
+                compound_rate: None,
+            })
+            .unwrap(),
+            funds: vec![Coin::new(5000u128, "uluna")],
+        })]
+    );
+
+    // provide asset is bonded directly without swap
+    let msg = ExecuteMsg::zap_to_bond {
+        contract: GOV.to_string(),
+        provide_asset: uusd_asset(10100u128),
+        swap_operations: vec![],
+        max_spread: Decimal::percent(1),
+        compound_rate: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: GOV.to_string(),
+            msg: to_binary(&GovCw20HookMsg::stake_tokens {
+                staker_addr: Some(USER1.to_string()),
+                days: Some(30u64),
+            })
+            .unwrap(),
+            funds: vec![Coin::new(10000u128, "uusd")],
+        })]
+    );
+}
+
+fn test_farm_registry(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    deps.querier.with_farms(&[
+        FarmInfo {
+            contract: SINGLE_FARM.to_string(),
+            farm_type: "pylon_liquid".to_string(),
+            asset_tokens: vec![TOKEN.to_string()],
+            staking_token: TOKEN.to_string(),
+            pair_contract: None,
+        },
+        FarmInfo {
+            contract: PAIR_FARM.to_string(),
+            farm_type: "astroport".to_string(),
+            asset_tokens: vec![TOKEN.to_string()],
+            staking_token: "lp_token".to_string(),
+            pair_contract: Some(PAIR.to_string()),
+        },
+    ]);
+
+    let zap_msg = |contract: &str| ExecuteMsg::zap_to_bond {
+        contract: contract.to_string(),
+        provide_asset: uusd_asset(10100u128),
+        swap_operations: vec![SwapOperation {
+            pair_contract: PAIR.to_string(),
+            asset_info: AssetInfo::Token {
+                contract_addr: TOKEN.to_string(),
+            },
+            belief_price: None,
+        }],
+        max_spread: Decimal::percent(1),
+        compound_rate: None,
+    };
+    let info = mock_info(USER1, &[Coin::new(10100u128, "uusd")]);
+
+    // not in allowlist and no registry
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), zap_msg(SINGLE_FARM));
+    assert_eq!(res, Err(StdError::generic_err("not allowed")));
+
+    let msg = ExecuteMsg::update_config {
+        insert_allowlist: None,
+        remove_allowlist: None,
+        farm_registry: Some(FARM_REGISTRY.to_string()),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg);
+    assert!(res.is_ok());
+
+    // registered single asset farm
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), zap_msg(SINGLE_FARM));
+    assert!(res.is_ok());
+
+    // registered farm with pair is for LP staker
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), zap_msg(PAIR_FARM));
+    assert_eq!(res, Err(StdError::generic_err("not allowed")));
+
+    // not registered
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), zap_msg(UNKNOWN_FARM));
+    assert_eq!(res, Err(StdError::generic_err("not allowed")));
+
+    // allowlist still works with registry
+    let res = execute(deps.as_mut(), mock_env(), info, zap_msg(FARM));
+    assert!(res.is_ok());
+}
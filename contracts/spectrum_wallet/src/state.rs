@@ -21,7 +21,7 @@ pub struct Config {
     #[serde(default = "default_addr")] pub terraswap_factory: CanonicalAddr,
 }
 
-pub fn config_store(storage: &mut dyn Storage) -> Singleton<Config> {
+pub fn config_store(storage: &mut dyn Storage) -> Singleton<'_, Config> {
     singleton(storage, KEY_CONFIG)
 }
 
@@ -31,7 +31,7 @@ pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
 
 static KEY_STATE: &[u8] = b"state";
 
-pub fn state_store(storage: &mut dyn Storage) -> Singleton<StateInfo> {
+pub fn state_store(storage: &mut dyn Storage) -> Singleton<'_, StateInfo> {
     singleton(storage, KEY_STATE)
 }
 
@@ -62,7 +62,7 @@ impl RewardInfo {
     }
 }
 
-pub fn reward_store(storage: &mut dyn Storage) -> Bucket<RewardInfo> {
+pub fn reward_store(storage: &mut dyn Storage) -> Bucket<'_, RewardInfo> {
     bucket(storage, PREFIX_REWARD)
 }
 
@@ -17,12 +17,22 @@ pub struct InstantiateMsg {
     pub reward_contract_addr: String,
     /// the reward token contract address
     pub reward_token_addr: String,
+    /// the owner who can register more pools, default to the instantiator
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// the spectrum gov contract, SPEC locked there boosts rewards of depositors
+    #[serde(default)]
+    pub spectrum_gov: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Cw20HookMsg {
-    Deposit {},
+    Deposit {
+        /// the depositor, deposits without it are held by the generator
+        #[serde(default)]
+        account: Option<Addr>,
+    },
 }
 
 /// ## Description
@@ -35,13 +45,22 @@ pub enum ExecuteMsg {
     /// Withdrawal pending rewards
     UpdateRewards {},
     /// Sends rewards to the recipient
-    SendRewards { account: Addr, amount: Uint128 },
+    SendRewards {
+        account: Addr,
+        amount: Uint128,
+        /// the pool to send rewards from, can be omitted when only one pool is registered
+        #[serde(default)]
+        lp_token: Option<Addr>,
+    },
     /// Withdrawal the rewards
     Withdraw {
         /// the recipient for withdrawal
         account: Addr,
         /// the amount of withdraw
         amount: Uint128,
+        /// the pool to withdraw from, can be omitted when only one pool is registered
+        #[serde(default)]
+        lp_token: Option<Addr>,
     },
     /// Withdrawal the rewards
     EmergencyWithdraw {
@@ -49,6 +68,28 @@ pub enum ExecuteMsg {
         account: Addr,
         /// the amount of withdraw
         amount: Uint128,
+        /// the pool to withdraw from, can be omitted when only one pool is registered
+        #[serde(default)]
+        lp_token: Option<Addr>,
+    },
+    /// Registers another liquidity pool, only the owner can execute it
+    RegisterPool {
+        /// the pair contract address
+        pair_addr: String,
+        /// the liquidity pool token contract address
+        lp_token_addr: String,
+        /// the reward contract address
+        reward_contract_addr: String,
+        /// the reward token contract address
+        reward_token_addr: String,
+    },
+    /// Recalculates the boost of the depositor from its SPEC lock in spectrum gov, anyone can execute it
+    Kick {
+        /// the depositor
+        account: Addr,
+        /// the pool of the deposit, can be omitted when only one pool is registered
+        #[serde(default)]
+        lp_token: Option<Addr>,
     },
     /// the callback of type [`CallbackMsg`]
     Callback(CallbackMsg),
@@ -62,6 +103,8 @@ pub enum CallbackMsg {
     TransferLpTokensAfterWithdraw {
         /// the recipient
         account: Addr,
+        /// the liquidity pool token to transfer
+        lp_token: Addr,
         /// the previous lp balance for calculate withdraw amount
         prev_lp_balance: Uint128,
     },
@@ -75,19 +118,67 @@ pub enum QueryMsg {
     /// Returns the contract's configuration
     Config {},
     /// Returns the deposit amount
-    Deposit {},
-    /// Returns the balance of reward token
-    Reward {},
+    Deposit { lp_token: Option<String> },
+    /// Returns the reward amount held for the pool
+    Reward { lp_token: Option<String> },
     /// Returns the pending rewards
-    PendingToken {},
+    PendingToken { lp_token: Option<String> },
     /// Returns the reward token contract address
-    RewardInfo {},
+    RewardInfo { lp_token: Option<String> },
+    /// Returns the deposit and the pending rewards of a depositor
+    UserInfo {
+        lp_token: Option<String>,
+        account: String,
+    },
+}
+
+/// ## Description
+/// This structure describes the user info query response.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserInfoResponse {
+    /// the deposit amount of the depositor
+    pub amount: Uint128,
+    /// the rewards of the depositor, including rewards still pending in the reward contract
+    pub pending_reward: Uint128,
+    /// the extra deposit weight from SPEC locked in spectrum gov
+    pub boost_amount: Uint128,
+}
+
+/// ## Description
+/// This structure describes a liquidity pool served by the proxy.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolResponse {
+    /// the pair contract address
+    pub pair_addr: String,
+    /// the liquidity pool token contract address
+    pub lp_token_addr: String,
+    /// the reward contract address
+    pub reward_contract_addr: String,
+    /// the reward token contract address
+    pub reward_token_addr: String,
 }
 
-pub type ConfigResponse = InstantiateMsg;
+/// ## Description
+/// This structure describes the config query response.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    /// the generator contract address
+    pub generator_contract_addr: String,
+    /// the owner who can register more pools
+    pub owner: String,
+    /// the spectrum gov contract for boosts
+    pub spectrum_gov: Option<String>,
+    /// the registered pools
+    pub pools: Vec<PoolResponse>,
+}
 
 /// ## Description
 /// This structure describes a migration message.
-/// We currently take no arguments for migrations
+/// The single pool config is moved to the pool list, and `owner` can register more pools.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    pub owner: String,
+    /// the spectrum gov contract for boosts
+    #[serde(default)]
+    pub spectrum_gov: Option<String>,
+}
@@ -2,6 +2,9 @@ use cosmwasm_std::{Decimal, Uint128};
 use cw20::Cw20ReceiveMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use crate::fee::{DepositFeeTier, FeeRecipient};
+use crate::incentive::{IncentiveExecuteMsg, IncentiveItem, IncentiveRewardItem};
+use crate::staker::ZapOutParams;
 
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -22,6 +25,12 @@ pub struct ConfigInfo {
     pub anchor_market: String,
     pub aust_token: String,
     pub pair_contract: String,
+    pub spectrum_staker: Option<String>,
+    #[serde(default)] pub fee_recipients: Vec<FeeRecipient>, // empty to split by community/platform/controller fee
+    #[serde(default)] pub deposit_fee_tiers: Vec<DepositFeeTier>,
+    #[serde(default)] pub deposit_fee_exempt: Vec<String>,
+    #[serde(default)] pub withdraw_fee: Decimal, // fee on unbond right after deposit, decays to zero
+    #[serde(default)] pub withdraw_fee_period: u64, // seconds after deposit_time until withdraw_fee is zero
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -35,16 +44,25 @@ pub enum ExecuteMsg {
         platform_fee: Option<Decimal>,
         controller_fee: Option<Decimal>,
         deposit_fee: Option<Decimal>,
+        spectrum_staker: Option<String>,
     },
-    // Unbond lp token
+    // Unbond lp token; if zap_out is given, LP is swapped to target_asset via spectrum_staker
     unbond {
         asset_token: String,
         amount: Uint128,
+        zap_out: Option<ZapOutParams>,
+    },
+    // Unbond lp token of staker_addr by spectrum_staker, LP is sent to spectrum_staker
+    unbond_for {
+        staker_addr: String,
+        asset_token: String,
+        amount: Uint128,
     },
     register_asset {
         asset_token: String,
         staking_token: String,
         weight: u32,
+        pair_contract: Option<String>, // ASTRO pair of staking_token, None to use config pair_contract
     },
     // Withdraw rewards
     withdraw {
@@ -56,13 +74,35 @@ pub enum ExecuteMsg {
     stake {
         asset_token: String,
     },
-    compound {},
+    // If the asset token is not given, then ASTRO pool is compounded
+    compound {
+        asset_token: Option<String>,
+    },
     update_bond {
         asset_token: String,
         amount_to_stake: Uint128,
         amount_to_auto: Uint128,
     },
-    send_fee {},
+    send_fee {
+        asset_token: Option<String>,
+        // aUST balance before the compound, only the aUST above it is fee of the pool
+        #[serde(default)]
+        previous_aust_balance: Option<Uint128>,
+    },
+    update_fee_config {
+        fee_recipients: Option<Vec<FeeRecipient>>,
+        deposit_fee_tiers: Option<Vec<DepositFeeTier>>,
+        insert_fee_exempt: Option<Vec<String>>,
+        remove_fee_exempt: Option<Vec<String>>,
+        withdraw_fee: Option<Decimal>,
+        withdraw_fee_period: Option<u64>,
+    },
+    // override fee recipients for a pool, None to use config
+    update_pool_fee_recipients {
+        asset_token: String,
+        fee_recipients: Option<Vec<FeeRecipient>>,
+    },
+    incentive(IncentiveExecuteMsg),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -72,6 +112,10 @@ pub enum Cw20HookMsg {
         asset_token: String,
         compound_rate: Option<Decimal>,
     },
+    add_incentive {
+        asset_token: String,
+        duration: u64,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -84,6 +128,11 @@ pub enum QueryMsg {
         staker_addr: String,
     },
     state {},
+    // get cumulative bond/unbond/withdraw stats and gain in LP terms
+    user_stats {
+        staker_addr: String,
+    },
+    incentive_tokens {},
 }
 
 // We define a custom struct for each query response
@@ -104,6 +153,9 @@ pub struct PoolItem {
     pub farm_share_index: Decimal,       // per stake bond share
     pub stake_spec_share_index: Decimal, // per stake bond share
     pub auto_spec_share_index: Decimal,  // per auto bond share
+    pub fee_recipients: Option<Vec<FeeRecipient>>,
+    pub pair_contract: Option<String>,
+    #[serde(default)] pub incentives: Vec<IncentiveItem>,
 }
 
 // We define a custom struct for each query response
@@ -130,6 +182,8 @@ pub struct RewardInfoResponseItem {
     pub pending_spec_reward: Uint128,
     pub deposit_amount: Option<Uint128>,
     pub deposit_time: Option<u64>,
+    pub withdraw_fee: Decimal, // fee rate if unbond now
+    #[serde(default)] pub incentive_rewards: Vec<IncentiveRewardItem>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
@@ -1,8 +1,10 @@
 use std::convert::TryFrom;
-use cosmwasm_std::{QuerierWrapper, StdError, StdResult, Uint128, Decimal};
+use cosmwasm_std::{to_binary, Api, CanonicalAddr, QuerierWrapper, StdError, StdResult, Uint128, Decimal};
+use cw20::Cw20ExecuteMsg;
 use terraswap::asset::{Asset};
 use terraswap::pair::PoolResponse;
 use terra_cosmwasm::TerraQuerier;
+use crate::staker::{Cw20HookMsg as StakerCw20HookMsg, ZapOutParams};
 
 pub fn compute_deposit_time(
     last_deposit_amount: Uint128,
@@ -51,3 +53,39 @@ pub fn compute_provide_after_swap(
 
     Ok(ask_reinvest_amt.multiply_ratio(offer_amount, ask_amount))
 }
+
+/// LP transfer on unbond: to staker, or to spectrum_staker to withdraw liquidity and swap to target_asset for staker
+pub fn unbond_lp_msg(
+    api: &dyn Api,
+    spectrum_staker: Option<&CanonicalAddr>,
+    staker_addr: String,
+    amount: Uint128,
+    zap_out: Option<ZapOutParams>,
+) -> StdResult<Cw20ExecuteMsg> {
+    let zap_out = match zap_out {
+        Some(zap_out) => zap_out,
+        None => {
+            return Ok(Cw20ExecuteMsg::Transfer {
+                recipient: staker_addr,
+                amount,
+            })
+        }
+    };
+    let spectrum_staker =
+        spectrum_staker.ok_or_else(|| StdError::generic_err("spectrum_staker is not set"))?;
+    Ok(Cw20ExecuteMsg::Send {
+        contract: api.addr_humanize(spectrum_staker)?.to_string(),
+        amount,
+        msg: to_binary(&StakerCw20HookMsg::zap_to_unbond {
+            sell_asset: zap_out.sell_asset,
+            sell_asset_b: zap_out.sell_asset_b,
+            target_asset: zap_out.target_asset,
+            belief_price: zap_out.belief_price,
+            belief_price_b: zap_out.belief_price_b,
+            max_spread: zap_out.max_spread,
+            swap_hints: zap_out.swap_hints,
+            staker_addr: Some(staker_addr),
+            min_receive: zap_out.min_receive,
+        })?,
+    })
+}
@@ -0,0 +1,206 @@
+use cosmwasm_std::{
+    to_binary, Addr, Api, CanonicalAddr, CosmosMsg, Decimal, StdError, StdResult, Uint128,
+    WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use crate::math::UDec128;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// aUST commission is split among recipients by weight
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeRecipient {
+    pub address: String,
+    pub weight: u32,
+}
+
+// deposit_fee applied when bond amount >= min_bond_amount
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositFeeTier {
+    pub min_bond_amount: Uint128,
+    pub deposit_fee: Decimal,
+}
+
+pub fn validate_fee_recipients(
+    api: &dyn Api,
+    fee_recipients: Vec<FeeRecipient>,
+) -> StdResult<Vec<(CanonicalAddr, u32)>> {
+    if !fee_recipients.is_empty() && fee_recipients.iter().all(|it| it.weight == 0) {
+        return Err(StdError::generic_err("fee_recipients total weight must be positive"));
+    }
+    fee_recipients
+        .into_iter()
+        .map(|it| Ok((api.addr_canonicalize(&it.address)?, it.weight)))
+        .collect()
+}
+
+pub fn validate_deposit_fee_tiers(deposit_fee_tiers: &[DepositFeeTier]) -> StdResult<()> {
+    if deposit_fee_tiers.iter().any(|tier| tier.deposit_fee > Decimal::one()) {
+        return Err(StdError::generic_err("deposit_fee must be 0 to 1"));
+    }
+    Ok(())
+}
+
+pub fn humanize_fee_recipients(
+    api: &dyn Api,
+    fee_recipients: &[(CanonicalAddr, u32)],
+) -> StdResult<Vec<FeeRecipient>> {
+    fee_recipients
+        .iter()
+        .map(|(address, weight)| Ok(FeeRecipient {
+            address: api.addr_humanize(address)?.to_string(),
+            weight: *weight,
+        }))
+        .collect()
+}
+
+pub fn canonicalize_fee_exempt(api: &dyn Api, addrs: &[String]) -> StdResult<Vec<CanonicalAddr>> {
+    addrs.iter().map(|addr| api.addr_canonicalize(addr)).collect()
+}
+
+pub fn humanize_fee_exempt(api: &dyn Api, addrs: &[CanonicalAddr]) -> StdResult<Vec<String>> {
+    addrs
+        .iter()
+        .map(|addr| api.addr_humanize(addr).map(|it| it.to_string()))
+        .collect()
+}
+
+pub fn update_fee_exempt(
+    api: &dyn Api,
+    deposit_fee_exempt: &mut Vec<CanonicalAddr>,
+    insert_fee_exempt: Option<Vec<String>>,
+    remove_fee_exempt: Option<Vec<String>>,
+) -> StdResult<()> {
+    if let Some(insert_fee_exempt) = insert_fee_exempt {
+        for addr_raw in canonicalize_fee_exempt(api, &insert_fee_exempt)? {
+            if !deposit_fee_exempt.contains(&addr_raw) {
+                deposit_fee_exempt.push(addr_raw);
+            }
+        }
+    }
+
+    if let Some(remove_fee_exempt) = remove_fee_exempt {
+        for addr_raw in canonicalize_fee_exempt(api, &remove_fee_exempt)? {
+            deposit_fee_exempt.retain(|it| it != &addr_raw);
+        }
+    }
+
+    Ok(())
+}
+
+// highest tier that bond amount reaches, else deposit_fee
+pub fn calc_tier_deposit_fee(
+    deposit_fee: Decimal,
+    deposit_fee_tiers: &[DepositFeeTier],
+    bond_amount: Uint128,
+) -> Decimal {
+    deposit_fee_tiers
+        .iter()
+        .filter(|tier| bond_amount >= tier.min_bond_amount)
+        .max_by_key(|tier| tier.min_bond_amount)
+        .map(|tier| tier.deposit_fee)
+        .unwrap_or(deposit_fee)
+}
+
+// fee recipients by weight, or fee_split (e.g. community/platform/controller fee) if there is none
+pub fn fee_recipient_weights(
+    fee_recipients: &[(CanonicalAddr, u32)],
+    fee_split: Vec<(CanonicalAddr, Decimal)>,
+) -> Vec<(CanonicalAddr, Uint128)> {
+    if !fee_recipients.is_empty() {
+        return fee_recipients
+            .iter()
+            .map(|(address, weight)| (address.clone(), Uint128::from(*weight)))
+            .collect();
+    }
+
+    let thousand = Uint128::from(1000u64);
+    fee_split
+        .into_iter()
+        .map(|(address, fee)| (address, thousand * fee))
+        .collect()
+}
+
+// split amount by weights, last recipient takes the remainder
+pub fn split_fee(
+    fee_amounts: &mut Vec<(CanonicalAddr, Uint128)>,
+    weights: Vec<(CanonicalAddr, Uint128)>,
+    amount: Uint128,
+) -> StdResult<()> {
+    let total_weight = weights.iter().fold(Uint128::zero(), |total, (_, weight)| total + *weight);
+    if amount.is_zero() || total_weight.is_zero() {
+        return Ok(());
+    }
+
+    let mut remaining_amount = amount;
+    let last_index = weights.len() - 1;
+    for (i, (recipient, weight)) in weights.into_iter().enumerate() {
+        let fee_amount = if i == last_index {
+            remaining_amount
+        } else {
+            amount.multiply_ratio(weight, total_weight)
+        };
+        remaining_amount = remaining_amount.checked_sub(fee_amount)?;
+        if let Some(item) = fee_amounts.iter_mut().find(|(address, _)| address == &recipient) {
+            item.1 += fee_amount;
+        } else {
+            fee_amounts.push((recipient, fee_amount));
+        }
+    }
+    Ok(())
+}
+
+pub fn transfer_fee_msgs(
+    api: &dyn Api,
+    fee_token: &Addr,
+    fee_amounts: Vec<(CanonicalAddr, Uint128)>,
+) -> StdResult<Vec<CosmosMsg>> {
+    fee_amounts
+        .into_iter()
+        .filter(|(_, amount)| !amount.is_zero())
+        .map(|(recipient, amount)| Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: fee_token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: api.addr_humanize(&recipient)?.to_string(),
+                amount,
+            })?,
+            funds: vec![],
+        })))
+        .collect()
+}
+
+// withdraw_fee decays linearly to zero over withdraw_fee_period after deposit_time
+pub fn calc_decayed_withdraw_fee(
+    withdraw_fee: Decimal,
+    withdraw_fee_period: u64,
+    deposit_time: u64,
+    now: u64,
+) -> Decimal {
+    let elapsed = now.saturating_sub(deposit_time);
+    if withdraw_fee.is_zero() || elapsed >= withdraw_fee_period {
+        return Decimal::zero();
+    }
+
+    UDec128::from(withdraw_fee)
+        .multiply_ratio(withdraw_fee_period - elapsed, withdraw_fee_period)
+        .into()
+}
+
+// withdraw fee LP stays staked in the farm, call after the unbonded shares are removed from the pool
+// credited to auto-compound bonders (lp balance above total_stake_bond_amount),
+// else to auto-stake bonders, and waived if nobody is left in the pool
+pub fn credit_withdraw_fee(
+    fee_amount: Uint128,
+    total_auto_bond_share: Uint128,
+    total_stake_bond_share: Uint128,
+    total_stake_bond_amount: &mut Uint128,
+) -> Uint128 {
+    if !total_auto_bond_share.is_zero() {
+        fee_amount
+    } else if !total_stake_bond_share.is_zero() {
+        *total_stake_bond_amount += fee_amount;
+        fee_amount
+    } else {
+        Uint128::zero()
+    }
+}
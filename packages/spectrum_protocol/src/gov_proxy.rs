@@ -8,6 +8,11 @@ use serde::{Deserialize, Serialize};
 pub struct ConfigInfo {
     pub farm_token: String,
     pub farm_gov: String,
+    #[serde(default)] pub vote_authority: Option<String>, // spectrum_gov, can update voting config
+    #[serde(default)] pub vote_delegate: Option<String>, // appointed by vote_authority to cast votes
+    #[serde(default)] pub vote_contract: Option<String>, // upstream contract to vote, default to farm_gov
+    pub owner: String, // can register depositors
+    #[serde(default)] pub unbonding_period: u64, // seconds before requested unstake can be claimed
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -18,7 +23,16 @@ pub enum QueryMsg {
     Staker {
         address: String,
     },
-    State {}
+    State {},
+    // list depositors with share and balance
+    Stakers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // pending unstake requests
+    Unbonding {
+        address: String,
+    },
 }
 
 // We define a custom struct for each query response
@@ -27,6 +41,36 @@ pub struct StakerResponse {
     pub balance: Uint128,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakerInfo {
+    pub address: String,
+    pub share: Uint128,
+    pub balance: Uint128,
+    pub allowed: bool,
+    pub cap: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakersResponse {
+    pub total_share: Uint128,
+    pub total_balance: Uint128,
+    pub stakers: Vec<StakerInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnbondingInfo {
+    pub amount: Uint128,
+    pub release_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnbondingResponse {
+    pub requests: Vec<UnbondingInfo>,
+    pub pending: Uint128,   // total requested, not claimed
+    pub matured: Uint128,   // passed release_at
+    pub claimable: Uint128, // matured and withdrawable from upstream gov now
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
@@ -34,6 +78,68 @@ pub enum ExecuteMsg {
     Unstake {
         amount: Option<Uint128>,
     },
+    // stop earning and queue amount, default to all balance, claim after unbonding_period
+    RequestUnstake {
+        amount: Option<Uint128>,
+    },
+    // withdraw matured requests, partially if upstream gov still locks some tokens
+    ClaimUnstake {},
+    // vote in upstream gov with pooled balance, by vote_authority or vote_delegate
+    CastVote {
+        poll_id: u64,
+        vote: VoteOption,
+        amount: Option<Uint128>, // default to pooled balance not requested for unstake
+    },
+    // stake upstream voting rewards back to gov, shared by all depositors
+    StakeVotingRewards {},
+    // by vote_authority
+    UpdateConfig {
+        vote_authority: Option<String>,
+        vote_contract: Option<String>,
+    },
+    // by vote_authority, None to remove delegate
+    SetVoteDelegate {
+        vote_delegate: Option<String>,
+    },
+    // by owner, allow farm contract to stake up to cap, can be called again to update cap
+    RegisterDepositor {
+        depositor: String,
+        cap: Option<Uint128>,
+    },
+    // by owner, depositor can only unstake
+    DeregisterDepositor {
+        depositor: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteOption {
+    Yes,
+    No,
+    Abstain,
+}
+
+// upstream behavior of generic gov proxy
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GovAdapter {
+    // hold farm_token in proxy
+    Token {},
+    // enter/leave xASTRO-style staking, vote in vote_contract
+    XAstro {
+        staking: String,
+        xastro_token: String,
+    },
+    Anchor {
+        gov: String,
+    },
+    Mirror {
+        gov: String,
+    },
+    Nexus {
+        gov: String,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -44,4 +150,8 @@ pub enum Cw20HookMsg {
 
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    #[serde(default)] pub vote_authority: Option<String>,
+    pub owner: String,
+    #[serde(default)] pub unbonding_period: Option<u64>,
+}
@@ -0,0 +1,533 @@
+use cosmwasm_std::{attr, Api, Attribute, Binary, CanonicalAddr, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128, WasmMsg};
+use cosmwasm_storage::{bucket, bucket_read, singleton, singleton_read, Bucket, Singleton};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use crate::common::calc_range_start_addr;
+use crate::gov_proxy::{StakerInfo, StakerResponse, StakersResponse, UnbondingInfo, UnbondingResponse};
+
+pub fn canonicalize_opt(api: &dyn Api, addr: Option<String>) -> StdResult<Option<CanonicalAddr>> {
+    if let Some(addr) = addr {
+        Ok(Some(api.addr_canonicalize(&addr)?))
+    } else {
+        Ok(None)
+    }
+}
+
+pub fn humanize_opt(api: &dyn Api, addr: &Option<CanonicalAddr>) -> StdResult<Option<String>> {
+    if let Some(addr) = addr {
+        Ok(Some(api.addr_humanize(addr)?.to_string()))
+    } else {
+        Ok(None)
+    }
+}
+
+// settings shared by all gov proxies, kept apart from the config of each proxy
+static KEY_PROXY_CONFIG: &[u8] = b"proxy_config";
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+pub struct ProxyConfig {
+    pub vote_authority: Option<CanonicalAddr>,
+    pub vote_delegate: Option<CanonicalAddr>,
+    pub vote_contract: Option<CanonicalAddr>,
+    pub owner: Option<CanonicalAddr>, // can register depositors, set at instantiate and migrate
+    pub unbonding_period: u64,
+}
+
+impl ProxyConfig {
+    pub fn can_vote(&self, sender: &CanonicalAddr) -> bool {
+        self.vote_authority.as_ref() == Some(sender) || self.vote_delegate.as_ref() == Some(sender)
+    }
+}
+
+pub fn store_proxy_config(storage: &mut dyn Storage, proxy_config: &ProxyConfig) -> StdResult<()> {
+    singleton(storage, KEY_PROXY_CONFIG).save(proxy_config)
+}
+
+// proxy deployed before voting has no proxy config yet
+pub fn read_proxy_config(storage: &dyn Storage) -> StdResult<ProxyConfig> {
+    Ok(singleton_read(storage, KEY_PROXY_CONFIG).may_load()?.unwrap_or_default())
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    vote_authority: Option<String>,
+    vote_contract: Option<String>,
+) -> StdResult<Response> {
+    let mut proxy_config = read_proxy_config(deps.storage)?;
+    if proxy_config.vote_authority != Some(deps.api.addr_canonicalize(info.sender.as_str())?) {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    if let Some(vote_authority) = vote_authority {
+        proxy_config.vote_authority = Some(deps.api.addr_canonicalize(&vote_authority)?);
+    }
+
+    if let Some(vote_contract) = vote_contract {
+        proxy_config.vote_contract = Some(deps.api.addr_canonicalize(&vote_contract)?);
+    }
+
+    store_proxy_config(deps.storage, &proxy_config)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
+}
+
+pub fn set_vote_delegate(
+    deps: DepsMut,
+    info: MessageInfo,
+    vote_delegate: Option<String>,
+) -> StdResult<Response> {
+    let mut proxy_config = read_proxy_config(deps.storage)?;
+    if proxy_config.vote_authority != Some(deps.api.addr_canonicalize(info.sender.as_str())?) {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    proxy_config.vote_delegate = canonicalize_opt(deps.api, vote_delegate)?;
+    store_proxy_config(deps.storage, &proxy_config)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "set_vote_delegate")]))
+}
+
+// send upstream vote msg to vote_contract, or default_vote_contract if not set
+pub fn cast_vote(
+    deps: Deps,
+    info: MessageInfo,
+    default_vote_contract: Option<&CanonicalAddr>,
+    poll_id: u64,
+    msg: Binary,
+    attributes: Vec<Attribute>,
+) -> StdResult<Response> {
+    let proxy_config = read_proxy_config(deps.storage)?;
+    if !proxy_config.can_vote(&deps.api.addr_canonicalize(info.sender.as_str())?) {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let vote_contract = proxy_config.vote_contract.as_ref()
+        .or(default_vote_contract)
+        .ok_or_else(|| StdError::generic_err("vote_contract is not set"))?;
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(vote_contract)?.to_string(),
+            msg,
+            funds: vec![],
+        }))
+        .add_attributes(vec![
+            attr("action", "cast_vote"),
+            attr("poll_id", poll_id.to_string()),
+        ])
+        .add_attributes(attributes))
+}
+
+// vote amount defaults to balance not requested for unstake, so pending requests can be claimed after poll
+pub fn vote_amount(
+    storage: &dyn Storage,
+    amount: Option<Uint128>,
+    total_balance: Uint128,
+) -> StdResult<Uint128> {
+    let available = read_state(storage)?.available(total_balance);
+    match amount {
+        None => Ok(available),
+        Some(amount) if amount > available => Err(StdError::generic_err("amount exceeds voting balance")),
+        Some(amount) => Ok(amount),
+    }
+}
+
+static KEY_STATE: &[u8] = b"state";
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct State {
+    pub total_share: Uint128,
+    #[serde(default)] pub total_pending: Uint128, // requested unstake, not claimed
+}
+
+impl State {
+    // pending unstake is still in upstream gov, but not owned by share holders
+    fn available(&self, total_balance: Uint128) -> Uint128 {
+        total_balance.checked_sub(self.total_pending).unwrap_or_default()
+    }
+
+    pub fn calc_share(&self, total_balance: Uint128, amount: Uint128) -> Uint128 {
+        let total_balance = self.available(total_balance);
+        if self.total_share.is_zero() || total_balance.is_zero() {
+            amount
+        } else {
+            amount.multiply_ratio(self.total_share, total_balance)
+        }
+    }
+
+    pub fn calc_balance(&self, total_balance: Uint128, share: Uint128) -> Uint128 {
+        let total_balance = self.available(total_balance);
+        if self.total_share.is_zero() {
+            Uint128::zero()
+        } else {
+            share.multiply_ratio(total_balance, self.total_share)
+        }
+    }
+
+    // share to burn for amount, rounded up
+    pub fn calc_withdraw_share(&self, total_balance: Uint128, amount: Uint128) -> Uint128 {
+        let mut withdraw_share = self.calc_share(total_balance, amount);
+        if self.calc_balance(total_balance, withdraw_share) < amount {
+            withdraw_share += Uint128::from(1u128);
+        }
+        withdraw_share
+    }
+}
+
+pub fn state_store(storage: &mut dyn Storage) -> Singleton<'_, State> {
+    singleton(storage, KEY_STATE)
+}
+
+pub fn read_state(storage: &dyn Storage) -> StdResult<State> {
+    singleton_read(storage, KEY_STATE).load()
+}
+
+static PREFIX_ACCOUNT: &[u8] = b"account";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+pub struct Account {
+    pub share: Uint128,                         // total staked balance
+    #[serde(default = "default_allowed")] pub allowed: bool, // registered depositor
+    #[serde(default)] pub cap: Option<Uint128>, // max staked balance
+    #[serde(default)] pub deposit: Uint128,     // principal, to calculate yield
+}
+
+// accounts staked before depositor allowlist stay allowed
+fn default_allowed() -> bool {
+    true
+}
+
+impl Account {
+    // principal is withdrawn along with share
+    pub fn withdraw_share(&mut self, withdraw_share: Uint128) {
+        if !self.share.is_zero() {
+            self.deposit -= self.deposit.multiply_ratio(withdraw_share, self.share);
+        }
+        self.share -= withdraw_share;
+    }
+}
+
+pub fn account_store(storage: &mut dyn Storage) -> Bucket<'_, Account> {
+    bucket(storage, PREFIX_ACCOUNT)
+}
+
+pub fn read_account(storage: &dyn Storage, key: &[u8]) -> StdResult<Option<Account>> {
+    bucket_read(storage, PREFIX_ACCOUNT).may_load(key)
+}
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+pub fn read_accounts(
+    storage: &dyn Storage,
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(CanonicalAddr, Account)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start_addr(start_after);
+
+    bucket_read(storage, PREFIX_ACCOUNT)
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, v) = item?;
+            Ok((CanonicalAddr::from(k), v))
+        })
+        .collect()
+}
+
+pub fn register_depositor(
+    deps: DepsMut,
+    info: MessageInfo,
+    depositor: String,
+    cap: Option<Uint128>,
+) -> StdResult<Response> {
+    let proxy_config = read_proxy_config(deps.storage)?;
+    if proxy_config.owner != Some(deps.api.addr_canonicalize(info.sender.as_str())?) {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    let depositor_raw = deps.api.addr_canonicalize(&depositor)?;
+    let mut account = read_account(deps.storage, depositor_raw.as_slice())?
+        .unwrap_or_default();
+    account.allowed = true;
+    account.cap = cap;
+    account_store(deps.storage).save(depositor_raw.as_slice(), &account)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "register_depositor"),
+        attr("depositor", depositor),
+    ]))
+}
+
+pub fn deregister_depositor(
+    deps: DepsMut,
+    info: MessageInfo,
+    depositor: String,
+) -> StdResult<Response> {
+    let proxy_config = read_proxy_config(deps.storage)?;
+    if proxy_config.owner != Some(deps.api.addr_canonicalize(info.sender.as_str())?) {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+
+    // keep share, so depositor can still unstake
+    let depositor_raw = deps.api.addr_canonicalize(&depositor)?;
+    let mut account = account_store(deps.storage).load(depositor_raw.as_slice())?;
+    account.allowed = false;
+    account_store(deps.storage).save(depositor_raw.as_slice(), &account)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "deregister_depositor"),
+        attr("depositor", depositor),
+    ]))
+}
+
+// mint share for amount sent by depositor, total_balance excludes amount
+pub fn stake(
+    storage: &mut dyn Storage,
+    depositor: &CanonicalAddr,
+    amount: Uint128,
+    total_balance: Uint128,
+) -> StdResult<()> {
+    if amount.is_zero() {
+        return Err(StdError::generic_err("Insufficient funds sent"));
+    }
+
+    let key = depositor.as_slice();
+    let mut account = read_account(storage, key)?
+        .unwrap_or_default();
+    if !account.allowed {
+        return Err(StdError::generic_err("depositor is not allowed"));
+    }
+
+    let mut state = read_state(storage)?;
+    if let Some(cap) = account.cap {
+        if state.calc_balance(total_balance, account.share) + amount > cap {
+            return Err(StdError::generic_err("exceed depositor cap"));
+        }
+    }
+
+    let share = state.calc_share(total_balance, amount);
+    account.share += share;
+    account.deposit += amount;
+    state.total_share += share;
+
+    state_store(storage).save(&state)?;
+    account_store(storage).save(key, &account)
+}
+
+// burn share for amount to withdraw now, default to all balance of staker
+pub fn unstake(
+    storage: &mut dyn Storage,
+    staker: &CanonicalAddr,
+    amount: Option<Uint128>,
+    total_balance: Uint128,
+    withdrawable: Uint128,
+) -> StdResult<Uint128> {
+    let key = staker.as_slice();
+    let mut state = read_state(storage)?;
+    let mut account = account_store(storage).load(key)?;
+
+    let user_balance = state.calc_balance(total_balance, account.share);
+    let amount = amount.unwrap_or(user_balance);
+    if amount > user_balance {
+        return Err(StdError::generic_err(
+            "User is trying to withdraw too many tokens.",
+        ));
+    }
+    let withdrawable = withdrawable
+        .checked_sub(state.total_pending)
+        .unwrap_or_default();
+    if amount > withdrawable {
+        return Err(StdError::generic_err("amount is locked in upstream gov, use request_unstake"));
+    }
+
+    let withdraw_share = state.calc_withdraw_share(total_balance, amount);
+    account.withdraw_share(withdraw_share);
+    state.total_share -= withdraw_share;
+
+    account_store(storage).save(key, &account)?;
+    state_store(storage).save(&state)?;
+
+    Ok(amount)
+}
+
+pub fn query_staker(
+    deps: Deps,
+    address: String,
+    total_balance: Uint128,
+) -> StdResult<StakerResponse> {
+    let state = read_state(deps.storage)?;
+    let addr_raw = deps.api.addr_canonicalize(&address)?;
+    let account = read_account(deps.storage, addr_raw.as_slice())?
+        .unwrap_or_default();
+
+    Ok(StakerResponse {
+        balance: state.calc_balance(total_balance, account.share),
+    })
+}
+
+pub fn query_stakers(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    total_balance: Uint128,
+) -> StdResult<StakersResponse> {
+    let state = read_state(deps.storage)?;
+    let start_after = canonicalize_opt(deps.api, start_after)?;
+    let stakers = read_accounts(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|(addr, account)| Ok(StakerInfo {
+            address: deps.api.addr_humanize(&addr)?.to_string(),
+            share: account.share,
+            balance: state.calc_balance(total_balance, account.share),
+            allowed: account.allowed,
+            cap: account.cap,
+        }))
+        .collect::<StdResult<Vec<StakerInfo>>>()?;
+
+    Ok(StakersResponse {
+        total_share: state.total_share,
+        total_balance,
+        stakers,
+    })
+}
+
+static PREFIX_UNBONDING: &[u8] = b"unbonding";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Unbonding {
+    pub amount: Uint128,
+    pub release_at: u64,
+}
+
+pub fn unbonding_store(storage: &mut dyn Storage) -> Bucket<'_, Vec<Unbonding>> {
+    bucket(storage, PREFIX_UNBONDING)
+}
+
+pub fn read_unbonding(storage: &dyn Storage, key: &[u8]) -> StdResult<Vec<Unbonding>> {
+    Ok(bucket_read(storage, PREFIX_UNBONDING).may_load(key)?.unwrap_or_default())
+}
+
+pub fn request_unstake(
+    storage: &mut dyn Storage,
+    env: &Env,
+    staker: &CanonicalAddr,
+    amount: Option<Uint128>,
+    total_balance: Uint128,
+) -> StdResult<Response> {
+    let key = staker.as_slice();
+    let proxy_config = read_proxy_config(storage)?;
+    let mut state = read_state(storage)?;
+    let mut account = account_store(storage).load(key)?;
+
+    let user_balance = state.calc_balance(total_balance, account.share);
+    let amount = amount.unwrap_or(user_balance);
+    if amount.is_zero() {
+        return Err(StdError::generic_err("amount must be more than 0"));
+    }
+    if amount > user_balance {
+        return Err(StdError::generic_err(
+            "User is trying to withdraw too many tokens.",
+        ));
+    }
+
+    // requested amount stops earning, and stays in upstream gov until claimed
+    let withdraw_share = state.calc_withdraw_share(total_balance, amount);
+    account.withdraw_share(withdraw_share);
+    state.total_share -= withdraw_share;
+    state.total_pending += amount;
+
+    let release_at = env.block.time.seconds() + proxy_config.unbonding_period;
+    let mut unbonding = read_unbonding(storage, key)?;
+    unbonding.push(Unbonding {
+        amount,
+        release_at,
+    });
+
+    account_store(storage).save(key, &account)?;
+    state_store(storage).save(&state)?;
+    unbonding_store(storage).save(key, &unbonding)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "request_unstake"),
+        attr("amount", amount),
+        attr("release_at", release_at.to_string()),
+    ]))
+}
+
+// claim matured requests up to withdrawable, returns claimed amount to withdraw from upstream gov
+pub fn claim_unstake(
+    storage: &mut dyn Storage,
+    env: &Env,
+    staker: &CanonicalAddr,
+    withdrawable: Uint128,
+) -> StdResult<(Uint128, Response)> {
+    let key = staker.as_slice();
+    let mut state = read_state(storage)?;
+
+    let now = env.block.time.seconds();
+    let mut unbonding = read_unbonding(storage, key)?;
+    let matured: Uint128 = unbonding.iter()
+        .filter(|it| it.release_at <= now)
+        .map(|it| it.amount)
+        .sum();
+    if matured.is_zero() {
+        return Err(StdError::generic_err("no matured unstake request"));
+    }
+
+    let amount = matured.min(withdrawable);
+    if amount.is_zero() {
+        return Err(StdError::generic_err("tokens are locked in upstream gov, try again later"));
+    }
+
+    // consume matured requests in order, partially claimed request keeps the rest
+    let mut remaining = amount;
+    for it in unbonding.iter_mut().filter(|it| it.release_at <= now) {
+        let claimed = it.amount.min(remaining);
+        it.amount -= claimed;
+        remaining -= claimed;
+    }
+    unbonding.retain(|it| !it.amount.is_zero());
+    if unbonding.is_empty() {
+        unbonding_store(storage).remove(key);
+    } else {
+        unbonding_store(storage).save(key, &unbonding)?;
+    }
+
+    state.total_pending = state.total_pending.checked_sub(amount)?;
+    state_store(storage).save(&state)?;
+
+    Ok((amount, Response::new().add_attributes(vec![
+        attr("action", "claim_unstake"),
+        attr("amount", amount),
+        attr("matured_remaining", matured.checked_sub(amount)?),
+    ])))
+}
+
+pub fn query_unbonding(
+    deps: Deps,
+    env: &Env,
+    address: String,
+    withdrawable: Uint128,
+) -> StdResult<UnbondingResponse> {
+    let addr_raw = deps.api.addr_canonicalize(&address)?;
+    let unbonding = read_unbonding(deps.storage, addr_raw.as_slice())?;
+
+    let now = env.block.time.seconds();
+    let pending: Uint128 = unbonding.iter().map(|it| it.amount).sum();
+    let matured: Uint128 = unbonding.iter()
+        .filter(|it| it.release_at <= now)
+        .map(|it| it.amount)
+        .sum();
+
+    Ok(UnbondingResponse {
+        requests: unbonding.into_iter()
+            .map(|it| UnbondingInfo {
+                amount: it.amount,
+                release_at: it.release_at,
+            })
+            .collect(),
+        pending,
+        matured,
+        claimable: matured.min(withdrawable),
+    })
+}
@@ -0,0 +1,531 @@
+use cosmwasm_std::{
+    attr, to_binary, Addr, Api, CanonicalAddr, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Order, QuerierWrapper, Response, StdError, StdResult, Storage, Uint128, WasmMsg,
+};
+use cosmwasm_storage::{bucket, bucket_read};
+use cw20::Cw20ExecuteMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use terraswap::querier::query_token_balance;
+
+/// a token keeps its slot in the pool after the stream ends, so bonders can still settle it
+pub const MAX_POOL_INCENTIVES: usize = 5;
+
+// third-party token streamed to the bonders of a pool
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Incentive {
+    pub token: CanonicalAddr,
+    pub depositor: CanonicalAddr,
+    pub remaining_amount: Uint128, // not yet streamed
+    pub last_time: u64,
+    pub end_time: u64,
+    pub auto_index: Decimal,  // per auto bond share
+    pub stake_index: Decimal, // per stake bond share
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IncentiveReward {
+    pub token: CanonicalAddr,
+    pub auto_index: Decimal,
+    pub stake_index: Decimal,
+    pub pending_reward: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IncentiveItem {
+    pub token: String,
+    pub depositor: String,
+    pub remaining_amount: Uint128,
+    pub end_time: u64,
+    pub auto_index: Decimal,
+    pub stake_index: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IncentiveRewardItem {
+    pub token: String,
+    pub pending_reward: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IncentiveTokensResponse {
+    pub tokens: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum IncentiveExecuteMsg {
+    update_incentive_tokens {
+        add: Option<Vec<String>>,
+        remove: Option<Vec<String>>,
+    },
+    // Withdraw incentives, if the tokens are not given, then all incentives are withdrawn
+    withdraw_incentives {
+        asset_token: String,
+        tokens: Option<Vec<String>>,
+    },
+    // Refund incentive left after the end of stream
+    refund_incentive {
+        asset_token: String,
+        token: String,
+    },
+}
+
+/// farm pool which streams incentives to its bonders
+pub trait IncentivePool {
+    fn incentives(&self) -> &[Incentive];
+    fn incentives_mut(&mut self) -> &mut Vec<Incentive>;
+    /// (total_stake_bond_amount, total_auto_bond_share, total_stake_bond_share)
+    fn bond_totals(&self) -> (Uint128, Uint128, Uint128);
+}
+
+/// bonder of a farm pool which receives incentives
+pub trait IncentiveBonder {
+    fn incentive_rewards_mut(&mut self) -> &mut Vec<IncentiveReward>;
+    /// (auto_bond_share, stake_bond_share)
+    fn bond_shares(&self) -> (Uint128, Uint128);
+}
+
+static PREFIX_INCENTIVE_TOKEN: &[u8] = b"incentive_token";
+static PREFIX_INCENTIVE_BALANCE: &[u8] = b"incentive_balance";
+
+/// owner managed list of tokens accepted as incentive
+pub fn update_incentive_tokens(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    add: Option<Vec<String>>,
+    remove: Option<Vec<String>>,
+) -> StdResult<()> {
+    for token in add.unwrap_or_default() {
+        let token_raw = api.addr_canonicalize(&token)?;
+        bucket(storage, PREFIX_INCENTIVE_TOKEN).save(token_raw.as_slice(), &true)?;
+    }
+    for token in remove.unwrap_or_default() {
+        let token_raw = api.addr_canonicalize(&token)?;
+        bucket::<bool>(storage, PREFIX_INCENTIVE_TOKEN).remove(token_raw.as_slice());
+    }
+    Ok(())
+}
+
+fn is_incentive_token(storage: &dyn Storage, token: &CanonicalAddr) -> StdResult<bool> {
+    Ok(bucket_read::<bool>(storage, PREFIX_INCENTIVE_TOKEN)
+        .may_load(token.as_slice())?
+        .unwrap_or(false))
+}
+
+pub fn query_incentive_tokens(
+    api: &dyn Api,
+    storage: &dyn Storage,
+) -> StdResult<IncentiveTokensResponse> {
+    let tokens = bucket_read::<bool>(storage, PREFIX_INCENTIVE_TOKEN)
+        .range(None, None, Order::Ascending)
+        .map(|item| {
+            let (k, _) = item?;
+            Ok(api.addr_humanize(&CanonicalAddr::from(k))?.to_string())
+        })
+        .collect::<StdResult<Vec<String>>>()?;
+    Ok(IncentiveTokensResponse { tokens })
+}
+
+/// token amount held by the farm for incentives, it must not be compounded
+fn read_incentive_balance(storage: &dyn Storage, token: &Addr) -> StdResult<Uint128> {
+    Ok(bucket_read(storage, PREFIX_INCENTIVE_BALANCE)
+        .may_load(token.as_bytes())?
+        .unwrap_or_else(Uint128::zero))
+}
+
+pub fn increase_incentive_balance(
+    storage: &mut dyn Storage,
+    token: &Addr,
+    amount: Uint128,
+) -> StdResult<()> {
+    let balance = read_incentive_balance(storage, token)?;
+    bucket(storage, PREFIX_INCENTIVE_BALANCE).save(token.as_bytes(), &(balance + amount))
+}
+
+fn decrease_incentive_balance(
+    storage: &mut dyn Storage,
+    token: &Addr,
+    amount: Uint128,
+) -> StdResult<()> {
+    let balance = read_incentive_balance(storage, token)?.checked_sub(amount)?;
+    bucket(storage, PREFIX_INCENTIVE_BALANCE).save(token.as_bytes(), &balance)
+}
+
+/// token balance excluding the amount held for incentives
+pub fn query_free_token_balance(
+    storage: &dyn Storage,
+    querier: &QuerierWrapper,
+    contract_addr: Addr,
+    account_addr: Addr,
+) -> StdResult<Uint128> {
+    let incentive_balance = read_incentive_balance(storage, &contract_addr)?;
+    let balance = query_token_balance(querier, contract_addr, account_addr)?;
+    Ok(balance.checked_sub(incentive_balance)?)
+}
+
+/// stream amount from now until now + duration
+/// the depositor tops up its running stream, another depositor has to wait until the stream is over
+/// pending stream must be distributed up to now before calling this
+pub fn schedule_incentive(
+    storage: &dyn Storage,
+    incentives: &mut Vec<Incentive>,
+    token: CanonicalAddr,
+    depositor: CanonicalAddr,
+    amount: Uint128,
+    duration: u64,
+    now: u64,
+) -> StdResult<()> {
+    if !is_incentive_token(storage, &token)? {
+        return Err(StdError::generic_err("token is not allowed for incentive"));
+    }
+    if duration == 0 {
+        return Err(StdError::generic_err("duration must be greater than 0"));
+    }
+
+    let end_time = now + duration;
+    if let Some(incentive) = incentives.iter_mut().find(|it| it.token == token) {
+        if incentive.depositor != depositor
+            && (now < incentive.end_time || !incentive.remaining_amount.is_zero())
+        {
+            return Err(StdError::generic_err("incentive is still running"));
+        }
+        incentive.depositor = depositor;
+        incentive.remaining_amount += amount;
+        incentive.last_time = now;
+        incentive.end_time = incentive.end_time.max(end_time);
+    } else {
+        if incentives.len() >= MAX_POOL_INCENTIVES {
+            return Err(StdError::generic_err("too many incentives in pool"));
+        }
+        incentives.push(Incentive {
+            token,
+            depositor,
+            remaining_amount: amount,
+            last_time: now,
+            end_time,
+            auto_index: Decimal::zero(),
+            stake_index: Decimal::zero(),
+        });
+    }
+
+    Ok(())
+}
+
+/// take back the amount left undistributed after the stream is over, e.g. nobody bonded for a while
+/// pending stream must be distributed up to now before calling this
+pub fn take_incentive_refund(
+    incentives: &mut [Incentive],
+    token: &CanonicalAddr,
+    depositor: &CanonicalAddr,
+    now: u64,
+) -> StdResult<Uint128> {
+    let incentive = incentives
+        .iter_mut()
+        .find(|it| it.token == *token)
+        .ok_or_else(|| StdError::generic_err("incentive not found"))?;
+    if incentive.depositor != *depositor {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+    if now < incentive.end_time {
+        return Err(StdError::generic_err("incentive is still running"));
+    }
+    if incentive.remaining_amount.is_zero() {
+        return Err(StdError::generic_err("nothing to refund"));
+    }
+
+    let amount = incentive.remaining_amount;
+    incentive.remaining_amount = Uint128::zero();
+    Ok(amount)
+}
+
+/// split streamed amount to auto-compound and auto-stake by lp amount, same as SPEC reward
+pub fn distribute_incentives(
+    incentives: &mut [Incentive],
+    now: u64,
+    lp_balance: Uint128,
+    total_stake_bond_amount: Uint128,
+    total_auto_bond_share: Uint128,
+    total_stake_bond_share: Uint128,
+) -> StdResult<()> {
+    for incentive in incentives.iter_mut() {
+        let to_time = now.min(incentive.end_time);
+        if to_time <= incentive.last_time {
+            continue;
+        }
+        let amount = incentive.remaining_amount.multiply_ratio(
+            to_time - incentive.last_time,
+            incentive.end_time - incentive.last_time,
+        );
+        incentive.last_time = to_time;
+
+        // nobody to receive, keep for the rest of the stream or refund
+        if lp_balance.is_zero() {
+            continue;
+        }
+
+        let stake_amount = amount.multiply_ratio(total_stake_bond_amount, lp_balance);
+        let auto_amount = amount.checked_sub(stake_amount)?;
+        let mut distributed_amount = Uint128::zero();
+        if !stake_amount.is_zero() && !total_stake_bond_share.is_zero() {
+            incentive.stake_index = incentive.stake_index
+                + Decimal::from_ratio(stake_amount, total_stake_bond_share);
+            distributed_amount += stake_amount;
+        }
+        if !auto_amount.is_zero() && !total_auto_bond_share.is_zero() {
+            incentive.auto_index =
+                incentive.auto_index + Decimal::from_ratio(auto_amount, total_auto_bond_share);
+            distributed_amount += auto_amount;
+        }
+        incentive.remaining_amount = incentive.remaining_amount.checked_sub(distributed_amount)?;
+    }
+
+    Ok(())
+}
+
+pub fn incentive_to_pool<P: IncentivePool>(
+    pool_info: &mut P,
+    now: u64,
+    lp_balance: Uint128,
+) -> StdResult<()> {
+    let (total_stake_bond_amount, total_auto_bond_share, total_stake_bond_share) =
+        pool_info.bond_totals();
+    distribute_incentives(
+        pool_info.incentives_mut(),
+        now,
+        lp_balance,
+        total_stake_bond_amount,
+        total_auto_bond_share,
+        total_stake_bond_share,
+    )
+}
+
+pub fn create_incentive_rewards(incentives: &[Incentive]) -> Vec<IncentiveReward> {
+    incentives
+        .iter()
+        .map(|it| IncentiveReward {
+            token: it.token.clone(),
+            auto_index: it.auto_index,
+            stake_index: it.stake_index,
+            pending_reward: Uint128::zero(),
+        })
+        .collect()
+}
+
+// withdraw incentive to pending reward
+pub fn settle_incentive_rewards(
+    incentives: &[Incentive],
+    rewards: &mut Vec<IncentiveReward>,
+    auto_bond_share: Uint128,
+    stake_bond_share: Uint128,
+) {
+    for incentive in incentives.iter() {
+        // reward info created before the incentive starts from zero index
+        let reward = match rewards.iter_mut().position(|it| it.token == incentive.token) {
+            Some(index) => &mut rewards[index],
+            None => {
+                rewards.push(IncentiveReward {
+                    token: incentive.token.clone(),
+                    auto_index: Decimal::zero(),
+                    stake_index: Decimal::zero(),
+                    pending_reward: Uint128::zero(),
+                });
+                rewards.last_mut().unwrap()
+            }
+        };
+        reward.pending_reward += auto_bond_share * (incentive.auto_index - reward.auto_index)
+            + stake_bond_share * (incentive.stake_index - reward.stake_index);
+        reward.auto_index = incentive.auto_index;
+        reward.stake_index = incentive.stake_index;
+    }
+}
+
+pub fn incentive_to_bonder<P: IncentivePool, B: IncentiveBonder>(pool_info: &P, bonder: &mut B) {
+    let (auto_bond_share, stake_bond_share) = bonder.bond_shares();
+    settle_incentive_rewards(
+        pool_info.incentives(),
+        bonder.incentive_rewards_mut(),
+        auto_bond_share,
+        stake_bond_share,
+    );
+}
+
+pub fn is_incentive_rewards_empty(rewards: &[IncentiveReward]) -> bool {
+    rewards.iter().all(|it| it.pending_reward.is_zero())
+}
+
+/// claim pending rewards of the given tokens; or all tokens
+pub fn claim_incentive_rewards(
+    rewards: &mut [IncentiveReward],
+    tokens: &Option<Vec<CanonicalAddr>>,
+) -> Vec<(CanonicalAddr, Uint128)> {
+    let mut claimed = vec![];
+    for reward in rewards.iter_mut() {
+        if reward.pending_reward.is_zero() {
+            continue;
+        }
+        if let Some(tokens) = tokens {
+            if !tokens.contains(&reward.token) {
+                continue;
+            }
+        }
+        claimed.push((reward.token.clone(), reward.pending_reward));
+        reward.pending_reward = Uint128::zero();
+    }
+    claimed
+}
+
+pub fn withdraw_incentive_msgs(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    claimed: Vec<(CanonicalAddr, Uint128)>,
+    recipient: &Addr,
+) -> StdResult<Vec<CosmosMsg>> {
+    claimed
+        .into_iter()
+        .map(|(token, amount)| {
+            let token = api.addr_humanize(&token)?;
+            decrease_incentive_balance(storage, &token, amount)?;
+            Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: token.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.to_string(),
+                    amount,
+                })?,
+                funds: vec![],
+            }))
+        })
+        .collect()
+}
+
+/// cw20 hook of the incentive token; the caller saves the pool
+#[allow(clippy::too_many_arguments)]
+pub fn add_incentive<P: IncentivePool>(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    pool_info: &mut P,
+    query_lp_balance: impl FnOnce(Deps, &P) -> StdResult<Uint128>,
+    depositor: String,
+    asset_token: String,
+    amount: Uint128,
+    duration: u64,
+) -> StdResult<Response> {
+    // reserve deposited token before reading the pool balance
+    increase_incentive_balance(deps.storage, &info.sender, amount)?;
+
+    let lp_balance = query_lp_balance(deps.as_ref(), pool_info)?;
+    incentive_to_pool(pool_info, env.block.time.seconds(), lp_balance)?;
+    schedule_incentive(
+        deps.storage,
+        pool_info.incentives_mut(),
+        deps.api.addr_canonicalize(info.sender.as_str())?,
+        deps.api.addr_canonicalize(&depositor)?,
+        amount,
+        duration,
+        env.block.time.seconds(),
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "add_incentive"),
+        attr("asset_token", asset_token),
+        attr("incentive_token", info.sender.as_str()),
+        attr("amount", amount),
+        attr("duration", duration.to_string()),
+    ]))
+}
+
+/// the caller saves the pool and the bonder
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw_incentives<P: IncentivePool, B: IncentiveBonder>(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    pool_info: &mut P,
+    bonder: &mut B,
+    query_lp_balance: impl FnOnce(Deps, &P) -> StdResult<Uint128>,
+    asset_token: String,
+    tokens: Option<Vec<String>>,
+) -> StdResult<Response> {
+    let lp_balance = query_lp_balance(deps.as_ref(), pool_info)?;
+    incentive_to_pool(pool_info, env.block.time.seconds(), lp_balance)?;
+    incentive_to_bonder(pool_info, bonder);
+
+    // claim only the selected tokens, so a broken token cannot block other incentives
+    let tokens = tokens
+        .map(|tokens| {
+            tokens
+                .iter()
+                .map(|token| deps.api.addr_canonicalize(token))
+                .collect::<StdResult<Vec<CanonicalAddr>>>()
+        })
+        .transpose()?;
+    let claimed = claim_incentive_rewards(bonder.incentive_rewards_mut(), &tokens);
+    let messages = withdraw_incentive_msgs(deps.api, deps.storage, claimed, &info.sender)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "withdraw_incentives"),
+        attr("asset_token", asset_token),
+    ]))
+}
+
+/// the caller saves the pool
+pub fn refund_incentive<P: IncentivePool>(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    pool_info: &mut P,
+    query_lp_balance: impl FnOnce(Deps, &P) -> StdResult<Uint128>,
+    asset_token: String,
+    token: String,
+) -> StdResult<Response> {
+    let lp_balance = query_lp_balance(deps.as_ref(), pool_info)?;
+    incentive_to_pool(pool_info, env.block.time.seconds(), lp_balance)?;
+    let token_raw = deps.api.addr_canonicalize(&token)?;
+    let amount = take_incentive_refund(
+        pool_info.incentives_mut(),
+        &token_raw,
+        &deps.api.addr_canonicalize(info.sender.as_str())?,
+        env.block.time.seconds(),
+    )?;
+
+    let messages =
+        withdraw_incentive_msgs(deps.api, deps.storage, vec![(token_raw, amount)], &info.sender)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "refund_incentive"),
+        attr("asset_token", asset_token),
+        attr("incentive_token", token),
+        attr("amount", amount),
+    ]))
+}
+
+pub fn to_incentive_items(api: &dyn Api, incentives: &[Incentive]) -> StdResult<Vec<IncentiveItem>> {
+    incentives
+        .iter()
+        .map(|it| {
+            Ok(IncentiveItem {
+                token: api.addr_humanize(&it.token)?.to_string(),
+                depositor: api.addr_humanize(&it.depositor)?.to_string(),
+                remaining_amount: it.remaining_amount,
+                end_time: it.end_time,
+                auto_index: it.auto_index,
+                stake_index: it.stake_index,
+            })
+        })
+        .collect()
+}
+
+pub fn to_incentive_reward_items(
+    api: &dyn Api,
+    rewards: &[IncentiveReward],
+) -> StdResult<Vec<IncentiveRewardItem>> {
+    rewards
+        .iter()
+        .map(|it| {
+            Ok(IncentiveRewardItem {
+                token: api.addr_humanize(&it.token)?.to_string(),
+                pending_reward: it.pending_reward,
+            })
+        })
+        .collect()
+}
@@ -3,7 +3,10 @@
 pub mod common;
 pub mod deployer;
 pub mod farm_helper;
+pub mod fee;
 pub mod gov;
+pub mod incentive;
+pub mod user_stat;
 pub mod math;
 pub mod platform;
 pub mod spec_farm;
@@ -20,7 +23,10 @@ pub mod orion_farm;
 pub mod nexus_nasset_psi_farm;
 pub mod pylon_liquid_farm;
 pub mod gov_proxy;
+pub mod gov_proxy_helper;
+pub mod xastro_gov_proxy;
 pub mod astroport_farm;
+pub mod spec_astroport_farm;
 pub mod astroport_luna_ust_farm;
 pub mod astroport_token_luna_farm;
 pub mod astroport_token_ust_farm;
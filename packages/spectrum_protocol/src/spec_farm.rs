@@ -37,6 +37,11 @@ pub enum Cw20HookMsg {
         staker_addr: Option<String>,
         asset_token: String,
     },
+    add_emission {
+        asset_token: String,
+        start_block: u64,
+        end_block: u64,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -63,6 +68,17 @@ pub struct PoolItem {
     pub weight: u32,
     pub state_spec_share_index: Decimal,
     pub spec_share_index: Decimal,
+    #[serde(default)] pub emissions: Vec<EmissionItem>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EmissionItem {
+    pub token: String,
+    pub start_block: u64,
+    pub end_block: u64,
+    pub amount: Uint128,
+    pub distributed_amount: Uint128,
+    pub reward_index: Decimal,
 }
 
 // We define a custom struct for each query response
@@ -79,6 +95,13 @@ pub struct RewardInfoResponseItem {
     pub pending_spec_reward: Uint128,
     pub spec_share: Uint128,
     pub spec_share_index: Decimal,
+    #[serde(default)] pub emission_rewards: Vec<EmissionRewardItem>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EmissionRewardItem {
+    pub token: String,
+    pub pending_reward: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
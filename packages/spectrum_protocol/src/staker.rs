@@ -1,4 +1,4 @@
-use cosmwasm_std::{Decimal, Uint128};
+use cosmwasm_std::{Binary, Decimal, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use terraswap::asset::{Asset, AssetInfo};
@@ -11,6 +11,97 @@ pub struct ConfigInfo {
     pub terraswap_factory: String,
     pub allowlist: Vec<String>,
     pub allow_all: bool,
+    #[serde(default)] pub factories: Vec<String>, // additional factories for route finding
+    #[serde(default)] pub route_assets: Vec<AssetInfo>, // intermediate assets for one-hop routes
+}
+
+/// allowlisted farm, pair_contract is None for single asset farm
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FarmInfo {
+    pub contract: String,
+    pub farm_type: String, // e.g. astroport, mirror, pylon_liquid
+    pub asset_tokens: Vec<String>,
+    pub staking_token: String,
+    pub pair_contract: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FarmsResponse {
+    pub farms: Vec<FarmInfo>,
+}
+
+/// zap_to_bond signed off-chain by owner, funded by owner's CW20 allowance to this contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ZapIntent {
+    pub owner: String,
+    pub nonce: u64,
+    pub expires_at: u64, // block height
+    pub token: String,
+    pub amount: Uint128,
+    pub relayer_fee: Option<Uint128>, // deducted from amount and paid to relayer
+    pub contract: String,
+    pub pair_asset: AssetInfo,
+    pub pair_asset_b: Option<AssetInfo>,
+    pub belief_price: Option<Decimal>,
+    pub belief_price_b: Option<Decimal>,
+    pub max_spread: Decimal,
+    pub compound_rate: Option<Decimal>,
+    pub asset_token: Option<String>,
+    pub swap_hints: Option<Vec<SwapOperation>>,
+}
+
+/// signed message is sha256 of JSON serialized ZapIntentPayload
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ZapIntentPayload {
+    pub chain_id: String,
+    pub contract_addr: String,
+    pub intent: ZapIntent,
+}
+
+/// recurring zap_to_bond funded by native token deposit
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScheduleInfo {
+    pub id: u64,
+    pub owner: String,
+    pub contract: String,
+    pub pair_asset: AssetInfo,
+    pub pair_asset_b: Option<AssetInfo>,
+    pub asset_token: Option<String>,
+    pub denom: String,
+    pub amount_per_period: Uint128, // include keeper_fee
+    pub keeper_fee: Uint128, // paid to executor for each period
+    pub period: u64, // in seconds
+    pub next_execution: u64, // in seconds
+    pub compound_rate: Option<Decimal>,
+    pub belief_price: Decimal, // price bound of pair_asset for every period
+    pub belief_price_b: Option<Decimal>,
+    pub max_spread: Decimal, // up to 5%
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SchedulesResponse {
+    pub schedules: Vec<ScheduleInfo>,
+}
+
+/// zap_to_bond escrowed until pool price of pair_asset in provide_asset is at or below limit_price
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LimitOrderInfo {
+    pub id: u64,
+    pub owner: String,
+    pub contract: String,
+    pub provide_asset: Asset,
+    pub pair_asset: AssetInfo,
+    pub asset_token: Option<String>,
+    pub limit_price: Decimal,
+    pub keeper_fee: Uint128, // deducted from provide_asset and paid to executor
+    pub compound_rate: Option<Decimal>,
+    pub max_spread: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LimitOrdersResponse {
+    pub orders: Vec<LimitOrderInfo>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -44,10 +135,123 @@ pub enum ExecuteMsg {
         asset_token: Option<String>,
         swap_hints: Option<Vec<SwapOperation>>,
     },
+    // split provide_asset by weight and zap each portion into its target farm
+    zap_to_bond_multi {
+        provide_asset: Asset,
+        targets: Vec<ZapTarget>,
+        max_spread: Decimal,
+    },
+    // zap a portion of zap_to_bond_multi after previous targets, so shared pairs are simulated with updated pools
+    zap_to_bond_multi_hook {
+        staker_addr: String,
+        provide_asset: Asset,
+        target: ZapTarget,
+        max_spread: Decimal,
+    },
+    // unbond LP from from_contract and bond into to_contract, LP is converted if pairs are different
+    // both farms must be registered with the pairs, from_contract must support unbond_for (astroport, mirror)
+    migrate_position {
+        from_contract: String,
+        from_asset_token: String,
+        from_pair: String,
+        amount: Uint128,
+        to_contract: String,
+        to_asset_token: Option<String>,
+        to_pair: String,
+        compound_rate: Option<Decimal>,
+        slippage_tolerance: Decimal,
+    },
+    migrate_position_hook {
+        staker_addr: String,
+        from_pair: String,
+        prev_staking_token_amount: Uint128,
+        to_contract: String,
+        to_asset_token: Option<String>,
+        to_pair: String,
+        compound_rate: Option<Decimal>,
+        slippage_tolerance: Decimal,
+    },
+    migrate_position_provide_hook {
+        staker_addr: String,
+        prev_assets: [Asset; 2],
+        to_contract: String,
+        to_asset_token: Option<String>,
+        to_pair: String,
+        compound_rate: Option<Decimal>,
+        slippage_tolerance: Decimal,
+    },
+    // execute zap intent on behalf of owner, sender receives relayer_fee
+    zap_to_bond_intent {
+        intent: ZapIntent,
+        signature: Binary,
+        pubkey: Binary, // owner's compressed secp256k1 pubkey, must derive owner address
+    },
+    // create recurring zap with sent native token
+    create_schedule {
+        contract: String,
+        pair_asset: AssetInfo,
+        pair_asset_b: Option<AssetInfo>,
+        asset_token: Option<String>,
+        amount_per_period: Uint128,
+        keeper_fee: Uint128,
+        period: u64,
+        start_time: Option<u64>,
+        compound_rate: Option<Decimal>,
+        belief_price: Decimal,
+        belief_price_b: Option<Decimal>,
+        max_spread: Decimal,
+    },
+    // top up schedule balance with sent native token
+    deposit_schedule {
+        id: u64,
+    },
+    // remove schedule and refund remaining balance
+    cancel_schedule {
+        id: u64,
+    },
+    // anyone can execute due schedules for keeper fee
+    execute_schedules {
+        ids: Vec<u64>,
+    },
+    // execute one due schedule, dispatched by execute_schedules so each zap simulates after the previous one
+    execute_schedule_hook {
+        id: u64,
+        keeper: String,
+    },
+    // escrow sent native token until limit_price is reached
+    create_limit_order {
+        contract: String,
+        provide_asset: Asset,
+        pair_asset: AssetInfo,
+        asset_token: Option<String>,
+        limit_price: Decimal,
+        keeper_fee: Uint128,
+        compound_rate: Option<Decimal>,
+        max_spread: Decimal,
+    },
+    // remove order and refund provide_asset
+    cancel_limit_order {
+        id: u64,
+    },
+    // anyone can execute order when limit_price is reached for keeper fee
+    execute_limit_order {
+        id: u64,
+    },
+    // add farms with metadata to allowlist
+    register_farms {
+        farms: Vec<FarmInfo>,
+    },
+    // remove farms from allowlist
+    deregister_farms {
+        contracts: Vec<String>,
+    },
     update_config {
         insert_allowlist: Option<Vec<String>>,
         remove_allowlist: Option<Vec<String>>,
         allow_all: Option<bool>,
+        insert_factories: Option<Vec<String>>,
+        remove_factories: Option<Vec<String>>,
+        route_assets: Option<Vec<AssetInfo>>,
     },
     zap_to_unbond_hook {
         staker_addr: String,
@@ -58,9 +262,32 @@ pub enum ExecuteMsg {
         belief_price_b: Option<Decimal>,
         max_spread: Decimal,
         swap_hints: Option<Vec<SwapOperation>>,
+        min_receive: Option<Uint128>,
+    },
+    // send target asset received since prev_target_asset to staker, fail if less than min_receive
+    zap_to_unbond_transfer_hook {
+        staker_addr: String,
+        prev_target_asset: Asset,
+        min_receive: Option<Uint128>,
     },
 }
 
+/// farm to receive a portion of zap_to_bond_multi, fields are the same as zap_to_bond
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ZapTarget {
+    pub contract: String,
+    pub asset_token: Option<String>,
+    pub pair_asset: AssetInfo,
+    pub pair_asset_b: Option<AssetInfo>,
+    pub belief_price: Option<Decimal>,
+    pub belief_price_b: Option<Decimal>,
+    pub compound_rate: Option<Decimal>,
+    pub swap_hints: Option<Vec<SwapOperation>>,
+    pub weight: u32,
+    // max_spread of this target, default to max_spread of zap_to_bond_multi
+    #[serde(default)] pub max_spread: Option<Decimal>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct SimulateZapToBondResponse {
     pub lp_amount: Uint128,
@@ -74,8 +301,54 @@ pub struct SimulateZapToBondResponse {
     pub swap_hint_prices: Option<Vec<Decimal>>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateZapToBondMultiResponse {
+    pub results: Vec<SimulateZapToBondMultiItem>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateZapToBondMultiItem {
+    pub contract: String,
+    pub asset_token: Option<String>,
+    pub provide_amount: Uint128, // portion of provide_asset by weight
+    pub simulation: SimulateZapToBondResponse,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateBestRouteResponse {
+    pub return_amount: Uint128,
+    pub swap_operations: Vec<SwapOperation>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub enum Cw20HookMsg {
+    // zap sent token into LP of contract, same as ExecuteMsg::zap_to_bond
+    zap_to_bond {
+        contract: String,
+        pair_asset: AssetInfo,
+        pair_asset_b: Option<AssetInfo>,
+        belief_price: Option<Decimal>,
+        belief_price_b: Option<Decimal>,
+        max_spread: Decimal,
+        compound_rate: Option<Decimal>,
+        asset_token: Option<String>,
+        swap_hints: Option<Vec<SwapOperation>>,
+    },
+    // zap sent token into multiple farms, same as ExecuteMsg::zap_to_bond_multi
+    zap_to_bond_multi {
+        targets: Vec<ZapTarget>,
+        max_spread: Decimal,
+    },
+    // escrow sent token until limit_price is reached, same as ExecuteMsg::create_limit_order
+    create_limit_order {
+        contract: String,
+        pair_asset: AssetInfo,
+        asset_token: Option<String>,
+        limit_price: Decimal,
+        keeper_fee: Uint128,
+        compound_rate: Option<Decimal>,
+        max_spread: Decimal,
+    },
     zap_to_unbond {
         sell_asset: AssetInfo,
         sell_asset_b: Option<AssetInfo>,
@@ -84,18 +357,69 @@ pub enum Cw20HookMsg {
         belief_price_b: Option<Decimal>,
         max_spread: Decimal,
         swap_hints: Option<Vec<SwapOperation>>,
+        staker_addr: Option<String>, // receiver of target_asset, default to LP sender
+        min_receive: Option<Uint128>,
     },
 }
 
+/// params for a farm to unbond LP through zap_to_unbond on behalf of the staker
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ZapOutParams {
+    pub sell_asset: AssetInfo,
+    pub sell_asset_b: Option<AssetInfo>,
+    pub target_asset: AssetInfo,
+    pub belief_price: Option<Decimal>,
+    pub belief_price_b: Option<Decimal>,
+    pub max_spread: Decimal,
+    pub swap_hints: Option<Vec<SwapOperation>>,
+    pub min_receive: Option<Uint128>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub enum QueryMsg {
     config {},
+    farm {
+        contract: String,
+    },
+    farms {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
     simulate_zap_to_bond {
         provide_asset: Asset,
         pair_asset: AssetInfo,
         pair_asset_b: Option<AssetInfo>,
         swap_hints: Option<Vec<SwapOperation>>,
     },
+    simulate_zap_to_bond_multi {
+        provide_asset: Asset,
+        targets: Vec<ZapTarget>,
+    },
+    is_nonce_used {
+        owner: String,
+        nonce: u64,
+    },
+    schedule {
+        id: u64,
+    },
+    schedules {
+        owner: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    limit_order {
+        id: u64,
+    },
+    limit_orders {
+        owner: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    // find best direct or one-hop route across factories
+    simulate_best_route {
+        offer_asset: Asset,
+        ask_asset: AssetInfo,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
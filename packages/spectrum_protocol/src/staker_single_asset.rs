@@ -1,4 +1,4 @@
-use cosmwasm_std::{Decimal};
+use cosmwasm_std::{Decimal, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use terraswap::asset::{Asset, AssetInfo};
@@ -7,6 +7,7 @@ use terraswap::asset::{Asset, AssetInfo};
 pub struct ConfigInfo {
     pub owner: String,
     pub allowlist: Vec<String>,
+    #[serde(default)] pub farm_registry: Option<String>, // spectrum_staker with registered farms
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -16,6 +17,34 @@ pub struct SwapOperation {
     pub belief_price: Option<Decimal>,
 }
 
+/// message to bond the final asset after swap operations, sent via Cw20 Send for token,
+/// or executed on the contract with the swapped coins as funds for native asset
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum BondHook {
+    // bond { staker_addr, compound_rate }, e.g. pylon_liquid, nasset farm
+    farm_bond {},
+    // spectrum_spec_farm bond { staker_addr, asset_token }
+    spec_farm_bond {
+        asset_token: String,
+    },
+    // gov stake_tokens { staker_addr, days }
+    gov_stake {
+        days: Option<u64>,
+    },
+}
+
+impl Default for BondHook {
+    fn default() -> Self {
+        BondHook::farm_bond {}
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateZapToBondResponse {
+    pub receive_amount: Uint128,
+    pub belief_prices: Vec<Decimal>, // belief price of each swap operation
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub enum ExecuteMsg {
     zap_to_bond {
@@ -36,12 +65,25 @@ pub enum ExecuteMsg {
     update_config {
         insert_allowlist: Option<Vec<String>>,
         remove_allowlist: Option<Vec<String>>,
+        farm_registry: Option<String>,
+    },
+    // None to reset to default farm_bond
+    set_bond_hook {
+        contract: String,
+        bond_hook: Option<BondHook>,
     },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub enum QueryMsg {
-    config {}
+    config {},
+    bond_hook {
+        contract: String,
+    },
+    simulate_zap_to_bond {
+        provide_asset: Asset,
+        swap_operations: Vec<SwapOperation>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
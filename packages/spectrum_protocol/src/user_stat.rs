@@ -0,0 +1,166 @@
+use cosmwasm_std::{CanonicalAddr, Deps, Order, StdResult, Storage, Uint128};
+use cosmwasm_storage::{Bucket, ReadonlyBucket};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+static PREFIX_USER_STAT: &[u8] = b"user_stat";
+
+// cumulative bond/unbond/withdraw stats of a staker in a pool, amounts in LP unless noted
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserStat {
+    pub lp_bonded: Uint128,
+    pub lp_unbonded: Uint128,
+    pub farm_withdrawn: Uint128,
+    pub spec_withdrawn: Uint128,
+    pub deposit_fee_paid: Uint128,
+    pub cost_basis: Uint128,
+    pub realized_gain: Uint128,
+    #[serde(default)] pub realized_loss: Uint128,
+    #[serde(default)] pub withdraw_fee_paid: Uint128,
+    #[serde(default)] pub farm2_withdrawn: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserStatsResponse {
+    pub staker_addr: String,
+    pub user_stats: Vec<UserStatsResponseItem>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserStatsResponseItem {
+    pub asset_token: String,
+    pub bond_amount: Uint128,      // current LP balance
+    pub lp_bonded: Uint128,        // cumulative LP bonded, after deposit fee
+    pub lp_unbonded: Uint128,      // cumulative LP unbonded, after withdraw fee
+    pub farm_withdrawn: Uint128,   // cumulative farm token withdrawn
+    pub spec_withdrawn: Uint128,   // cumulative SPEC withdrawn
+    pub deposit_fee_paid: Uint128, // cumulative deposit fee in LP
+    pub cost_basis: Uint128,       // LP cost of current bond_amount
+    pub realized_gain: Uint128,    // LP gain on unbonded amount, after withdraw fee
+    pub realized_loss: Uint128,    // LP loss on unbonded amount, after withdraw fee
+    pub unrealized_gain: Uint128,  // bond_amount - cost_basis, if positive
+    pub unrealized_loss: Uint128,  // cost_basis - bond_amount, if positive
+    pub withdraw_fee_paid: Uint128, // cumulative withdraw fee in LP
+    #[serde(default)] pub farm2_withdrawn: Uint128, // cumulative second farm token withdrawn, dual reward farms only
+}
+
+/// returns a bucket with cumulative bond/unbond/withdraw stats of this owner (query it by owner)
+pub fn user_stat_store<'a>(
+    storage: &'a mut dyn Storage,
+    owner: &CanonicalAddr,
+) -> Bucket<'a, UserStat> {
+    Bucket::multilevel(storage, &[PREFIX_USER_STAT, owner.as_slice()])
+}
+
+/// returns a bucket with cumulative bond/unbond/withdraw stats of this owner (query it by owner)
+/// (read-only version for queries)
+pub fn user_stat_read<'a>(
+    storage: &'a dyn Storage,
+    owner: &CanonicalAddr,
+) -> ReadonlyBucket<'a, UserStat> {
+    ReadonlyBucket::multilevel(storage, &[PREFIX_USER_STAT, owner.as_slice()])
+}
+
+impl UserStat {
+    pub fn create(cost_basis: Uint128) -> UserStat {
+        UserStat {
+            lp_bonded: Uint128::zero(),
+            lp_unbonded: Uint128::zero(),
+            farm_withdrawn: Uint128::zero(),
+            spec_withdrawn: Uint128::zero(),
+            deposit_fee_paid: Uint128::zero(),
+            cost_basis,
+            realized_gain: Uint128::zero(),
+            realized_loss: Uint128::zero(),
+            withdraw_fee_paid: Uint128::zero(),
+            farm2_withdrawn: Uint128::zero(),
+        }
+    }
+
+    /// amount is the bonded LP after deposit fee
+    pub fn bond(&mut self, amount: Uint128, deposit_fee: Uint128) {
+        self.lp_bonded += amount;
+        self.cost_basis += amount;
+        self.deposit_fee_paid += deposit_fee;
+    }
+
+    /// realize gain or loss on the unbonded portion at average cost, net of withdraw fee
+    pub fn unbond(
+        &mut self,
+        amount: Uint128,
+        user_balance: Uint128,
+        withdraw_fee: Uint128,
+    ) -> StdResult<()> {
+        let cost = self.cost_basis.multiply_ratio(amount, user_balance);
+        let net_amount = amount.checked_sub(withdraw_fee)?;
+        self.cost_basis = self.cost_basis.checked_sub(cost)?;
+        self.realized_gain += net_amount.saturating_sub(cost);
+        self.realized_loss += cost.saturating_sub(net_amount);
+        self.lp_unbonded += net_amount;
+        self.withdraw_fee_paid += withdraw_fee;
+        Ok(())
+    }
+}
+
+/// positions opened before stats were tracked start with their current balance as cost basis
+pub fn read_user_stat(
+    storage: &dyn Storage,
+    staker_addr: &CanonicalAddr,
+    asset_token: &[u8],
+    bond_amount: Uint128,
+) -> StdResult<UserStat> {
+    let user_stat = user_stat_read(storage, staker_addr).may_load(asset_token)?;
+    Ok(user_stat.unwrap_or_else(|| UserStat::create(bond_amount)))
+}
+
+/// asset_tokens are the pool keys the staker is bonded in,
+/// bond_amount returns the asset token name and the current LP balance of the staker in a pool
+pub fn query_user_stats(
+    deps: Deps,
+    staker_addr: String,
+    asset_tokens: Vec<Vec<u8>>,
+    bond_amount: impl Fn(&[u8]) -> StdResult<(String, Uint128)>,
+) -> StdResult<UserStatsResponse> {
+    let staker_addr_raw = deps.api.addr_canonicalize(&staker_addr)?;
+
+    // stats are kept after a position is closed; reward infos may predate stats
+    let mut user_stats: BTreeMap<Vec<u8>, Option<UserStat>> = BTreeMap::new();
+    for item in user_stat_read(deps.storage, &staker_addr_raw).range(None, None, Order::Ascending) {
+        let (k, v) = item?;
+        user_stats.insert(k, Some(v));
+    }
+    for asset_token in asset_tokens {
+        user_stats.entry(asset_token).or_insert(None);
+    }
+
+    let user_stats = user_stats
+        .into_iter()
+        .map(|(asset_token, user_stat)| {
+            let (asset_token, bond_amount) = bond_amount(&asset_token)?;
+            let user_stat = user_stat.unwrap_or_else(|| UserStat::create(bond_amount));
+
+            Ok(UserStatsResponseItem {
+                asset_token,
+                bond_amount,
+                lp_bonded: user_stat.lp_bonded,
+                lp_unbonded: user_stat.lp_unbonded,
+                farm_withdrawn: user_stat.farm_withdrawn,
+                spec_withdrawn: user_stat.spec_withdrawn,
+                deposit_fee_paid: user_stat.deposit_fee_paid,
+                cost_basis: user_stat.cost_basis,
+                realized_gain: user_stat.realized_gain,
+                realized_loss: user_stat.realized_loss,
+                unrealized_gain: bond_amount.saturating_sub(user_stat.cost_basis),
+                unrealized_loss: user_stat.cost_basis.saturating_sub(bond_amount),
+                withdraw_fee_paid: user_stat.withdraw_fee_paid,
+                farm2_withdrawn: user_stat.farm2_withdrawn,
+            })
+        })
+        .collect::<StdResult<Vec<UserStatsResponseItem>>>()?;
+
+    Ok(UserStatsResponse {
+        staker_addr,
+        user_stats,
+    })
+}
@@ -0,0 +1,97 @@
+use cosmwasm_std::{Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use crate::gov_proxy::VoteOption;
+
+// gov_proxy messages, with xASTRO compound and exchange rate
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {}, // get config
+    // get Gov bond_amount
+    Staker {
+        address: String,
+    },
+    State {},
+    // list depositors with share and balance
+    Stakers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // pending unstake requests
+    Unbonding {
+        address: String,
+    },
+    // xASTRO to ASTRO ratio
+    ExchangeRate {},
+    // staker xASTRO share and yield since deposit
+    StakerYield {
+        address: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExchangeRateResponse {
+    pub exchange_rate: Decimal,  // ASTRO per xASTRO
+    pub total_share: Uint128,    // xASTRO supply
+    pub total_balance: Uint128,  // ASTRO staked in xASTRO
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakerYieldResponse {
+    pub balance: Uint128,           // in ASTRO
+    pub upstream_share: Uint128,    // in xASTRO
+    pub deposit: Uint128,           // principal not yet withdrawn
+    pub accumulated_yield: Uint128, // balance over deposit
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    Unstake {
+        amount: Option<Uint128>,
+    },
+    // stop earning and queue amount, default to all balance, claim after unbonding_period
+    RequestUnstake {
+        amount: Option<Uint128>,
+    },
+    // withdraw matured requests
+    ClaimUnstake {},
+    // vote in assembly with all xASTRO balance, by vote_authority or vote_delegate
+    CastVote {
+        poll_id: u64,
+        vote: VoteOption,
+        amount: Option<Uint128>, // not supported
+    },
+    // not supported
+    StakeVotingRewards {},
+    // by vote_authority
+    UpdateConfig {
+        vote_authority: Option<String>,
+        vote_contract: Option<String>,
+    },
+    // by vote_authority, None to remove delegate
+    SetVoteDelegate {
+        vote_delegate: Option<String>,
+    },
+    // by owner, allow farm contract to stake up to cap, can be called again to update cap
+    RegisterDepositor {
+        depositor: String,
+        cap: Option<Uint128>,
+    },
+    // by owner, depositor can only unstake
+    DeregisterDepositor {
+        depositor: String,
+    },
+    // enter ASTRO held by proxy to xASTRO, shared by all depositors, can be called by anyone
+    Compound {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {
+    #[serde(default)] pub vote_authority: Option<String>,
+    pub owner: String,
+    #[serde(default)] pub unbonding_period: Option<u64>,
+}
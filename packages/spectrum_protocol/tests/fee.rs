@@ -0,0 +1,50 @@
+use cosmwasm_std::{Decimal, Uint128};
+use spectrum_protocol::fee::{calc_decayed_withdraw_fee, credit_withdraw_fee};
+
+#[test]
+fn test_decayed_withdraw_fee() {
+    let withdraw_fee = Decimal::percent(10);
+
+    assert_eq!(calc_decayed_withdraw_fee(withdraw_fee, 1000, 100, 100), withdraw_fee);
+    assert_eq!(calc_decayed_withdraw_fee(withdraw_fee, 1000, 100, 600), Decimal::percent(5));
+    assert_eq!(calc_decayed_withdraw_fee(withdraw_fee, 1000, 100, 1100), Decimal::zero());
+    assert_eq!(calc_decayed_withdraw_fee(withdraw_fee, 0, 100, 100), Decimal::zero());
+    assert_eq!(calc_decayed_withdraw_fee(Decimal::zero(), 1000, 100, 100), Decimal::zero());
+}
+
+#[test]
+fn test_credit_withdraw_fee() {
+    let fee = Uint128::from(5u128);
+
+    // auto-compound bonders remain, fee stays in lp balance above total_stake_bond_amount
+    let mut total_stake_bond_amount = Uint128::from(100u128);
+    let credited = credit_withdraw_fee(
+        fee,
+        Uint128::from(50u128),
+        Uint128::from(100u128),
+        &mut total_stake_bond_amount,
+    );
+    assert_eq!(credited, fee);
+    assert_eq!(total_stake_bond_amount, Uint128::from(100u128));
+
+    // no auto-compound share left, fee goes to auto-stake bonders
+    let credited = credit_withdraw_fee(
+        fee,
+        Uint128::zero(),
+        Uint128::from(100u128),
+        &mut total_stake_bond_amount,
+    );
+    assert_eq!(credited, fee);
+    assert_eq!(total_stake_bond_amount, Uint128::from(105u128));
+
+    // last bonder left the pool, nobody to credit so fee is waived
+    let mut total_stake_bond_amount = Uint128::zero();
+    let credited = credit_withdraw_fee(
+        fee,
+        Uint128::zero(),
+        Uint128::zero(),
+        &mut total_stake_bond_amount,
+    );
+    assert_eq!(credited, Uint128::zero());
+    assert_eq!(total_stake_bond_amount, Uint128::zero());
+}
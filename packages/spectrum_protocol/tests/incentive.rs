@@ -0,0 +1,195 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{
+    to_binary, CosmosMsg, Deps, Env, StdError, StdResult, Timestamp, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use spectrum_protocol::incentive::{
+    add_incentive, refund_incentive, update_incentive_tokens, withdraw_incentives, Incentive,
+    IncentiveBonder, IncentivePool, IncentiveReward,
+};
+
+const TOKEN: &str = "bribe";
+const DEPOSITOR: &str = "depositor";
+const STAKER: &str = "staker";
+const ASSET: &str = "asset";
+
+#[derive(Default)]
+struct Pool {
+    incentives: Vec<Incentive>,
+    total_stake_bond_amount: Uint128,
+    total_auto_bond_share: Uint128,
+    total_stake_bond_share: Uint128,
+}
+
+impl IncentivePool for Pool {
+    fn incentives(&self) -> &[Incentive] {
+        &self.incentives
+    }
+
+    fn incentives_mut(&mut self) -> &mut Vec<Incentive> {
+        &mut self.incentives
+    }
+
+    fn bond_totals(&self) -> (Uint128, Uint128, Uint128) {
+        (
+            self.total_stake_bond_amount,
+            self.total_auto_bond_share,
+            self.total_stake_bond_share,
+        )
+    }
+}
+
+#[derive(Default)]
+struct Bonder {
+    incentive_rewards: Vec<IncentiveReward>,
+    auto_bond_share: Uint128,
+    stake_bond_share: Uint128,
+}
+
+impl IncentiveBonder for Bonder {
+    fn incentive_rewards_mut(&mut self) -> &mut Vec<IncentiveReward> {
+        &mut self.incentive_rewards
+    }
+
+    fn bond_shares(&self) -> (Uint128, Uint128) {
+        (self.auto_bond_share, self.stake_bond_share)
+    }
+}
+
+fn env_at(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(seconds);
+    env
+}
+
+fn transfer_msg(recipient: &str, amount: u128) -> CosmosMsg {
+    CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: TOKEN.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+        })
+        .unwrap(),
+        funds: vec![],
+    })
+}
+
+#[test]
+fn test_incentive_stream() {
+    let mut deps = mock_dependencies(&[]);
+    let token_info = mock_info(TOKEN, &[]);
+    let lp_balance = |_: Deps, _: &Pool| -> StdResult<Uint128> { Ok(Uint128::from(100u128)) };
+
+    // 60 LP auto-compound, 40 LP auto-stake, single bonder
+    let mut pool = Pool {
+        total_stake_bond_amount: Uint128::from(40u128),
+        total_auto_bond_share: Uint128::from(60u128),
+        total_stake_bond_share: Uint128::from(40u128),
+        ..Pool::default()
+    };
+    let mut bonder = Bonder {
+        auto_bond_share: Uint128::from(60u128),
+        stake_bond_share: Uint128::from(40u128),
+        ..Bonder::default()
+    };
+
+    let res = add_incentive(
+        deps.as_mut(),
+        &env_at(0),
+        &token_info,
+        &mut pool,
+        lp_balance,
+        DEPOSITOR.to_string(),
+        ASSET.to_string(),
+        Uint128::from(1000u128),
+        100,
+    );
+    assert_eq!(
+        res.unwrap_err(),
+        StdError::generic_err("token is not allowed for incentive")
+    );
+
+    update_incentive_tokens(&deps.api, &mut deps.storage, Some(vec![TOKEN.to_string()]), None)
+        .unwrap();
+    add_incentive(
+        deps.as_mut(),
+        &env_at(0),
+        &token_info,
+        &mut pool,
+        lp_balance,
+        DEPOSITOR.to_string(),
+        ASSET.to_string(),
+        Uint128::from(1000u128),
+        100,
+    )
+    .unwrap();
+
+    // half of the stream is claimable at half time
+    let res = withdraw_incentives(
+        deps.as_mut(),
+        &env_at(50),
+        &mock_info(STAKER, &[]),
+        &mut pool,
+        &mut bonder,
+        lp_balance,
+        ASSET.to_string(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].msg, transfer_msg(STAKER, 500));
+
+    // stream is still running
+    let res = refund_incentive(
+        deps.as_mut(),
+        &env_at(50),
+        &mock_info(DEPOSITOR, &[]),
+        &mut pool,
+        lp_balance,
+        ASSET.to_string(),
+        TOKEN.to_string(),
+    );
+    assert_eq!(res.unwrap_err(), StdError::generic_err("incentive is still running"));
+
+    // everybody unbonds, the rest of the stream is kept for refund
+    pool.total_stake_bond_amount = Uint128::zero();
+    pool.total_auto_bond_share = Uint128::zero();
+    pool.total_stake_bond_share = Uint128::zero();
+    let no_lp_balance = |_: Deps, _: &Pool| -> StdResult<Uint128> { Ok(Uint128::zero()) };
+
+    let res = refund_incentive(
+        deps.as_mut(),
+        &env_at(200),
+        &mock_info(STAKER, &[]),
+        &mut pool,
+        no_lp_balance,
+        ASSET.to_string(),
+        TOKEN.to_string(),
+    );
+    assert_eq!(res.unwrap_err(), StdError::generic_err("unauthorized"));
+
+    let res = refund_incentive(
+        deps.as_mut(),
+        &env_at(200),
+        &mock_info(DEPOSITOR, &[]),
+        &mut pool,
+        no_lp_balance,
+        ASSET.to_string(),
+        TOKEN.to_string(),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].msg, transfer_msg(DEPOSITOR, 500));
+
+    // already refunded
+    let res = refund_incentive(
+        deps.as_mut(),
+        &env_at(200),
+        &mock_info(DEPOSITOR, &[]),
+        &mut pool,
+        no_lp_balance,
+        ASSET.to_string(),
+        TOKEN.to_string(),
+    );
+    assert_eq!(res.unwrap_err(), StdError::generic_err("nothing to refund"));
+}